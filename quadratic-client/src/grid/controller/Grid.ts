@@ -13,6 +13,7 @@ import init, {
   GridController,
   JsCodeResult,
   JsComputeGetCells,
+  JsonExportShape,
   JsRenderBorders,
   MinMax,
   PasteSpecial,
@@ -24,19 +25,32 @@ import init, {
 import {
   CellAlign,
   CellFormatSummary,
+  Chart,
+  CellVerticalAlign,
   CellWrap,
   CodeCellLanguage,
+  ConditionalFormat,
+  CsvExportOptions,
   FormattingSummary,
+  JsChart,
   JsClipboard,
   JsCodeCell,
+  JsCommentThread,
+  JsFindReplace,
   JsHtmlOutput,
   JsRenderCell,
   JsRenderCodeCell,
   JsRenderFill,
+  Locale,
   Rect,
+  SearchMatch,
   SearchOptions,
   SheetPos,
+  SheetProtection,
+  SheetRect,
   TransactionSummary,
+  Validation,
+  ValidationReport,
 } from '../../quadratic-core/types';
 import { GridFile } from '../../schemas';
 import { SheetCursorSave } from '../sheet/SheetCursor';
@@ -149,6 +163,38 @@ export class Grid {
       pixiApp.cellsSheets.updateBorders(summary.border_sheets_modified);
     }
 
+    if (summary.merged_cells_modified.length) {
+      window.dispatchEvent(new CustomEvent('merged-cells-update', { detail: summary.merged_cells_modified }));
+    }
+
+    if (summary.locale_modified) {
+      window.dispatchEvent(new CustomEvent('locale-update'));
+    }
+
+    if (summary.validations_modified.length) {
+      window.dispatchEvent(new CustomEvent('validations-update', { detail: summary.validations_modified }));
+    }
+
+    if (summary.conditional_formats_modified.length) {
+      window.dispatchEvent(
+        new CustomEvent('conditional-formats-update', { detail: summary.conditional_formats_modified })
+      );
+    }
+
+    if (summary.comments_modified.length) {
+      window.dispatchEvent(new CustomEvent('comments-update', { detail: summary.comments_modified }));
+    }
+
+    if (summary.error && 'ValidationRejected' in summary.error) {
+      window.dispatchEvent(
+        new CustomEvent('validation-message', { detail: { message: summary.error.ValidationRejected, rejected: true } })
+      );
+    } else if (summary.validation_warning) {
+      window.dispatchEvent(
+        new CustomEvent('validation-message', { detail: { message: summary.validation_warning, rejected: false } })
+      );
+    }
+
     if (summary.generate_thumbnail) {
       this.thumbnailDirty = true;
     }
@@ -188,6 +234,10 @@ export class Grid {
   openFromContents(contents: string, lastSequenceNum: number): boolean {
     try {
       this.gridController = GridController.newFromFile(contents, lastSequenceNum);
+      const report = this.getFileReport();
+      if (report.problems.length) {
+        console.warn(`${report.repaired ? 'Repaired' : 'Found'} problems in the file`, report.problems);
+      }
       return true;
     } catch (e) {
       console.warn(e);
@@ -199,6 +249,11 @@ export class Grid {
     return this.gridController.exportToFile();
   }
 
+  // problems found (and repaired) when the file was opened
+  getFileReport(): ValidationReport {
+    return JSON.parse(this.gridController.getFileReport());
+  }
+
   getVersion(): string {
     return this.gridController.getVersion();
   }
@@ -222,6 +277,13 @@ export class Grid {
     return this.gridController.getSheetColor(sheetId);
   }
 
+  getSheetFrozen(sheetId: string): { rows: number; columns: number } {
+    return {
+      rows: this.gridController.getSheetFrozenRows(sheetId),
+      columns: this.gridController.getSheetFrozenColumns(sheetId),
+    };
+  }
+
   //#endregion
 
   //#region set sheet operations
@@ -252,6 +314,11 @@ export class Grid {
     this.transactionResponse(summary);
   }
 
+  setSheetFrozen(sheetId: string, rows: number, columns: number) {
+    const summary = this.gridController.setSheetFrozen(sheetId, rows, columns, sheets.getCursorPosition());
+    this.transactionResponse(summary);
+  }
+
   duplicateSheet(sheetId: string) {
     const summary = this.gridController.duplicateSheet(sheetId, sheets.getCursorPosition());
     this.transactionResponse(summary);
@@ -353,6 +420,27 @@ export class Grid {
     this.transactionResponse(summary);
   }
 
+  // sets an Excel-style number format code, e.g. '#,##0.00;[Red](#,##0.00)'
+  setCellCustomNumberFormat(sheetId: string, rectangle: Rectangle, code: string) {
+    const summary = this.gridController.setCellCustomNumberFormat(
+      sheetId,
+      rectangleToRect(rectangle),
+      code,
+      sheets.getCursorPosition()
+    );
+    this.transactionResponse(summary);
+  }
+
+  setCellDateTimeFormat(sheetId: string, rectangle: Rectangle, code: string) {
+    const summary = this.gridController.setCellDateTimeFormat(
+      sheetId,
+      rectangleToRect(rectangle),
+      code,
+      sheets.getCursorPosition()
+    );
+    this.transactionResponse(summary);
+  }
+
   toggleCommas(sheetId: string, source: Pos, rectangle: Rectangle) {
     const summary = this.gridController.toggleCommas(
       sheetId,
@@ -392,6 +480,66 @@ export class Grid {
     this.transactionResponse(summary);
   }
 
+  setCellUnderline(sheetId: string, rectangle: Rectangle, underline: boolean | undefined) {
+    const summary = this.gridController.setCellUnderline(
+      sheetId,
+      rectangleToRect(rectangle),
+      underline,
+      sheets.getCursorPosition()
+    );
+    this.transactionResponse(summary);
+  }
+
+  setCellStrikeThrough(sheetId: string, rectangle: Rectangle, strikeThrough: boolean | undefined) {
+    const summary = this.gridController.setCellStrikeThrough(
+      sheetId,
+      rectangleToRect(rectangle),
+      strikeThrough,
+      sheets.getCursorPosition()
+    );
+    this.transactionResponse(summary);
+  }
+
+  setCellFontSize(sheetId: string, rectangle: Rectangle, fontSize: number | undefined) {
+    const summary = this.gridController.setCellFontSize(
+      sheetId,
+      rectangleToRect(rectangle),
+      fontSize,
+      sheets.getCursorPosition()
+    );
+    this.transactionResponse(summary);
+  }
+
+  setCellFontFamily(sheetId: string, rectangle: Rectangle, fontFamily: string | undefined) {
+    const summary = this.gridController.setCellFontFamily(
+      sheetId,
+      rectangleToRect(rectangle),
+      fontFamily,
+      sheets.getCursorPosition()
+    );
+    this.transactionResponse(summary);
+  }
+
+  setCellVerticalAlign(sheetId: string, rectangle: Rectangle, verticalAlign: CellVerticalAlign | undefined) {
+    const summary = this.gridController.setCellVerticalAlign(
+      sheetId,
+      rectangleToRect(rectangle),
+      verticalAlign,
+      sheets.getCursorPosition()
+    );
+    this.transactionResponse(summary);
+  }
+
+  setCellTextRotation(sheetId: string, rectangle: Rectangle, textRotation: number | undefined) {
+    const summary = this.gridController.setCellTextRotation(
+      sheetId,
+      rectangleToRect(rectangle),
+      textRotation,
+      sheets.getCursorPosition()
+    );
+    this.transactionResponse(summary);
+  }
+
   setCellTextColor(sheetId: string, rectangle: Rectangle, textColor: string | undefined) {
     const summary = this.gridController.setCellTextColor(
       sheetId,
@@ -444,6 +592,136 @@ export class Grid {
     this.transactionResponse(summary);
   }
 
+  mergeCells(sheetId: string, rectangle: Rectangle) {
+    const summary = this.gridController.mergeCells(sheetId, rectangleToRect(rectangle), sheets.getCursorPosition());
+    this.transactionResponse(summary);
+  }
+
+  unmergeCells(sheetId: string, rectangle: Rectangle) {
+    const summary = this.gridController.unmergeCells(sheetId, rectangleToRect(rectangle), sheets.getCursorPosition());
+    this.transactionResponse(summary);
+  }
+
+  setLocale(locale: Locale) {
+    const summary = this.gridController.setLocale(locale, sheets.getCursorPosition());
+    this.transactionResponse(summary);
+  }
+
+  getLocale(): Locale {
+    return JSON.parse(this.gridController.getLocale());
+  }
+
+  addValidation(sheetId: string, validation: Validation) {
+    const summary = this.gridController.addValidation(sheetId, validation, sheets.getCursorPosition());
+    this.transactionResponse(summary);
+  }
+
+  removeValidations(sheetId: string, rectangle: Rectangle) {
+    const summary = this.gridController.removeValidations(
+      sheetId,
+      rectangleToRect(rectangle),
+      sheets.getCursorPosition()
+    );
+    this.transactionResponse(summary);
+  }
+
+  addConditionalFormat(sheetId: string, conditionalFormat: ConditionalFormat) {
+    const summary = this.gridController.addConditionalFormat(sheetId, conditionalFormat, sheets.getCursorPosition());
+    this.transactionResponse(summary);
+  }
+
+  removeConditionalFormats(sheetId: string, rectangle: Rectangle) {
+    const summary = this.gridController.removeConditionalFormats(
+      sheetId,
+      rectangleToRect(rectangle),
+      sheets.getCursorPosition()
+    );
+    this.transactionResponse(summary);
+  }
+
+  setUserId(userId: string | undefined) {
+    this.gridController.setUserId(userId);
+  }
+
+  lockSheet(sheetId: string, editors: string[]) {
+    const summary = this.gridController.lockSheet(sheetId, editors, sheets.getCursorPosition());
+    this.transactionResponse(summary);
+  }
+
+  unlockSheet(sheetId: string) {
+    const summary = this.gridController.unlockSheet(sheetId, sheets.getCursorPosition());
+    this.transactionResponse(summary);
+  }
+
+  addProtectedRange(sheetId: string, rectangle: Rectangle, editors: string[]) {
+    const summary = this.gridController.addProtectedRange(
+      sheetId,
+      rectangleToRect(rectangle),
+      editors,
+      sheets.getCursorPosition()
+    );
+    this.transactionResponse(summary);
+  }
+
+  removeProtectedRanges(sheetId: string, rectangle: Rectangle) {
+    const summary = this.gridController.removeProtectedRanges(
+      sheetId,
+      rectangleToRect(rectangle),
+      sheets.getCursorPosition()
+    );
+    this.transactionResponse(summary);
+  }
+
+  addComment(sheetId: string, x: number, y: number, author: string, text: string) {
+    const summary = this.gridController.addComment(sheetId, new Pos(x, y), author, text, sheets.getCursorPosition());
+    this.transactionResponse(summary);
+  }
+
+  editComment(sheetId: string, x: number, y: number, commentId: string, text: string) {
+    const summary = this.gridController.editComment(
+      sheetId,
+      new Pos(x, y),
+      commentId,
+      text,
+      sheets.getCursorPosition()
+    );
+    this.transactionResponse(summary);
+  }
+
+  deleteComment(sheetId: string, x: number, y: number, commentId: string) {
+    const summary = this.gridController.deleteComment(sheetId, new Pos(x, y), commentId, sheets.getCursorPosition());
+    this.transactionResponse(summary);
+  }
+
+  setCommentResolved(sheetId: string, x: number, y: number, resolved: boolean) {
+    const summary = this.gridController.setCommentResolved(
+      sheetId,
+      new Pos(x, y),
+      resolved,
+      sheets.getCursorPosition()
+    );
+    this.transactionResponse(summary);
+  }
+
+  deleteCommentThreads(sheetId: string, rectangle: Rectangle) {
+    const summary = this.gridController.deleteCommentThreads(
+      sheetId,
+      rectangleToRect(rectangle),
+      sheets.getCursorPosition()
+    );
+    this.transactionResponse(summary);
+  }
+
+  setChart(sheetId: string, x: number, y: number, chart: Chart) {
+    const summary = this.gridController.setChart(sheetId, new Pos(x, y), chart, sheets.getCursorPosition());
+    this.transactionResponse(summary);
+  }
+
+  deleteChart(sheetId: string, x: number, y: number) {
+    const summary = this.gridController.deleteChart(sheetId, new Pos(x, y), sheets.getCursorPosition());
+    this.transactionResponse(summary);
+  }
+
   setCellRenderSize(sheetId: string, x: number, y: number, width: number, height: number) {
     const summary = this.gridController.setCellRenderSize(
       sheetId,
@@ -463,6 +741,12 @@ export class Grid {
     return this.gridController.getEditCell(sheetId, pos);
   }
 
+  // returns the cells opened by a link within the workbook (a url starting with #)
+  getLinkTarget(sheetId: string, url: string): SheetRect | undefined {
+    const target = this.gridController.getLinkTarget(sheetId, url);
+    return target ? JSON.parse(target) : undefined;
+  }
+
   cellHasContent(sheetId: string, column: number, row: number): boolean {
     return this.gridController.hasRenderCells(sheetId, posToRect(column, row));
   }
@@ -486,6 +770,49 @@ export class Grid {
     return JSON.parse(data);
   }
 
+  getMergedCells(sheetId: string): Rect[] {
+    const data = this.gridController.getMergedCells(sheetId);
+    return JSON.parse(data);
+  }
+
+  getValidations(sheetId: string): Validation[] {
+    const data = this.gridController.getValidations(sheetId);
+    return JSON.parse(data);
+  }
+
+  getConditionalFormats(sheetId: string): ConditionalFormat[] {
+    const data = this.gridController.getConditionalFormats(sheetId);
+    return JSON.parse(data);
+  }
+
+  getSheetProtection(sheetId: string): SheetProtection {
+    return JSON.parse(this.gridController.getSheetProtection(sheetId));
+  }
+
+  getCommentThreads(sheetId: string): JsCommentThread[] {
+    const data = this.gridController.getCommentThreads(sheetId);
+    return JSON.parse(data);
+  }
+
+  getCharts(sheetId: string): JsChart[] {
+    const data = this.gridController.getCharts(sheetId);
+    return JSON.parse(data);
+  }
+
+  getChartSvg(sheetId: string, x: number, y: number): string {
+    return this.gridController.getChartSvg(sheetId, new Pos(x, y));
+  }
+
+  // returns the dropdown options for a cell with a list validation
+  getValidationList(sheetId: string, x: number, y: number): string[] | undefined {
+    return this.gridController.getValidationList(sheetId, new Pos(x, y));
+  }
+
+  getInvalidCells(sheetId: string): Coordinate[] {
+    const data = this.gridController.getInvalidCells(sheetId);
+    return JSON.parse(data);
+  }
+
   getCodeCell(sheetId: string, x: number, y: number): JsCodeCell | undefined {
     return this.gridController.getCodeCell(sheetId, new Pos(x, y));
   }
@@ -604,8 +931,8 @@ export class Grid {
 
   //#region Clipboard
 
-  copyToClipboard(sheetId: string, rectangle: Rectangle): JsClipboard {
-    return this.gridController.copyToClipboard(sheetId, rectangleToRect(rectangle));
+  copyToClipboard(sheetId: string, rectangle: Rectangle, skipHidden = false): JsClipboard {
+    return this.gridController.copyToClipboard(sheetId, rectangleToRect(rectangle), skipHidden);
   }
 
   cutToClipboard(sheetId: string, rectangle: Rectangle): { html: string; plainText: string } {
@@ -639,6 +966,18 @@ export class Grid {
     this.transactionResponse(summary);
   }
 
+  moveCells(options: { sheetId: string; rectangle: Rectangle; destSheetId: string; x: number; y: number }) {
+    const { sheetId, rectangle, destSheetId, x, y } = options;
+    const summary = this.gridController.moveCells(
+      sheetId,
+      rectangleToRect(rectangle),
+      destSheetId,
+      new Pos(x, y),
+      sheets.getCursorPosition()
+    );
+    this.transactionResponse(summary);
+  }
+
   //#endregion
 
   //#region Imports
@@ -690,19 +1029,59 @@ export class Grid {
 
   //#region Exports
 
-  exportCsvSelection(): string {
+  exportCsvSelection(options?: CsvExportOptions): string {
     if (!this.gridController) throw new Error('Expected grid to be defined in Grid');
 
     debugTimeReset();
-    const csv = this.gridController.exportCsvSelection(
-      sheets.sheet.id,
-      rectangleToRect(sheets.sheet.cursor.getRectangle())
-    );
+    const sheetId = sheets.sheet.id;
+    const selection = rectangleToRect(sheets.sheet.cursor.getRectangle());
+    const csv = options
+      ? this.gridController.exportCsvSelectionWithOptions(sheetId, selection, options)
+      : this.gridController.exportCsvSelection(sheetId, selection);
     debugTimeCheck(`processing and exporting csv file`);
 
     return csv;
   }
 
+  exportJsonSelection(shape: JsonExportShape, formatted: boolean): string {
+    if (!this.gridController) throw new Error('Expected grid to be defined in Grid');
+
+    debugTimeReset();
+    const json = this.gridController.exportJsonSelection(
+      sheets.sheet.id,
+      rectangleToRect(sheets.sheet.cursor.getRectangle()),
+      shape,
+      formatted
+    );
+    debugTimeCheck(`processing and exporting json`);
+
+    return json;
+  }
+
+  exportMarkdownSelection(formatted: boolean): string {
+    if (!this.gridController) throw new Error('Expected grid to be defined in Grid');
+
+    debugTimeReset();
+    const markdown = this.gridController.exportMarkdownSelection(
+      sheets.sheet.id,
+      rectangleToRect(sheets.sheet.cursor.getRectangle()),
+      formatted
+    );
+    debugTimeCheck(`processing and exporting markdown`);
+
+    return markdown;
+  }
+
+  exportXlsx(): Uint8Array {
+    if (!this.gridController) throw new Error('Expected grid to be defined in Grid');
+
+    debugTimeReset();
+    const xlsx = this.gridController.exportXlsx();
+    debugTimeCheck(`processing and exporting xlsx file`);
+
+    return xlsx;
+  }
+
   //#endregion
 
   //#region column/row sizes
@@ -717,6 +1096,16 @@ export class Grid {
     this.transactionResponse(summary);
   }
 
+  setColumnsHidden(sheetId: string, columns: number[], hidden: boolean) {
+    const summary = this.gridController.setColumnsHidden(sheetId, columns, hidden, sheets.getCursorPosition());
+    this.transactionResponse(summary);
+  }
+
+  setRowsHidden(sheetId: string, rows: number[], hidden: boolean) {
+    const summary = this.gridController.setRowsHidden(sheetId, rows, hidden, sheets.getCursorPosition());
+    this.transactionResponse(summary);
+  }
+
   getOffsets(sheetId: string): SheetOffsets {
     return this.gridController.getOffsets(sheetId);
   }
@@ -789,11 +1178,12 @@ export class Grid {
   //#region Summarize
   //-----------------
 
-  summarizeSelection(decimal_places: number = 9) {
+  summarizeSelection(decimal_places: number = 9, skipHidden = false) {
     return this.gridController.summarizeSelection(
       sheets.sheet.id,
       rectangleToRect(sheets.sheet.cursor.getRectangle()),
-      BigInt(decimal_places)
+      BigInt(decimal_places),
+      skipHidden
     );
   }
 
@@ -841,6 +1231,26 @@ export class Grid {
     return this.gridController.search(text, options);
   }
 
+  // returns the number of replacements and the next match to replace
+  findReplace(
+    text: string,
+    replace: string,
+    options: SearchOptions,
+    replaceAll: boolean,
+    current?: SearchMatch
+  ): { count: number; next: SearchMatch | null } {
+    const { summary, count, next }: JsFindReplace = this.gridController.findReplace(
+      text,
+      replace,
+      options,
+      replaceAll,
+      current,
+      sheets.getCursorPosition()
+    );
+    this.transactionResponse(summary);
+    return { count, next };
+  }
+
   //#endregion
 }
 
@@ -22,6 +22,8 @@ export class Sheet {
   name: string;
   order: string;
   color?: string;
+  frozenRows = 0;
+  frozenColumns = 0;
 
   constructor(index: number | 'test') {
     if (index === 'test') {
@@ -36,6 +38,7 @@ export class Sheet {
       this.name = grid.getSheetName(sheetId) ?? '';
       this.order = grid.getSheetOrder(sheetId);
       this.color = grid.getSheetColor(sheetId);
+      this.updateFrozen();
       this.offsets = grid.getOffsets(this.id);
     }
     this.cursor = new SheetCursor(this);
@@ -45,6 +48,13 @@ export class Sheet {
     this.name = grid.getSheetName(this.id) ?? '';
     this.order = grid.getSheetOrder(this.id);
     this.color = grid.getSheetColor(this.id);
+    this.updateFrozen();
+  }
+
+  private updateFrozen() {
+    const { rows, columns } = grid.getSheetFrozen(this.id);
+    this.frozenRows = rows;
+    this.frozenColumns = columns;
   }
 
   //#region set sheet actions
@@ -200,6 +200,8 @@ export class Multiplayer {
     if (!this.fileId) throw new Error('Expected fileId to be defined in enterFileRoom');
     const user = this.user;
     if (!user?.sub) throw new Error('Expected user to be defined in enterFileRoom');
+    // edits are checked against the file's locked sheets and protected ranges
+    grid.setUserId(user.sub);
     // ensure the user doesn't join a room twice
     const enterRoom: SendEnterRoom = {
       type: 'EnterRoom',
@@ -24,6 +24,7 @@ export function Search() {
     case_sensitive: false,
     whole_cell: false,
     search_code: false,
+    search_comments: false,
     sheet_id: sheets.sheet.id,
   });
   const [results, setResults] = useState<SheetPos[]>([]);
@@ -68,7 +69,9 @@ export function Search() {
     });
   };
 
-  const changeOptions = (option: 'case_sensitive' | 'whole_cell' | 'search_code' | 'sheet') => {
+  const changeOptions = (
+    option: 'case_sensitive' | 'whole_cell' | 'search_code' | 'search_comments' | 'sheet'
+  ) => {
     let updatedSearchOptions: SearchOptions;
     if (option === 'sheet') {
       if (searchOptions.sheet_id) {
@@ -122,6 +125,7 @@ export function Search() {
         case_sensitive: false,
         whole_cell: false,
         search_code: false,
+        search_comments: false,
         sheet_id: sheets.sheet.id,
       });
 
@@ -234,6 +238,12 @@ export function Search() {
               >
                 Search within code
               </DropdownMenuCheckboxItem>
+              <DropdownMenuCheckboxItem
+                checked={searchOptions.search_comments}
+                onCheckedChange={() => changeOptions('search_comments')}
+              >
+                Search within comments
+              </DropdownMenuCheckboxItem>
             </DropdownMenuContent>
           </DropdownMenu>
           <Button
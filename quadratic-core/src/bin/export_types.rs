@@ -1,14 +1,28 @@
 use std::fs::create_dir_all;
 
 use quadratic_core::{
-    controller::transaction_summary::{CellSheetsModified, TransactionSummary},
+    controller::{
+        export::{CsvExportOptions, CsvLineEnding, CsvQuoteStyle},
+        transaction_summary::{CellSheetsModified, TransactionSummary},
+    },
     grid::{
+        file::validate::{ValidationProblem, ValidationReport},
         js_types::{
             JsCodeCell, JsHtmlOutput, JsRenderCell, JsRenderCellSpecial, JsRenderCodeCell,
             JsRenderCodeCellState,
         },
-        sheet::search::SearchOptions,
-        CodeCellLanguage,
+        locale::{CurrencyPosition, DateOrder},
+        sheet::{
+            charts::{Chart, ChartSeries, ChartType},
+            comments::{Comment, CommentThread},
+            conditional_formats::{
+                CompareOperator, ConditionalFormat, ConditionalFormatRule, ConditionalFormatStyle,
+            },
+            protection::{ProtectedRange, SheetProtection},
+            search::{SearchMatch, SearchOptions, SearchValueMode},
+            validations::{Validation, ValidationAction, ValidationCriteria},
+        },
+        CodeCellLanguage, Locale,
     },
     Rect, *,
 };
@@ -43,27 +57,60 @@ fn main() {
         formulas::CellRefCoord,
         grid::GridBounds,
         grid::CellAlign,
+        grid::CellVerticalAlign,
         grid::CellWrap,
         grid::NumericFormat,
         grid::NumericFormatKind,
         grid::BoolSummary,
         grid::SheetId,
+        grid::CommentId,
         grid::js_types::JsRenderCell,
         grid::js_types::JsRenderFill,
         grid::js_types::FormattingSummary,
         grid::js_types::CellFormatSummary,
         grid::js_types::JsClipboard,
+        grid::js_types::JsFindReplace,
+        grid::js_types::JsCommentThread,
+        grid::js_types::JsChart,
         ArraySize,
         Axis,
         Instant,
         Duration,
+        Link,
         RunError,
         RunErrorMsg,
         Pos,
         Rect,
         Span,
+        SearchMatch,
         SearchOptions,
+        SearchValueMode,
         SheetPos,
+        SheetRect,
+        CsvExportOptions,
+        CsvQuoteStyle,
+        CsvLineEnding,
+        Validation,
+        ValidationAction,
+        ValidationCriteria,
+        CompareOperator,
+        ConditionalFormat,
+        ConditionalFormatRule,
+        ConditionalFormatStyle,
+        Comment,
+        CommentThread,
+        ProtectedRange,
+        SheetProtection,
+        Chart,
+        ChartSeries,
+        ChartType,
+        Image,
+        ImageFormat,
+        Locale,
+        CurrencyPosition,
+        DateOrder,
+        ValidationReport,
+        ValidationProblem,
     );
 
     if create_dir_all("../quadratic-client/src/quadratic-core").is_ok() {
@@ -0,0 +1,20 @@
+//! Prints the changes between two .grid files.
+//!
+//! Usage: `cargo run --bin grid_diff -- <old.grid> <new.grid>`
+
+use quadratic_core::grid::file::import_vec;
+
+fn main() {
+    let args = std::env::args().collect::<Vec<_>>();
+    let [_, old, new] = args.as_slice() else {
+        eprintln!("usage: grid_diff <old.grid> <new.grid>");
+        std::process::exit(1);
+    };
+
+    let load = |path: &String| {
+        let contents = std::fs::read(path).expect("failed to read file");
+        import_vec(&contents).expect("failed to import file")
+    };
+
+    print!("{}", load(old).diff(&load(new)).summary());
+}
@@ -27,6 +27,28 @@ impl GridController {
             Some(dependent_cells)
         }
     }
+
+    /// Searches all charts in all sheets for charts that draw cells within the given sheet_rect.
+    pub fn get_dependent_charts(&self, sheet_rect: &SheetRect) -> Option<HashSet<SheetPos>> {
+        let dependent_charts: HashSet<SheetPos> = self
+            .grid
+            .sheets()
+            .iter()
+            .flat_map(|sheet| {
+                sheet
+                    .charts
+                    .iter()
+                    .filter(|(_, chart)| chart.depends_on(sheet_rect))
+                    .map(|(pos, _)| pos.to_sheet_pos(sheet.id))
+            })
+            .collect();
+
+        if dependent_charts.is_empty() {
+            None
+        } else {
+            Some(dependent_charts)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -37,6 +37,7 @@ impl GridController {
         transaction: &mut PendingTransaction,
     ) -> TransactionSummary {
         self.recalculate_sheet_bounds(transaction);
+        self.mark_conditional_formats_modified(transaction);
         if transaction.complete {
             match transaction.transaction_type {
                 TransactionType::User => {
@@ -73,6 +74,13 @@ impl GridController {
         operations: Vec<Operation>,
         cursor: Option<String>,
     ) -> TransactionSummary {
+        if let Err(error) = self.check_protection(&operations) {
+            return TransactionSummary::error(error);
+        }
+        let validation_warning = match self.check_validations(&operations) {
+            Ok(warning) => warning,
+            Err(error) => return TransactionSummary::error(error),
+        };
         let mut transaction = PendingTransaction {
             transaction_type: TransactionType::User,
             operations: operations.into(),
@@ -80,7 +88,9 @@ impl GridController {
             ..Default::default()
         };
         self.start_transaction(&mut transaction);
-        self.finalize_transaction(&mut transaction)
+        let mut summary = self.finalize_transaction(&mut transaction);
+        summary.validation_warning = validation_warning;
+        summary
     }
 
     pub fn start_undo_transaction(
@@ -0,0 +1,33 @@
+use crate::controller::{
+    active_transactions::pending_transaction::PendingTransaction, operations::operation::Operation,
+    GridController,
+};
+
+impl GridController {
+    pub fn execute_set_chart(&mut self, transaction: &mut PendingTransaction, op: Operation) {
+        if let Operation::SetChart { sheet_pos, chart } = op {
+            let Some(sheet) = self.try_sheet_mut(sheet_pos.sheet_id) else {
+                // sheet may have been deleted
+                return;
+            };
+            let old_chart = sheet.set_chart(sheet_pos.into(), chart.clone());
+            transaction
+                .summary
+                .charts_modified
+                .insert(sheet_pos.sheet_id);
+            transaction.summary.html.insert(sheet_pos.sheet_id);
+            transaction.summary.generate_thumbnail |= self.thumbnail_dirty_sheet_pos(sheet_pos);
+
+            transaction
+                .forward_operations
+                .push(Operation::SetChart { sheet_pos, chart });
+            transaction.reverse_operations.insert(
+                0,
+                Operation::SetChart {
+                    sheet_pos,
+                    chart: old_chart,
+                },
+            );
+        }
+    }
+}
@@ -36,6 +36,19 @@ impl GridController {
                     }
                 });
             });
+
+        // charts are drawn from the current values, so they only need redrawing
+        if let Some(charts) = self.get_dependent_charts(output) {
+            for chart_sheet_pos in charts {
+                transaction
+                    .summary
+                    .charts_modified
+                    .insert(chart_sheet_pos.sheet_id);
+                transaction.summary.html.insert(chart_sheet_pos.sheet_id);
+                transaction.summary.generate_thumbnail |=
+                    self.thumbnail_dirty_sheet_pos(chart_sheet_pos);
+            }
+        }
     }
 
     // delete any code runs within the sheet_rect.
@@ -0,0 +1,155 @@
+use crate::controller::{
+    active_transactions::pending_transaction::PendingTransaction, operations::operation::Operation,
+    GridController,
+};
+
+impl GridController {
+    pub fn execute_add_comment(&mut self, transaction: &mut PendingTransaction, op: Operation) {
+        if let Operation::AddComment { sheet_pos, comment } = op {
+            let Some(sheet) = self.try_sheet_mut(sheet_pos.sheet_id) else {
+                // sheet may have been deleted
+                return;
+            };
+            let comment_id = comment.id;
+            if !sheet.add_comment(sheet_pos.into(), comment.clone()) {
+                return;
+            }
+            transaction
+                .summary
+                .comments_modified
+                .insert(sheet_pos.sheet_id);
+
+            transaction
+                .forward_operations
+                .push(Operation::AddComment { sheet_pos, comment });
+            transaction.reverse_operations.insert(
+                0,
+                Operation::DeleteComment {
+                    sheet_pos,
+                    comment_id,
+                },
+            );
+        }
+    }
+
+    pub fn execute_edit_comment(&mut self, transaction: &mut PendingTransaction, op: Operation) {
+        if let Operation::EditComment {
+            sheet_pos,
+            comment_id,
+            text,
+        } = op
+        {
+            let Some(sheet) = self.try_sheet_mut(sheet_pos.sheet_id) else {
+                // sheet may have been deleted
+                return;
+            };
+            // the comment may have been deleted by another user
+            let Some(old_text) = sheet.edit_comment(sheet_pos.into(), comment_id, text.clone())
+            else {
+                return;
+            };
+            transaction
+                .summary
+                .comments_modified
+                .insert(sheet_pos.sheet_id);
+
+            transaction.forward_operations.push(Operation::EditComment {
+                sheet_pos,
+                comment_id,
+                text,
+            });
+            transaction.reverse_operations.insert(
+                0,
+                Operation::EditComment {
+                    sheet_pos,
+                    comment_id,
+                    text: old_text,
+                },
+            );
+        }
+    }
+
+    pub fn execute_delete_comment(&mut self, transaction: &mut PendingTransaction, op: Operation) {
+        if let Operation::DeleteComment {
+            sheet_pos,
+            comment_id,
+        } = op
+        {
+            let Some(sheet) = self.try_sheet_mut(sheet_pos.sheet_id) else {
+                // sheet may have been deleted
+                return;
+            };
+            let resolved = sheet
+                .comment_thread(sheet_pos.into())
+                .is_some_and(|thread| thread.resolved);
+            let Some(comment) = sheet.delete_comment(sheet_pos.into(), comment_id) else {
+                return;
+            };
+            let thread_removed = sheet.comment_thread(sheet_pos.into()).is_none();
+            transaction
+                .summary
+                .comments_modified
+                .insert(sheet_pos.sheet_id);
+
+            transaction
+                .forward_operations
+                .push(Operation::DeleteComment {
+                    sheet_pos,
+                    comment_id,
+                });
+
+            // restoring the last comment of a resolved thread also restores
+            // its resolved state
+            if thread_removed && resolved {
+                transaction.reverse_operations.insert(
+                    0,
+                    Operation::SetCommentResolved {
+                        sheet_pos,
+                        resolved,
+                    },
+                );
+            }
+            transaction
+                .reverse_operations
+                .insert(0, Operation::AddComment { sheet_pos, comment });
+        }
+    }
+
+    pub fn execute_set_comment_resolved(
+        &mut self,
+        transaction: &mut PendingTransaction,
+        op: Operation,
+    ) {
+        if let Operation::SetCommentResolved {
+            sheet_pos,
+            resolved,
+        } = op
+        {
+            let Some(sheet) = self.try_sheet_mut(sheet_pos.sheet_id) else {
+                // sheet may have been deleted
+                return;
+            };
+            let Some(old_resolved) = sheet.set_comment_resolved(sheet_pos.into(), resolved) else {
+                return;
+            };
+            transaction
+                .summary
+                .comments_modified
+                .insert(sheet_pos.sheet_id);
+
+            transaction
+                .forward_operations
+                .push(Operation::SetCommentResolved {
+                    sheet_pos,
+                    resolved,
+                });
+            transaction.reverse_operations.insert(
+                0,
+                Operation::SetCommentResolved {
+                    sheet_pos,
+                    resolved: old_resolved,
+                },
+            );
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use crate::controller::{
+    active_transactions::pending_transaction::PendingTransaction, operations::operation::Operation,
+    GridController,
+};
+
+impl GridController {
+    pub fn execute_set_conditional_formats(
+        &mut self,
+        transaction: &mut PendingTransaction,
+        op: Operation,
+    ) {
+        if let Operation::SetConditionalFormats {
+            sheet_id,
+            conditional_formats,
+        } = op
+        {
+            let Some(sheet) = self.try_sheet_mut(sheet_id) else {
+                // sheet may have been deleted
+                return;
+            };
+            let old_conditional_formats =
+                std::mem::replace(&mut sheet.conditional_formats, conditional_formats.clone());
+
+            // both the old and new formats need to be re-rendered
+            let rects: Vec<_> = old_conditional_formats
+                .iter()
+                .chain(conditional_formats.iter())
+                .filter_map(|conditional_format| conditional_format.bounded_rect(sheet))
+                .collect();
+            for rect in rects {
+                transaction
+                    .summary
+                    .add_cell_sheets_modified_rect(&rect.to_sheet_rect(sheet_id));
+            }
+            transaction
+                .summary
+                .conditional_formats_modified
+                .insert(sheet_id);
+            transaction.summary.fill_sheets_modified.insert(sheet_id);
+
+            transaction
+                .forward_operations
+                .push(Operation::SetConditionalFormats {
+                    sheet_id,
+                    conditional_formats,
+                });
+            transaction.reverse_operations.insert(
+                0,
+                Operation::SetConditionalFormats {
+                    sheet_id,
+                    conditional_formats: old_conditional_formats,
+                },
+            );
+        }
+    }
+}
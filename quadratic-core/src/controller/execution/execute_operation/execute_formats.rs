@@ -45,6 +45,15 @@ impl GridController {
                 CellFmtArray::Wrap(wrap) => CellFmtArray::Wrap(
                     self.set_cell_formats_for_type::<CellWrap>(&sheet_rect, wrap),
                 ),
+                CellFmtArray::VerticalAlign(vertical_align) => CellFmtArray::VerticalAlign(
+                    self.set_cell_formats_for_type::<CellVerticalAlign>(
+                        &sheet_rect,
+                        vertical_align,
+                    ),
+                ),
+                CellFmtArray::TextRotation(text_rotation) => CellFmtArray::TextRotation(
+                    self.set_cell_formats_for_type::<TextRotation>(&sheet_rect, text_rotation),
+                ),
                 CellFmtArray::NumericFormat(num_fmt) => CellFmtArray::NumericFormat(
                     self.set_cell_formats_for_type::<NumericFormat>(&sheet_rect, num_fmt),
                 ),
@@ -60,6 +69,18 @@ impl GridController {
                 CellFmtArray::Italic(italic) => CellFmtArray::Italic(
                     self.set_cell_formats_for_type::<Italic>(&sheet_rect, italic),
                 ),
+                CellFmtArray::Underline(underline) => CellFmtArray::Underline(
+                    self.set_cell_formats_for_type::<Underline>(&sheet_rect, underline),
+                ),
+                CellFmtArray::StrikeThrough(strike_through) => CellFmtArray::StrikeThrough(
+                    self.set_cell_formats_for_type::<StrikeThrough>(&sheet_rect, strike_through),
+                ),
+                CellFmtArray::FontSize(font_size) => CellFmtArray::FontSize(
+                    self.set_cell_formats_for_type::<FontSize>(&sheet_rect, font_size),
+                ),
+                CellFmtArray::FontFamily(font_family) => CellFmtArray::FontFamily(
+                    self.set_cell_formats_for_type::<FontFamily>(&sheet_rect, font_family),
+                ),
                 CellFmtArray::TextColor(text_color) => CellFmtArray::TextColor(
                     self.set_cell_formats_for_type::<TextColor>(&sheet_rect, text_color),
                 ),
@@ -0,0 +1,34 @@
+use crate::{
+    controller::{
+        active_transactions::pending_transaction::PendingTransaction,
+        operations::operation::Operation, GridController,
+    },
+    grid::GridBounds,
+};
+
+impl GridController {
+    pub fn execute_set_locale(&mut self, transaction: &mut PendingTransaction, op: Operation) {
+        if let Operation::SetLocale { locale } = op {
+            let old_locale = *self.grid.locale();
+            self.grid.set_locale(locale);
+            transaction.summary.locale_modified = true;
+            transaction.summary.generate_thumbnail = true;
+
+            // every number and date may display differently
+            for sheet in self.grid.sheets() {
+                if let GridBounds::NonEmpty(rect) = sheet.bounds(true) {
+                    transaction
+                        .summary
+                        .add_cell_sheets_modified_rect(&rect.to_sheet_rect(sheet.id));
+                }
+            }
+
+            transaction
+                .forward_operations
+                .push(Operation::SetLocale { locale });
+            transaction
+                .reverse_operations
+                .insert(0, Operation::SetLocale { locale: old_locale });
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use crate::{
+    controller::{
+        active_transactions::pending_transaction::PendingTransaction,
+        operations::operation::Operation, GridController,
+    },
+    Rect, SheetRect,
+};
+
+impl GridController {
+    /// Updates the summary for a change to merged cells in the given regions.
+    fn merged_cells_changed(
+        &self,
+        transaction: &mut PendingTransaction,
+        sheet_rect: SheetRect,
+        removed: &[Rect],
+    ) {
+        let sheet_id = sheet_rect.sheet_id;
+        transaction.summary.merged_cells_modified.insert(sheet_id);
+        transaction.summary.border_sheets_modified.insert(sheet_id);
+        transaction.summary.fill_sheets_modified.insert(sheet_id);
+        transaction
+            .summary
+            .add_cell_sheets_modified_rect(&sheet_rect);
+        for rect in removed {
+            transaction
+                .summary
+                .add_cell_sheets_modified_rect(&rect.to_sheet_rect(sheet_id));
+        }
+        transaction.summary.generate_thumbnail |= self.thumbnail_dirty_sheet_rect(&sheet_rect);
+    }
+
+    pub fn execute_merge_cells(&mut self, transaction: &mut PendingTransaction, op: Operation) {
+        if let Operation::MergeCells { sheet_rect } = op {
+            let Some(sheet) = self.try_sheet_mut(sheet_rect.sheet_id) else {
+                // sheet may have been deleted
+                return;
+            };
+            let removed = sheet.merge_cells(sheet_rect.into());
+            self.merged_cells_changed(transaction, sheet_rect, &removed);
+
+            transaction
+                .forward_operations
+                .push(Operation::MergeCells { sheet_rect });
+            let mut reverse_operations = vec![Operation::UnmergeCells { sheet_rect }];
+            reverse_operations.extend(removed.iter().map(|rect| Operation::MergeCells {
+                sheet_rect: rect.to_sheet_rect(sheet_rect.sheet_id),
+            }));
+            transaction
+                .reverse_operations
+                .splice(0..0, reverse_operations);
+
+            // code output may now spill into (or out of) a merged region
+            if transaction.is_user() {
+                self.check_all_spills(transaction, sheet_rect.sheet_id);
+            }
+        }
+    }
+
+    pub fn execute_unmerge_cells(&mut self, transaction: &mut PendingTransaction, op: Operation) {
+        if let Operation::UnmergeCells { sheet_rect } = op {
+            let Some(sheet) = self.try_sheet_mut(sheet_rect.sheet_id) else {
+                // sheet may have been deleted
+                return;
+            };
+            let removed = sheet.unmerge_cells(sheet_rect.into());
+            self.merged_cells_changed(transaction, sheet_rect, &removed);
+
+            transaction
+                .forward_operations
+                .push(Operation::UnmergeCells { sheet_rect });
+            let reverse_operations = removed.iter().map(|rect| Operation::MergeCells {
+                sheet_rect: rect.to_sheet_rect(sheet_rect.sheet_id),
+            });
+            transaction
+                .reverse_operations
+                .splice(0..0, reverse_operations);
+
+            // code output may now spill into (or out of) a merged region
+            if transaction.is_user() {
+                self.check_all_spills(transaction, sheet_rect.sheet_id);
+            }
+        }
+    }
+}
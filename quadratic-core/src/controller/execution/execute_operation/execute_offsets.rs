@@ -3,7 +3,7 @@ use crate::{
         active_transactions::pending_transaction::PendingTransaction,
         operations::operation::Operation, GridController,
     },
-    SheetPos,
+    Pos, SheetPos, SheetRect,
 };
 
 impl GridController {
@@ -83,6 +83,112 @@ impl GridController {
             );
         }
     }
+
+    pub fn execute_set_column_hidden(
+        &mut self,
+        transaction: &mut PendingTransaction,
+        op: Operation,
+    ) {
+        if let Operation::SetColumnHidden {
+            sheet_id,
+            column,
+            hidden,
+        } = op
+        {
+            let Some(sheet) = self.try_sheet_mut(sheet_id) else {
+                // sheet may have been deleted
+                return;
+            };
+            transaction
+                .forward_operations
+                .push(Operation::SetColumnHidden {
+                    sheet_id,
+                    column,
+                    hidden,
+                });
+            let old_hidden = sheet.offsets.set_column_hidden(column, hidden);
+            transaction.summary.offsets_modified.insert(sheet.id);
+            transaction.summary.fill_sheets_modified.insert(sheet.id);
+
+            // cells in the column are not rendered while it is hidden
+            if let Some((min, max)) = sheet.column_bounds(column, true) {
+                transaction
+                    .summary
+                    .add_cell_sheets_modified_rect(&SheetRect::new_pos_span(
+                        Pos { x: column, y: min },
+                        Pos { x: column, y: max },
+                        sheet_id,
+                    ));
+            }
+            if transaction.is_user_undo_redo() {
+                transaction.summary.generate_thumbnail |=
+                    self.thumbnail_dirty_sheet_pos(SheetPos {
+                        x: column,
+                        y: 0,
+                        sheet_id,
+                    });
+            }
+            transaction.reverse_operations.insert(
+                0,
+                Operation::SetColumnHidden {
+                    sheet_id,
+                    column,
+                    hidden: old_hidden,
+                },
+            );
+        }
+    }
+
+    pub fn execute_set_row_hidden(&mut self, transaction: &mut PendingTransaction, op: Operation) {
+        if let Operation::SetRowHidden {
+            sheet_id,
+            row,
+            hidden,
+        } = op
+        {
+            let Some(sheet) = self.try_sheet_mut(sheet_id) else {
+                // sheet may have been deleted
+                return;
+            };
+            transaction
+                .forward_operations
+                .push(Operation::SetRowHidden {
+                    sheet_id,
+                    row,
+                    hidden,
+                });
+            let old_hidden = sheet.offsets.set_row_hidden(row, hidden);
+            transaction.summary.offsets_modified.insert(sheet.id);
+            transaction.summary.fill_sheets_modified.insert(sheet.id);
+
+            // cells in the row are not rendered while it is hidden
+            if let Some((min, max)) = sheet.row_bounds(row, true) {
+                transaction
+                    .summary
+                    .add_cell_sheets_modified_rect(&SheetRect::new_pos_span(
+                        Pos { x: min, y: row },
+                        Pos { x: max, y: row },
+                        sheet_id,
+                    ));
+            }
+            if transaction.is_user_undo_redo() {
+                transaction.summary.generate_thumbnail |=
+                    self.thumbnail_dirty_sheet_pos(SheetPos {
+                        x: 0,
+                        y: row,
+                        sheet_id,
+                    });
+            }
+            transaction.reverse_operations.insert(
+                0,
+                Operation::SetRowHidden {
+                    sheet_id,
+                    row,
+                    hidden: old_hidden,
+                },
+            );
+        }
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,39 @@
+use crate::controller::{
+    active_transactions::pending_transaction::PendingTransaction, operations::operation::Operation,
+    GridController,
+};
+
+impl GridController {
+    pub fn execute_set_sheet_protection(
+        &mut self,
+        transaction: &mut PendingTransaction,
+        op: Operation,
+    ) {
+        if let Operation::SetSheetProtection {
+            sheet_id,
+            protection,
+        } = op
+        {
+            let Some(sheet) = self.try_sheet_mut(sheet_id) else {
+                // sheet may have been deleted
+                return;
+            };
+            let old_protection = std::mem::replace(&mut sheet.protection, protection.clone());
+            transaction.summary.protections_modified.insert(sheet_id);
+
+            transaction
+                .forward_operations
+                .push(Operation::SetSheetProtection {
+                    sheet_id,
+                    protection,
+                });
+            transaction.reverse_operations.insert(
+                0,
+                Operation::SetSheetProtection {
+                    sheet_id,
+                    protection: old_protection,
+                },
+            );
+        }
+    }
+}
@@ -167,6 +167,42 @@ impl GridController {
         }
     }
 
+    pub(crate) fn execute_set_sheet_frozen(
+        &mut self,
+        transaction: &mut PendingTransaction,
+        op: Operation,
+    ) {
+        if let Operation::SetSheetFrozen {
+            sheet_id,
+            rows,
+            columns,
+        } = op
+        {
+            let Some(sheet) = self.try_sheet_mut(sheet_id) else {
+                // sheet may have been deleted
+                return;
+            };
+            let old_rows = std::mem::replace(&mut sheet.frozen_rows, rows);
+            let old_columns = std::mem::replace(&mut sheet.frozen_columns, columns);
+            transaction.summary.sheet_list_modified = true;
+            transaction
+                .forward_operations
+                .push(Operation::SetSheetFrozen {
+                    sheet_id,
+                    rows,
+                    columns,
+                });
+            transaction.reverse_operations.insert(
+                0,
+                Operation::SetSheetFrozen {
+                    sheet_id,
+                    rows: old_rows,
+                    columns: old_columns,
+                },
+            );
+        }
+    }
+
     pub(crate) fn execute_duplicate_sheet(
         &mut self,
         transaction: &mut PendingTransaction,
@@ -277,6 +313,21 @@ mod tests {
         assert!(summary.sheet_list_modified);
     }
 
+    #[test]
+    fn test_set_sheet_frozen() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let summary = gc.set_sheet_frozen(sheet_id, 1, 2, None);
+        assert_eq!(gc.grid.sheets()[0].frozen_rows, 1);
+        assert_eq!(gc.grid.sheets()[0].frozen_columns, 2);
+        assert!(summary.save);
+        assert!(summary.sheet_list_modified);
+        let summary = gc.undo(None);
+        assert_eq!(gc.grid.sheets()[0].frozen_rows, 0);
+        assert_eq!(gc.grid.sheets()[0].frozen_columns, 0);
+        assert!(summary.sheet_list_modified);
+    }
+
     #[test]
     fn test_sheet_reorder() {
         let mut gc = GridController::test();
@@ -0,0 +1,35 @@
+use crate::controller::{
+    active_transactions::pending_transaction::PendingTransaction, operations::operation::Operation,
+    GridController,
+};
+
+impl GridController {
+    pub fn execute_set_validations(&mut self, transaction: &mut PendingTransaction, op: Operation) {
+        if let Operation::SetValidations {
+            sheet_id,
+            validations,
+        } = op
+        {
+            let Some(sheet) = self.try_sheet_mut(sheet_id) else {
+                // sheet may have been deleted
+                return;
+            };
+            let old_validations = std::mem::replace(&mut sheet.validations, validations.clone());
+            transaction.summary.validations_modified.insert(sheet_id);
+
+            transaction
+                .forward_operations
+                .push(Operation::SetValidations {
+                    sheet_id,
+                    validations,
+                });
+            transaction.reverse_operations.insert(
+                0,
+                Operation::SetValidations {
+                    sheet_id,
+                    validations: old_validations,
+                },
+            );
+        }
+    }
+}
@@ -3,11 +3,18 @@ use crate::controller::operations::operation::Operation;
 use crate::controller::GridController;
 
 pub mod execute_borders;
+pub mod execute_charts;
 pub mod execute_code;
+pub mod execute_comments;
+pub mod execute_conditional_formats;
 pub mod execute_cursor;
 pub mod execute_formats;
+pub mod execute_locale;
+pub mod execute_merge;
 pub mod execute_offsets;
+pub mod execute_protection;
 pub mod execute_sheets;
+pub mod execute_validations;
 pub mod execute_values;
 
 impl GridController {
@@ -24,16 +31,39 @@ impl GridController {
                 Operation::ComputeCode { .. } => self.execute_compute_code(transaction, op),
                 Operation::SetCellFormats { .. } => self.execute_set_cell_formats(transaction, op),
                 Operation::SetBorders { .. } => self.execute_set_borders(transaction, op),
+                Operation::MergeCells { .. } => self.execute_merge_cells(transaction, op),
+                Operation::UnmergeCells { .. } => self.execute_unmerge_cells(transaction, op),
+                Operation::SetValidations { .. } => self.execute_set_validations(transaction, op),
+                Operation::SetConditionalFormats { .. } => {
+                    self.execute_set_conditional_formats(transaction, op);
+                }
+                Operation::AddComment { .. } => self.execute_add_comment(transaction, op),
+                Operation::EditComment { .. } => self.execute_edit_comment(transaction, op),
+                Operation::DeleteComment { .. } => self.execute_delete_comment(transaction, op),
+                Operation::SetCommentResolved { .. } => {
+                    self.execute_set_comment_resolved(transaction, op);
+                }
+                Operation::SetSheetProtection { .. } => {
+                    self.execute_set_sheet_protection(transaction, op);
+                }
+                Operation::SetChart { .. } => self.execute_set_chart(transaction, op),
+
+                Operation::SetLocale { .. } => self.execute_set_locale(transaction, op),
 
                 Operation::AddSheet { .. } => self.execute_add_sheet(transaction, op),
                 Operation::DeleteSheet { .. } => self.execute_delete_sheet(transaction, op),
                 Operation::ReorderSheet { .. } => self.execute_reorder_sheet(transaction, op),
                 Operation::SetSheetName { .. } => self.execute_set_sheet_name(transaction, op),
                 Operation::SetSheetColor { .. } => self.execute_set_sheet_color(transaction, op),
+                Operation::SetSheetFrozen { .. } => self.execute_set_sheet_frozen(transaction, op),
                 Operation::DuplicateSheet { .. } => self.execute_duplicate_sheet(transaction, op),
 
                 Operation::ResizeColumn { .. } => self.execute_resize_column(transaction, op),
                 Operation::ResizeRow { .. } => self.execute_resize_row(transaction, op),
+                Operation::SetColumnHidden { .. } => {
+                    self.execute_set_column_hidden(transaction, op);
+                }
+                Operation::SetRowHidden { .. } => self.execute_set_row_hidden(transaction, op),
 
                 Operation::SetCursor { .. } => self.execute_set_cursor(transaction, op),
             }
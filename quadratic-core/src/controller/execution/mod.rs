@@ -5,7 +5,9 @@ pub mod run_code;
 pub mod spills;
 
 use super::active_transactions::pending_transaction::PendingTransaction;
-use crate::controller::GridController;
+use crate::controller::{transaction_summary::CellSheetsModified, GridController};
+use crate::grid::sheet::conditional_formats::ConditionalFormatRule;
+use crate::SheetRect;
 use serde::{Deserialize, Serialize};
 
 #[derive(Default, Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -31,6 +33,52 @@ impl GridController {
             });
     }
 
+    /// Conditional formats whose matches depend on other cells (e.g., top N or
+    /// custom formulas) are re-rendered when any of their cells change.
+    /// Custom formulas are also re-rendered when the cells they reference
+    /// change, which may be on other sheets.
+    pub fn mark_conditional_formats_modified(&self, transaction: &mut PendingTransaction) {
+        let modified: Vec<SheetRect> = transaction
+            .summary
+            .cell_sheets_modified
+            .iter()
+            .filter_map(CellSheetsModified::sheet_rect)
+            .collect();
+        if modified.is_empty() {
+            return;
+        }
+        let is_modified = |rect: SheetRect| modified.iter().any(|m| m.intersects(rect));
+        for sheet in self.grid.sheets() {
+            let mut fills_modified = false;
+            for conditional_format in &sheet.conditional_formats {
+                let rect_modified = is_modified(conditional_format.rect.to_sheet_rect(sheet.id));
+                fills_modified |= rect_modified;
+                let is_dirty = match conditional_format.rule {
+                    ConditionalFormatRule::Custom { .. } if !rect_modified => {
+                        match conditional_format.custom_references(&self.grid, sheet) {
+                            Some(references) => references.into_iter().any(is_modified),
+                            None => true,
+                        }
+                    }
+                    ConditionalFormatRule::Custom { .. } => true,
+                    _ => rect_modified && conditional_format.depends_on_other_cells(),
+                };
+                if !is_dirty {
+                    continue;
+                }
+                if let Some(rect) = conditional_format.bounded_rect(sheet) {
+                    transaction
+                        .summary
+                        .add_cell_sheets_modified_rect(&rect.to_sheet_rect(sheet.id));
+                    fills_modified = true;
+                }
+            }
+            if fills_modified {
+                transaction.summary.fill_sheets_modified.insert(sheet.id);
+            }
+        }
+    }
+
     /// Sets the last_sequence_num for multiplayer. This should only be called when receiving the sequence_num.
     pub fn set_last_sequence_num(&mut self, last_sequence_num: u64) {
         self.transactions.last_sequence_num = last_sequence_num;
@@ -56,6 +56,8 @@ impl GridController {
                 // then do the more expensive checks to see if there is a spill error
                 if sheet.has_cell_value_in_rect(&output, Some(*pos))
                     || sheet.has_code_cell_in_rect(&output, *pos)
+                    || sheet.has_merged_cells_in_rect(output)
+                    || sheet.protection.blocks_output(*pos, output)
                 {
                     // if spill error has not been set, then set it and start the more expensive checks for all later code_cells.
                     if !code_run.spill_error {
@@ -109,7 +111,14 @@ mod tests {
             wrap: None,
             bold: None,
             italic: None,
+            underline: None,
+            strike_through: None,
             text_color: None,
+            font_size: None,
+            font_family: None,
+            vertical_align: None,
+            text_rotation: None,
+            link: None,
             special: Some(JsRenderCellSpecial::SpillError),
         }]
     }
@@ -129,7 +138,14 @@ mod tests {
             wrap: None,
             bold: None,
             italic: None,
+            underline: None,
+            strike_through: None,
             text_color: None,
+            font_size: None,
+            font_family: None,
+            vertical_align: None,
+            text_rotation: None,
+            link: None,
             special: None,
         }]
     }
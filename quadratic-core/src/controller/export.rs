@@ -1,42 +1,625 @@
-use anyhow::Result;
-use csv::Writer;
+use std::collections::HashMap;
+use std::io::{Cursor, Write};
+
+use anyhow::{anyhow, bail, Result};
+use bigdecimal::ToPrimitive;
+use csv::{QuoteStyle, Terminator, WriterBuilder};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
 
 use super::GridController;
-use crate::{grid::SheetId, Rect};
+use crate::{
+    grid::{GridBounds, Sheet, SheetId},
+    util::{column_name, escape_xml},
+    CellValue, Image, ImageFormat, Link, Pos, Rect,
+};
+
+const XLSX_MAIN_NAMESPACE: &str = "http://schemas.openxmlformats.org/spreadsheetml/2006/main";
+const XLSX_RELATIONSHIPS: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+const XLSX_PACKAGE_RELATIONSHIPS: &str =
+    "http://schemas.openxmlformats.org/package/2006/relationships";
+const XLSX_DRAWING_NAMESPACE: &str =
+    "http://schemas.openxmlformats.org/drawingml/2006/spreadsheetDrawing";
+const XLSX_DRAWINGML_NAMESPACE: &str = "http://schemas.openxmlformats.org/drawingml/2006/main";
+
+/// Size of a pixel in English Metric Units, which XLSX drawings use.
+const EMU_PER_PIXEL: u64 = 9525;
+
+/// Shape of the JSON created by [`GridController::export_json_selection`].
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub enum JsonExportShape {
+    /// An array of objects keyed by the values in the first row of the selection.
+    #[default]
+    Objects,
+
+    /// An array of rows, where each row is an array of values.
+    Arrays,
+}
+
+/// Quoting style used by the CSV exports.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub enum CsvQuoteStyle {
+    /// Quote fields only when they contain a delimiter, quote, or newline.
+    #[default]
+    Necessary,
+
+    /// Quote every field.
+    Always,
+
+    /// Quote every field that is not a number.
+    NonNumeric,
+
+    /// Never quote fields.
+    Never,
+}
+
+/// Line ending used by the CSV exports.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub enum CsvLineEnding {
+    #[default]
+    Lf,
+    CrLf,
+}
+
+/// Options for the CSV exports. Missing options use their defaults: raw
+/// values, the locale's delimiter (a comma unless the locale uses a decimal
+/// comma), necessary quoting, and `\n` line endings.
+#[derive(Default, Debug, Serialize, Deserialize, PartialEq, Clone, Eq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub struct CsvExportOptions {
+    /// Export the formatted display values (numeric format, decimals, and
+    /// commas) instead of the raw values.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formatted: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delimiter: Option<char>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quote_style: Option<CsvQuoteStyle>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_ending: Option<CsvLineEnding>,
+}
 
 impl GridController {
     /// exports a CSV string from a selection on the grid.
     ///
     /// Returns a [`String`].
-    pub fn export_csv_selection(&self, sheet_id: SheetId, selection: &Rect) -> Result<String> {
-        // todo: handle this better
-        let Some(sheet) = self.try_sheet(sheet_id) else {
-            return Ok("".to_string());
+    pub fn export_csv_selection(
+        &self,
+        sheet_id: SheetId,
+        selection: &Rect,
+        options: CsvExportOptions,
+    ) -> Result<String> {
+        let sheet = self
+            .try_sheet(sheet_id)
+            .ok_or_else(|| anyhow!("Sheet not found: {sheet_id}"))?;
+        Self::write_csv(sheet, selection, &options)
+    }
+
+    /// exports a CSV string of all cells in a sheet (ignoring formatting-only
+    /// cells). An empty sheet returns an empty string.
+    ///
+    /// Returns a [`String`].
+    pub fn export_csv_sheet(&self, sheet_id: SheetId, options: CsvExportOptions) -> Result<String> {
+        let sheet = self
+            .try_sheet(sheet_id)
+            .ok_or_else(|| anyhow!("Sheet not found: {sheet_id}"))?;
+        match sheet.bounds(true) {
+            GridBounds::Empty => Ok("".to_string()),
+            GridBounds::NonEmpty(bounds) => Self::write_csv(sheet, &bounds, &options),
+        }
+    }
+
+    /// Writes the values in a rect to a CSV string.
+    fn write_csv(sheet: &Sheet, selection: &Rect, options: &CsvExportOptions) -> Result<String> {
+        let delimiter = options
+            .delimiter
+            .unwrap_or_else(|| sheet.locale.csv_delimiter());
+        if !delimiter.is_ascii() {
+            bail!("CSV delimiter must be an ASCII character, got {delimiter:?}");
+        }
+        let quote_style = match options.quote_style.unwrap_or_default() {
+            CsvQuoteStyle::Necessary => QuoteStyle::Necessary,
+            CsvQuoteStyle::Always => QuoteStyle::Always,
+            CsvQuoteStyle::NonNumeric => QuoteStyle::NonNumeric,
+            CsvQuoteStyle::Never => QuoteStyle::Never,
         };
-        let values = sheet
-            .cell_values_in_rect(selection, false)?
-            .into_cell_values_vec()
-            .iter()
-            .map(|record| record.to_string())
-            .collect::<Vec<String>>();
-        let width = selection.width() as usize;
-        let mut writer = Writer::from_writer(vec![]);
+        let terminator = match options.line_ending.unwrap_or_default() {
+            CsvLineEnding::Lf => Terminator::Any(b'\n'),
+            CsvLineEnding::CrLf => Terminator::CRLF,
+        };
+        let formatted = options.formatted.unwrap_or(false);
+        let mut writer = WriterBuilder::new()
+            .delimiter(delimiter as u8)
+            .quote_style(quote_style)
+            .terminator(terminator)
+            .from_writer(vec![]);
 
-        values.chunks(width).for_each(|row| {
-            writer.write_record(row).unwrap_or_default();
-        });
+        for y in selection.y_range() {
+            let row = selection
+                .x_range()
+                .map(|x| Self::export_csv_value(sheet, Pos { x, y }, formatted));
+            writer.write_record(row)?;
+        }
 
         let output = String::from_utf8(writer.into_inner()?)?;
 
         Ok(output)
     }
+
+    /// exports a JSON string from a selection on the grid.
+    ///
+    /// When `shape` is [`JsonExportShape::Objects`], the first row of the
+    /// selection is used as the keys for the remaining rows. When `formatted`
+    /// is true, numbers are exported as strings using their numeric formatting.
+    ///
+    /// Returns a [`String`].
+    pub fn export_json_selection(
+        &self,
+        sheet_id: SheetId,
+        selection: &Rect,
+        shape: JsonExportShape,
+        formatted: bool,
+    ) -> Result<String> {
+        let sheet = self
+            .try_sheet(sheet_id)
+            .ok_or_else(|| anyhow!("Sheet not found: {sheet_id}"))?;
+        let rows = selection
+            .y_range()
+            .map(|y| {
+                selection
+                    .x_range()
+                    .map(|x| Self::export_json_value(sheet, Pos { x, y }, formatted))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let output = match shape {
+            JsonExportShape::Arrays => serde_json::to_string(&rows)?,
+            JsonExportShape::Objects => {
+                let mut rows = rows.into_iter();
+                let headers =
+                    Self::export_headers(sheet, selection, rows.next().unwrap_or_default().len());
+                let objects = rows
+                    .map(|row| {
+                        headers
+                            .iter()
+                            .cloned()
+                            .zip(row)
+                            .collect::<IndexMap<String, serde_json::Value>>()
+                    })
+                    .collect::<Vec<_>>();
+                serde_json::to_string(&objects)?
+            }
+        };
+
+        Ok(output)
+    }
+
+    /// exports a GitHub-flavored Markdown table from a selection on the grid.
+    /// The first row of the selection is used as the table's header. Columns
+    /// that only contain numbers are right-aligned.
+    ///
+    /// Returns a [`String`].
+    pub fn export_markdown_selection(
+        &self,
+        sheet_id: SheetId,
+        selection: &Rect,
+        formatted: bool,
+    ) -> Result<String> {
+        let sheet = self
+            .try_sheet(sheet_id)
+            .ok_or_else(|| anyhow!("Sheet not found: {sheet_id}"))?;
+        let width = selection.width() as usize;
+        let headers = Self::export_headers(sheet, selection, width);
+        let body = (selection.min.y + 1..=selection.max.y)
+            .map(|y| {
+                selection
+                    .x_range()
+                    .map(|x| Self::export_string_value(sheet, Pos { x, y }, formatted))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut output = String::new();
+        Self::push_markdown_row(&mut output, &headers);
+
+        let alignment = selection
+            .x_range()
+            .map(|x| {
+                let mut values = (selection.min.y + 1..=selection.max.y)
+                    .filter_map(|y| sheet.display_value(Pos { x, y }))
+                    .filter(|value| !value.is_blank_or_empty_string())
+                    .peekable();
+                let numeric = values.peek().is_some()
+                    && values.all(|value| matches!(value, CellValue::Number(_)));
+                if numeric { "---:" } else { "---" }.to_string()
+            })
+            .collect::<Vec<_>>();
+        Self::push_markdown_row(&mut output, &alignment);
+
+        body.iter()
+            .for_each(|row| Self::push_markdown_row(&mut output, row));
+
+        Ok(output)
+    }
+
+    /// Returns the value at a position as a string, optionally applying its numeric formatting.
+    fn export_string_value(sheet: &Sheet, pos: Pos, formatted: bool) -> String {
+        if formatted {
+            sheet.formatted_display_value(pos).unwrap_or_default()
+        } else {
+            sheet
+                .display_value(pos)
+                .map(|value| value.to_string())
+                .unwrap_or_default()
+        }
+    }
+
+    /// Returns the value at a position as a CSV field. Numbers use the
+    /// locale's decimal separator.
+    fn export_csv_value(sheet: &Sheet, pos: Pos, formatted: bool) -> String {
+        match sheet.display_value(pos) {
+            Some(CellValue::Number(n)) if !formatted => {
+                sheet.locale.localize_number(&n.to_string(), None)
+            }
+            _ => Self::export_string_value(sheet, pos, formatted),
+        }
+    }
+
+    /// Returns the value at a position as a typed JSON value.
+    fn export_json_value(sheet: &Sheet, pos: Pos, formatted: bool) -> serde_json::Value {
+        let Some(value) = sheet.display_value(pos) else {
+            return serde_json::Value::Null;
+        };
+        match value {
+            CellValue::Blank => serde_json::Value::Null,
+            CellValue::Logical(logical) => serde_json::Value::Bool(logical),
+            CellValue::Number(_) if formatted => {
+                serde_json::Value::String(sheet.formatted_display_value(pos).unwrap_or_default())
+            }
+            CellValue::Number(number) => {
+                let json_number = if number.is_integer() {
+                    number.to_i64().map(serde_json::Number::from)
+                } else {
+                    number.to_f64().and_then(serde_json::Number::from_f64)
+                };
+
+                // numbers that do not fit in a JSON number are exported as strings
+                json_number.map_or_else(
+                    || serde_json::Value::String(number.to_string()),
+                    serde_json::Value::Number,
+                )
+            }
+            value => serde_json::Value::String(value.to_string()),
+        }
+    }
+
+    /// Returns unique headers using the first row of the selection. Empty
+    /// headers are replaced by the column name.
+    fn export_headers(sheet: &Sheet, selection: &Rect, width: usize) -> Vec<String> {
+        let mut headers: Vec<String> = vec![];
+        for x in selection.x_range().take(width) {
+            let pos = Pos {
+                x,
+                y: selection.min.y,
+            };
+            let mut header = Self::export_string_value(sheet, pos, false);
+            if header.is_empty() {
+                header = crate::util::column_name(x);
+            }
+            let mut unique = header.clone();
+            let mut count = 1;
+            while headers.contains(&unique) {
+                count += 1;
+                unique = format!("{header}_{count}");
+            }
+            headers.push(unique);
+        }
+        headers
+    }
+
+    /// exports the workbook to an XLSX file. Every sheet's values (including
+    /// code outputs), links and PNG and JPEG images are exported. Formatting
+    /// and SVG images are not exported.
+    ///
+    /// Cells keep their addresses: the cell at column x, row y is exported to
+    /// column x, row y + 1, since XLSX rows start at 1 (so A0 is exported to
+    /// A1). A sheet with values in negative columns (or rows) is shifted right
+    /// (or down) so its first column is A (or its first row is 1).
+    ///
+    /// Returns the bytes of the file.
+    pub fn export_xlsx(&self) -> Result<Vec<u8>> {
+        let sheets = self.grid.sheets();
+
+        // cells keep their addresses, except that sheets with negative
+        // columns or rows are shifted so they start at column A or row 1
+        let origins = sheets
+            .iter()
+            .map(|sheet| {
+                let origin = match sheet.bounds(true) {
+                    GridBounds::Empty => Pos::ORIGIN,
+                    GridBounds::NonEmpty(bounds) => Pos {
+                        x: bounds.min.x.min(0),
+                        y: bounds.min.y.min(0),
+                    },
+                };
+                (sheet.id, origin)
+            })
+            .collect::<HashMap<_, _>>();
+
+        let mut content_types = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Default Extension="png" ContentType="image/png"/><Default Extension="jpeg" ContentType="image/jpeg"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/>"#,
+        );
+        let mut workbook = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><workbook xmlns="{XLSX_MAIN_NAMESPACE}" xmlns:r="{XLSX_RELATIONSHIPS}"><sheets>"#
+        );
+        let mut workbook_rels = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="{XLSX_PACKAGE_RELATIONSHIPS}">"#
+        );
+        let mut files = vec![];
+        for (index, sheet) in sheets.iter().enumerate() {
+            let n = index + 1;
+            content_types.push_str(&format!(
+                r#"<Override PartName="/xl/worksheets/sheet{n}.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>"#
+            ));
+            workbook.push_str(&format!(
+                r#"<sheet name="{}" sheetId="{n}" r:id="rId{n}"/>"#,
+                escape_xml(&sheet.name)
+            ));
+            workbook_rels.push_str(&format!(
+                r#"<Relationship Id="rId{n}" Type="{XLSX_RELATIONSHIPS}/worksheet" Target="worksheets/sheet{n}.xml"/>"#
+            ));
+            let sheet_files = self.xlsx_sheet(sheet, n, &origins);
+            if sheet_files
+                .iter()
+                .any(|(name, _)| *name == format!("xl/drawings/drawing{n}.xml"))
+            {
+                content_types.push_str(&format!(
+                    r#"<Override PartName="/xl/drawings/drawing{n}.xml" ContentType="application/vnd.openxmlformats-officedocument.drawing+xml"/>"#
+                ));
+            }
+            files.extend(sheet_files);
+        }
+        content_types.push_str("</Types>");
+        workbook.push_str("</sheets></workbook>");
+        workbook_rels.push_str("</Relationships>");
+        files.push(("[Content_Types].xml".into(), content_types.into_bytes()));
+        files.push((
+            "_rels/.rels".into(),
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="{XLSX_PACKAGE_RELATIONSHIPS}"><Relationship Id="rId1" Type="{XLSX_RELATIONSHIPS}/officeDocument" Target="xl/workbook.xml"/></Relationships>"#
+            )
+            .into_bytes(),
+        ));
+        files.push(("xl/workbook.xml".into(), workbook.into_bytes()));
+        files.push((
+            "xl/_rels/workbook.xml.rels".into(),
+            workbook_rels.into_bytes(),
+        ));
+
+        let mut file = Cursor::new(vec![]);
+        let mut zip = zip::ZipWriter::new(&mut file);
+        for (name, contents) in files {
+            zip.start_file(name, zip::write::FileOptions::default())?;
+            zip.write_all(&contents)?;
+        }
+        zip.finish()?;
+        drop(zip);
+
+        Ok(file.into_inner())
+    }
+
+    /// Returns the files of the `n`th sheet: its worksheet XML and, if it
+    /// links to URLs or has images, its relationships, drawing and images.
+    fn xlsx_sheet(
+        &self,
+        sheet: &Sheet,
+        n: usize,
+        origins: &HashMap<SheetId, Pos>,
+    ) -> Vec<(String, Vec<u8>)> {
+        let origin = origins.get(&sheet.id).copied().unwrap_or(Pos::ORIGIN);
+        let mut sheet_data = String::new();
+        let mut hyperlinks = String::new();
+        let mut rels = String::new();
+        let mut images = vec![];
+        if let GridBounds::NonEmpty(bounds) = sheet.bounds(true) {
+            for y in bounds.y_range() {
+                let mut row = String::new();
+                for x in bounds.x_range() {
+                    let pos = Pos { x, y };
+                    let Some(value) = sheet.display_value(pos) else {
+                        continue;
+                    };
+                    let reference = xlsx_cell_ref(pos, origin);
+                    row.push_str(&match &value {
+                        CellValue::Image(image) => {
+                            if image.format != ImageFormat::Svg {
+                                images.push((pos, image.clone()));
+                            }
+                            continue;
+                        }
+                        CellValue::Blank
+                        | CellValue::Code(_)
+                        | CellValue::Html(_)
+                        | CellValue::Error(_) => continue,
+                        CellValue::Number(number) => {
+                            format!(r#"<c r="{reference}"><v>{number}</v></c>"#)
+                        }
+                        CellValue::Logical(logical) => {
+                            format!(r#"<c r="{reference}" t="b"><v>{}</v></c>"#, *logical as u8)
+                        }
+                        value => format!(
+                            r#"<c r="{reference}" t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#,
+                            escape_xml(&value.to_string())
+                        ),
+                    });
+                    if let CellValue::Link(link) = &value {
+                        if link.internal_reference().is_some() {
+                            if let Some(location) = self.xlsx_link_location(link, sheet, origins) {
+                                hyperlinks.push_str(&format!(
+                                    r#"<hyperlink ref="{reference}" location="{}"/>"#,
+                                    escape_xml(&location)
+                                ));
+                            }
+                        } else {
+                            let id = format!("rId{}", rels.matches("<Relationship ").count() + 1);
+                            rels.push_str(&format!(
+                                r#"<Relationship Id="{id}" Type="{XLSX_RELATIONSHIPS}/hyperlink" Target="{}" TargetMode="External"/>"#,
+                                escape_xml(&link.url)
+                            ));
+                            hyperlinks.push_str(&format!(
+                                r#"<hyperlink ref="{reference}" r:id="{id}"/>"#
+                            ));
+                        }
+                    }
+                }
+                if !row.is_empty() {
+                    sheet_data.push_str(&format!(r#"<row r="{}">{row}</row>"#, y - origin.y + 1));
+                }
+            }
+        }
+
+        let mut xml = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><worksheet xmlns="{XLSX_MAIN_NAMESPACE}" xmlns:r="{XLSX_RELATIONSHIPS}"><sheetData>{sheet_data}</sheetData>"#
+        );
+        if !hyperlinks.is_empty() {
+            xml.push_str(&format!("<hyperlinks>{hyperlinks}</hyperlinks>"));
+        }
+        let mut files = vec![];
+        if !images.is_empty() {
+            let id = format!("rId{}", rels.matches("<Relationship ").count() + 1);
+            rels.push_str(&format!(
+                r#"<Relationship Id="{id}" Type="{XLSX_RELATIONSHIPS}/drawing" Target="../drawings/drawing{n}.xml"/>"#
+            ));
+            xml.push_str(&format!(r#"<drawing r:id="{id}"/>"#));
+            files.extend(Self::xlsx_drawing(sheet, n, origin, images));
+        }
+        xml.push_str("</worksheet>");
+        files.insert(0, (format!("xl/worksheets/sheet{n}.xml"), xml.into_bytes()));
+        if !rels.is_empty() {
+            files.push((
+                format!("xl/worksheets/_rels/sheet{n}.xml.rels"),
+                format!(
+                    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="{XLSX_PACKAGE_RELATIONSHIPS}">{rels}</Relationships>"#
+                )
+                .into_bytes(),
+            ));
+        }
+        files
+    }
+
+    /// Returns the drawing XML of the `n`th sheet, its relationships and its
+    /// image files. Each image is anchored at the top left of its cell and
+    /// has the cell's render size, or else the image's size.
+    fn xlsx_drawing(
+        sheet: &Sheet,
+        n: usize,
+        origin: Pos,
+        images: Vec<(Pos, Image)>,
+    ) -> Vec<(String, Vec<u8>)> {
+        let mut drawing = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><xdr:wsDr xmlns:xdr="{XLSX_DRAWING_NAMESPACE}" xmlns:a="{XLSX_DRAWINGML_NAMESPACE}" xmlns:r="{XLSX_RELATIONSHIPS}">"#
+        );
+        let mut rels = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="{XLSX_PACKAGE_RELATIONSHIPS}">"#
+        );
+        let mut files = vec![];
+        for (index, (pos, image)) in images.into_iter().enumerate() {
+            let i = index + 1;
+            let media = format!("sheet{n}_image{i}.{}", image.format.extension());
+            let pixels = |size: Option<String>, default: u32| {
+                size.and_then(|size| size.trim().trim_end_matches("px").parse::<f64>().ok())
+                    .map_or(default as u64, |size| size.max(0.0).round() as u64)
+            };
+            let render_size = sheet.render_size(pos);
+            let width = pixels(render_size.clone().map(|size| size.w), image.width);
+            let height = pixels(render_size.map(|size| size.h), image.height);
+            drawing.push_str(&format!(
+                r#"<xdr:oneCellAnchor><xdr:from><xdr:col>{}</xdr:col><xdr:colOff>0</xdr:colOff><xdr:row>{}</xdr:row><xdr:rowOff>0</xdr:rowOff></xdr:from><xdr:ext cx="{}" cy="{}"/><xdr:pic><xdr:nvPicPr><xdr:cNvPr id="{i}" name="Picture {i}"/><xdr:cNvPicPr/></xdr:nvPicPr><xdr:blipFill><a:blip r:embed="rId{i}"/><a:stretch><a:fillRect/></a:stretch></xdr:blipFill><xdr:spPr><a:prstGeom prst="rect"><a:avLst/></a:prstGeom></xdr:spPr></xdr:pic><xdr:clientData/></xdr:oneCellAnchor>"#,
+                pos.x - origin.x,
+                pos.y - origin.y,
+                width * EMU_PER_PIXEL,
+                height * EMU_PER_PIXEL,
+            ));
+            rels.push_str(&format!(
+                r#"<Relationship Id="rId{i}" Type="{XLSX_RELATIONSHIPS}/image" Target="../media/{media}"/>"#
+            ));
+            files.push((format!("xl/media/{media}"), image.data));
+        }
+        drawing.push_str("</xdr:wsDr>");
+        rels.push_str("</Relationships>");
+        files.push((format!("xl/drawings/drawing{n}.xml"), drawing.into_bytes()));
+        files.push((
+            format!("xl/drawings/_rels/drawing{n}.xml.rels"),
+            rels.into_bytes(),
+        ));
+        files
+    }
+
+    /// Returns the XLSX location (e.g. `'Sheet 2'!A1:B5`) of a link to cells
+    /// in the workbook, using the exported position of the cells.
+    fn xlsx_link_location(
+        &self,
+        link: &Link,
+        sheet: &Sheet,
+        origins: &HashMap<SheetId, Pos>,
+    ) -> Option<String> {
+        let target = link.internal_target(&self.grid, sheet.id)?;
+        let target_sheet = self.try_sheet(target.sheet_id)?;
+        let origin = origins
+            .get(&target.sheet_id)
+            .copied()
+            .unwrap_or(Pos::ORIGIN);
+        let mut location = format!(
+            "'{}'!{}",
+            target_sheet.name.replace('\'', "''"),
+            xlsx_cell_ref(target.min, origin)
+        );
+        if target.max != target.min {
+            location.push(':');
+            location.push_str(&xlsx_cell_ref(target.max, origin));
+        }
+        Some(location)
+    }
+
+    /// Appends a row to a Markdown table, escaping pipes and newlines.
+    fn push_markdown_row(output: &mut String, row: &[String]) {
+        output.push('|');
+        row.iter().for_each(|value| {
+            let escaped = value
+                .replace('|', "\\|")
+                .replace("\r\n", "<br>")
+                .replace('\n', "<br>");
+            output.push(' ');
+            output.push_str(&escaped);
+            output.push_str(" |");
+        });
+        output.push('\n');
+    }
+}
+
+/// Returns the A1 reference of a cell in an exported XLSX sheet, where
+/// `origin` is exported to A1 (see [`GridController::export_xlsx`]).
+fn xlsx_cell_ref(pos: Pos, origin: Pos) -> String {
+    format!("{}{}", column_name(pos.x - origin.x), pos.y - origin.y + 1)
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
-    use crate::Rect;
+    use crate::{
+        grid::{CodeCellLanguage, Locale},
+        Rect, SheetPos,
+    };
+    use bigdecimal::BigDecimal;
+    use std::str::FromStr;
 
     fn test_setup(selection: &Rect, vals: &[&str]) -> (GridController, SheetId) {
         let mut grid_controller = GridController::test();
@@ -62,10 +645,271 @@ mod tests {
         let (grid_controller, sheet_id) = test_setup(&selected, &vals);
         let sheet = grid_controller.sheet(sheet_id);
         let result = grid_controller
-            .export_csv_selection(sheet.id, &selected)
+            .export_csv_selection(sheet.id, &selected, CsvExportOptions::default())
             .unwrap();
         let expected = "1,2,3,4\n5,6,7,8\n9,10,11,12\n13,14,15,16\n";
 
         assert_eq!(&result, expected);
     }
+
+    #[test]
+    fn exports_a_csv_with_options() {
+        let selected: Rect = Rect::new_span((0, 0).into(), (2, 1).into());
+        let vals = vec!["name", "amount", "note", "a", "$1,234.5", "x;y"];
+        let (grid_controller, sheet_id) = test_setup(&selected, &vals);
+
+        let result = grid_controller
+            .export_csv_selection(
+                sheet_id,
+                &selected,
+                CsvExportOptions {
+                    formatted: Some(true),
+                    delimiter: Some(';'),
+                    quote_style: Some(CsvQuoteStyle::NonNumeric),
+                    line_ending: Some(CsvLineEnding::CrLf),
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            result,
+            "\"name\";\"amount\";\"note\"\r\n\"a\";\"$1,234.50\";\"x;y\"\r\n"
+        );
+
+        let result = grid_controller.export_csv_selection(
+            sheet_id,
+            &selected,
+            CsvExportOptions {
+                delimiter: Some('é'),
+                ..Default::default()
+            },
+        );
+        assert!(result.is_err());
+
+        let result =
+            grid_controller.export_csv_selection(SheetId::new(), &selected, Default::default());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn exports_a_csv_sheet() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        assert_eq!(
+            gc.export_csv_sheet(sheet_id, Default::default()).unwrap(),
+            ""
+        );
+
+        gc.set_cell_value((1, 1, sheet_id).into(), "a".into(), None);
+        gc.set_cell_value((3, 2, sheet_id).into(), "1.5".into(), None);
+        let result = gc.export_csv_sheet(sheet_id, Default::default()).unwrap();
+        assert_eq!(result, "a,,\n,,1.5\n");
+    }
+
+    #[test]
+    fn imports_and_exports_a_csv_in_a_locale() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_locale(Locale::from_tag("de").unwrap(), None)
+            .unwrap();
+        gc.import_csv(
+            sheet_id,
+            "name;amount\na;1.234,5\n".as_bytes(),
+            "amounts.csv",
+            (0, 0).into(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            gc.sheet(sheet_id).cell_value((1, 1).into()),
+            Some(CellValue::Number(BigDecimal::from_str("1234.5").unwrap()))
+        );
+
+        let result = gc.export_csv_sheet(sheet_id, Default::default()).unwrap();
+        assert_eq!(result, "name;amount\na;1234,5\n");
+    }
+
+    #[test]
+    fn exports_json_objects() {
+        let selected: Rect = Rect::new_span((0, 0).into(), (2, 2).into());
+        let vals = vec!["name", "amount", "", "a", "1.5", "true", "b", "$1,000", ""];
+        let (grid_controller, sheet_id) = test_setup(&selected, &vals);
+
+        let result = grid_controller
+            .export_json_selection(sheet_id, &selected, JsonExportShape::Objects, false)
+            .unwrap();
+        assert_eq!(
+            result,
+            r#"[{"name":"a","amount":1.5,"C":true},{"name":"b","amount":1000,"C":null}]"#
+        );
+
+        let result = grid_controller
+            .export_json_selection(sheet_id, &selected, JsonExportShape::Objects, true)
+            .unwrap();
+        assert_eq!(
+            result,
+            r#"[{"name":"a","amount":"1.5","C":true},{"name":"b","amount":"$1,000.00","C":null}]"#
+        );
+    }
+
+    #[test]
+    fn exports_json_arrays_with_code_output() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_cell_value((0, 0, sheet_id).into(), "hello".into(), None);
+        gc.set_code_cell(
+            SheetPos {
+                x: 1,
+                y: 0,
+                sheet_id,
+            },
+            CodeCellLanguage::Formula,
+            "1 + 1".into(),
+            None,
+        );
+        let selected = Rect::new_span((0, 0).into(), (1, 0).into());
+        let result = gc
+            .export_json_selection(sheet_id, &selected, JsonExportShape::Arrays, false)
+            .unwrap();
+        assert_eq!(result, r#"[["hello",2]]"#);
+    }
+
+    #[test]
+    fn exports_json_and_markdown_from_missing_sheets() {
+        let gc = GridController::test();
+        let selected = Rect::new_span((0, 0).into(), (1, 1).into());
+        assert!(gc
+            .export_json_selection(SheetId::new(), &selected, JsonExportShape::Arrays, false)
+            .is_err());
+        assert!(gc
+            .export_markdown_selection(SheetId::new(), &selected, false)
+            .is_err());
+    }
+
+    #[test]
+    fn exports_json_duplicate_headers() {
+        let selected: Rect = Rect::new_span((0, 0).into(), (1, 1).into());
+        let vals = vec!["a", "a", "1", "2"];
+        let (grid_controller, sheet_id) = test_setup(&selected, &vals);
+        let result = grid_controller
+            .export_json_selection(sheet_id, &selected, JsonExportShape::Objects, false)
+            .unwrap();
+        assert_eq!(result, r#"[{"a":1,"a_2":2}]"#);
+    }
+
+    #[test]
+    fn exports_markdown() {
+        let selected: Rect = Rect::new_span((0, 0).into(), (1, 2).into());
+        let vals = vec!["name", "amount", "a|b", "10%", "c", "1,234"];
+        let (grid_controller, sheet_id) = test_setup(&selected, &vals);
+
+        let result = grid_controller
+            .export_markdown_selection(sheet_id, &selected, false)
+            .unwrap();
+        assert_eq!(
+            result,
+            "| name | amount |\n| --- | ---: |\n| a\\|b | 0.1 |\n| c | 1234 |\n"
+        );
+
+        let result = grid_controller
+            .export_markdown_selection(sheet_id, &selected, true)
+            .unwrap();
+        assert_eq!(
+            result,
+            "| name | amount |\n| --- | ---: |\n| a\\|b | 10% |\n| c | 1,234 |\n"
+        );
+    }
+
+    #[test]
+    fn exports_xlsx() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.add_sheet(None);
+        let other_id = gc.sheet_ids()[1];
+        gc.set_sheet_name(other_id, "Totals & more".into(), None);
+
+        // values keep their addresses
+        let sheet = gc.grid.try_sheet_mut(sheet_id).unwrap();
+        sheet.set_cell_value(Pos { x: 1, y: 3 }, "name");
+        sheet.set_cell_value(Pos { x: 2, y: 3 }, 12.5);
+        sheet.set_cell_value(Pos { x: 3, y: 3 }, true);
+        sheet.set_cell_value(
+            Pos { x: 1, y: 4 },
+            CellValue::Link(Link::new("Quadratic", "https://quadratichq.com/?a=1&b=2").unwrap()),
+        );
+        sheet.set_cell_value(
+            Pos { x: 2, y: 4 },
+            CellValue::Link(Link::new("totals", "#'Totals & more'!A1:B2").unwrap()),
+        );
+        sheet.recalculate_bounds();
+        let other = gc.grid.try_sheet_mut(other_id).unwrap();
+        other.set_cell_value(Pos { x: 0, y: 1 }, 1);
+        // negative coordinates are moved to A1
+        other.set_cell_value(Pos { x: -1, y: -2 }, 2);
+        other.recalculate_bounds();
+
+        let file = gc.export_xlsx().unwrap();
+        let mut archive = zip::ZipArchive::new(Cursor::new(file.clone())).unwrap();
+        let mut read = |name: &str| {
+            let mut contents = String::new();
+            std::io::Read::read_to_string(&mut archive.by_name(name).unwrap(), &mut contents)
+                .unwrap();
+            contents
+        };
+        let sheet_xml = read("xl/worksheets/sheet1.xml");
+        assert!(sheet_xml.contains(r#"<row r="4"><c r="B4" t="inlineStr"><is><t xml:space="preserve">name</t></is></c><c r="C4"><v>12.5</v></c><c r="D4" t="b"><v>1</v></c></row>"#));
+        assert!(sheet_xml.contains(r#"<hyperlinks><hyperlink ref="B5" r:id="rId1"/><hyperlink ref="C5" location="'Totals &amp; more'!B4:C5"/></hyperlinks>"#));
+        let other_xml = read("xl/worksheets/sheet2.xml");
+        assert!(other_xml.contains(r#"<row r="1"><c r="A1"><v>2</v></c></row>"#));
+        assert!(other_xml.contains(r#"<row r="4"><c r="B4"><v>1</v></c></row>"#));
+        assert!(read("xl/worksheets/_rels/sheet1.xml.rels")
+            .contains(r#"Target="https://quadratichq.com/?a=1&amp;b=2" TargetMode="External""#));
+
+        // the file can be imported
+        let mut imported = GridController::test();
+        imported.import_excel(file, "export.xlsx").unwrap();
+        let sheet = imported.grid.sheets()[1].clone();
+        assert_eq!(
+            sheet.cell_value(Pos { x: 1, y: 0 }),
+            Some(CellValue::Number(BigDecimal::from_str("12.5").unwrap()))
+        );
+        assert_eq!(
+            sheet.cell_value(Pos { x: 0, y: 1 }),
+            Some(CellValue::Text("Quadratic".into()))
+        );
+        assert_eq!(imported.grid.sheets()[2].name, "Totals & more");
+    }
+
+    #[test]
+    fn exports_xlsx_images() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let sheet = gc.grid.try_sheet_mut(sheet_id).unwrap();
+        sheet.set_cell_value(Pos { x: 0, y: 0 }, "chart");
+        sheet.set_cell_value(Pos { x: 1, y: 2 }, CellValue::Image(Image::test()));
+        sheet.recalculate_bounds();
+
+        let file = gc.export_xlsx().unwrap();
+        let mut archive = zip::ZipArchive::new(Cursor::new(file)).unwrap();
+        let mut read = |name: &str| {
+            let mut contents = vec![];
+            std::io::Read::read_to_end(&mut archive.by_name(name).unwrap(), &mut contents).unwrap();
+            contents
+        };
+        assert_eq!(read("xl/media/sheet1_image1.png"), Image::test().data);
+        let sheet_xml = String::from_utf8(read("xl/worksheets/sheet1.xml")).unwrap();
+        assert!(!sheet_xml.contains(r#"r="B3""#));
+        assert!(sheet_xml.ends_with(r#"<drawing r:id="rId1"/></worksheet>"#));
+        let drawing = String::from_utf8(read("xl/drawings/drawing1.xml")).unwrap();
+        assert!(drawing.contains(
+            r#"<xdr:from><xdr:col>1</xdr:col><xdr:colOff>0</xdr:colOff><xdr:row>2</xdr:row><xdr:rowOff>0</xdr:rowOff></xdr:from><xdr:ext cx="19050" cy="28575"/>"#
+        ));
+        assert!(
+            String::from_utf8(read("xl/drawings/_rels/drawing1.xml.rels"))
+                .unwrap()
+                .contains(r#"Target="../media/sheet1_image1.png""#)
+        );
+        assert!(String::from_utf8(read("[Content_Types].xml"))
+            .unwrap()
+            .contains(r#"PartName="/xl/drawings/drawing1.xml""#));
+    }
 }
@@ -1,5 +1,5 @@
 use self::{active_transactions::ActiveTransactions, transaction::Transaction};
-use crate::grid::Grid;
+use crate::grid::{file::validate::ValidationReport, Grid};
 #[cfg(feature = "js")]
 use wasm_bindgen::prelude::*;
 
@@ -26,6 +26,12 @@ pub struct GridController {
 
     // holds information about transactions in progress
     transactions: ActiveTransactions,
+
+    // id of the user making changes, checked against sheet protections
+    user_id: Option<String>,
+
+    // problems found (and repaired) when the grid was loaded from a file
+    file_report: ValidationReport,
 }
 
 impl GridController {
@@ -37,6 +43,15 @@ impl GridController {
         }
     }
 
+    /// Creates a controller for a grid loaded from a file, keeping the
+    /// report of the problems found in the file.
+    pub fn from_file(grid: Grid, last_sequence_num: u64, file_report: ValidationReport) -> Self {
+        GridController {
+            file_report,
+            ..Self::from_grid(grid, last_sequence_num)
+        }
+    }
+
     pub fn grid(&self) -> &Grid {
         &self.grid
     }
@@ -45,6 +60,20 @@ impl GridController {
         &mut self.grid
     }
 
+    pub fn file_report(&self) -> &ValidationReport {
+        &self.file_report
+    }
+
+    pub fn user_id(&self) -> Option<&str> {
+        self.user_id.as_deref()
+    }
+
+    /// Sets the id of the user making changes. Protected ranges and locked
+    /// sheets can only be changed by the users they list.
+    pub fn set_user_id(&mut self, user_id: Option<String>) {
+        self.user_id = user_id;
+    }
+
     pub fn test() -> Self {
         Self::from_grid(Grid::new(), 0)
     }
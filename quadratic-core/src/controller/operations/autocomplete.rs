@@ -143,6 +143,8 @@ impl GridController {
             let ops = self.expand_right(sheet_id, &selection, &new_range, down_range, up_range)?;
             operations.extend(ops);
         }
+
+        operations.extend(self.expand_merged_cells(sheet_id, &selection, &range));
         Ok(operations)
     }
 
@@ -152,6 +154,59 @@ impl GridController {
 
         ops.extend(self.delete_cells_rect_operations(delete_range));
         ops.extend(self.clear_formatting_operations(delete_range));
+        if self
+            .try_sheet(delete_range.sheet_id)
+            .is_some_and(|sheet| !sheet.merged_cells_in_rect(delete_range.into()).is_empty())
+        {
+            ops.push(Operation::UnmergeCells {
+                sheet_rect: delete_range,
+            });
+        }
+        ops
+    }
+
+    /// Repeats the merged cells within the selection across the range, using
+    /// the same pattern as the values (the selection is tiled).
+    fn expand_merged_cells(
+        &self,
+        sheet_id: SheetId,
+        selection: &Rect,
+        range: &Rect,
+    ) -> Vec<Operation> {
+        let Some(sheet) = self.try_sheet(sheet_id) else {
+            return vec![];
+        };
+        let merged_cells = sheet
+            .merged_cells_in_rect(*selection)
+            .into_iter()
+            .filter(|rect| selection.contains(rect.min) && selection.contains(rect.max))
+            .collect::<Vec<_>>();
+        if merged_cells.is_empty() {
+            return vec![];
+        }
+
+        let w = selection.width() as i64;
+        let h = selection.height() as i64;
+        let tiles_x = (range.min.x - selection.min.x).div_euclid(w)
+            ..=(range.max.x - selection.min.x).div_euclid(w);
+        let tiles_y = (range.min.y - selection.min.y).div_euclid(h)
+            ..=(range.max.y - selection.min.y).div_euclid(h);
+
+        let mut ops = vec![];
+        for tile_y in tiles_y {
+            for tile_x in tiles_x.clone() {
+                if tile_x == 0 && tile_y == 0 {
+                    continue;
+                }
+                for merged in &merged_cells {
+                    let mut rect = *merged;
+                    rect.translate(tile_x * w, tile_y * h);
+                    if range.contains(rect.min) && range.contains(rect.max) {
+                        ops.extend(self.merge_cells_operations(rect.to_sheet_rect(sheet_id)));
+                    }
+                }
+            }
+        }
         ops
     }
 
@@ -6,7 +6,7 @@ use crate::{
     cell_values::CellValues,
     controller::GridController,
     grid::{formatting::CellFmtArray, NumericDecimals, NumericFormat, NumericFormatKind},
-    CellValue, RunLengthEncoding, SheetPos, SheetRect,
+    CellValue, Link, RunLengthEncoding, SheetPos, SheetRect,
 };
 
 use super::operation::Operation;
@@ -25,6 +25,12 @@ impl GridController {
     ) -> (Vec<Operation>, CellValue) {
         let mut ops = vec![];
         let sheet_rect: SheetRect = sheet_pos.into();
+
+        // numbers typed in the workbook's locale are parsed in the US form
+        let locale = *self.grid.locale();
+        let canonical = locale.canonical_number(value);
+        let value = canonical.as_deref().unwrap_or(value);
+
         let cell_value = if value.is_empty() {
             CellValue::Blank
         } else if let Some((currency, number)) = CellValue::unpack_currency(value) {
@@ -85,6 +91,33 @@ impl GridController {
                 )),
             });
             CellValue::Number(percent)
+        } else if let Some((instant, code)) = CellValue::unpack_date_time(value, locale.date_order)
+        {
+            // keep an existing date/time format so typed dates display consistently
+            let has_date_format = self.try_sheet(sheet_pos.sheet_id).is_some_and(|sheet| {
+                matches!(
+                    sheet.get_formatting_value::<NumericFormat>(sheet_pos.into()),
+                    Some(NumericFormat {
+                        kind: NumericFormatKind::DateTime(_),
+                        ..
+                    })
+                )
+            });
+            if !has_date_format {
+                ops.push(Operation::SetCellFormats {
+                    sheet_rect,
+                    attr: CellFmtArray::NumericFormat(RunLengthEncoding::repeat(
+                        Some(NumericFormat {
+                            kind: NumericFormatKind::DateTime(code.to_string()),
+                            symbol: None,
+                        }),
+                        1,
+                    )),
+                });
+            }
+            CellValue::Instant(instant)
+        } else if let Some(link) = Link::detect(value) {
+            CellValue::Link(link)
         } else {
             CellValue::Text(value.into())
         };
@@ -104,6 +137,7 @@ impl GridController {
 
         // convert the string to a cell value and generate necessary operations
         let (operations, cell_value) = self.string_to_cell_value(sheet_pos, value);
+
         ops.extend(operations);
 
         ops.push(Operation::SetCellValues {
@@ -140,7 +174,11 @@ mod test {
 
     use bigdecimal::BigDecimal;
 
-    use crate::{controller::GridController, grid::SheetId, CellValue, SheetPos};
+    use crate::{
+        controller::GridController,
+        grid::{NumericFormat, NumericFormatKind, SheetId},
+        CellValue, Link, SheetPos,
+    };
 
     #[test]
     fn test() {
@@ -226,6 +264,58 @@ mod test {
         );
     }
 
+    #[test]
+    fn date_to_cell_value() {
+        let mut gc = GridController::test();
+        let sheet_pos = SheetPos {
+            x: 1,
+            y: 2,
+            sheet_id: gc.sheet_ids()[0],
+        };
+        gc.set_cell_value(sheet_pos, "3/1/2024".into(), None);
+        let sheet = gc.sheet(sheet_pos.sheet_id);
+        assert!(matches!(
+            sheet.cell_value(sheet_pos.into()),
+            Some(CellValue::Instant(_))
+        ));
+        assert_eq!(
+            sheet.display_value(sheet_pos.into()).map(|v| v.to_string()),
+            Some("2024-03-01 00:00:00".to_string())
+        );
+        assert_eq!(
+            sheet
+                .get_formatting_value::<NumericFormat>(sheet_pos.into())
+                .map(|format| format.kind),
+            Some(NumericFormatKind::DateTime("m/d/yyyy".into()))
+        );
+
+        // an existing date format is kept
+        let (ops, value) = gc.string_to_cell_value(sheet_pos, "2024-03-02");
+        assert_eq!(ops.len(), 0);
+        assert!(matches!(value, CellValue::Instant(_)));
+    }
+
+    #[test]
+    fn url_to_cell_value() {
+        let mut gc = GridController::test();
+        let sheet_pos = SheetPos {
+            x: 1,
+            y: 2,
+            sheet_id: SheetId::test(),
+        };
+        let (ops, value) = gc.string_to_cell_value(sheet_pos, "www.quadratichq.com");
+        assert_eq!(ops.len(), 0);
+        assert_eq!(
+            value,
+            CellValue::Link(
+                Link::new("www.quadratichq.com", "https://www.quadratichq.com").unwrap()
+            )
+        );
+
+        let (_, value) = gc.string_to_cell_value(sheet_pos, "quadratichq.com");
+        assert_eq!(value, CellValue::Text("quadratichq.com".into()));
+    }
+
     #[test]
     fn problematic_number() {
         let mut gc = GridController::test();
@@ -0,0 +1,32 @@
+use super::operation::Operation;
+use crate::{controller::GridController, grid::sheet::charts::Chart, SheetPos};
+
+impl GridController {
+    /// Creates operations to add (or replace) the chart anchored to a cell.
+    pub fn set_chart_operations(&self, sheet_pos: SheetPos, chart: Chart) -> Vec<Operation> {
+        let Some(sheet) = self.try_sheet(sheet_pos.sheet_id) else {
+            return vec![];
+        };
+        if sheet.chart(sheet_pos.into()) == Some(&chart) {
+            return vec![];
+        }
+        vec![Operation::SetChart {
+            sheet_pos,
+            chart: Some(chart),
+        }]
+    }
+
+    /// Creates operations to delete the chart anchored to a cell.
+    pub fn delete_chart_operations(&self, sheet_pos: SheetPos) -> Vec<Operation> {
+        let Some(sheet) = self.try_sheet(sheet_pos.sheet_id) else {
+            return vec![];
+        };
+        if sheet.chart(sheet_pos.into()).is_none() {
+            return vec![];
+        }
+        vec![Operation::SetChart {
+            sheet_pos,
+            chart: None,
+        }]
+    }
+}
@@ -1,11 +1,19 @@
-use super::operation::Operation;
+use super::{
+    html_table::{parse_html_table, HtmlTable},
+    operation::Operation,
+    plain_text::{detect_delimiter, parse_delimited},
+};
 use crate::{
     cell_values::CellValues,
     controller::{user_actions::clipboard::PasteSpecial, GridController},
-    grid::{formatting::CellFmtArray, generate_borders_full, BorderSelection, CellBorders},
-    CellValue, Pos, SheetPos, SheetRect,
+    grid::{
+        formatting::CellFmtArray, generate_borders_full, sheet::comments::CommentThread,
+        BorderSelection, CellBorders,
+    },
+    CellValue, Pos, Rect, RunLengthEncoding, SheetPos, SheetRect,
 };
 use anyhow::{Error, Result};
+use bigdecimal::{BigDecimal, Zero};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 
@@ -20,6 +28,145 @@ pub struct Clipboard {
 
     pub formats: Vec<CellFmtArray>,
     pub borders: Vec<(i64, i64, Option<CellBorders>)>,
+
+    // widths of the copied columns for use with PasteSpecial::ColumnWidths
+    #[serde(default)]
+    pub column_widths: Vec<f64>,
+
+    // merged regions fully inside the copy, relative to its top-left cell
+    #[serde(default)]
+    pub merged_cells: Vec<Rect>,
+
+    // comment threads, relative to the top-left cell
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub comments: Vec<(Pos, CommentThread)>,
+}
+
+impl Clipboard {
+    /// Swaps rows and columns, including formats and borders.
+    pub fn transpose(&self) -> Self {
+        let borders = self
+            .borders
+            .iter()
+            .map(|(x, y, cell_borders)| {
+                // [left, top, right, bottom] becomes [top, left, bottom, right]
+                let cell_borders = cell_borders.map(|cell_borders| {
+                    let [left, top, right, bottom] = cell_borders.borders;
+                    CellBorders {
+                        borders: [top, left, bottom, right],
+                    }
+                });
+                (*y, *x, cell_borders)
+            })
+            .collect();
+        Clipboard {
+            w: self.h,
+            h: self.w,
+            cells: self.cells.transpose(),
+            values: self.values.transpose(),
+            formats: self
+                .formats
+                .iter()
+                .map(|format| format.transpose(self.w as usize, self.h as usize))
+                .collect(),
+            borders,
+            column_widths: vec![],
+            merged_cells: self
+                .merged_cells
+                .iter()
+                .map(|rect| Rect {
+                    min: Pos {
+                        x: rect.min.y,
+                        y: rect.min.x,
+                    },
+                    max: Pos {
+                        x: rect.max.y,
+                        y: rect.max.x,
+                    },
+                })
+                .collect(),
+            comments: self
+                .comments
+                .iter()
+                .map(|(pos, thread)| (Pos { x: pos.y, y: pos.x }, thread.clone()))
+                .collect(),
+        }
+    }
+
+    /// Keeps only the given columns and rows (relative to the clipboard),
+    /// including their formats and borders. Merged cells are kept only if all
+    /// of their columns and rows are kept.
+    pub fn select(&self, columns: &[u32], rows: &[u32]) -> Self {
+        let new_index = |indexes: &[u32], index: i64| {
+            indexes
+                .iter()
+                .position(|i| *i as i64 == index)
+                .map(|i| i as i64)
+        };
+        let select_values = |values: &CellValues| {
+            let mut selected = CellValues::new(columns.len() as u32, rows.len() as u32);
+            for (new_y, y) in rows.iter().enumerate() {
+                for (new_x, x) in columns.iter().enumerate() {
+                    if let Some(value) = values.get(*x, *y) {
+                        selected.set(new_x as u32, new_y as u32, value.clone());
+                    }
+                }
+            }
+            selected
+        };
+        let columns_usize = columns.iter().map(|x| *x as usize).collect::<Vec<_>>();
+        let rows_usize = rows.iter().map(|y| *y as usize).collect::<Vec<_>>();
+
+        Clipboard {
+            w: columns.len() as u32,
+            h: rows.len() as u32,
+            cells: select_values(&self.cells),
+            values: select_values(&self.values),
+            formats: self
+                .formats
+                .iter()
+                .map(|format| format.select(self.w as usize, &columns_usize, &rows_usize))
+                .collect(),
+            borders: self
+                .borders
+                .iter()
+                .filter_map(|(x, y, cell_borders)| {
+                    Some((new_index(columns, *x)?, new_index(rows, *y)?, *cell_borders))
+                })
+                .collect(),
+            column_widths: columns
+                .iter()
+                .filter_map(|x| self.column_widths.get(*x as usize).copied())
+                .collect(),
+            merged_cells: self
+                .merged_cells
+                .iter()
+                .filter_map(|rect| {
+                    let min_x = new_index(columns, rect.min.x)?;
+                    let min_y = new_index(rows, rect.min.y)?;
+                    let max_x = new_index(columns, rect.max.x)?;
+                    let max_y = new_index(rows, rect.max.y)?;
+                    (max_x - min_x == rect.max.x - rect.min.x
+                        && max_y - min_y == rect.max.y - rect.min.y)
+                        .then_some(Rect::new_span(
+                            Pos { x: min_x, y: min_y },
+                            Pos { x: max_x, y: max_y },
+                        ))
+                })
+                .collect(),
+            comments: self
+                .comments
+                .iter()
+                .filter_map(|(pos, thread)| {
+                    let pos = Pos {
+                        x: new_index(columns, pos.x)?,
+                        y: new_index(rows, pos.y)?,
+                    };
+                    Some((pos, thread.clone()))
+                })
+                .collect(),
+        }
+    }
 }
 
 impl GridController {
@@ -28,7 +175,14 @@ impl GridController {
         sheet_rect: SheetRect,
     ) -> (Vec<Operation>, String, String) {
         let copy = self.copy_to_clipboard(sheet_rect);
-        let operations = self.delete_values_and_formatting_operations(sheet_rect);
+        let mut operations = self.delete_values_and_formatting_operations(sheet_rect);
+        operations.extend(self.delete_comment_threads_operations(sheet_rect));
+        if self
+            .try_sheet(sheet_rect.sheet_id)
+            .is_some_and(|sheet| !sheet.merged_cells_in_rect(sheet_rect.into()).is_empty())
+        {
+            operations.push(Operation::UnmergeCells { sheet_rect });
+        }
         (operations, copy.0, copy.1)
     }
 
@@ -64,17 +218,103 @@ impl GridController {
         }
     }
 
-    fn set_clipboard_cells(
+    /// Sets the non-blank values as a set of operations, one for each
+    /// vertical run of values, so that blank cells leave the sheet untouched.
+    fn set_non_blank_cell_values(start_pos: SheetPos, values: &CellValues) -> Vec<Operation> {
+        let mut ops = vec![];
+        for (x, column) in values.columns.iter().enumerate() {
+            let mut runs: Vec<(u64, Vec<&CellValue>)> = vec![];
+            for (y, value) in column.iter() {
+                if *value == CellValue::Blank {
+                    continue;
+                }
+                match runs.last_mut() {
+                    Some((start, run)) if *start + run.len() as u64 == *y => run.push(value),
+                    _ => runs.push((*y, vec![value])),
+                }
+            }
+            for (y, run) in runs {
+                let mut run_values = CellValues::new(1, run.len() as u32);
+                for (i, value) in run.into_iter().enumerate() {
+                    run_values.set(0, i as u32, value.clone());
+                }
+                ops.push(Operation::SetCellValues {
+                    sheet_pos: SheetPos {
+                        x: start_pos.x + x as i64,
+                        y: start_pos.y + y as i64,
+                        sheet_id: start_pos.sheet_id,
+                    },
+                    values: run_values,
+                });
+            }
+        }
+        ops
+    }
+
+    /// Combines the clipboard's numbers with the numbers at the destination.
+    /// Blank destination cells count as zero; anything else that is not a
+    /// number (on either side) is left unchanged.
+    fn arithmetic_cell_values(
+        &self,
+        start_pos: SheetPos,
+        clipboard: &Clipboard,
+        special: PasteSpecial,
+    ) -> CellValues {
+        let mut values = CellValues::new(clipboard.w, clipboard.h);
+        let Some(sheet) = self.try_sheet(start_pos.sheet_id) else {
+            return values;
+        };
+        for (x, y, value) in clipboard.values.into_iter() {
+            let CellValue::Number(source) = value else {
+                continue;
+            };
+            let pos = Pos {
+                x: start_pos.x + x as i64,
+                y: start_pos.y + y as i64,
+            };
+            let destination = match sheet.cell_value(pos) {
+                None | Some(CellValue::Blank) if sheet.display_value(pos).is_none() => {
+                    BigDecimal::zero()
+                }
+                Some(CellValue::Number(destination)) => destination,
+                _ => continue,
+            };
+            if let Some(result) = special.combine(&destination, source) {
+                values.set(x, y, CellValue::Number(result));
+            }
+        }
+        values
+    }
+
+    pub(crate) fn set_clipboard_cells(
         &mut self,
         start_pos: SheetPos,
         clipboard: Clipboard,
         special: PasteSpecial,
     ) -> Vec<Operation> {
+        // a transposed paste is a normal paste of the transposed clipboard
+        let (clipboard, special) = match special {
+            PasteSpecial::Transpose => (clipboard.transpose(), PasteSpecial::None),
+            _ => (clipboard, special),
+        };
+
         let formats = clipboard.formats.clone();
         let borders = clipboard.borders.clone();
 
         let mut ops = vec![];
 
+        let compute_code = |code: Vec<(u32, u32)>| {
+            code.into_iter()
+                .map(|(x, y)| Operation::ComputeCode {
+                    sheet_pos: SheetPos {
+                        x: start_pos.x + x as i64,
+                        y: start_pos.y + y as i64,
+                        sheet_id: start_pos.sheet_id,
+                    },
+                })
+                .collect::<Vec<_>>()
+        };
+
         match special {
             PasteSpecial::None => {
                 let (values, code) =
@@ -85,15 +325,7 @@ impl GridController {
                         values: values.clone(),
                     });
                 }
-
-                code.iter().for_each(|(x, y)| {
-                    let sheet_pos = SheetPos {
-                        x: start_pos.x + *x as i64,
-                        y: start_pos.y + *y as i64,
-                        sheet_id: start_pos.sheet_id,
-                    };
-                    ops.push(Operation::ComputeCode { sheet_pos });
-                });
+                ops.extend(compute_code(code));
             }
             PasteSpecial::Values => {
                 let (values, _) =
@@ -105,9 +337,68 @@ impl GridController {
                     });
                 }
             }
+            PasteSpecial::SkipBlanks => {
+                let (values, code) = GridController::cell_values_from_clipboard_cells(
+                    &clipboard,
+                    PasteSpecial::None,
+                );
+                if let Some(values) = values {
+                    ops.extend(GridController::set_non_blank_cell_values(start_pos, values));
+                }
+                ops.extend(compute_code(code));
+            }
+            PasteSpecial::Formulas => {
+                let (_, code) = GridController::cell_values_from_clipboard_cells(
+                    &clipboard,
+                    PasteSpecial::None,
+                );
+                let mut values = CellValues::new(clipboard.w, clipboard.h);
+                for (x, y) in code.iter() {
+                    if let Some(value) = clipboard.cells.get(*x, *y) {
+                        values.set(*x, *y, value.clone());
+                    }
+                }
+                ops.extend(GridController::set_non_blank_cell_values(
+                    start_pos, &values,
+                ));
+                ops.extend(compute_code(code));
+            }
+            PasteSpecial::ColumnWidths => {
+                clipboard
+                    .column_widths
+                    .iter()
+                    .enumerate()
+                    .for_each(|(x, width)| {
+                        ops.push(Operation::ResizeColumn {
+                            sheet_id: start_pos.sheet_id,
+                            column: start_pos.x + x as i64,
+                            new_size: *width,
+                        });
+                    });
+            }
+            special if special.is_arithmetic() => {
+                let values = self.arithmetic_cell_values(start_pos, &clipboard, special);
+                ops.extend(GridController::set_non_blank_cell_values(
+                    start_pos, &values,
+                ));
+            }
             _ => (),
         }
 
+        // comments are pasted with the cells' values
+        if matches!(special, PasteSpecial::None | PasteSpecial::SkipBlanks) {
+            for (pos, thread) in clipboard.comments.iter() {
+                ops.extend(self.paste_comment_thread_operations(
+                    SheetPos {
+                        x: start_pos.x + pos.x,
+                        y: start_pos.y + pos.y,
+                        sheet_id: start_pos.sheet_id,
+                    },
+                    thread,
+                ));
+            }
+        }
+
         let sheet_rect = SheetRect {
             min: start_pos.into(),
             max: Pos {
@@ -117,8 +408,34 @@ impl GridController {
             sheet_id: start_pos.sheet_id,
         };
 
-        // paste formats and borders unless pasting only values
-        if !matches!(special, PasteSpecial::Values) {
+        // paste formats and borders unless pasting only values (or another partial paste)
+        if matches!(
+            special,
+            PasteSpecial::None | PasteSpecial::Formats | PasteSpecial::SkipBlanks
+        ) {
+            // replace any merged cells in the destination with the clipboard's
+            if self
+                .try_sheet(start_pos.sheet_id)
+                .is_some_and(|sheet| !sheet.merged_cells_in_rect(sheet_rect.into()).is_empty())
+            {
+                ops.push(Operation::UnmergeCells { sheet_rect });
+            }
+            clipboard.merged_cells.iter().for_each(|rect| {
+                ops.push(Operation::MergeCells {
+                    sheet_rect: SheetRect::new_pos_span(
+                        Pos {
+                            x: start_pos.x + rect.min.x,
+                            y: start_pos.y + rect.min.y,
+                        },
+                        Pos {
+                            x: start_pos.x + rect.max.x,
+                            y: start_pos.y + rect.max.y,
+                        },
+                        start_pos.sheet_id,
+                    ),
+                });
+            });
+
             formats.iter().for_each(|format| {
                 ops.push(Operation::SetCellFormats {
                     sheet_rect,
@@ -183,41 +500,159 @@ impl GridController {
         if matches!(special, PasteSpecial::Formats) {
             return vec![];
         }
-        let lines: Vec<&str> = clipboard.split('\n').collect();
+        let rows = parse_delimited(&clipboard, detect_delimiter(&clipboard));
 
         let mut ops = vec![];
 
-        // calculate the width by checking the first line (with the assumption that all lines should have the same width)
-        let w = lines
-            .first()
-            .map(|line| line.split('\t').count())
-            .unwrap_or(0);
-        let mut cell_values = CellValues::new(w as u32, lines.len() as u32);
-        lines.iter().enumerate().for_each(|(y, line)| {
-            line.split('\t').enumerate().for_each(|(x, value)| {
+        // rows may have different lengths, so use the widest row
+        let w = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        let mut cell_values = CellValues::new(w as u32, rows.len() as u32);
+        rows.iter().enumerate().for_each(|(y, row)| {
+            row.iter().enumerate().for_each(|(x, value)| {
+                // numeric formats land where the value will be pasted
+                let (format_x, format_y) = match special {
+                    PasteSpecial::Transpose => (y, x),
+                    _ => (x, y),
+                };
                 let (operations, cell_value) = self.string_to_cell_value(
                     SheetPos {
-                        x: start_pos.x + x as i64,
-                        y: start_pos.y + y as i64,
+                        x: start_pos.x + format_x as i64,
+                        y: start_pos.y + format_y as i64,
                         sheet_id: start_pos.sheet_id,
                     },
                     value,
                 );
-                ops.extend(operations);
+                if matches!(
+                    special,
+                    PasteSpecial::None
+                        | PasteSpecial::Values
+                        | PasteSpecial::Transpose
+                        | PasteSpecial::SkipBlanks
+                ) {
+                    ops.extend(operations);
+                }
                 if cell_value != CellValue::Blank {
                     cell_values.set(x as u32, y as u32, cell_value);
                 }
             });
         });
 
-        ops.push(Operation::SetCellValues {
-            sheet_pos: start_pos,
+        // paste through a Clipboard so the other paste special modes apply
+        let clipboard = Clipboard {
+            w: cell_values.w,
+            h: cell_values.h,
+            cells: cell_values.clone(),
             values: cell_values,
-        });
+            formats: vec![],
+            borders: vec![],
+            column_widths: vec![],
+            merged_cells: vec![],
+            comments: vec![],
+        };
+        ops.extend(self.set_clipboard_cells(start_pos, clipboard, special));
+        ops
+    }
+
+    /// Converts a table copied from another spreadsheet or web page into a
+    /// Clipboard and pastes it.
+    fn paste_html_table_operations(
+        &mut self,
+        start_pos: SheetPos,
+        table: HtmlTable,
+        special: PasteSpecial,
+    ) -> Vec<Operation> {
+        let mut ops = vec![];
+        let mut values = CellValues::new(table.w, table.h);
+        let mut align = RunLengthEncoding::new();
+        let mut vertical_align = RunLengthEncoding::new();
+        let mut text_rotation = RunLengthEncoding::new();
+        let mut bold = RunLengthEncoding::new();
+        let mut italic = RunLengthEncoding::new();
+        let mut underline = RunLengthEncoding::new();
+        let mut strike_through = RunLengthEncoding::new();
+        let mut font_size = RunLengthEncoding::new();
+        let mut font_family = RunLengthEncoding::new();
+        let mut text_color = RunLengthEncoding::new();
+        let mut fill_color = RunLengthEncoding::new();
+
+        // formats are row-major to match set_cell_formats_for_type
+        for y in 0..table.h {
+            for x in 0..table.w {
+                let Some(cell) = table.cell(x, y) else {
+                    align.push(None);
+                    vertical_align.push(None);
+                    text_rotation.push(None);
+                    bold.push(None);
+                    italic.push(None);
+                    underline.push(None);
+                    strike_through.push(None);
+                    font_size.push(None);
+                    font_family.push(None);
+                    text_color.push(None);
+                    fill_color.push(None);
+                    continue;
+                };
+                if !matches!(special, PasteSpecial::Formats) {
+                    let (operations, cell_value) = match &cell.link {
+                        Some(url) if !cell.text.is_empty() => (
+                            vec![],
+                            CellValue::link(cell.text.clone(), url.clone()),
+                        ),
+                        _ => self.string_to_cell_value(
+                            SheetPos {
+                                x: start_pos.x + x as i64,
+                                y: start_pos.y + y as i64,
+                                sheet_id: start_pos.sheet_id,
+                            },
+                            &cell.text,
+                        ),
+                    };
+                    ops.extend(operations);
+                    if cell_value != CellValue::Blank {
+                        values.set(x, y, cell_value);
+                    }
+                }
+                align.push(cell.style.align);
+                vertical_align.push(cell.style.vertical_align);
+                text_rotation.push(cell.style.text_rotation);
+                bold.push(cell.style.bold);
+                italic.push(cell.style.italic);
+                underline.push(cell.style.underline);
+                strike_through.push(cell.style.strike_through);
+                font_size.push(cell.style.font_size);
+                font_family.push(cell.style.font_family.clone());
+                text_color.push(cell.style.text_color.clone());
+                fill_color.push(cell.style.fill_color.clone());
+            }
+        }
+
+        let clipboard = Clipboard {
+            w: table.w,
+            h: table.h,
+            cells: values.clone(),
+            values,
+            formats: vec![
+                CellFmtArray::Align(align),
+                CellFmtArray::VerticalAlign(vertical_align),
+                CellFmtArray::TextRotation(text_rotation),
+                CellFmtArray::Bold(bold),
+                CellFmtArray::Italic(italic),
+                CellFmtArray::Underline(underline),
+                CellFmtArray::StrikeThrough(strike_through),
+                CellFmtArray::FontSize(font_size),
+                CellFmtArray::FontFamily(font_family),
+                CellFmtArray::TextColor(text_color),
+                CellFmtArray::FillColor(fill_color),
+            ],
+            borders: vec![],
+            column_widths: vec![],
+            merged_cells: vec![],
+            comments: vec![],
+        };
+        ops.extend(self.set_clipboard_cells(start_pos, clipboard, special));
         ops
     }
 
-    // todo: parse table structure to provide better pasting experience from other spreadsheets
     pub fn paste_html_operations(
         &mut self,
         sheet_pos: SheetPos,
@@ -229,7 +664,13 @@ impl GridController {
             Err(_) => Err(Error::msg("Regex creation error")),
             Ok(re) => {
                 let Some(data) = re.captures(&html) else {
-                    return Err(Error::msg("Regex capture error"));
+                    // html from other spreadsheets and web pages
+                    return match parse_html_table(&html) {
+                        Some(table) => {
+                            Ok(self.paste_html_table_operations(sheet_pos, table, special))
+                        }
+                        None => Err(Error::msg("Regex capture error")),
+                    };
                 };
                 let result = &data.get(1).map_or("", |m| m.as_str());
 
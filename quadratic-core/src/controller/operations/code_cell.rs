@@ -14,6 +14,11 @@ impl GridController {
         language: CodeCellLanguage,
         code: String,
     ) -> Vec<Operation> {
+        // formulas are stored in the US form
+        let code = match language {
+            CodeCellLanguage::Formula => self.grid.locale().formula_to_canonical(&code),
+            _ => code,
+        };
         vec![
             Operation::SetCellValues {
                 sheet_pos,
@@ -0,0 +1,140 @@
+use super::operation::Operation;
+use crate::{
+    controller::GridController,
+    grid::{
+        sheet::comments::{Comment, CommentThread},
+        CommentId,
+    },
+    SheetPos, SheetRect,
+};
+
+impl GridController {
+    /// Creates operations to add a comment to the end of a cell's thread.
+    pub fn add_comment_operations(
+        &self,
+        sheet_pos: SheetPos,
+        author: String,
+        text: String,
+    ) -> Vec<Operation> {
+        vec![Operation::AddComment {
+            sheet_pos,
+            comment: Comment::new(author, text),
+        }]
+    }
+
+    fn comment_thread(&self, sheet_pos: SheetPos) -> Option<&CommentThread> {
+        self.try_sheet(sheet_pos.sheet_id)?
+            .comment_thread(sheet_pos.into())
+    }
+
+    fn find_comment(&self, sheet_pos: SheetPos, comment_id: CommentId) -> Option<&Comment> {
+        self.comment_thread(sheet_pos)?
+            .comments
+            .iter()
+            .find(|comment| comment.id == comment_id)
+    }
+
+    /// Creates operations to change the text of a comment in a cell's thread.
+    /// Returns no operations if the comment does not exist or does not
+    /// change.
+    pub fn edit_comment_operations(
+        &self,
+        sheet_pos: SheetPos,
+        comment_id: CommentId,
+        text: String,
+    ) -> Vec<Operation> {
+        if self
+            .find_comment(sheet_pos, comment_id)
+            .filter(|comment| comment.text != text)
+            .is_none()
+        {
+            return vec![];
+        }
+        vec![Operation::EditComment {
+            sheet_pos,
+            comment_id,
+            text,
+        }]
+    }
+
+    /// Creates operations to delete a comment from a cell's thread. The
+    /// thread is deleted with its last comment.
+    pub fn delete_comment_operations(
+        &self,
+        sheet_pos: SheetPos,
+        comment_id: CommentId,
+    ) -> Vec<Operation> {
+        if self.find_comment(sheet_pos, comment_id).is_none() {
+            return vec![];
+        }
+        vec![Operation::DeleteComment {
+            sheet_pos,
+            comment_id,
+        }]
+    }
+
+    /// Creates operations to resolve (or reopen) a cell's thread.
+    pub fn set_comment_resolved_operations(
+        &self,
+        sheet_pos: SheetPos,
+        resolved: bool,
+    ) -> Vec<Operation> {
+        if self
+            .comment_thread(sheet_pos)
+            .filter(|thread| thread.resolved != resolved)
+            .is_none()
+        {
+            return vec![];
+        }
+        vec![Operation::SetCommentResolved {
+            sheet_pos,
+            resolved,
+        }]
+    }
+
+    /// Creates operations to replace a cell's thread with a copy of `thread`.
+    /// The copied comments get new ids so that they can be edited separately
+    /// from the originals.
+    pub fn paste_comment_thread_operations(
+        &self,
+        sheet_pos: SheetPos,
+        thread: &CommentThread,
+    ) -> Vec<Operation> {
+        let mut ops = self.delete_comment_threads_operations(sheet_pos.into());
+        ops.extend(thread.comments.iter().map(|comment| Operation::AddComment {
+            sheet_pos,
+            comment: Comment {
+                id: CommentId::new(),
+                ..comment.clone()
+            },
+        }));
+        if thread.resolved {
+            ops.push(Operation::SetCommentResolved {
+                sheet_pos,
+                resolved: true,
+            });
+        }
+        ops
+    }
+
+    /// Creates operations to delete all comment threads within the
+    /// sheet_rect.
+    pub fn delete_comment_threads_operations(&self, sheet_rect: SheetRect) -> Vec<Operation> {
+        let Some(sheet) = self.try_sheet(sheet_rect.sheet_id) else {
+            return vec![];
+        };
+        sheet
+            .comment_threads_in_rect(sheet_rect.into())
+            .into_iter()
+            .flat_map(|(pos, thread)| {
+                thread
+                    .comments
+                    .iter()
+                    .map(move |comment| Operation::DeleteComment {
+                        sheet_pos: pos.to_sheet_pos(sheet_rect.sheet_id),
+                        comment_id: comment.id,
+                    })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,50 @@
+use super::operation::Operation;
+use crate::{
+    controller::GridController,
+    grid::{sheet::conditional_formats::ConditionalFormat, SheetId},
+    SheetRect,
+};
+
+impl GridController {
+    /// Creates operations to add a conditional format to a sheet.
+    pub fn add_conditional_format_operations(
+        &self,
+        sheet_id: SheetId,
+        conditional_format: ConditionalFormat,
+    ) -> Vec<Operation> {
+        let Some(sheet) = self.try_sheet(sheet_id) else {
+            return vec![];
+        };
+        let mut conditional_formats = sheet.conditional_formats.clone();
+        conditional_formats.push(conditional_format);
+        vec![Operation::SetConditionalFormats {
+            sheet_id,
+            conditional_formats,
+        }]
+    }
+
+    /// Creates operations to remove all conditional formats that intersect
+    /// the sheet_rect.
+    pub fn remove_conditional_formats_operations(&self, sheet_rect: SheetRect) -> Vec<Operation> {
+        let Some(sheet) = self.try_sheet(sheet_rect.sheet_id) else {
+            return vec![];
+        };
+        if !sheet
+            .conditional_formats
+            .iter()
+            .any(|conditional_format| conditional_format.rect.intersects(sheet_rect.into()))
+        {
+            return vec![];
+        }
+        let conditional_formats = sheet
+            .conditional_formats
+            .iter()
+            .filter(|conditional_format| !conditional_format.rect.intersects(sheet_rect.into()))
+            .cloned()
+            .collect();
+        vec![Operation::SetConditionalFormats {
+            sheet_id: sheet_rect.sheet_id,
+            conditional_formats,
+        }]
+    }
+}
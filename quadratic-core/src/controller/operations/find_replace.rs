@@ -0,0 +1,209 @@
+use super::operation::Operation;
+use crate::{
+    cell_values::CellValues,
+    controller::GridController,
+    grid::sheet::search::{SearchMatch, SearchOptions, SearchQuery},
+    CellValue, CodeCellValue, SheetPos,
+};
+use regex::{NoExpand, Regex};
+
+/// Replaces matches in text: every match, or only the match at `index`.
+/// When `expand` is set, `$1` or `${name}` in the replacement are replaced
+/// with the regex's capture groups.
+///
+/// Returns the new text, the number of replacements, and (when replacing a
+/// single match) the index of the next match in the new text, if any.
+fn replace_text(
+    regex: &Regex,
+    text: &str,
+    replace: &str,
+    expand: bool,
+    index: Option<usize>,
+) -> Option<(String, usize, Option<usize>)> {
+    let Some(index) = index else {
+        let count = regex.find_iter(text).count();
+        if count == 0 {
+            return None;
+        }
+        let replaced = if expand {
+            regex.replace_all(text, replace)
+        } else {
+            regex.replace_all(text, NoExpand(replace))
+        };
+        return Some((replaced.into_owned(), count, None));
+    };
+
+    let captures = regex.captures_iter(text).nth(index)?;
+    let found = captures.get(0)?;
+    let mut replacement = String::new();
+    if expand {
+        captures.expand(replace, &mut replacement);
+    } else {
+        replacement.push_str(replace);
+    }
+    let end = found.start() + replacement.len();
+    let replaced = format!(
+        "{}{replacement}{}",
+        &text[..found.start()],
+        &text[found.end()..]
+    );
+
+    // skip matches inside the replacement (eg, replacing `a` with `aa`)
+    let next = regex
+        .find_iter(&replaced)
+        .take_while(|m| m.start() < end)
+        .count();
+    let has_next = regex.find_iter(&replaced).nth(next).is_some();
+    Some((replaced, 1, has_next.then_some(next)))
+}
+
+impl GridController {
+    /// Replaces matches in a cell: every match, or only the match at `index`.
+    /// Returns the operations, the number of replacements, and the index of
+    /// the next match in the cell (see [`replace_text`]).
+    fn replace_in_cell(
+        &self,
+        sheet_pos: SheetPos,
+        search_query: &SearchQuery,
+        replace: &str,
+        expand: bool,
+        index: Option<usize>,
+    ) -> Option<(Vec<Operation>, usize, Option<usize>)> {
+        let sheet = self.try_sheet(sheet_pos.sheet_id)?;
+
+        // matches in code_run output and numbers are not replaced
+        match sheet.cell_value(sheet_pos.into())? {
+            // a criteria match replaces the entire cell
+            CellValue::Text(_) if search_query.criterion.is_some() => {
+                if index.is_some_and(|index| index > 0) {
+                    return None;
+                }
+                let values = if replace.is_empty() {
+                    CellValues::new(1, 1)
+                } else {
+                    CellValues::from(CellValue::from(replace))
+                };
+                Some((
+                    vec![Operation::SetCellValues { sheet_pos, values }],
+                    1,
+                    None,
+                ))
+            }
+            CellValue::Text(text) => {
+                let (text, replaced, next) =
+                    replace_text(&search_query.regex, &text, replace, expand, index)?;
+                let values = if text.is_empty() {
+                    CellValues::new(1, 1)
+                } else {
+                    CellValues::from(CellValue::Text(text))
+                };
+                Some((
+                    vec![Operation::SetCellValues { sheet_pos, values }],
+                    replaced,
+                    next,
+                ))
+            }
+            CellValue::Code(code_cell) if search_query.search_code => {
+                let (code, replaced, next) = replace_text(
+                    &search_query.code_regex,
+                    &code_cell.code,
+                    replace,
+                    expand,
+                    index,
+                )?;
+                let ops = vec![
+                    Operation::SetCellValues {
+                        sheet_pos,
+                        values: CellValues::from(CellValue::Code(CodeCellValue {
+                            language: code_cell.language,
+                            code,
+                        })),
+                    },
+                    Operation::ComputeCode { sheet_pos },
+                ];
+                Some((ops, replaced, next))
+            }
+            _ => None,
+        }
+    }
+
+    /// Creates operations to replace matches of a search in text cells (and
+    /// code cells if `search_code` is set). Regex searches may use capture
+    /// groups in the replacement.
+    ///
+    /// When `replace_all` is false, only one match is replaced: the `current`
+    /// match, or the first match if there is no current match. The next match
+    /// is returned so the caller can step through the matches.
+    ///
+    /// Returns the operations, the number of replacements, and the next match.
+    pub fn find_replace_operations(
+        &self,
+        query: &str,
+        replace: &str,
+        options: SearchOptions,
+        replace_all: bool,
+        current: Option<SearchMatch>,
+    ) -> (Vec<Operation>, usize, Option<SearchMatch>) {
+        let mut ops = vec![];
+        let mut count = 0;
+
+        if query.is_empty() {
+            return (ops, count, None);
+        }
+        let Some(search_query) = SearchQuery::new(query, &options) else {
+            return (ops, count, None);
+        };
+        let expand = options.regex.unwrap_or(false);
+        let positions = self.grid.search(&query.to_string(), options);
+
+        if replace_all {
+            for sheet_pos in positions {
+                if let Some((cell_ops, replaced, _)) =
+                    self.replace_in_cell(sheet_pos, &search_query, replace, expand, None)
+                {
+                    ops.extend(cell_ops);
+                    count += replaced;
+                }
+            }
+            return (ops, count, None);
+        }
+
+        // start at the current match if it is still in the results
+        let (start, start_index) = current
+            .and_then(|current| {
+                let start = positions
+                    .iter()
+                    .position(|sheet_pos| *sheet_pos == current.sheet_pos)?;
+                Some((start, current.index))
+            })
+            .unwrap_or((0, 0));
+        for (i, sheet_pos) in positions.iter().enumerate().skip(start) {
+            let index = if i == start { start_index } else { 0 };
+            let Some((cell_ops, replaced, next)) =
+                self.replace_in_cell(*sheet_pos, &search_query, replace, expand, Some(index))
+            else {
+                continue;
+            };
+            ops.extend(cell_ops);
+
+            // move the cursor to the replaced cell
+            ops.push(Operation::SetCursor {
+                sheet_rect: (*sheet_pos).into(),
+            });
+
+            let next = match next {
+                Some(index) => Some(SearchMatch {
+                    sheet_pos: *sheet_pos,
+                    index,
+                }),
+                None => positions.get(i + 1).map(|sheet_pos| SearchMatch {
+                    sheet_pos: *sheet_pos,
+                    index: 0,
+                }),
+            };
+            return (ops, replaced, next);
+        }
+
+        (ops, count, None)
+    }
+}
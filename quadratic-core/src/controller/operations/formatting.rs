@@ -83,7 +83,7 @@ impl GridController {
             .decimal_places(source.into(), is_percentage)
             .unwrap_or(0);
         let new_precision = i16::max(0, source_decimals + (delta as i16));
-        
+
         vec![Operation::SetCellFormats {
             sheet_rect,
             attr: CellFmtArray::NumericDecimals(RunLengthEncoding::repeat(
@@ -127,6 +127,14 @@ impl GridController {
                 sheet_rect,
                 attr: CellFmtArray::Wrap(RunLengthEncoding::repeat(None, len)),
             },
+            Operation::SetCellFormats {
+                sheet_rect,
+                attr: CellFmtArray::VerticalAlign(RunLengthEncoding::repeat(None, len)),
+            },
+            Operation::SetCellFormats {
+                sheet_rect,
+                attr: CellFmtArray::TextRotation(RunLengthEncoding::repeat(None, len)),
+            },
             Operation::SetCellFormats {
                 sheet_rect,
                 attr: CellFmtArray::NumericFormat(RunLengthEncoding::repeat(None, len)),
@@ -143,6 +151,22 @@ impl GridController {
                 sheet_rect,
                 attr: CellFmtArray::Italic(RunLengthEncoding::repeat(None, len)),
             },
+            Operation::SetCellFormats {
+                sheet_rect,
+                attr: CellFmtArray::Underline(RunLengthEncoding::repeat(None, len)),
+            },
+            Operation::SetCellFormats {
+                sheet_rect,
+                attr: CellFmtArray::StrikeThrough(RunLengthEncoding::repeat(None, len)),
+            },
+            Operation::SetCellFormats {
+                sheet_rect,
+                attr: CellFmtArray::FontSize(RunLengthEncoding::repeat(None, len)),
+            },
+            Operation::SetCellFormats {
+                sheet_rect,
+                attr: CellFmtArray::FontFamily(RunLengthEncoding::repeat(None, len)),
+            },
             Operation::SetCellFormats {
                 sheet_rect,
                 attr: CellFmtArray::TextColor(RunLengthEncoding::repeat(None, len)),
@@ -0,0 +1,666 @@
+//! A small parser for the HTML tables that other spreadsheets (Excel, Google
+//! Sheets) and web pages put on the clipboard.
+//!
+//! Only the first top-level `<table>` is parsed. `colspan` and `rowspan` are
+//! expanded into the grid, and the inline styles that map to Quadratic's
+//! formatting are kept (bold, italic, underline, strikethrough, font size and
+//! family, text color, fill color, alignment, and text rotation).
+
+use crate::grid::{CellAlign, CellVerticalAlign, FontSize, TextRotation};
+use crate::Link;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct HtmlCellStyle {
+    pub bold: Option<bool>,
+    pub italic: Option<bool>,
+    pub underline: Option<bool>,
+    pub strike_through: Option<bool>,
+    /// Font size in points.
+    pub font_size: Option<i16>,
+    pub font_family: Option<String>,
+    pub text_color: Option<String>,
+    pub fill_color: Option<String>,
+    pub align: Option<CellAlign>,
+    pub vertical_align: Option<CellVerticalAlign>,
+    pub text_rotation: Option<i16>,
+}
+
+impl HtmlCellStyle {
+    /// Sets any attributes that are not already set from another style.
+    fn inherit(&mut self, other: &HtmlCellStyle) {
+        self.bold = self.bold.or(other.bold);
+        self.italic = self.italic.or(other.italic);
+        self.underline = self.underline.or(other.underline);
+        self.strike_through = self.strike_through.or(other.strike_through);
+        self.font_size = self.font_size.or(other.font_size);
+        self.font_family = self
+            .font_family
+            .take()
+            .or_else(|| other.font_family.clone());
+        self.text_color = self.text_color.take().or_else(|| other.text_color.clone());
+        self.fill_color = self.fill_color.take().or_else(|| other.fill_color.clone());
+        self.align = self.align.or(other.align);
+        self.vertical_align = self.vertical_align.or(other.vertical_align);
+        self.text_rotation = self.text_rotation.or(other.text_rotation);
+    }
+
+    /// Parses an inline `style` attribute.
+    fn parse(style: &str) -> Self {
+        let mut parsed = HtmlCellStyle::default();
+        for declaration in style.split(';') {
+            let Some((property, value)) = declaration.split_once(':') else {
+                continue;
+            };
+            let value = value.trim().trim_end_matches("!important").trim();
+            if property.trim().eq_ignore_ascii_case("font-family") {
+                parsed.font_family = parse_font_family(value);
+                continue;
+            }
+            let value = value.to_lowercase();
+            match property.trim().to_lowercase().as_str() {
+                "font-weight" => {
+                    parsed.bold = match value.as_str() {
+                        "bold" | "bolder" => Some(true),
+                        "normal" | "lighter" => Some(false),
+                        weight => weight.parse::<u32>().ok().map(|weight| weight >= 600),
+                    }
+                }
+                "font-style" => {
+                    parsed.italic = match value.as_str() {
+                        "italic" | "oblique" => Some(true),
+                        "normal" => Some(false),
+                        _ => None,
+                    }
+                }
+                "text-decoration" | "text-decoration-line" => {
+                    if value == "none" {
+                        parsed.underline = Some(false);
+                        parsed.strike_through = Some(false);
+                    }
+                    if value.contains("underline") {
+                        parsed.underline = Some(true);
+                    }
+                    if value.contains("line-through") {
+                        parsed.strike_through = Some(true);
+                    }
+                }
+                "font-size" => parsed.font_size = parse_font_size(&value),
+                "color" => parsed.text_color = parse_color(&value),
+                "background-color" | "background" => parsed.fill_color = parse_color(&value),
+                "text-align" => parsed.align = parse_align(&value),
+                "vertical-align" => parsed.vertical_align = parse_vertical_align(&value),
+                // Excel writes rotated text as mso-rotate
+                "mso-rotate" => {
+                    parsed.text_rotation = value
+                        .parse::<i16>()
+                        .ok()
+                        .filter(|rotation| TextRotation::RANGE.contains(rotation));
+                }
+                _ => (),
+            }
+        }
+        parsed
+    }
+}
+
+/// Returns a color if it is a hex or rgb() color. Named and system colors
+/// (e.g., Excel's `windowtext`) are ignored.
+fn parse_color(value: &str) -> Option<String> {
+    let value = value.trim();
+    let is_hex = value.strip_prefix('#').is_some_and(|hex| {
+        matches!(hex.len(), 3 | 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit())
+    });
+    let is_rgb = (value.starts_with("rgb(") || value.starts_with("rgba(")) && value.ends_with(')');
+    (is_hex || is_rgb).then(|| value.to_string())
+}
+
+fn parse_align(value: &str) -> Option<CellAlign> {
+    match value.trim() {
+        "left" | "start" => Some(CellAlign::Left),
+        "center" => Some(CellAlign::Center),
+        "right" | "end" => Some(CellAlign::Right),
+        _ => None,
+    }
+}
+
+fn parse_vertical_align(value: &str) -> Option<CellVerticalAlign> {
+    match value.trim() {
+        "top" | "text-top" => Some(CellVerticalAlign::Top),
+        "middle" => Some(CellVerticalAlign::Middle),
+        "bottom" | "text-bottom" => Some(CellVerticalAlign::Bottom),
+        _ => None,
+    }
+}
+
+/// Returns a font size in points. Sizes in pixels are converted (a point is
+/// 4/3 of a pixel); keywords such as `large` are ignored.
+fn parse_font_size(value: &str) -> Option<i16> {
+    let value = value.trim();
+    let (number, scale) = if let Some(number) = value.strip_suffix("pt") {
+        (number, 1.0)
+    } else if let Some(number) = value.strip_suffix("px") {
+        (number, 0.75)
+    } else {
+        return None;
+    };
+    let size = (number.trim().parse::<f64>().ok()? * scale).round();
+    (size >= *FontSize::RANGE.start() as f64 && size <= *FontSize::RANGE.end() as f64)
+        .then_some(size as i16)
+}
+
+/// Returns the first family of a `font-family` list, without quotes.
+fn parse_font_family(value: &str) -> Option<String> {
+    let family = value
+        .split(',')
+        .next()?
+        .trim()
+        .trim_matches(|c| c == '"' || c == '\'');
+    (!family.is_empty()).then(|| family.to_string())
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct HtmlCell {
+    pub text: String,
+    pub style: HtmlCellStyle,
+
+    /// Target of the first link in the cell.
+    pub link: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct HtmlTable {
+    pub w: u32,
+    pub h: u32,
+
+    /// Cells in row-major order. Cells covered by a colspan or rowspan have
+    /// empty text and the style of the spanning cell.
+    pub rows: Vec<Vec<Option<HtmlCell>>>,
+}
+
+impl HtmlTable {
+    pub fn cell(&self, x: u32, y: u32) -> Option<&HtmlCell> {
+        self.rows.get(y as usize)?.get(x as usize)?.as_ref()
+    }
+
+    fn place(&mut self, x: usize, y: usize, cell: HtmlCell) {
+        if self.rows.len() <= y {
+            self.rows.resize(y + 1, vec![]);
+        }
+        let row = &mut self.rows[y];
+        if row.len() <= x {
+            row.resize(x + 1, None);
+        }
+        row[x] = Some(cell);
+        self.w = self.w.max(x as u32 + 1);
+        self.h = self.h.max(y as u32 + 1);
+    }
+
+    fn is_occupied(&self, x: usize, y: usize) -> bool {
+        self.rows
+            .get(y)
+            .and_then(|row| row.get(x))
+            .is_some_and(|cell| cell.is_some())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    Open {
+        name: String,
+        attributes: Vec<(String, String)>,
+    },
+    Close(String),
+    Text(&'a str),
+}
+
+impl Token<'_> {
+    fn attribute(&self, attribute: &str) -> Option<&str> {
+        match self {
+            Token::Open { attributes, .. } => attributes
+                .iter()
+                .find(|(name, _)| name == attribute)
+                .map(|(_, value)| value.as_str()),
+            _ => None,
+        }
+    }
+}
+
+fn decode(text: &str) -> String {
+    htmlescape::decode_html(text).unwrap_or_else(|_| text.to_string())
+}
+
+/// Parses the attributes of a start tag (the part after the tag name).
+fn parse_attributes(mut s: &str) -> Vec<(String, String)> {
+    let mut attributes = vec![];
+    loop {
+        s = s.trim_start_matches(|c: char| c.is_whitespace() || c == '/');
+        if s.is_empty() {
+            return attributes;
+        }
+        let name_end = s
+            .find(|c: char| c.is_whitespace() || c == '=' || c == '/')
+            .unwrap_or(s.len());
+        let name = s[..name_end].to_lowercase();
+        s = s[name_end..].trim_start();
+
+        let mut value = String::new();
+        if let Some(rest) = s.strip_prefix('=') {
+            let rest = rest.trim_start();
+            let (raw, remaining) = match rest.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let inner = &rest[1..];
+                    let end = inner.find(quote).unwrap_or(inner.len());
+                    (&inner[..end], inner.get(end + 1..).unwrap_or(""))
+                }
+                _ => {
+                    let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+                    (&rest[..end], &rest[end..])
+                }
+            };
+            value = decode(raw);
+            s = remaining;
+        }
+        attributes.push((name, value));
+    }
+}
+
+/// Splits html into tags and text. Comments, doctypes, and the contents of
+/// `<style>` and `<script>` are skipped.
+fn tokenize(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    let mut rest = html;
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            tokens.push(Token::Text(rest));
+            break;
+        };
+        if start > 0 {
+            tokens.push(Token::Text(&rest[..start]));
+        }
+        rest = &rest[start..];
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+
+        // find the end of the tag, ignoring any '>' inside quoted attributes
+        let mut quote = None;
+        let end = rest.char_indices().skip(1).find_map(|(i, c)| {
+            match (quote, c) {
+                (None, '"' | '\'') => quote = Some(c),
+                (Some(q), c) if q == c => quote = None,
+                (None, '>') => return Some(i),
+                _ => (),
+            }
+            None
+        });
+        let Some(end) = end else {
+            break;
+        };
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+
+        if let Some(name) = tag.strip_prefix('/') {
+            tokens.push(Token::Close(name.trim().to_lowercase()));
+        } else if !tag.starts_with('!') && !tag.starts_with('?') {
+            let name_end = tag
+                .find(|c: char| c.is_whitespace() || c == '/')
+                .unwrap_or(tag.len());
+            let name = tag[..name_end].to_lowercase();
+            let attributes = parse_attributes(&tag[name_end..]);
+
+            if name == "style" || name == "script" {
+                let close = format!("</{name}");
+                rest = rest
+                    .to_ascii_lowercase()
+                    .find(&close)
+                    .and_then(|index| rest[index..].find('>').map(|end| &rest[index + end + 1..]))
+                    .unwrap_or("");
+                continue;
+            }
+            tokens.push(Token::Open { name, attributes });
+        }
+    }
+    tokens
+}
+
+/// A cell that is being parsed.
+struct OpenCell {
+    text: String,
+    style: HtmlCellStyle,
+    link: Option<String>,
+    colspan: usize,
+    rowspan: usize,
+}
+
+/// Parses the first table in an html string. Returns `None` if there is no
+/// table with at least one cell.
+pub fn parse_html_table(html: &str) -> Option<HtmlTable> {
+    let mut table = HtmlTable::default();
+    let mut depth = 0;
+    let mut y: Option<usize> = None;
+    let mut x = 0;
+    let mut cell: Option<OpenCell> = None;
+    let mut row_style = HtmlCellStyle::default();
+    let mut found_cell = false;
+
+    let mut close_cell = |table: &mut HtmlTable, cell: &mut Option<OpenCell>, x: &mut usize, y| {
+        let Some(open) = cell.take() else {
+            return;
+        };
+        let text = open
+            .text
+            .lines()
+            .map(|line| line.trim())
+            .collect::<Vec<_>>()
+            .join("\n")
+            .trim()
+            .to_string();
+        for dy in 0..open.rowspan {
+            for dx in 0..open.colspan {
+                let (text, link) = if dx == 0 && dy == 0 {
+                    (text.clone(), open.link.clone())
+                } else {
+                    (String::new(), None)
+                };
+                table.place(
+                    *x + dx,
+                    y + dy,
+                    HtmlCell {
+                        text,
+                        style: open.style.clone(),
+                        link,
+                    },
+                );
+            }
+        }
+        *x += open.colspan;
+        found_cell = true;
+    };
+
+    for token in tokenize(html) {
+        match &token {
+            Token::Open { name, .. } if name == "table" => {
+                depth += 1;
+                continue;
+            }
+            Token::Close(name) if name == "table" && depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    if let Some(y) = y {
+                        close_cell(&mut table, &mut cell, &mut x, y);
+                    }
+                    break;
+                }
+                continue;
+            }
+            _ if depth == 0 => continue,
+
+            // nested tables are flattened into the text of their cell
+            _ if depth > 1 => {
+                if let (Token::Text(text), Some(open)) = (&token, cell.as_mut()) {
+                    open.text.push_str(&decode(text));
+                }
+                continue;
+            }
+            _ => (),
+        }
+
+        match token {
+            Token::Open { ref name, .. } if name == "tr" => {
+                if let Some(y) = y {
+                    close_cell(&mut table, &mut cell, &mut x, y);
+                }
+                y = Some(y.map_or(0, |y| y + 1));
+                x = 0;
+                row_style = HtmlCellStyle::parse(token.attribute("style").unwrap_or_default());
+            }
+            Token::Open { ref name, .. } if name == "td" || name == "th" => {
+                let row = match y {
+                    Some(row) => {
+                        close_cell(&mut table, &mut cell, &mut x, row);
+                        row
+                    }
+                    None => {
+                        y = Some(0);
+                        0
+                    }
+                };
+                while table.is_occupied(x, row) {
+                    x += 1;
+                }
+                let span = |attribute| {
+                    token
+                        .attribute(attribute)
+                        .and_then(|span: &str| span.trim().parse::<usize>().ok())
+                        .unwrap_or(1)
+                        .clamp(1, 1000)
+                };
+                let mut style = HtmlCellStyle::parse(token.attribute("style").unwrap_or_default());
+                if style.align.is_none() {
+                    style.align = token.attribute("align").and_then(parse_align);
+                }
+                if style.fill_color.is_none() {
+                    style.fill_color = token.attribute("bgcolor").and_then(parse_color);
+                }
+                if style.vertical_align.is_none() {
+                    style.vertical_align = token.attribute("valign").and_then(parse_vertical_align);
+                }
+                if name == "th" && style.bold.is_none() {
+                    style.bold = Some(true);
+                }
+                style.inherit(&row_style);
+                cell = Some(OpenCell {
+                    text: String::new(),
+                    style,
+                    link: None,
+                    colspan: span("colspan"),
+                    rowspan: span("rowspan"),
+                });
+            }
+            Token::Close(ref name) if name == "td" || name == "th" => {
+                if let Some(y) = y {
+                    close_cell(&mut table, &mut cell, &mut x, y);
+                }
+            }
+            Token::Close(ref name) if name == "tr" => {
+                if let Some(y) = y {
+                    close_cell(&mut table, &mut cell, &mut x, y);
+                }
+            }
+            Token::Open { ref name, .. } if cell.is_some() => {
+                let Some(open) = cell.as_mut() else {
+                    continue;
+                };
+                match name.as_str() {
+                    "br" => open.text.push('\n'),
+                    "a" if open.link.is_none() => {
+                        open.link = token
+                            .attribute("href")
+                            .map(decode)
+                            .filter(|href| Link::is_allowed_url(href));
+                    }
+                    "b" | "strong" if open.style.bold.is_none() => open.style.bold = Some(true),
+                    "i" | "em" if open.style.italic.is_none() => open.style.italic = Some(true),
+                    "u" | "ins" if open.style.underline.is_none() => {
+                        open.style.underline = Some(true);
+                    }
+                    "s" | "strike" | "del" if open.style.strike_through.is_none() => {
+                        open.style.strike_through = Some(true);
+                    }
+                    _ => {
+                        let mut inline =
+                            HtmlCellStyle::parse(token.attribute("style").unwrap_or_default());
+                        // backgrounds of inline elements do not fill the cell
+                        inline.fill_color = None;
+                        open.style.inherit(&inline);
+                    }
+                }
+            }
+            Token::Close(ref name) if matches!(name.as_str(), "p" | "div") => {
+                if let Some(open) = cell.as_mut() {
+                    open.text.push('\n');
+                }
+            }
+            Token::Text(text) => {
+                if let Some(open) = cell.as_mut() {
+                    // collapse whitespace the way a browser does
+                    let text = decode(text).replace('\u{a0}', " ");
+                    let mut collapsed = String::new();
+                    for (i, word) in text.split_whitespace().enumerate() {
+                        if i > 0 {
+                            collapsed.push(' ');
+                        }
+                        collapsed.push_str(word);
+                    }
+                    if text.starts_with(char::is_whitespace) && !open.text.is_empty() {
+                        open.text.push(' ');
+                    }
+                    open.text.push_str(&collapsed);
+                    if text.ends_with(char::is_whitespace) && !collapsed.is_empty() {
+                        open.text.push(' ');
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    if let (Some(y), true) = (y, cell.is_some()) {
+        close_cell(&mut table, &mut cell, &mut x, y);
+    }
+
+    found_cell.then_some(table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(table: &HtmlTable, x: u32, y: u32) -> Option<&str> {
+        table.cell(x, y).map(|cell| cell.text.as_str())
+    }
+
+    #[test]
+    fn parses_a_simple_table() {
+        let html = r#"<meta charset="utf-8"><table><tbody>
+            <tr><th>Name</th><th>Amount</th></tr>
+            <tr><td> a&amp;b </td><td>$1,234.50</td></tr>
+        </tbody></table>"#;
+        let table = parse_html_table(html).unwrap();
+        assert_eq!((table.w, table.h), (2, 2));
+        assert_eq!(text(&table, 0, 0), Some("Name"));
+        assert_eq!(table.cell(0, 0).unwrap().style.bold, Some(true));
+        assert_eq!(text(&table, 0, 1), Some("a&b"));
+        assert_eq!(text(&table, 1, 1), Some("$1,234.50"));
+    }
+
+    #[test]
+    fn expands_colspan_and_rowspan() {
+        let html = r#"<table>
+            <tr><td colspan="2" style="background-color:#ff0000">a</td><td rowspan=2>b</td></tr>
+            <tr><td>c</td><td>d</td></tr>
+        </table>"#;
+        let table = parse_html_table(html).unwrap();
+        assert_eq!((table.w, table.h), (3, 2));
+        assert_eq!(text(&table, 0, 0), Some("a"));
+        assert_eq!(text(&table, 1, 0), Some(""));
+        assert_eq!(
+            table.cell(1, 0).unwrap().style.fill_color,
+            Some("#ff0000".into())
+        );
+        assert_eq!(text(&table, 2, 0), Some("b"));
+        assert_eq!(text(&table, 0, 1), Some("c"));
+        assert_eq!(text(&table, 1, 1), Some("d"));
+        assert_eq!(text(&table, 2, 1), Some(""));
+    }
+
+    #[test]
+    fn parses_styles() {
+        let html = r#"<html><head><style>td { color: red; }</style></head><body>
+            <!--StartFragment--><table style="border-collapse:collapse">
+            <tr style="font-style:italic">
+                <td style="font-weight:700;color:rgb(0, 0, 255);text-align:center">x</td>
+                <td align=right><b>y</b><br>z</td>
+                <td style="color:windowtext;font-style:normal"><span style="font-weight:bold">w</span></td>
+            </tr></table><!--EndFragment--></body></html>"#;
+        let table = parse_html_table(html).unwrap();
+
+        let x = &table.cell(0, 0).unwrap().style;
+        assert_eq!(x.bold, Some(true));
+        assert_eq!(x.italic, Some(true));
+        assert_eq!(x.text_color, Some("rgb(0, 0, 255)".into()));
+        assert_eq!(x.align, Some(CellAlign::Center));
+
+        let y = table.cell(1, 0).unwrap();
+        assert_eq!(y.text, "y\nz");
+        assert_eq!(y.style.bold, Some(true));
+        assert_eq!(y.style.align, Some(CellAlign::Right));
+
+        let w = &table.cell(2, 0).unwrap().style;
+        assert_eq!(w.bold, Some(true));
+        assert_eq!(w.italic, Some(false));
+        assert_eq!(w.text_color, None);
+    }
+
+    #[test]
+    fn parses_text_styles() {
+        let html = r#"<table><tr>
+            <td style="text-decoration:underline line-through;font-size:16px;font-family:'Courier New', monospace">a</td>
+            <td valign=top style="font-size:11.0pt;mso-rotate:90"><u>b</u></td>
+            <td style="vertical-align:middle;font-size:large"><s>c</s></td>
+        </tr></table>"#;
+        let table = parse_html_table(html).unwrap();
+
+        let a = &table.cell(0, 0).unwrap().style;
+        assert_eq!(a.underline, Some(true));
+        assert_eq!(a.strike_through, Some(true));
+        assert_eq!(a.font_size, Some(12));
+        assert_eq!(a.font_family, Some("Courier New".into()));
+
+        let b = &table.cell(1, 0).unwrap().style;
+        assert_eq!(b.underline, Some(true));
+        assert_eq!(b.font_size, Some(11));
+        assert_eq!(b.vertical_align, Some(CellVerticalAlign::Top));
+        assert_eq!(b.text_rotation, Some(90));
+
+        let c = &table.cell(2, 0).unwrap().style;
+        assert_eq!(c.strike_through, Some(true));
+        assert_eq!(c.font_size, None);
+        assert_eq!(c.vertical_align, Some(CellVerticalAlign::Middle));
+    }
+
+    #[test]
+    fn parses_links() {
+        let html = r#"<table><tr>
+            <td><a href="https://example.com/?a=1&amp;b=2">Example</a></td>
+            <td><a>no target</a></td>
+            <td><a href="&#106;avascript:alert(1)">Click</a></td>
+        </tr></table>"#;
+        let table = parse_html_table(html).unwrap();
+        let a = table.cell(0, 0).unwrap();
+        assert_eq!(a.text, "Example");
+        assert_eq!(a.link, Some("https://example.com/?a=1&b=2".into()));
+        assert_eq!(table.cell(1, 0).unwrap().link, None);
+        assert_eq!(table.cell(2, 0).unwrap().link, None);
+    }
+
+    #[test]
+    fn skips_styles_with_non_ascii_text() {
+        // 'İ' is longer in bytes once lowercased
+        let html = format!(
+            "<style>td {{ font-family: {}; }}</style><table><tr><td>a</td></tr></table>",
+            "İ".repeat(40)
+        );
+        let table = parse_html_table(&html).unwrap();
+        assert_eq!((table.w, table.h), (1, 1));
+        assert_eq!(text(&table, 0, 0), Some("a"));
+    }
+
+    #[test]
+    fn ignores_html_without_a_table() {
+        assert_eq!(parse_html_table("<p>hello</p>"), None);
+        assert_eq!(parse_html_table("<table></table>"), None);
+    }
+}
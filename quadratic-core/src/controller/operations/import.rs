@@ -1,12 +1,275 @@
-use std::io::Cursor;
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
 
 use anyhow::{anyhow, bail, Result};
 use bytes::Bytes;
 use calamine::{Data as ExcelData, Reader as ExcelReader, Xlsx, XlsxError};
+use lazy_static::lazy_static;
 use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use regex::Regex;
 
-use super::operation::Operation;
-use crate::{cell_values::CellValues, controller::GridController, grid::SheetId, CellValue, Pos};
+use super::{operation::Operation, plain_text::detect_csv_delimiter};
+use crate::{
+    cell_values::CellValues,
+    controller::GridController,
+    grid::{formatting::CellFmtArray, NumericFormat, NumericFormatKind, SheetId},
+    util::column_from_name,
+    CellValue, Duration, Pos, Rect, RunLengthEncoding,
+};
+
+/// The parts of an XLSX file that calamine does not expose, read directly
+/// from the XML.
+#[derive(Default)]
+struct ExcelXml {
+    /// The XML of each sheet, keyed by sheet name.
+    sheets: HashMap<String, String>,
+    styles: Option<String>,
+}
+
+lazy_static! {
+    static ref XML_ATTR: Regex = Regex::new(r#"\s([\w:]+)="([^"]*)""#).unwrap();
+    static ref RELATIONSHIP_TAG: Regex = Regex::new(r"<Relationship\b[^>]*>").unwrap();
+    static ref SHEET_TAG: Regex = Regex::new(r"<sheet\b[^>]*>").unwrap();
+    static ref NUM_FMT_TAG: Regex = Regex::new(r"<numFmt\b[^>]*>").unwrap();
+    static ref XF_TAG: Regex = Regex::new(r"<xf\b[^>]*>").unwrap();
+    static ref CELL_TAG: Regex = Regex::new(r"<c\b[^>]*>").unwrap();
+    static ref CELL_REFERENCE: Regex = Regex::new(r"^([A-Z]+)(\d+)$").unwrap();
+    static ref MERGE_CELL: Regex =
+        Regex::new(r#"<mergeCell\s+ref="([A-Z]+)(\d+):([A-Z]+)(\d+)""#).unwrap();
+}
+
+/// Returns the value of an attribute in an XML tag.
+fn xml_attr(tag: &str, name: &str) -> Option<String> {
+    XML_ATTR
+        .captures_iter(tag)
+        .find(|captures| &captures[1] == name)
+        .map(|captures| captures[2].to_string())
+}
+
+/// Reads the sheet and style XML of an XLSX file. Returns empty XML if the
+/// file cannot be read.
+fn excel_xml(file: &[u8]) -> ExcelXml {
+    let mut xml = ExcelXml::default();
+    let Ok(mut archive) = zip::ZipArchive::new(Cursor::new(file)) else {
+        return xml;
+    };
+    let mut read_file = |name: &str| -> Option<String> {
+        let mut contents = String::new();
+        archive
+            .by_name(name)
+            .ok()?
+            .read_to_string(&mut contents)
+            .ok()?;
+        Some(contents)
+    };
+    xml.styles = read_file("xl/styles.xml");
+    let (Some(workbook), Some(rels)) = (
+        read_file("xl/workbook.xml"),
+        read_file("xl/_rels/workbook.xml.rels"),
+    ) else {
+        return xml;
+    };
+
+    // relationship id -> path of the sheet xml
+    let targets = RELATIONSHIP_TAG
+        .find_iter(&rels)
+        .filter_map(|tag| {
+            let id = xml_attr(tag.as_str(), "Id")?;
+            let target = xml_attr(tag.as_str(), "Target")?;
+            let path = match target.strip_prefix('/') {
+                Some(absolute) => absolute.to_string(),
+                None => format!("xl/{target}"),
+            };
+            Some((id, path))
+        })
+        .collect::<HashMap<_, _>>();
+
+    for tag in SHEET_TAG.find_iter(&workbook) {
+        let (Some(name), Some(id)) = (
+            xml_attr(tag.as_str(), "name"),
+            xml_attr(tag.as_str(), "r:id"),
+        ) else {
+            continue;
+        };
+        if let Some(sheet_xml) = targets.get(&id).and_then(|path| read_file(path)) {
+            xml.sheets.insert(unescape_xml(&name), sheet_xml);
+        }
+    }
+    xml
+}
+
+/// Parses a cell reference such as `B12` into a zero-based position from A1.
+fn excel_cell_pos(column: &str, row: &str) -> Option<Pos> {
+    Some(Pos {
+        x: column_from_name(column)?,
+        y: row.parse::<i64>().ok()? - 1,
+    })
+}
+
+/// Reads the merged cells of each sheet in an XLSX file, keyed by sheet name.
+fn excel_merged_cells(xml: &ExcelXml) -> HashMap<String, Vec<Rect>> {
+    xml.sheets
+        .iter()
+        .filter_map(|(name, sheet_xml)| {
+            let rects = MERGE_CELL
+                .captures_iter(sheet_xml)
+                .filter_map(|captures| {
+                    Some(Rect::new_span(
+                        excel_cell_pos(&captures[1], &captures[2])?,
+                        excel_cell_pos(&captures[3], &captures[4])?,
+                    ))
+                })
+                .collect::<Vec<_>>();
+            (!rects.is_empty()).then(|| (name.clone(), rects))
+        })
+        .collect()
+}
+
+/// Returns the code of a built-in XLSX number format. General and date
+/// formats are not included.
+fn excel_builtin_number_format(id: u32) -> Option<&'static str> {
+    Some(match id {
+        1 => "0",
+        2 => "0.00",
+        3 => "#,##0",
+        4 => "#,##0.00",
+        9 => "0%",
+        10 => "0.00%",
+        11 => "0.00E+00",
+        12 => "# ?/?",
+        13 => "# ??/??",
+        14 => "m/d/yyyy",
+        15 => "d-mmm-yy",
+        16 => "d-mmm",
+        17 => "mmm-yy",
+        18 => "h:mm AM/PM",
+        19 => "h:mm:ss AM/PM",
+        20 => "h:mm",
+        21 => "h:mm:ss",
+        22 => "m/d/yyyy h:mm",
+        37 => "#,##0 ;(#,##0)",
+        38 => "#,##0 ;[Red](#,##0)",
+        39 => "#,##0.00;(#,##0.00)",
+        40 => "#,##0.00;[Red](#,##0.00)",
+        45 => "mm:ss",
+        46 => "[h]:mm:ss",
+        47 => "mm:ss.0",
+        48 => "##0.0E+0",
+        49 => "@",
+        _ => return None,
+    })
+}
+
+/// Returns true if a format code contains date or time parts.
+fn is_date_format(code: &str) -> bool {
+    let mut chars = code.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                chars.by_ref().find(|&c| c == '"');
+            }
+            '\\' | '_' | '*' => {
+                chars.next();
+            }
+            '[' => {
+                let bracket: String = chars.by_ref().take_while(|&c| c != ']').collect();
+                if bracket.chars().all(|c| "hHmMsS".contains(c)) && !bracket.is_empty() {
+                    return true;
+                }
+            }
+            'd' | 'D' | 'm' | 'M' | 'y' | 'Y' | 'h' | 'H' | 's' | 'S' => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Reads the number format of each cell in an XLSX file, keyed by sheet
+/// name. Date formats become date/time formats. Cells with General formats
+/// are not included.
+fn excel_number_formats(xml: &ExcelXml) -> HashMap<String, Vec<(Pos, NumericFormatKind)>> {
+    let Some(styles) = &xml.styles else {
+        return HashMap::new();
+    };
+    let custom_formats = NUM_FMT_TAG
+        .find_iter(styles)
+        .filter_map(|tag| {
+            let id = xml_attr(tag.as_str(), "numFmtId")?.parse::<u32>().ok()?;
+            let code = xml_attr(tag.as_str(), "formatCode")?;
+            Some((id, unescape_xml(&code)))
+        })
+        .collect::<HashMap<_, _>>();
+
+    // the style index of a cell is an index into cellXfs
+    let cell_xfs = styles
+        .split_once("<cellXfs")
+        .and_then(|(_, rest)| rest.split_once("</cellXfs>"))
+        .map_or("", |(cell_xfs, _)| cell_xfs);
+    let style_formats = XF_TAG
+        .find_iter(cell_xfs)
+        .map(|tag| {
+            let id = xml_attr(tag.as_str(), "numFmtId")?.parse::<u32>().ok()?;
+            let code = custom_formats
+                .get(&id)
+                .cloned()
+                .or_else(|| excel_builtin_number_format(id).map(str::to_string))?;
+            if code.eq_ignore_ascii_case("general") {
+                None
+            } else if is_date_format(&code) {
+                Some(NumericFormatKind::DateTime(code))
+            } else {
+                Some(NumericFormatKind::Custom(code))
+            }
+        })
+        .collect::<Vec<_>>();
+
+    xml.sheets
+        .iter()
+        .filter_map(|(name, sheet_xml)| {
+            let formats = CELL_TAG
+                .find_iter(sheet_xml)
+                .filter_map(|tag| {
+                    let style = xml_attr(tag.as_str(), "s")?.parse::<usize>().ok()?;
+                    let code = style_formats.get(style)?.clone()?;
+                    let reference = xml_attr(tag.as_str(), "r")?;
+                    let captures = CELL_REFERENCE.captures(&reference)?;
+                    Some((excel_cell_pos(&captures[1], &captures[2])?, code))
+                })
+                .collect::<Vec<_>>();
+            (!formats.is_empty()).then(|| (name.clone(), formats))
+        })
+        .collect()
+}
+
+/// Groups cell formats into runs of consecutive rows in a column with the
+/// same format. Returns the first cell, length, and format of each run.
+fn excel_format_runs(formats: &[(Pos, NumericFormatKind)]) -> Vec<(Pos, usize, NumericFormatKind)> {
+    let mut formats = formats.iter().collect::<Vec<_>>();
+    formats.sort_by_key(|(pos, _)| (pos.x, pos.y));
+
+    let mut runs: Vec<(Pos, usize, NumericFormatKind)> = vec![];
+    for (pos, kind) in formats {
+        match runs.last_mut() {
+            Some((start, len, run_kind))
+                if start.x == pos.x && start.y + *len as i64 == pos.y && run_kind == kind =>
+            {
+                *len += 1;
+            }
+            _ => runs.push((*pos, 1, kind.clone())),
+        }
+    }
+    runs
+}
+
+/// Unescapes the predefined XML entities in an attribute value.
+fn unescape_xml(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
 
 impl GridController {
     /// Imports a CSV file into the grid.
@@ -18,7 +281,15 @@ impl GridController {
         insert_at: Pos,
     ) -> Result<Vec<Operation>> {
         let error = |message: String| anyhow!("Error parsing CSV file {}: {}", file_name, message);
-        let width = csv::ReaderBuilder::new().from_reader(file).headers()?.len() as u32;
+        let delimiter = detect_csv_delimiter(
+            &String::from_utf8_lossy(file),
+            self.grid.locale().csv_delimiter(),
+        ) as u8;
+        let width = csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .from_reader(file)
+            .headers()?
+            .len() as u32;
 
         if width == 0 {
             bail!("empty files cannot be processed");
@@ -26,6 +297,7 @@ impl GridController {
 
         let mut reader = csv::ReaderBuilder::new()
             .has_headers(false)
+            .delimiter(delimiter)
             .from_reader(file);
 
         let mut ops = vec![] as Vec<Operation>;
@@ -71,6 +343,9 @@ impl GridController {
         let error =
             |message: String| anyhow!("Error parsing Excel file {}: {}", file_name, message);
 
+        let xml = excel_xml(&file);
+        let merged_cells = excel_merged_cells(&xml);
+        let number_formats = excel_number_formats(&xml);
         let cursor = Cursor::new(file);
         let mut workbook: Xlsx<_> =
             ExcelReader::new(cursor).map_err(|e: XlsxError| error(e.to_string()))?;
@@ -89,6 +364,31 @@ impl GridController {
                     .map_err(|e: XlsxError| error(e.to_string()))?;
                 let size = range.get_size();
 
+                // values are inserted from the start of the range, not A1
+                let (start_y, start_x) = range.start().unwrap_or_default();
+                for rect in merged_cells.get(&sheet_name).into_iter().flatten() {
+                    let mut rect = *rect;
+                    rect.translate(insert_at.x - start_x as i64, insert_at.y - start_y as i64);
+                    ops.push(Operation::MergeCells {
+                        sheet_rect: rect.to_sheet_rect(sheet_id),
+                    });
+                }
+                let formats = number_formats
+                    .get(&sheet_name)
+                    .map_or(&[][..], Vec::as_slice);
+                for (pos, len, kind) in excel_format_runs(formats) {
+                    let numeric_format = NumericFormat { kind, symbol: None };
+                    let mut rect = Rect::from_numbers(pos.x, pos.y, 1, len as i64);
+                    rect.translate(insert_at.x - start_x as i64, insert_at.y - start_y as i64);
+                    ops.push(Operation::SetCellFormats {
+                        sheet_rect: rect.to_sheet_rect(sheet_id),
+                        attr: CellFmtArray::NumericFormat(RunLengthEncoding::repeat(
+                            Some(numeric_format),
+                            len,
+                        )),
+                    });
+                }
+
                 for row in range.rows() {
                     for col in row.iter() {
                         let cell_value = match col {
@@ -99,21 +399,21 @@ impl GridController {
                             ExcelData::Float(ref value) => {
                                 CellValue::unpack_str_float(&value.to_string(), CellValue::Blank)
                             }
-                            // TODO(ddimaria): implement when implementing Instant
-                            // ExcelData::DateTime(ref value) => match value.is_datetime() {
-                            //     true => value.as_datetime().map_or_else(
-                            //         || CellValue::Blank,
-                            //         |v| CellValue::Instant(v.into()),
-                            //     ),
-                            //     false => CellValue::Text(value.to_string()),
-                            // },
-                            // TODO(ddimaria): remove when implementing Instant
                             ExcelData::DateTime(ref value) => match value.is_datetime() {
                                 true => value.as_datetime().map_or_else(
                                     || CellValue::Blank,
-                                    |v| CellValue::Text(v.to_string()),
+                                    |v| CellValue::Instant(v.into()),
+                                ),
+                                false => value.as_duration().map_or_else(
+                                    || CellValue::Text(value.to_string()),
+                                    |v| {
+                                        CellValue::Duration(Duration {
+                                            years: 0,
+                                            months: 0,
+                                            seconds: v.num_milliseconds() as f64 / 1000.0,
+                                        })
+                                    },
                                 ),
-                                false => CellValue::Text(value.to_string()),
                             },
                             ExcelData::Int(ref value) => {
                                 CellValue::unpack_str_float(&value.to_string(), CellValue::Blank)
@@ -191,3 +491,119 @@ impl GridController {
         Ok(ops)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::io::Write;
+
+    use super::*;
+
+    #[test]
+    fn reads_excel_merged_cells() {
+        let mut file = Cursor::new(vec![]);
+        let mut zip = zip::ZipWriter::new(&mut file);
+        let options = zip::write::FileOptions::default();
+        let mut add = |name: &str, contents: &str| {
+            zip.start_file(name, options).unwrap();
+            zip.write_all(contents.as_bytes()).unwrap();
+        };
+        add(
+            "xl/workbook.xml",
+            r#"<workbook><sheets><sheet name="A &amp; B" sheetId="1" r:id="rId1"/></sheets></workbook>"#,
+        );
+        add(
+            "xl/_rels/workbook.xml.rels",
+            r#"<Relationships><Relationship Id="rId1" Type="worksheet" Target="worksheets/sheet1.xml"/></Relationships>"#,
+        );
+        add(
+            "xl/worksheets/sheet1.xml",
+            r#"<worksheet><mergeCells count="2"><mergeCell ref="A1:B2"/><mergeCell ref="AA10:AB10"/></mergeCells></worksheet>"#,
+        );
+        zip.finish().unwrap();
+        drop(zip);
+
+        let merged_cells = excel_merged_cells(&excel_xml(file.get_ref()));
+        assert_eq!(
+            merged_cells.get("A & B"),
+            Some(&vec![
+                Rect::from_numbers(0, 0, 2, 2),
+                Rect::from_numbers(26, 9, 2, 1),
+            ])
+        );
+        assert!(excel_merged_cells(&excel_xml(b"not a zip")).is_empty());
+    }
+
+    #[test]
+    fn reads_excel_number_formats() {
+        let styles = r#"<styleSheet>
+            <numFmts count="2">
+                <numFmt numFmtId="164" formatCode="&quot;$&quot;#,##0;(&quot;$&quot;#,##0)"/>
+                <numFmt numFmtId="165" formatCode="yyyy-mm-dd"/>
+            </numFmts>
+            <cellStyleXfs count="1"><xf numFmtId="4"/></cellStyleXfs>
+            <cellXfs count="5">
+                <xf numFmtId="0"/>
+                <xf numFmtId="164" applyNumberFormat="1"/>
+                <xf numFmtId="10" applyNumberFormat="1"><alignment horizontal="left"/></xf>
+                <xf numFmtId="165" applyNumberFormat="1"/>
+                <xf numFmtId="14" applyNumberFormat="1"/>
+            </cellXfs>
+        </styleSheet>"#;
+        let sheet = r#"<worksheet><sheetData><row r="1">
+            <c r="A1" s="1"><v>1</v></c><c r="B1" s="2"><v>0.5</v></c>
+            <c r="C1" s="3"><v>45000</v></c><c r="D1" s="4"><v>45000</v></c>
+            <c r="E1"><v>1</v></c><c r="F1" s="0"><v>1</v></c>
+        </row></sheetData></worksheet>"#;
+        let xml = ExcelXml {
+            sheets: HashMap::from([("Sheet1".to_string(), sheet.to_string())]),
+            styles: Some(styles.to_string()),
+        };
+        assert_eq!(
+            excel_number_formats(&xml).get("Sheet1"),
+            Some(&vec![
+                (
+                    Pos { x: 0, y: 0 },
+                    NumericFormatKind::Custom("\"$\"#,##0;(\"$\"#,##0)".into())
+                ),
+                (
+                    Pos { x: 1, y: 0 },
+                    NumericFormatKind::Custom("0.00%".into())
+                ),
+                (
+                    Pos { x: 2, y: 0 },
+                    NumericFormatKind::DateTime("yyyy-mm-dd".into())
+                ),
+                (
+                    Pos { x: 3, y: 0 },
+                    NumericFormatKind::DateTime("m/d/yyyy".into())
+                ),
+            ])
+        );
+
+        assert!(is_date_format("[h]:mm"));
+        assert!(!is_date_format("[Red]#,##0.00;\"Days\""));
+    }
+
+    #[test]
+    fn groups_excel_formats_into_runs() {
+        let percent = NumericFormatKind::Custom("0%".into());
+        let date = NumericFormatKind::DateTime("m/d/yyyy".into());
+        let formats = [
+            (Pos { x: 0, y: 2 }, percent.clone()),
+            (Pos { x: 0, y: 0 }, percent.clone()),
+            (Pos { x: 0, y: 1 }, percent.clone()),
+            (Pos { x: 0, y: 3 }, date.clone()),
+            (Pos { x: 0, y: 5 }, date.clone()),
+            (Pos { x: 1, y: 0 }, percent.clone()),
+        ];
+        assert_eq!(
+            excel_format_runs(&formats),
+            vec![
+                (Pos { x: 0, y: 0 }, 3, percent.clone()),
+                (Pos { x: 0, y: 3 }, 1, date.clone()),
+                (Pos { x: 0, y: 5 }, 1, date),
+                (Pos { x: 1, y: 0 }, 1, percent),
+            ]
+        );
+    }
+}
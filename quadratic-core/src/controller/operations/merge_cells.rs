@@ -0,0 +1,40 @@
+use super::operation::Operation;
+use crate::{cell_values::CellValues, controller::GridController, SheetPos, SheetRect};
+
+impl GridController {
+    /// Creates operations to merge the cells in a sheet_rect. Only the value of
+    /// the top-left cell is kept; all other values in the region are deleted.
+    pub fn merge_cells_operations(&self, sheet_rect: SheetRect) -> Vec<Operation> {
+        let mut ops = vec![];
+        if sheet_rect.len() <= 1 {
+            return ops;
+        }
+        ops.push(Operation::MergeCells { sheet_rect });
+
+        let w = sheet_rect.width() as u32;
+        let h = sheet_rect.height() as u32;
+        let SheetRect { min, sheet_id, .. } = sheet_rect;
+
+        // rest of the first row
+        if w > 1 {
+            ops.push(Operation::SetCellValues {
+                sheet_pos: SheetPos::new(sheet_id, min.x + 1, min.y),
+                values: CellValues::new(w - 1, 1),
+            });
+        }
+        // all remaining rows
+        if h > 1 {
+            ops.push(Operation::SetCellValues {
+                sheet_pos: SheetPos::new(sheet_id, min.x, min.y + 1),
+                values: CellValues::new(w, h - 1),
+            });
+        }
+        ops
+    }
+
+    /// Creates operations to unmerge any merged cells that intersect the
+    /// sheet_rect.
+    pub fn unmerge_cells_operations(&self, sheet_rect: SheetRect) -> Vec<Operation> {
+        vec![Operation::UnmergeCells { sheet_rect }]
+    }
+}
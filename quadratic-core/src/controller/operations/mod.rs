@@ -5,9 +5,19 @@ pub mod autocomplete;
 ///
 pub mod borders;
 pub mod cell_value;
+pub mod charts;
 pub mod clipboard;
 pub mod code_cell;
+pub mod comments;
+pub mod conditional_formats;
+pub mod find_replace;
 pub mod formatting;
+pub mod html_table;
 pub mod import;
+pub mod merge_cells;
+pub mod move_cells;
 pub mod operation;
+pub mod plain_text;
+pub mod protection;
 pub mod sheets;
+pub mod validations;
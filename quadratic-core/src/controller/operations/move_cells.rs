@@ -0,0 +1,275 @@
+use super::{clipboard::Clipboard, operation::Operation};
+use crate::{
+    cell_values::CellValues,
+    controller::{user_actions::clipboard::PasteSpecial, GridController},
+    formulas::{replace_cell_references, CellRef, CellRefCoord, RangeRef},
+    grid::{get_cell_borders_in_rect, CodeCellLanguage, CodeRun},
+    CellValue, Pos, Rect, SheetPos, SheetRect,
+};
+
+/// A move of the cells in `source` so that its top-left cell ends up at `dest`.
+#[derive(Debug, Copy, Clone)]
+struct CellsMove {
+    source: SheetRect,
+    dest: SheetPos,
+}
+
+impl CellsMove {
+    /// Returns true if the rect is entirely inside the moved cells.
+    fn moves(&self, sheet_rect: SheetRect) -> bool {
+        sheet_rect.sheet_id == self.source.sheet_id
+            && self
+                .source
+                .contains(sheet_rect.min.to_sheet_pos(sheet_rect.sheet_id))
+            && self
+                .source
+                .contains(sheet_rect.max.to_sheet_pos(sheet_rect.sheet_id))
+    }
+
+    /// Returns where a position inside the moved cells ends up.
+    fn translate(&self, pos: Pos) -> Pos {
+        Pos {
+            x: pos.x - self.source.min.x + self.dest.x,
+            y: pos.y - self.source.min.y + self.dest.y,
+        }
+    }
+
+    /// Returns where a rect inside the moved cells ends up, or the rect itself
+    /// if it was not moved.
+    fn translate_sheet_rect(&self, sheet_rect: SheetRect) -> SheetRect {
+        if self.moves(sheet_rect) {
+            SheetRect {
+                min: self.translate(sheet_rect.min),
+                max: self.translate(sheet_rect.max),
+                sheet_id: self.dest.sheet_id,
+            }
+        } else {
+            sheet_rect
+        }
+    }
+}
+
+impl GridController {
+    /// Rewrites a formula that is moving from `old` to `new` so that its
+    /// references still point at the same cells after the move. References
+    /// entirely inside the moved cells follow them to their new location.
+    fn move_formula_references(
+        &self,
+        code: &str,
+        old: SheetPos,
+        new: SheetPos,
+        cells_move: CellsMove,
+    ) -> String {
+        let rebase = |coord: CellRefCoord, target: i64, base: i64| match coord {
+            CellRefCoord::Relative(_) => CellRefCoord::Relative(target - base),
+            CellRefCoord::Absolute(_) => CellRefCoord::Absolute(target),
+        };
+
+        replace_cell_references(code, old.into(), |range_ref| {
+            let (start, end) = match range_ref {
+                RangeRef::Cell { pos } => (pos, pos),
+                RangeRef::CellRange { start, end } => (start, end),
+                _ => return None,
+            };
+            let sheet_id = match &start.sheet {
+                Some(sheet_name) => self.grid.try_sheet_from_name(sheet_name.clone())?.id,
+                None => old.sheet_id,
+            };
+            let referenced = SheetRect::new_pos_span(
+                start.resolve_from(old.into()),
+                end.resolve_from(old.into()),
+                sheet_id,
+            );
+            let moved = cells_move.moves(referenced);
+            if !moved && old == new {
+                return None;
+            }
+            let target = cells_move.translate_sheet_rect(referenced);
+
+            // a sheet name is needed once the formula and its target are on different sheets
+            let sheet = if start.sheet.is_some() || target.sheet_id != new.sheet_id {
+                Some(self.try_sheet(target.sheet_id)?.name.clone())
+            } else {
+                None
+            };
+            let rebased = |cell_ref: &CellRef, corner: Pos, sheet: Option<String>| CellRef {
+                sheet,
+                x: rebase(cell_ref.x, corner.x, new.x),
+                y: rebase(cell_ref.y, corner.y, new.y),
+            };
+
+            // the corners of a range are not normalized, so keep track of which is which
+            let translate = |pos: Pos| {
+                if moved {
+                    cells_move.translate(pos)
+                } else {
+                    pos
+                }
+            };
+            let start_pos = translate(start.resolve_from(old.into()));
+            let end_pos = translate(end.resolve_from(old.into()));
+            let range_ref = match range_ref {
+                RangeRef::CellRange { .. } => RangeRef::CellRange {
+                    start: rebased(start, start_pos, sheet),
+                    end: rebased(end, end_pos, None),
+                },
+                _ => RangeRef::Cell {
+                    pos: rebased(start, start_pos, sheet),
+                },
+            };
+            Some(range_ref.a1_string(new.into()))
+        })
+    }
+
+    /// Creates operations to move the cells in `source` (values, code cells
+    /// with their runs, formats, borders, merged cells and comments) so that
+    /// its top-left cell is at `dest`. Formulas that reference the moved cells
+    /// are updated to point at their new location. Python code is not
+    /// rewritten.
+    pub fn move_cells_operations(&mut self, source: SheetRect, dest: SheetPos) -> Vec<Operation> {
+        if source.min.to_sheet_pos(source.sheet_id) == dest {
+            return vec![];
+        }
+        let Some(sheet) = self.try_sheet(source.sheet_id) else {
+            return vec![];
+        };
+        let cells_move = CellsMove { source, dest };
+        let rect: Rect = source.into();
+
+        let mut cells = CellValues::new(source.width() as u32, source.height() as u32);
+        for y in source.y_range() {
+            for x in source.x_range() {
+                let pos = Pos { x, y };
+                let Some(mut value) = sheet.cell_value(pos) else {
+                    continue;
+                };
+                if let CellValue::Code(code_cell) = &mut value {
+                    if code_cell.language == CodeCellLanguage::Formula {
+                        code_cell.code = self.move_formula_references(
+                            &code_cell.code,
+                            pos.to_sheet_pos(source.sheet_id),
+                            cells_move.translate(pos).to_sheet_pos(dest.sheet_id),
+                            cells_move,
+                        );
+                    }
+                }
+                cells.set((x - source.min.x) as u32, (y - source.min.y) as u32, value);
+            }
+        }
+
+        let code_runs: Vec<(Pos, CodeRun)> = sheet
+            .code_runs
+            .iter()
+            .filter(|(pos, _)| rect.contains(**pos))
+            .map(|(pos, code_run)| {
+                let mut code_run = code_run.clone();
+                if matches!(
+                    sheet.cell_value(*pos),
+                    Some(CellValue::Code(code_cell)) if code_cell.language == CodeCellLanguage::Formula
+                ) {
+                    code_run.cells_accessed = code_run
+                        .cells_accessed
+                        .into_iter()
+                        .map(|sheet_rect| cells_move.translate_sheet_rect(sheet_rect))
+                        .collect();
+                }
+                (cells_move.translate(*pos), code_run)
+            })
+            .collect();
+
+        let merged_cells = sheet
+            .merged_cells_in_rect(rect)
+            .into_iter()
+            .filter(|merged| rect.contains(merged.min) && rect.contains(merged.max))
+            .map(|mut merged| {
+                merged.translate(-source.min.x, -source.min.y);
+                merged
+            })
+            .collect();
+        let clipboard = Clipboard {
+            w: source.width() as u32,
+            h: source.height() as u32,
+            cells,
+            values: CellValues::new(0, 0),
+            formats: self.get_all_cell_formats(source),
+            borders: get_cell_borders_in_rect(sheet, rect),
+            column_widths: vec![],
+            merged_cells,
+            comments: sheet
+                .comment_threads_in_rect(rect)
+                .into_iter()
+                .map(|(pos, thread)| {
+                    (
+                        Pos {
+                            x: pos.x - source.min.x,
+                            y: pos.y - source.min.y,
+                        },
+                        thread.clone(),
+                    )
+                })
+                .collect(),
+        };
+
+        // formulas outside the moved cells that reference them
+        let mut references = vec![];
+        for sheet in self.grid.sheets() {
+            for pos in sheet.code_runs.keys() {
+                let sheet_pos = pos.to_sheet_pos(sheet.id);
+                if source.contains(sheet_pos) {
+                    continue;
+                }
+                let Some(CellValue::Code(mut code_cell)) = sheet.cell_value(*pos) else {
+                    continue;
+                };
+                if code_cell.language != CodeCellLanguage::Formula {
+                    continue;
+                }
+                let code =
+                    self.move_formula_references(&code_cell.code, sheet_pos, sheet_pos, cells_move);
+                if code != code_cell.code {
+                    code_cell.code = code;
+                    references.push((sheet_pos, CellValue::Code(code_cell)));
+                }
+            }
+        }
+
+        let mut ops = self.delete_values_and_formatting_operations(source);
+        ops.extend(self.delete_comment_threads_operations(source));
+        if self
+            .try_sheet(source.sheet_id)
+            .is_some_and(|sheet| !sheet.merged_cells_in_rect(rect).is_empty())
+        {
+            ops.push(Operation::UnmergeCells { sheet_rect: source });
+        }
+
+        // code cells keep their runs instead of being computed again
+        ops.extend(
+            self.set_clipboard_cells(dest, clipboard, PasteSpecial::None)
+                .into_iter()
+                .filter(|op| !matches!(op, Operation::ComputeCode { .. })),
+        );
+        // moved runs are added after the destination's runs, in their original order
+        let index = self
+            .try_sheet(dest.sheet_id)
+            .map_or(0, |sheet| sheet.code_runs.len());
+        ops.extend(
+            code_runs
+                .into_iter()
+                .enumerate()
+                .map(|(i, (pos, code_run))| Operation::SetCodeRun {
+                    sheet_pos: pos.to_sheet_pos(dest.sheet_id),
+                    code_run: Some(code_run),
+                    index: index + i,
+                }),
+        );
+
+        references.into_iter().for_each(|(sheet_pos, value)| {
+            ops.push(Operation::SetCellValues {
+                sheet_pos,
+                values: CellValues::from(value),
+            });
+            ops.push(Operation::ComputeCode { sheet_pos });
+        });
+        ops
+    }
+}
@@ -3,7 +3,14 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     cell_values::CellValues,
-    grid::{formatting::CellFmtArray, CodeRun, Sheet, SheetBorders, SheetId},
+    grid::{
+        formatting::CellFmtArray,
+        sheet::{
+            charts::Chart, comments::Comment, conditional_formats::ConditionalFormat,
+            protection::SheetProtection, validations::Validation,
+        },
+        CodeRun, CommentId, Locale, Sheet, SheetBorders, SheetId,
+    },
     SheetPos, SheetRect,
 };
 
@@ -29,6 +36,50 @@ pub enum Operation {
         sheet_rect: SheetRect,
         borders: SheetBorders,
     },
+    MergeCells {
+        sheet_rect: SheetRect,
+    },
+    UnmergeCells {
+        sheet_rect: SheetRect,
+    },
+    SetValidations {
+        sheet_id: SheetId,
+        validations: Vec<Validation>,
+    },
+    SetConditionalFormats {
+        sheet_id: SheetId,
+        conditional_formats: Vec<ConditionalFormat>,
+    },
+    AddComment {
+        sheet_pos: SheetPos,
+        comment: Comment,
+    },
+    EditComment {
+        sheet_pos: SheetPos,
+        comment_id: CommentId,
+        text: String,
+    },
+    DeleteComment {
+        sheet_pos: SheetPos,
+        comment_id: CommentId,
+    },
+    SetCommentResolved {
+        sheet_pos: SheetPos,
+        resolved: bool,
+    },
+    SetSheetProtection {
+        sheet_id: SheetId,
+        protection: SheetProtection,
+    },
+    SetChart {
+        sheet_pos: SheetPos,
+        chart: Option<Chart>,
+    },
+
+    // Workbook operations
+    SetLocale {
+        locale: Locale,
+    },
 
     // Sheet metadata operations
 
@@ -53,6 +104,11 @@ pub enum Operation {
         sheet_id: SheetId,
         color: Option<String>,
     },
+    SetSheetFrozen {
+        sheet_id: SheetId,
+        rows: u32,
+        columns: u32,
+    },
     ReorderSheet {
         target: SheetId,
         order: String,
@@ -69,6 +125,16 @@ pub enum Operation {
         row: i64,
         new_size: f64,
     },
+    SetColumnHidden {
+        sheet_id: SheetId,
+        column: i64,
+        hidden: bool,
+    },
+    SetRowHidden {
+        sheet_id: SheetId,
+        row: i64,
+        hidden: bool,
+    },
 
     // used for User transactions to set cursor (eg, Paste)
     SetCursor {
@@ -111,6 +177,15 @@ impl fmt::Display for Operation {
                 "SetSheetColor {{ sheet_id: {}, color: {:?} }}",
                 sheet_id, color
             ),
+            Operation::SetSheetFrozen {
+                sheet_id,
+                rows,
+                columns,
+            } => write!(
+                fmt,
+                "SetSheetFrozen {{ sheet_id: {}, rows: {}, columns: {} }}",
+                sheet_id, rows, columns
+            ),
             Operation::ReorderSheet { target, order } => write!(
                 fmt,
                 "ReorderSheet {{ target: {}, order: {} }}",
@@ -134,7 +209,98 @@ impl fmt::Display for Operation {
                 "ResizeRow {{ sheet_id: {}, row: {}, new_size: {} }}",
                 sheet_id, row, new_size
             ),
+            Operation::SetColumnHidden {
+                sheet_id,
+                column,
+                hidden,
+            } => write!(
+                fmt,
+                "SetColumnHidden {{ sheet_id: {}, column: {}, hidden: {} }}",
+                sheet_id, column, hidden
+            ),
+            Operation::SetRowHidden {
+                sheet_id,
+                row,
+                hidden,
+            } => write!(
+                fmt,
+                "SetRowHidden {{ sheet_id: {}, row: {}, hidden: {} }}",
+                sheet_id, row, hidden
+            ),
             Operation::SetBorders { .. } => write!(fmt, "SetBorders {{ todo }}"),
+            Operation::MergeCells { sheet_rect } => {
+                write!(fmt, "MergeCells {{ sheet_rect: {} }}", sheet_rect)
+            }
+            Operation::UnmergeCells { sheet_rect } => {
+                write!(fmt, "UnmergeCells {{ sheet_rect: {} }}", sheet_rect)
+            }
+            Operation::SetValidations {
+                sheet_id,
+                validations,
+            } => write!(
+                fmt,
+                "SetValidations {{ sheet_id: {}, validations: {} }}",
+                sheet_id,
+                validations.len()
+            ),
+            Operation::SetConditionalFormats {
+                sheet_id,
+                conditional_formats,
+            } => write!(
+                fmt,
+                "SetConditionalFormats {{ sheet_id: {}, conditional_formats: {} }}",
+                sheet_id,
+                conditional_formats.len()
+            ),
+            Operation::AddComment { sheet_pos, comment } => write!(
+                fmt,
+                "AddComment {{ sheet_pos: {}, comment_id: {} }}",
+                sheet_pos, comment.id
+            ),
+            Operation::EditComment {
+                sheet_pos,
+                comment_id,
+                ..
+            } => write!(
+                fmt,
+                "EditComment {{ sheet_pos: {}, comment_id: {} }}",
+                sheet_pos, comment_id
+            ),
+            Operation::DeleteComment {
+                sheet_pos,
+                comment_id,
+            } => write!(
+                fmt,
+                "DeleteComment {{ sheet_pos: {}, comment_id: {} }}",
+                sheet_pos, comment_id
+            ),
+            Operation::SetCommentResolved {
+                sheet_pos,
+                resolved,
+            } => write!(
+                fmt,
+                "SetCommentResolved {{ sheet_pos: {}, resolved: {} }}",
+                sheet_pos, resolved
+            ),
+            Operation::SetChart { sheet_pos, chart } => write!(
+                fmt,
+                "SetChart {{ sheet_pos: {}, chart_type: {:?} }}",
+                sheet_pos,
+                chart.as_ref().map(|chart| chart.chart_type)
+            ),
+            Operation::SetSheetProtection {
+                sheet_id,
+                protection,
+            } => write!(
+                fmt,
+                "SetSheetProtection {{ sheet_id: {}, locked: {}, ranges: {} }}",
+                sheet_id,
+                protection.locked.is_some(),
+                protection.ranges.len()
+            ),
+            Operation::SetLocale { locale } => {
+                write!(fmt, "SetLocale {{ locale: {:?} }}", locale)
+            }
             Operation::SetCursor { sheet_rect } => {
                 write!(fmt, "SetCursor {{ sheet_rect: {} }}", sheet_rect)
             }
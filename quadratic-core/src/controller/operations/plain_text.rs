@@ -0,0 +1,256 @@
+//! Parses delimited plain text from the clipboard. Spreadsheets put
+//! tab-separated values on the clipboard and quote any field that contains a
+//! tab, newline, or quote; other apps may use comma-separated values. Also
+//! detects the delimiter of imported CSV files.
+
+/// Finds the delimiter for clipboard text. Tabs are used if there is a tab
+/// outside of a quoted field. Commas are only used when there are multiple
+/// lines, each has the same number of commas, and the text looks like CSV:
+/// a field is quoted or two neighboring fields are not numbers. This keeps a
+/// column of numbers like `1,234` in one column.
+pub fn detect_delimiter(text: &str) -> char {
+    let mut tabs = 0;
+    let mut commas_per_line = vec![0];
+    let quoted = for_each_unquoted(text, |c| match c {
+        '\t' => tabs += 1,
+        ',' => {
+            if let Some(commas) = commas_per_line.last_mut() {
+                *commas += 1;
+            }
+        }
+        '\n' => commas_per_line.push(0),
+        _ => (),
+    });
+    if tabs > 0 {
+        return '\t';
+    }
+
+    // a trailing newline does not start a new line
+    if text.ends_with('\n') {
+        commas_per_line.pop();
+    }
+    let consistent = commas_per_line
+        .first()
+        .is_some_and(|first| *first > 0 && commas_per_line.iter().all(|commas| commas == first));
+    if commas_per_line.len() > 1 && consistent && (quoted || has_text_fields(text)) {
+        ','
+    } else {
+        '\t'
+    }
+}
+
+/// Finds the delimiter of a CSV file from its first lines: a comma,
+/// semicolon, or tab that appears the same number of times on each line.
+/// `preferred` (the locale's delimiter) breaks ties and is used when no
+/// delimiter is found.
+pub fn detect_csv_delimiter(text: &str, preferred: char) -> char {
+    const CANDIDATES: [char; 3] = [',', ';', '\t'];
+    const SAMPLE_LINES: usize = 20;
+
+    let sample = match text.match_indices('\n').nth(SAMPLE_LINES - 1) {
+        Some((end, _)) => &text[..=end],
+        None => text,
+    };
+
+    let mut counts_per_line = vec![[0usize; 3]];
+    for_each_unquoted(sample, |c| {
+        if c == '\n' {
+            counts_per_line.push([0; 3]);
+        } else if let Some(index) = CANDIDATES.iter().position(|candidate| *candidate == c) {
+            if let Some(counts) = counts_per_line.last_mut() {
+                counts[index] += 1;
+            }
+        }
+    });
+    if sample.ends_with('\n') {
+        counts_per_line.pop();
+    }
+
+    let consistent = CANDIDATES
+        .iter()
+        .enumerate()
+        .filter_map(|(index, candidate)| {
+            let first = counts_per_line.first()?[index];
+            (first > 0 && counts_per_line.iter().all(|counts| counts[index] == first))
+                .then_some((*candidate, first))
+        })
+        .collect::<Vec<_>>();
+    if consistent
+        .iter()
+        .any(|(candidate, _)| *candidate == preferred)
+    {
+        return preferred;
+    }
+    consistent
+        .iter()
+        .max_by_key(|(_, count)| *count)
+        .map_or(preferred, |(candidate, _)| *candidate)
+}
+
+/// Returns whether a line of comma-separated text has two neighboring fields
+/// that are not numbers.
+fn has_text_fields(text: &str) -> bool {
+    let is_number = |field: &String| {
+        let field = field.trim();
+        !field.is_empty()
+            && field
+                .chars()
+                .all(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | '$' | '%'))
+    };
+    parse_delimited(text, ',').iter().any(|row| {
+        row.windows(2)
+            .any(|fields| !is_number(&fields[0]) && !is_number(&fields[1]))
+    })
+}
+
+/// Calls `f` for every character that is not inside a quoted field. Returns
+/// whether any field was quoted.
+fn for_each_unquoted(text: &str, mut f: impl FnMut(char)) -> bool {
+    let mut quoted = false;
+    let mut in_quotes = false;
+    let mut field_start = true;
+    for c in text.chars() {
+        if in_quotes {
+            // an escaped quote ("") leaves and re-enters the quoted field
+            if c == '"' {
+                in_quotes = false;
+            }
+            continue;
+        }
+        if c == '"' && field_start {
+            in_quotes = true;
+            quoted = true;
+            field_start = false;
+            continue;
+        }
+        field_start = matches!(c, '\t' | ',' | ';' | '\n' | '\r');
+        f(c);
+    }
+    quoted
+}
+
+/// Parses delimited text into rows of fields. A field is quoted only if it
+/// starts with a quote; `""` inside a quoted field is a literal quote. Lines
+/// may end with `\n` or `\r\n`, and a single trailing line ending is ignored.
+/// If a quoted field is never closed, the quotes are treated as text.
+pub fn parse_delimited(text: &str, delimiter: char) -> Vec<Vec<String>> {
+    let text = text
+        .strip_suffix("\r\n")
+        .or_else(|| text.strip_suffix('\n'))
+        .unwrap_or(text);
+
+    let mut rows = vec![];
+    let mut row = vec![];
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut field_start = true;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            if c == '"' {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    in_quotes = false;
+                }
+            } else if c == '\r' && chars.peek() == Some(&'\n') {
+                // line endings inside cells are kept as \n
+            } else {
+                field.push(c);
+            }
+            continue;
+        }
+        match c {
+            '"' if field_start => {
+                in_quotes = true;
+                field_start = false;
+            }
+            c if c == delimiter => {
+                row.push(std::mem::take(&mut field));
+                field_start = true;
+            }
+            '\r' if chars.peek() == Some(&'\n') => (),
+            '\n' => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+                field_start = true;
+            }
+            c => {
+                field.push(c);
+                field_start = false;
+            }
+        }
+    }
+    if in_quotes {
+        return text
+            .split('\n')
+            .map(|line| {
+                let line = line.strip_suffix('\r').unwrap_or(line);
+                line.split(delimiter).map(String::from).collect()
+            })
+            .collect();
+    }
+    row.push(field);
+    rows.push(row);
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_delimiter() {
+        assert_eq!(detect_delimiter("a\tb\nc\td"), '\t');
+        assert_eq!(detect_delimiter("a,b\nc,d\n"), ',');
+        assert_eq!(detect_delimiter("a,b\tc"), '\t');
+        assert_eq!(detect_delimiter("$1,234"), '\t');
+        assert_eq!(detect_delimiter("1,234\n5"), '\t');
+        assert_eq!(detect_delimiter("1,234\n5,678"), '\t');
+        assert_eq!(detect_delimiter("1,234\n5,678\n"), '\t');
+        assert_eq!(detect_delimiter("Name,Amount\n5,678"), ',');
+        assert_eq!(detect_delimiter("\"1\",234\n5,678"), ',');
+        assert_eq!(detect_delimiter("\"a\tb\",c\n\"d\",e"), ',');
+    }
+
+    #[test]
+    fn detects_csv_delimiter() {
+        assert_eq!(detect_csv_delimiter("a,b,c\n1,2,3\n", ';'), ',');
+        assert_eq!(detect_csv_delimiter("a;b\n1,5;2,5\n", ','), ';');
+        assert_eq!(detect_csv_delimiter("1,5;2,5\n3,5;4,5", ';'), ';');
+        assert_eq!(detect_csv_delimiter("a\tb\n\"x;y\"\tz", ','), '\t');
+        assert_eq!(detect_csv_delimiter("a\nb", ';'), ';');
+
+        // only the first lines are checked
+        let mut csv = "a,b\n".repeat(25);
+        csv.push('c');
+        assert_eq!(detect_csv_delimiter(&csv, ';'), ',');
+    }
+
+    #[test]
+    fn parses_quoted_fields() {
+        let text = "a\t\"multi\r\nline\"\t\"tab\there\"\r\n\"say \"\"hi\"\"\"\t\tx\"y\r\n";
+        assert_eq!(
+            parse_delimited(text, '\t'),
+            vec![
+                vec!["a", "multi\nline", "tab\there"],
+                vec!["say \"hi\"", "", "x\"y"],
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_blank_lines_and_ragged_rows() {
+        assert_eq!(
+            parse_delimited("a\n\nb,c,d", ','),
+            vec![vec!["a"], vec![""], vec!["b", "c", "d"]]
+        );
+        assert_eq!(parse_delimited("", '\t'), vec![vec![""]]);
+        assert_eq!(
+            parse_delimited("\"open\ta\r\nb", '\t'),
+            vec![vec!["\"open", "a"], vec!["b"]]
+        );
+    }
+}
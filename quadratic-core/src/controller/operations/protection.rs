@@ -0,0 +1,225 @@
+use super::operation::Operation;
+use crate::{
+    controller::GridController,
+    error_core::{CoreError, Result},
+    grid::{
+        sheet::protection::{ProtectedRange, SheetProtection},
+        SheetId,
+    },
+    Rect, SheetRect,
+};
+
+/// Returns the rect in A1 notation, e.g. `A1:B5`.
+fn rect_a1(rect: Rect) -> String {
+    if rect.min == rect.max {
+        rect.min.a1_string()
+    } else {
+        format!("{}:{}", rect.min.a1_string(), rect.max.a1_string())
+    }
+}
+
+fn check_sheet(protection: &SheetProtection, user_id: Option<&str>) -> Result<()> {
+    if protection.can_edit_sheet(user_id) {
+        Ok(())
+    } else {
+        Err(CoreError::Protected("the sheet is locked".into()))
+    }
+}
+
+fn check_rect(protection: &SheetProtection, rect: Rect, user_id: Option<&str>) -> Result<()> {
+    check_sheet(protection, user_id)?;
+    match protection.protected_range_in_rect(rect, user_id) {
+        Some(range) => Err(CoreError::Protected(format!(
+            "cells {} are protected",
+            rect_a1(range.rect)
+        ))),
+        None => Ok(()),
+    }
+}
+
+fn check_change(old: &SheetProtection, new: &SheetProtection, user_id: Option<&str>) -> Result<()> {
+    check_sheet(old, user_id)?;
+    if old.can_change_to(new, user_id) {
+        Ok(())
+    } else {
+        Err(CoreError::Protected(
+            "only the editors of a protected range can change or remove it".into(),
+        ))
+    }
+}
+
+impl Operation {
+    /// Checks that the user can apply the operation. `protection` returns
+    /// the protection of a sheet, or `None` if the sheet does not exist.
+    pub fn check_protection<'a>(
+        &self,
+        user_id: Option<&str>,
+        protection: impl Fn(SheetId) -> Option<&'a SheetProtection>,
+    ) -> Result<()> {
+        let rect = |sheet_rect: &SheetRect| -> Result<()> {
+            match protection(sheet_rect.sheet_id) {
+                Some(sheet_protection) => {
+                    check_rect(sheet_protection, (*sheet_rect).into(), user_id)
+                }
+                None => Ok(()),
+            }
+        };
+        let sheet = |sheet_id: &SheetId| -> Result<()> {
+            match protection(*sheet_id) {
+                Some(sheet_protection) => check_sheet(sheet_protection, user_id),
+                None => Ok(()),
+            }
+        };
+        match self {
+            Operation::SetCellValues { sheet_pos, values } => rect(&SheetRect::from_numbers(
+                sheet_pos.x,
+                sheet_pos.y,
+                values.w.max(1) as i64,
+                values.h.max(1) as i64,
+                sheet_pos.sheet_id,
+            )),
+            // the output of a code run may not spill into protected ranges
+            // that do not contain it (see `check_spill`), so only its own
+            // output needs checking
+            Operation::SetCodeRun {
+                sheet_pos,
+                code_run: Some(code_run),
+                ..
+            } => rect(&code_run.output_sheet_rect(*sheet_pos, false)),
+            Operation::SetCodeRun { sheet_pos, .. }
+            | Operation::ComputeCode { sheet_pos }
+            | Operation::AddComment { sheet_pos, .. }
+            | Operation::EditComment { sheet_pos, .. }
+            | Operation::DeleteComment { sheet_pos, .. }
+            | Operation::SetCommentResolved { sheet_pos, .. }
+            | Operation::SetChart { sheet_pos, .. } => {
+                rect(&SheetRect::single_sheet_pos(*sheet_pos))
+            }
+            Operation::SetCellFormats { sheet_rect, .. }
+            | Operation::SetBorders { sheet_rect, .. }
+            | Operation::MergeCells { sheet_rect }
+            | Operation::UnmergeCells { sheet_rect } => rect(sheet_rect),
+            Operation::SetValidations {
+                sheet_id,
+                validations,
+            } => validations
+                .iter()
+                .try_for_each(|validation| rect(&validation.rect.to_sheet_rect(*sheet_id))),
+            Operation::SetConditionalFormats {
+                sheet_id,
+                conditional_formats,
+            } => conditional_formats
+                .iter()
+                .try_for_each(|format| rect(&format.rect.to_sheet_rect(*sheet_id))),
+            Operation::SetSheetName { sheet_id, .. }
+            | Operation::SetSheetColor { sheet_id, .. }
+            | Operation::SetSheetFrozen { sheet_id, .. }
+            | Operation::ReorderSheet {
+                target: sheet_id, ..
+            }
+            | Operation::ResizeColumn { sheet_id, .. }
+            | Operation::ResizeRow { sheet_id, .. }
+            | Operation::SetColumnHidden { sheet_id, .. }
+            | Operation::SetRowHidden { sheet_id, .. } => sheet(sheet_id),
+            Operation::DeleteSheet { sheet_id } => match protection(*sheet_id) {
+                Some(old) => check_change(old, &SheetProtection::default(), user_id),
+                None => Ok(()),
+            },
+            Operation::SetSheetProtection {
+                sheet_id,
+                protection: new,
+            } => match protection(*sheet_id) {
+                Some(old) => check_change(old, new, user_id),
+                None => Ok(()),
+            },
+            Operation::SetLocale { .. }
+            | Operation::AddSheet { .. }
+            | Operation::DuplicateSheet { .. }
+            | Operation::SetCursor { .. } => Ok(()),
+        }
+    }
+}
+
+impl GridController {
+    /// Checks that the current user can apply all of the operations.
+    pub fn check_protection(&self, operations: &[Operation]) -> Result<()> {
+        let user_id = self.user_id();
+        operations.iter().try_for_each(|op| {
+            op.check_protection(user_id, |sheet_id| {
+                self.try_sheet(sheet_id).map(|sheet| &sheet.protection)
+            })
+        })
+    }
+
+    /// Adds the current user to the editors so they do not lock themselves
+    /// out.
+    fn with_current_user(&self, mut editors: Vec<String>) -> Vec<String> {
+        if let Some(user_id) = self.user_id() {
+            if !editors.iter().any(|editor| editor == user_id) {
+                editors.push(user_id.to_string());
+            }
+        }
+        editors
+    }
+
+    fn set_sheet_protection_operations(
+        &self,
+        sheet_id: SheetId,
+        update: impl FnOnce(&mut SheetProtection),
+    ) -> Vec<Operation> {
+        let Some(sheet) = self.try_sheet(sheet_id) else {
+            return vec![];
+        };
+        let mut protection = sheet.protection.clone();
+        update(&mut protection);
+        if protection == sheet.protection {
+            return vec![];
+        }
+        vec![Operation::SetSheetProtection {
+            sheet_id,
+            protection,
+        }]
+    }
+
+    /// Creates operations to lock a sheet so only the editors (and the
+    /// current user) can edit it.
+    pub fn lock_sheet_operations(&self, sheet_id: SheetId, editors: Vec<String>) -> Vec<Operation> {
+        let editors = self.with_current_user(editors);
+        self.set_sheet_protection_operations(sheet_id, |protection| {
+            protection.locked = Some(editors);
+        })
+    }
+
+    /// Creates operations to unlock a sheet.
+    pub fn unlock_sheet_operations(&self, sheet_id: SheetId) -> Vec<Operation> {
+        self.set_sheet_protection_operations(sheet_id, |protection| {
+            protection.locked = None;
+        })
+    }
+
+    /// Creates operations to protect a range so only the editors (and the
+    /// current user) can edit it.
+    pub fn add_protected_range_operations(
+        &self,
+        sheet_rect: SheetRect,
+        editors: Vec<String>,
+    ) -> Vec<Operation> {
+        let editors = self.with_current_user(editors);
+        self.set_sheet_protection_operations(sheet_rect.sheet_id, |protection| {
+            protection.ranges.push(ProtectedRange {
+                rect: sheet_rect.into(),
+                editors,
+            });
+        })
+    }
+
+    /// Creates operations to remove all protected ranges that intersect the
+    /// sheet_rect.
+    pub fn remove_protected_ranges_operations(&self, sheet_rect: SheetRect) -> Vec<Operation> {
+        self.set_sheet_protection_operations(sheet_rect.sheet_id, |protection| {
+            protection
+                .ranges
+                .retain(|range| !range.rect.intersects(sheet_rect.into()));
+        })
+    }
+}
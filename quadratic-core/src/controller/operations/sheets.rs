@@ -22,6 +22,19 @@ impl GridController {
         vec![Operation::SetSheetColor { sheet_id, color }]
     }
 
+    pub fn set_sheet_frozen_operations(
+        &mut self,
+        sheet_id: SheetId,
+        rows: u32,
+        columns: u32,
+    ) -> Vec<Operation> {
+        vec![Operation::SetSheetFrozen {
+            sheet_id,
+            rows,
+            columns,
+        }]
+    }
+
     /// Returns all sheet names
     pub fn sheet_names(&self) -> Vec<&str> {
         self.grid.sheets().iter().map(|s| s.name.as_str()).collect()
@@ -0,0 +1,112 @@
+use super::operation::Operation;
+use crate::{
+    controller::GridController,
+    error_core::{CoreError, Result},
+    grid::{
+        sheet::validations::{Validation, ValidationAction},
+        SheetId,
+    },
+    CellValue, SheetPos, SheetRect,
+};
+
+impl GridController {
+    /// Creates operations to add a validation to a sheet. Existing validations
+    /// that are entirely covered by the new one are removed.
+    pub fn add_validation_operations(
+        &self,
+        sheet_id: SheetId,
+        validation: Validation,
+    ) -> Vec<Operation> {
+        let Some(sheet) = self.try_sheet(sheet_id) else {
+            return vec![];
+        };
+        let mut validations: Vec<Validation> = sheet
+            .validations
+            .iter()
+            .filter(|existing| {
+                !(validation.rect.contains(existing.rect.min)
+                    && validation.rect.contains(existing.rect.max))
+            })
+            .cloned()
+            .collect();
+        validations.push(validation);
+        vec![Operation::SetValidations {
+            sheet_id,
+            validations,
+        }]
+    }
+
+    /// Creates operations to remove all validations that intersect the
+    /// sheet_rect.
+    pub fn remove_validations_operations(&self, sheet_rect: SheetRect) -> Vec<Operation> {
+        let Some(sheet) = self.try_sheet(sheet_rect.sheet_id) else {
+            return vec![];
+        };
+        if !sheet
+            .validations
+            .iter()
+            .any(|validation| validation.rect.intersects(sheet_rect.into()))
+        {
+            return vec![];
+        }
+        let validations = sheet
+            .validations
+            .iter()
+            .filter(|validation| !validation.rect.intersects(sheet_rect.into()))
+            .cloned()
+            .collect();
+        vec![Operation::SetValidations {
+            sheet_id: sheet_rect.sheet_id,
+            validations,
+        }]
+    }
+
+    /// Returns the validation that the value fails at sheet_pos, if any.
+    pub fn failed_validation(&self, sheet_pos: SheetPos, value: &CellValue) -> Option<Validation> {
+        let sheet = self.try_sheet(sheet_pos.sheet_id)?;
+        let validation = sheet.validation_at(sheet_pos.into())?;
+        (!validation.is_valid(&self.grid, sheet_pos, value)).then(|| validation.clone())
+    }
+
+    /// Checks the values that the operations set against the sheets' data
+    /// validations. Returns an error if a value fails a rejecting validation,
+    /// otherwise the message of the first warning validation a value fails.
+    /// Code cells are not checked, since their values are not known yet.
+    pub fn check_validations(&self, operations: &[Operation]) -> Result<Option<String>> {
+        let mut warning = None;
+        for operation in operations {
+            let Operation::SetCellValues { sheet_pos, values } = operation else {
+                continue;
+            };
+            if self
+                .try_sheet(sheet_pos.sheet_id)
+                .filter(|sheet| !sheet.validations.is_empty())
+                .is_none()
+            {
+                continue;
+            }
+            for (x, y, value) in values.into_iter() {
+                if matches!(value, CellValue::Code(_)) {
+                    continue;
+                }
+                let sheet_pos = SheetPos {
+                    x: sheet_pos.x + x as i64,
+                    y: sheet_pos.y + y as i64,
+                    sheet_id: sheet_pos.sheet_id,
+                };
+                let Some(validation) = self.failed_validation(sheet_pos, value) else {
+                    continue;
+                };
+                match validation.action {
+                    ValidationAction::Reject => {
+                        return Err(CoreError::ValidationRejected(validation.error_message()));
+                    }
+                    ValidationAction::Warn => {
+                        warning.get_or_insert_with(|| validation.error_message());
+                    }
+                }
+            }
+        }
+        Ok(warning)
+    }
+}
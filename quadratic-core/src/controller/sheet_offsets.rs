@@ -60,11 +60,52 @@ impl GridController {
             TransactionSummary::default()
         }
     }
+
+    /// Hides or unhides columns. Values in hidden columns are still available
+    /// to formulas.
+    pub fn set_columns_hidden(
+        &mut self,
+        sheet_id: SheetId,
+        columns: Vec<i64>,
+        hidden: bool,
+        cursor: Option<String>,
+    ) -> TransactionSummary {
+        let ops = columns
+            .into_iter()
+            .map(|column| Operation::SetColumnHidden {
+                sheet_id,
+                column,
+                hidden,
+            })
+            .collect();
+        self.start_user_transaction(ops, cursor)
+    }
+
+    /// Hides or unhides rows. Values in hidden rows are still available to
+    /// formulas.
+    pub fn set_rows_hidden(
+        &mut self,
+        sheet_id: SheetId,
+        rows: Vec<i64>,
+        hidden: bool,
+        cursor: Option<String>,
+    ) -> TransactionSummary {
+        let ops = rows
+            .into_iter()
+            .map(|row| Operation::SetRowHidden {
+                sheet_id,
+                row,
+                hidden,
+            })
+            .collect();
+        self.start_user_transaction(ops, cursor)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{grid::CodeCellLanguage, CellValue, Pos, Rect, SheetPos};
 
     #[test]
     fn test_commit_offsets_resize() {
@@ -116,4 +157,46 @@ mod tests {
         let sheet = gc.sheet(sheet_id);
         assert_eq!(sheet.offsets.row_height(1), 300f64);
     }
+
+    #[test]
+    fn test_set_columns_and_rows_hidden() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_cell_value(SheetPos::new(sheet_id, 0, 1), "1".into(), None);
+        gc.set_cell_value(SheetPos::new(sheet_id, 0, 2), "2".into(), None);
+
+        let summary = gc.set_rows_hidden(sheet_id, vec![1], true, None);
+        assert!(summary.offsets_modified.contains(&sheet_id));
+        gc.set_columns_hidden(sheet_id, vec![2, 3], true, None);
+
+        let sheet = gc.sheet(sheet_id);
+        assert!(sheet.offsets.is_row_hidden(1));
+        assert_eq!(sheet.offsets.row_height(1), 0.0);
+        assert_eq!(sheet.offsets.hidden_columns(), vec![2, 3]);
+        assert_eq!(
+            sheet.get_render_cells(Rect::from_numbers(0, 0, 1, 3)).len(),
+            1
+        );
+
+        // formulas still see values in hidden rows
+        gc.set_code_cell(
+            SheetPos::new(sheet_id, 1, 0),
+            CodeCellLanguage::Formula,
+            "SUM(A1:A2)".into(),
+            None,
+        );
+        assert_eq!(
+            gc.sheet(sheet_id).display_value(Pos { x: 1, y: 0 }),
+            Some(CellValue::Number(3.into()))
+        );
+
+        gc.undo(None);
+        gc.undo(None);
+        assert_eq!(
+            gc.sheet(sheet_id).offsets.hidden_columns(),
+            Vec::<i64>::new()
+        );
+        gc.set_rows_hidden(sheet_id, vec![1], false, None);
+        assert!(!gc.sheet(sheet_id).offsets.is_row_hidden(1));
+    }
 }
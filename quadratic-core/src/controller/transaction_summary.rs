@@ -26,6 +26,19 @@ impl CellSheetsModified {
             y,
         }
     }
+
+    /// Returns the cells covered by the region, or None if the sheet id is
+    /// invalid.
+    pub fn sheet_rect(&self) -> Option<SheetRect> {
+        let sheet_id = self.sheet_id.parse::<SheetId>().ok()?;
+        Some(SheetRect::from_numbers(
+            self.x as i64 * CELL_SHEET_WIDTH as i64,
+            self.y as i64 * CELL_SHEET_HEIGHT as i64,
+            CELL_SHEET_WIDTH as i64,
+            CELL_SHEET_HEIGHT as i64,
+            sheet_id,
+        ))
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
@@ -40,9 +53,30 @@ pub struct TransactionSummary {
     /// Sheets where code_cell arrays have been modified.
     pub code_cells_modified: HashSet<SheetId>,
 
+    /// Sheets where merged cells have been modified.
+    pub merged_cells_modified: HashSet<SheetId>,
+
+    /// Sheets where data validations have been modified.
+    pub validations_modified: HashSet<SheetId>,
+
+    /// Sheets where conditional formats have been modified.
+    pub conditional_formats_modified: HashSet<SheetId>,
+
+    /// Sheets where cell comments have been modified.
+    pub comments_modified: HashSet<SheetId>,
+
+    /// Sheets where the sheet lock or protected ranges have been modified.
+    pub protections_modified: HashSet<SheetId>,
+
+    /// Sheets where charts, or the values they draw, have been modified.
+    pub charts_modified: HashSet<SheetId>,
+
     /// Sheet metadata or order was modified.
     pub sheet_list_modified: bool,
 
+    /// The workbook's locale was modified.
+    pub locale_modified: bool,
+
     /// CellSheet regions that need updating
     pub cell_sheets_modified: HashSet<CellSheetsModified>,
 
@@ -70,6 +104,9 @@ pub struct TransactionSummary {
 
     // pass error to client for TS handling
     pub error: Option<CoreError>,
+
+    // message for a value that was set but failed a data validation
+    pub validation_warning: Option<String>,
 }
 
 impl TransactionSummary {
@@ -91,7 +128,14 @@ impl TransactionSummary {
         self.fill_sheets_modified.clear();
         self.border_sheets_modified.clear();
         self.code_cells_modified.clear();
+        self.merged_cells_modified.clear();
+        self.validations_modified.clear();
+        self.conditional_formats_modified.clear();
+        self.comments_modified.clear();
+        self.protections_modified.clear();
+        self.charts_modified.clear();
         self.sheet_list_modified = false;
+        self.locale_modified = false;
         self.cell_sheets_modified.clear();
         self.offsets_modified.clear();
         self.cursor = None;
@@ -110,7 +154,20 @@ impl TransactionSummary {
             .extend(summary.border_sheets_modified.iter().cloned());
         self.code_cells_modified
             .extend(summary.code_cells_modified.iter().cloned());
+        self.merged_cells_modified
+            .extend(summary.merged_cells_modified.iter().cloned());
+        self.validations_modified
+            .extend(summary.validations_modified.iter().cloned());
+        self.conditional_formats_modified
+            .extend(summary.conditional_formats_modified.iter().cloned());
+        self.comments_modified
+            .extend(summary.comments_modified.iter().cloned());
+        self.protections_modified
+            .extend(summary.protections_modified.iter().cloned());
+        self.charts_modified
+            .extend(summary.charts_modified.iter().cloned());
         self.sheet_list_modified |= summary.sheet_list_modified;
+        self.locale_modified |= summary.locale_modified;
         self.cell_sheets_modified
             .extend(summary.cell_sheets_modified.iter().cloned());
         self.offsets_modified
@@ -601,4 +601,32 @@ mod tests {
         let result = grid.autocomplete(SheetId::new(), selected, range, None);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_expand_merged_cells() {
+        let selected: Rect = Rect::new_span(Pos { x: 0, y: 0 }, Pos { x: 1, y: 1 });
+        let range: Rect = Rect::new_span(Pos { x: 0, y: 0 }, Pos { x: 1, y: 4 });
+        let (mut grid, sheet_id) = test_setup_rect(&selected);
+        grid.merge_cells(SheetRect::from_numbers(0, 0, 2, 1, sheet_id), None);
+
+        grid.autocomplete(sheet_id, selected, range, None).unwrap();
+        let sheet = grid.sheet(sheet_id);
+        assert_eq!(
+            sheet.merged_cells,
+            vec![
+                Rect::from_numbers(0, 0, 2, 1),
+                Rect::from_numbers(0, 2, 2, 1),
+                Rect::from_numbers(0, 4, 2, 1),
+            ]
+        );
+
+        // shrinking removes the merged cells outside the range
+        let range: Rect = Rect::new_span(Pos { x: 0, y: 0 }, Pos { x: 1, y: 1 });
+        let selected: Rect = Rect::new_span(Pos { x: 0, y: 0 }, Pos { x: 1, y: 4 });
+        grid.autocomplete(sheet_id, selected, range, None).unwrap();
+        assert_eq!(
+            grid.sheet(sheet_id).merged_cells,
+            vec![Rect::from_numbers(0, 0, 2, 1)]
+        );
+    }
 }
@@ -5,7 +5,9 @@ use crate::{SheetPos, SheetRect};
 impl GridController {
     /// Starts a transaction to set the value of a cell by converting a user's String input
     ///
-    /// Returns a [`TransactionSummary`].
+    /// Returns a [`TransactionSummary`]. If the value fails a data validation,
+    /// the summary holds either the error (and the value is not set) or a
+    /// warning.
     pub fn set_cell_value(
         &mut self,
         sheet_pos: SheetPos,
@@ -18,7 +20,8 @@ impl GridController {
 
     /// Starts a transaction to set cell values using a 2d array of user's &str input where [[1, 2, 3], [4, 5, 6]] creates a grid of width 3 and height 2.
     ///
-    /// Returns a [`TransactionSummary`].
+    /// Returns a [`TransactionSummary`]. If any value fails a rejecting data
+    /// validation, the summary holds the error and no values are set.
     pub fn set_cell_values(
         &mut self,
         sheet_pos: SheetPos,
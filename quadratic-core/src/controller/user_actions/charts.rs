@@ -0,0 +1,104 @@
+use crate::{
+    controller::{transaction_summary::TransactionSummary, GridController},
+    grid::sheet::charts::Chart,
+    SheetPos,
+};
+
+impl GridController {
+    /// Adds (or replaces) the chart anchored to a cell.
+    pub fn set_chart(
+        &mut self,
+        sheet_pos: SheetPos,
+        chart: Chart,
+        cursor: Option<String>,
+    ) -> TransactionSummary {
+        let ops = self.set_chart_operations(sheet_pos, chart);
+        self.start_user_transaction(ops, cursor)
+    }
+
+    /// Deletes the chart anchored to a cell.
+    pub fn delete_chart(
+        &mut self,
+        sheet_pos: SheetPos,
+        cursor: Option<String>,
+    ) -> TransactionSummary {
+        let ops = self.delete_chart_operations(sheet_pos);
+        self.start_user_transaction(ops, cursor)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        controller::GridController,
+        grid::sheet::charts::{Chart, ChartSeries, ChartType},
+        Pos, SheetPos, SheetRect,
+    };
+
+    #[test]
+    fn sets_and_deletes_charts() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let sheet_pos = SheetPos::new(sheet_id, 3, 0);
+        let chart = Chart::new(
+            ChartType::Line,
+            None,
+            vec![ChartSeries {
+                name: None,
+                values: SheetRect::from_numbers(0, 0, 1, 3, sheet_id),
+            }],
+        );
+        let summary = gc.set_chart(sheet_pos, chart.clone(), None);
+        assert!(summary.charts_modified.contains(&sheet_id));
+        assert!(summary.html.contains(&sheet_id));
+        assert_eq!(gc.sheet(sheet_id).chart(sheet_pos.into()), Some(&chart));
+
+        let output = gc.sheet(sheet_id).get_chart_html_output(gc.grid());
+        assert_eq!(output.len(), 1);
+        assert!(output[0].html.contains("<svg"));
+        assert_eq!(output[0].w, Some("480".into()));
+
+        // setting the same chart does nothing
+        assert!(gc.set_chart_operations(sheet_pos, chart.clone()).is_empty());
+
+        gc.delete_chart(sheet_pos, None);
+        assert_eq!(gc.sheet(sheet_id).chart(sheet_pos.into()), None);
+        gc.undo(None);
+        assert_eq!(gc.sheet(sheet_id).chart(sheet_pos.into()), Some(&chart));
+        gc.undo(None);
+        assert!(gc.sheet(sheet_id).charts.is_empty());
+        gc.redo(None);
+        assert_eq!(gc.sheet(sheet_id).chart(sheet_pos.into()), Some(&chart));
+    }
+
+    #[test]
+    fn redraws_charts_when_their_values_change() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_chart(
+            SheetPos::new(sheet_id, 5, 5),
+            Chart::new(
+                ChartType::Bar,
+                None,
+                vec![ChartSeries {
+                    name: None,
+                    values: SheetRect::from_numbers(0, 0, 1, 3, sheet_id),
+                }],
+            ),
+            None,
+        );
+
+        let summary = gc.set_cell_value(SheetPos::new(sheet_id, 0, 1), "12".into(), None);
+        assert!(summary.charts_modified.contains(&sheet_id));
+        assert!(summary.html.contains(&sheet_id));
+        let svg = gc
+            .sheet(sheet_id)
+            .chart(Pos { x: 5, y: 5 })
+            .unwrap()
+            .to_svg(gc.grid());
+        assert!(svg.contains(">15</text>"));
+
+        let summary = gc.set_cell_value(SheetPos::new(sheet_id, 1, 1), "12".into(), None);
+        assert!(summary.charts_modified.is_empty());
+    }
+}
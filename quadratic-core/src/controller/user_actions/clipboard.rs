@@ -3,7 +3,14 @@ use crate::controller::{
     operations::clipboard::Clipboard, transaction_summary::TransactionSummary, GridController,
 };
 use crate::Rect;
-use crate::{grid::get_cell_borders_in_rect, Pos, SheetPos, SheetRect};
+use crate::{
+    grid::{
+        get_cell_borders_in_rect, Bold, FontFamily, FontSize, Italic, Sheet, StrikeThrough,
+        Underline,
+    },
+    CellValue, Pos, SheetPos, SheetRect,
+};
+use bigdecimal::{BigDecimal, Zero};
 use htmlescape;
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
@@ -14,11 +21,100 @@ pub enum PasteSpecial {
     None,
     Values,
     Formats,
+
+    /// swaps the clipboard's rows and columns (including formats and borders)
+    Transpose,
+
+    /// blank clipboard cells do not overwrite the destination
+    SkipBlanks,
+
+    /// only pastes code cells
+    Formulas,
+
+    /// only pastes the copied columns' widths
+    ColumnWidths,
+
+    // combines clipboard numbers with the destination's numbers
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+impl PasteSpecial {
+    /// Combines a destination number with a clipboard number for the
+    /// arithmetic modes. Returns None for other modes and division by zero.
+    pub fn combine(&self, destination: &BigDecimal, source: &BigDecimal) -> Option<BigDecimal> {
+        match self {
+            PasteSpecial::Add => Some(destination + source),
+            PasteSpecial::Subtract => Some(destination - source),
+            PasteSpecial::Multiply => Some(destination * source),
+            PasteSpecial::Divide if !source.is_zero() => Some(destination / source),
+            _ => None,
+        }
+    }
+
+    pub fn is_arithmetic(&self) -> bool {
+        matches!(
+            self,
+            PasteSpecial::Add
+                | PasteSpecial::Subtract
+                | PasteSpecial::Multiply
+                | PasteSpecial::Divide
+        )
+    }
+}
+
+/// Returns the inline CSS for a cell's text styling.
+fn html_text_style(sheet: &Sheet, pos: Pos) -> String {
+    let mut style = String::new();
+    if sheet.get_formatting_value::<Bold>(pos) == Some(true) {
+        style.push_str("font-weight:bold;");
+    }
+    if sheet.get_formatting_value::<Italic>(pos) == Some(true) {
+        style.push_str("font-style:italic;");
+    }
+    let decorations = [
+        (sheet.get_formatting_value::<Underline>(pos), "underline"),
+        (
+            sheet.get_formatting_value::<StrikeThrough>(pos),
+            "line-through",
+        ),
+    ]
+    .into_iter()
+    .filter(|(value, _)| *value == Some(true))
+    .map(|(_, decoration)| decoration)
+    .collect::<Vec<_>>();
+    if !decorations.is_empty() {
+        style.push_str(&format!("text-decoration:{};", decorations.join(" ")));
+    }
+    if let Some(font_size) = sheet.get_formatting_value::<FontSize>(pos) {
+        style.push_str(&format!("font-size:{font_size}pt;"));
+    }
+    if let Some(font_family) = sheet.get_formatting_value::<FontFamily>(pos) {
+        let font_family = font_family.replace('\\', "\\\\").replace('\'', "\\'");
+        style.push_str(&format!("font-family:'{font_family}';"));
+    }
+    style
 }
 
 impl GridController {
     /// Copies clipboard to (plain_text, html).
     pub fn copy_to_clipboard(&self, sheet_rect: SheetRect) -> (String, String) {
+        self.copy_cells_to_clipboard(sheet_rect, false)
+    }
+
+    /// Copies clipboard to (plain_text, html), leaving out hidden columns and
+    /// rows.
+    pub fn copy_visible_to_clipboard(&self, sheet_rect: SheetRect) -> (String, String) {
+        self.copy_cells_to_clipboard(sheet_rect, true)
+    }
+
+    fn copy_cells_to_clipboard(
+        &self,
+        sheet_rect: SheetRect,
+        skip_hidden: bool,
+    ) -> (String, String) {
         let mut cells = CellValues::new(sheet_rect.width() as u32, sheet_rect.height() as u32);
         let mut plain_text = String::new();
         let mut html = String::from("<tbody>");
@@ -29,15 +125,24 @@ impl GridController {
             return (String::new(), String::new());
         };
 
-        for y in sheet_rect.y_range() {
-            if y != sheet_rect.min.y {
+        let columns = sheet_rect
+            .x_range()
+            .filter(|x| !skip_hidden || !sheet.offsets.is_column_hidden(*x))
+            .collect::<Vec<_>>();
+        let rows = sheet_rect
+            .y_range()
+            .filter(|y| !skip_hidden || !sheet.offsets.is_row_hidden(*y))
+            .collect::<Vec<_>>();
+
+        for (row_index, &y) in rows.iter().enumerate() {
+            if row_index != 0 {
                 plain_text.push('\n');
                 html.push_str("</tr>");
             }
             html.push_str("<tr>");
 
-            for x in sheet_rect.x_range() {
-                if x != sheet_rect.min.x {
+            for (column_index, &x) in columns.iter().enumerate() {
+                if column_index != 0 {
                     plain_text.push('\t');
                     html.push_str("</td>");
                 }
@@ -70,30 +175,26 @@ impl GridController {
 
                 // add styling for html (only used for pasting to other spreadsheets)
                 // todo: add text color, fill, etc.
-                let (bold, italic) =
-                    if let Some(format) = sheet.get_existing_cell_format_summary(pos) {
-                        (
-                            format.bold.is_some_and(|bold| bold),
-                            format.italic.is_some_and(|italic| italic),
-                        )
-                    } else {
-                        (false, false)
-                    };
-                if bold || italic {
-                    html.push_str("<span style={");
-                    if bold {
-                        html.push_str("font-weight:bold;");
-                    }
-                    if italic {
-                        html.push_str("font-style:italic;");
-                    }
-                    html.push_str("}>");
+                let style = html_text_style(sheet, pos);
+                if !style.is_empty() {
+                    html.push_str(&format!(
+                        "<span style=\"{}\">",
+                        htmlescape::encode_attribute(&style)
+                    ));
                 }
                 if let Some(value) = &simple_value {
                     plain_text.push_str(&value.to_string());
-                    html.push_str(&value.to_string());
+                    if let CellValue::Link(link) = value {
+                        html.push_str(&format!(
+                            "<a href=\"{}\">{}</a>",
+                            htmlescape::encode_attribute(&link.url),
+                            htmlescape::encode_minimal(&link.text)
+                        ));
+                    } else {
+                        html.push_str(&value.to_string());
+                    }
                 }
-                if bold || italic {
+                if !style.is_empty() {
                     html.push_str("</span>");
                 }
             }
@@ -160,14 +261,51 @@ impl GridController {
 
         let formats = self.get_all_cell_formats(sheet_rect);
         let borders = get_cell_borders_in_rect(sheet, sheet_rect.into());
-        let clipboard = Clipboard {
+        let column_widths = sheet_rect
+            .x_range()
+            .map(|x| sheet.offsets.column_width(x))
+            .collect();
+        let merged_cells = sheet
+            .merged_cells_in_rect(clipboard_rect)
+            .into_iter()
+            .filter(|rect| clipboard_rect.contains(rect.min) && clipboard_rect.contains(rect.max))
+            .map(|mut rect| {
+                rect.translate(-sheet_rect.min.x, -sheet_rect.min.y);
+                rect
+            })
+            .collect();
+        let mut clipboard = Clipboard {
             cells,
             formats,
             borders,
             values,
+            column_widths,
+            merged_cells,
+            comments: sheet
+                .comment_threads_in_rect(clipboard_rect)
+                .into_iter()
+                .map(|(pos, thread)| {
+                    (
+                        Pos {
+                            x: pos.x - sheet_rect.min.x,
+                            y: pos.y - sheet_rect.min.y,
+                        },
+                        thread.clone(),
+                    )
+                })
+                .collect(),
             w: sheet_rect.width() as u32,
             h: sheet_rect.height() as u32,
         };
+        if columns.len() != clipboard.w as usize || rows.len() != clipboard.h as usize {
+            let relative = |indexes: &[i64], min: i64| {
+                indexes.iter().map(|i| (i - min) as u32).collect::<Vec<_>>()
+            };
+            clipboard = clipboard.select(
+                &relative(&columns, sheet_rect.min.x),
+                &relative(&rows, sheet_rect.min.y),
+            );
+        }
 
         html.push_str("</tr></tbody></table>");
         let mut final_html = String::from("<table data-quadratic=\"");
@@ -221,12 +359,13 @@ mod test {
         color::Rgba,
         controller::GridController,
         grid::{
-            generate_borders, js_types::CellFormatSummary, set_rect_borders, BorderSelection,
-            BorderStyle, CellBorderLine, CodeCellLanguage, Sheet,
+            generate_borders, js_types::CellFormatSummary, set_rect_borders, Bold, BorderSelection,
+            BorderStyle, CellAlign, CellBorderLine, CodeCellLanguage, FillColor, Italic,
+            NumericFormat, NumericFormatKind, Sheet,
         },
-        CellValue, Pos, Rect, SheetPos, SheetRect,
+        CellValue, Link, Pos, Rect, SheetPos, SheetRect,
     };
-    use bigdecimal::BigDecimal;
+    use std::str::FromStr;
 
     fn set_borders(sheet: &mut Sheet) {
         let selection = vec![BorderSelection::All];
@@ -710,6 +849,65 @@ mod test {
         assert_eq!(cell21.unwrap(), CellValue::Number(BigDecimal::from(12)));
     }
 
+    #[test]
+    fn paste_from_quadratic_clipboard_with_quoted_styles() {
+        let mut gc = GridController::default();
+        let sheet_id = gc.sheet_ids()[0];
+        for (x, y, value) in [(1, 1, "1, 1"), (3, 2, "12")] {
+            gc.set_cell_value(SheetPos { x, y, sheet_id }, value.into(), None);
+        }
+        gc.set_cell_bold(
+            SheetRect::single_pos(Pos { x: 1, y: 1 }, sheet_id),
+            Some(true),
+            None,
+        );
+        gc.set_cell_italic(
+            SheetRect::single_pos(Pos { x: 3, y: 2 }, sheet_id),
+            Some(true),
+            None,
+        );
+
+        // styles are written as quoted and escaped attributes
+        let sheet_rect = SheetRect::from_numbers(0, 0, 4, 4, sheet_id);
+        let (_, html) = gc.copy_to_clipboard(sheet_rect);
+        for (style, value) in [("font-weight:bold;", "1, 1"), ("font-style:italic;", "12")] {
+            assert!(html.contains(&format!(
+                "<span style=\"{}\">{value}</span>",
+                htmlescape::encode_attribute(style)
+            )));
+        }
+
+        gc.paste_from_clipboard(
+            SheetPos {
+                x: 1,
+                y: 2,
+                sheet_id,
+            },
+            None,
+            Some(html),
+            PasteSpecial::None,
+            None,
+        );
+
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(
+            sheet.display_value(Pos { x: 2, y: 3 }),
+            Some(CellValue::Text(String::from("1, 1")))
+        );
+        assert_eq!(
+            sheet.display_value(Pos { x: 4, y: 4 }),
+            Some(CellValue::Number(BigDecimal::from(12)))
+        );
+        assert_eq!(
+            sheet.get_formatting_value::<Bold>(Pos { x: 2, y: 3 }),
+            Some(true)
+        );
+        assert_eq!(
+            sheet.get_formatting_value::<Italic>(Pos { x: 4, y: 4 }),
+            Some(true)
+        );
+    }
+
     #[test]
     fn paste_special_values() {
         let mut gc = GridController::default();
@@ -911,4 +1109,528 @@ mod test {
         let sheet = gc.sheet(sheet_id);
         assert_eq!(sheet.display_value(Pos { x: 0, y: 0 }), None);
     }
+
+    #[test]
+    fn copies_and_pastes_links() {
+        let mut gc = GridController::default();
+        let sheet_id = gc.sheet_ids()[0];
+        let link = CellValue::Link(Link::new("Quadratic", "https://quadratichq.com").unwrap());
+        gc.sheet_mut(sheet_id)
+            .set_cell_value(Pos { x: 0, y: 0 }, link.clone());
+
+        let (plain_text, html) =
+            gc.copy_to_clipboard(SheetRect::single_pos(Pos { x: 0, y: 0 }, sheet_id));
+        assert_eq!(plain_text, "Quadratic");
+        assert!(html.contains(&format!(
+            "<a href=\"{}\">Quadratic</a>",
+            htmlescape::encode_attribute("https://quadratichq.com")
+        )));
+
+        // links in tables from other applications are kept
+        gc.paste_from_clipboard(
+            SheetPos {
+                x: 1,
+                y: 0,
+                sheet_id,
+            },
+            Some(String::from("Quadratic")),
+            Some(String::from(
+                r#"<table><tr><td><a href="https://quadratichq.com">Quadratic</a></td></tr></table>"#,
+            )),
+            PasteSpecial::None,
+            None,
+        );
+        assert_eq!(
+            gc.sheet(sheet_id).cell_value(Pos { x: 1, y: 0 }),
+            Some(link)
+        );
+
+        // links that could run code are pasted as text
+        gc.paste_from_clipboard(
+            SheetPos {
+                x: 2,
+                y: 0,
+                sheet_id,
+            },
+            Some(String::from("Click")),
+            Some(String::from(
+                r#"<table><tr><td><a href="javascript:alert(document.cookie)">Click</a></td></tr></table>"#,
+            )),
+            PasteSpecial::None,
+            None,
+        );
+        assert_eq!(
+            gc.sheet(sheet_id).cell_value(Pos { x: 2, y: 0 }),
+            Some(CellValue::Text("Click".into()))
+        );
+    }
+
+    #[test]
+    fn paste_html_table_from_other_spreadsheet() {
+        let mut gc = GridController::default();
+        let sheet_id = gc.sheet_ids()[0];
+
+        let html = r#"<google-sheets-html-origin><style type="text/css"><!--td {border: 1px solid #cccccc;}--></style>
+            <table xmlns="http://www.w3.org/1999/xhtml" cellspacing="0" cellpadding="0" dir="ltr" border="1"><colgroup><col width="100"/><col width="100"/></colgroup><tbody>
+            <tr style="height:21px;"><td colspan="2" style="font-weight:bold;background-color:#ff0000;text-align:center;">Sales</td></tr>
+            <tr style="height:21px;"><td style="font-style:italic;">North</td><td style="text-align:right;">$1,234.50</td></tr>
+            </tbody></table>"#;
+
+        gc.paste_from_clipboard(
+            SheetPos {
+                x: 1,
+                y: 1,
+                sheet_id,
+            },
+            Some(String::from("Sales\nNorth\t$1,234.50")),
+            Some(html.to_string()),
+            PasteSpecial::None,
+            None,
+        );
+
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(
+            sheet.cell_value(Pos { x: 1, y: 1 }),
+            Some(CellValue::Text(String::from("Sales")))
+        );
+        assert_eq!(sheet.cell_value(Pos { x: 2, y: 1 }), None);
+        assert_eq!(
+            sheet.cell_value(Pos { x: 1, y: 2 }),
+            Some(CellValue::Text(String::from("North")))
+        );
+        assert_eq!(
+            sheet.cell_value(Pos { x: 2, y: 2 }),
+            Some(CellValue::Number(BigDecimal::from_str("1234.5").unwrap()))
+        );
+        assert_eq!(
+            sheet
+                .get_formatting_value::<NumericFormat>(Pos { x: 2, y: 2 })
+                .map(|format| format.kind),
+            Some(NumericFormatKind::Currency)
+        );
+
+        // colspan formats apply to every covered cell
+        for x in 1..=2 {
+            assert_eq!(
+                sheet.get_formatting_value::<Bold>(Pos { x, y: 1 }),
+                Some(true)
+            );
+            assert_eq!(
+                sheet.get_formatting_value::<FillColor>(Pos { x, y: 1 }),
+                Some(String::from("#ff0000"))
+            );
+            assert_eq!(
+                sheet.get_formatting_value::<CellAlign>(Pos { x, y: 1 }),
+                Some(CellAlign::Center)
+            );
+        }
+        assert_eq!(
+            sheet.get_formatting_value::<Italic>(Pos { x: 1, y: 2 }),
+            Some(true)
+        );
+        assert_eq!(
+            sheet.get_formatting_value::<CellAlign>(Pos { x: 2, y: 2 }),
+            Some(CellAlign::Right)
+        );
+
+        gc.undo(None);
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(sheet.cell_value(Pos { x: 1, y: 1 }), None);
+        assert_eq!(sheet.get_formatting_value::<Bold>(Pos { x: 1, y: 1 }), None);
+    }
+
+    #[test]
+    fn paste_html_without_table_uses_plain_text() {
+        let mut gc = GridController::default();
+        let sheet_id = gc.sheet_ids()[0];
+
+        gc.paste_from_clipboard(
+            SheetPos {
+                x: 0,
+                y: 0,
+                sheet_id,
+            },
+            Some(String::from("hello")),
+            Some(String::from("<p>hello</p>")),
+            PasteSpecial::None,
+            None,
+        );
+        assert_eq!(
+            gc.sheet(sheet_id).cell_value(Pos { x: 0, y: 0 }),
+            Some(CellValue::Text(String::from("hello")))
+        );
+    }
+
+    fn paste_special(
+        gc: &mut GridController,
+        sheet_rect: SheetRect,
+        pos: Pos,
+        special: PasteSpecial,
+    ) {
+        let (plain, html) = gc.copy_to_clipboard(sheet_rect);
+        gc.paste_from_clipboard(
+            SheetPos {
+                x: pos.x,
+                y: pos.y,
+                sheet_id: sheet_rect.sheet_id,
+            },
+            Some(plain),
+            Some(html),
+            special,
+            None,
+        );
+    }
+
+    #[test]
+    fn paste_special_transpose() {
+        let mut gc = GridController::default();
+        let sheet_id = gc.sheet_ids()[0];
+        for (x, y, value) in [(0, 0, "a"), (1, 0, "b"), (2, 0, "c")] {
+            gc.set_cell_value(SheetPos { x, y, sheet_id }, value.into(), None);
+        }
+        gc.set_cell_bold(
+            SheetRect::single_pos(Pos { x: 2, y: 0 }, sheet_id),
+            Some(true),
+            None,
+        );
+
+        let sheet_rect = SheetRect::new_pos_span(Pos { x: 0, y: 0 }, Pos { x: 2, y: 0 }, sheet_id);
+        paste_special(
+            &mut gc,
+            sheet_rect,
+            Pos { x: 5, y: 5 },
+            PasteSpecial::Transpose,
+        );
+
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(
+            sheet.cell_value(Pos { x: 5, y: 5 }),
+            Some(CellValue::from("a"))
+        );
+        assert_eq!(
+            sheet.cell_value(Pos { x: 5, y: 6 }),
+            Some(CellValue::from("b"))
+        );
+        assert_eq!(
+            sheet.cell_value(Pos { x: 5, y: 7 }),
+            Some(CellValue::from("c"))
+        );
+        assert_eq!(sheet.cell_value(Pos { x: 6, y: 5 }), None);
+        assert_eq!(
+            sheet.get_formatting_value::<Bold>(Pos { x: 5, y: 7 }),
+            Some(true)
+        );
+        assert_eq!(sheet.get_formatting_value::<Bold>(Pos { x: 7, y: 5 }), None);
+    }
+
+    #[test]
+    fn paste_special_skip_blanks() {
+        let mut gc = GridController::default();
+        let sheet_id = gc.sheet_ids()[0];
+        for (x, y, value) in [(0, 0, "a"), (0, 2, "c"), (5, 1, "keep")] {
+            gc.set_cell_value(SheetPos { x, y, sheet_id }, value.into(), None);
+        }
+
+        let sheet_rect = SheetRect::new_pos_span(Pos { x: 0, y: 0 }, Pos { x: 0, y: 2 }, sheet_id);
+        paste_special(
+            &mut gc,
+            sheet_rect,
+            Pos { x: 5, y: 0 },
+            PasteSpecial::SkipBlanks,
+        );
+
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(
+            sheet.cell_value(Pos { x: 5, y: 0 }),
+            Some(CellValue::from("a"))
+        );
+        assert_eq!(
+            sheet.cell_value(Pos { x: 5, y: 1 }),
+            Some(CellValue::from("keep"))
+        );
+        assert_eq!(
+            sheet.cell_value(Pos { x: 5, y: 2 }),
+            Some(CellValue::from("c"))
+        );
+
+        // a normal paste overwrites with blanks
+        paste_special(&mut gc, sheet_rect, Pos { x: 5, y: 0 }, PasteSpecial::None);
+        assert_eq!(gc.sheet(sheet_id).cell_value(Pos { x: 5, y: 1 }), None);
+    }
+
+    #[test]
+    fn paste_special_formulas() {
+        let mut gc = GridController::default();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_cell_value(
+            SheetPos {
+                x: 0,
+                y: 0,
+                sheet_id,
+            },
+            "text".into(),
+            None,
+        );
+        gc.set_code_cell(
+            SheetPos {
+                x: 1,
+                y: 0,
+                sheet_id,
+            },
+            CodeCellLanguage::Formula,
+            "1 + 1".into(),
+            None,
+        );
+        gc.set_cell_value(
+            SheetPos {
+                x: 5,
+                y: 0,
+                sheet_id,
+            },
+            "keep".into(),
+            None,
+        );
+
+        let sheet_rect = SheetRect::new_pos_span(Pos { x: 0, y: 0 }, Pos { x: 1, y: 0 }, sheet_id);
+        paste_special(
+            &mut gc,
+            sheet_rect,
+            Pos { x: 5, y: 0 },
+            PasteSpecial::Formulas,
+        );
+
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(
+            sheet.cell_value(Pos { x: 5, y: 0 }),
+            Some(CellValue::from("keep"))
+        );
+        assert_eq!(
+            sheet.display_value(Pos { x: 6, y: 0 }),
+            Some(CellValue::Number(BigDecimal::from(2)))
+        );
+    }
+
+    #[test]
+    fn paste_special_column_widths() {
+        let mut gc = GridController::default();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.commit_single_resize(sheet_id, Some(1), None, 200.0, None);
+        gc.set_cell_value(
+            SheetPos {
+                x: 0,
+                y: 0,
+                sheet_id,
+            },
+            "a".into(),
+            None,
+        );
+
+        let sheet_rect = SheetRect::new_pos_span(Pos { x: 0, y: 0 }, Pos { x: 1, y: 0 }, sheet_id);
+        paste_special(
+            &mut gc,
+            sheet_rect,
+            Pos { x: 4, y: 3 },
+            PasteSpecial::ColumnWidths,
+        );
+
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(sheet.offsets.column_width(4), sheet.offsets.column_width(0));
+        assert_eq!(sheet.offsets.column_width(5), 200.0);
+        assert_eq!(sheet.cell_value(Pos { x: 4, y: 3 }), None);
+
+        gc.undo(None);
+        assert_ne!(gc.sheet(sheet_id).offsets.column_width(5), 200.0);
+    }
+
+    #[test]
+    fn paste_special_arithmetic() {
+        let mut gc = GridController::default();
+        let sheet_id = gc.sheet_ids()[0];
+        for (x, y, value) in [
+            (0, 0, "2"),
+            (0, 1, "0"),
+            (0, 2, "4"),
+            (0, 3, "5"),
+            (1, 0, "10"),
+            (1, 1, "10"),
+            (1, 2, "text"),
+        ] {
+            gc.set_cell_value(SheetPos { x, y, sheet_id }, value.into(), None);
+        }
+
+        let number = |gc: &GridController, y| gc.sheet(sheet_id).cell_value(Pos { x: 1, y });
+
+        let sheet_rect = SheetRect::new_pos_span(Pos { x: 0, y: 0 }, Pos { x: 0, y: 3 }, sheet_id);
+        paste_special(&mut gc, sheet_rect, Pos { x: 1, y: 0 }, PasteSpecial::Add);
+        assert_eq!(
+            number(&gc, 0),
+            Some(CellValue::Number(BigDecimal::from(12)))
+        );
+        assert_eq!(
+            number(&gc, 1),
+            Some(CellValue::Number(BigDecimal::from(10)))
+        );
+        assert_eq!(number(&gc, 2), Some(CellValue::from("text")));
+        assert_eq!(number(&gc, 3), Some(CellValue::Number(BigDecimal::from(5))));
+
+        paste_special(
+            &mut gc,
+            sheet_rect,
+            Pos { x: 1, y: 0 },
+            PasteSpecial::Divide,
+        );
+        assert_eq!(number(&gc, 0), Some(CellValue::Number(BigDecimal::from(6))));
+        assert_eq!(number(&gc, 3), Some(CellValue::Number(BigDecimal::from(1))));
+        // division by zero leaves the destination unchanged
+        assert_eq!(
+            number(&gc, 1),
+            Some(CellValue::Number(BigDecimal::from(10)))
+        );
+
+        paste_special(
+            &mut gc,
+            sheet_rect,
+            Pos { x: 1, y: 0 },
+            PasteSpecial::Multiply,
+        );
+        assert_eq!(
+            number(&gc, 0),
+            Some(CellValue::Number(BigDecimal::from(12)))
+        );
+        assert_eq!(number(&gc, 3), Some(CellValue::Number(BigDecimal::from(5))));
+
+        paste_special(
+            &mut gc,
+            sheet_rect,
+            Pos { x: 1, y: 0 },
+            PasteSpecial::Subtract,
+        );
+        assert_eq!(
+            number(&gc, 0),
+            Some(CellValue::Number(BigDecimal::from(10)))
+        );
+        assert_eq!(number(&gc, 3), Some(CellValue::Number(BigDecimal::from(0))));
+    }
+
+    #[test]
+    fn paste_plain_text_with_quoted_fields() {
+        let mut gc = GridController::default();
+        let sheet_id = gc.sheet_ids()[0];
+
+        // the format spreadsheet apps use: tabs, \r\n, and quoted multi-line cells
+        let plain_text = "a\t\"multi\r\nline\"\r\n1\t2\t\"with \"\"quotes\"\"\"\r\n";
+        gc.paste_from_clipboard(
+            SheetPos {
+                x: 0,
+                y: 0,
+                sheet_id,
+            },
+            Some(plain_text.to_string()),
+            None,
+            PasteSpecial::None,
+            None,
+        );
+
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(
+            sheet.cell_value(Pos { x: 0, y: 0 }),
+            Some(CellValue::from("a"))
+        );
+        assert_eq!(
+            sheet.cell_value(Pos { x: 1, y: 0 }),
+            Some(CellValue::from("multi\nline"))
+        );
+        assert_eq!(
+            sheet.cell_value(Pos { x: 1, y: 1 }),
+            Some(CellValue::Number(BigDecimal::from(2)))
+        );
+        // the second row is wider than the first
+        assert_eq!(
+            sheet.cell_value(Pos { x: 2, y: 1 }),
+            Some(CellValue::from("with \"quotes\""))
+        );
+        assert_eq!(sheet.cell_value(Pos { x: 0, y: 2 }), None);
+    }
+
+    #[test]
+    fn paste_plain_text_csv() {
+        let mut gc = GridController::default();
+        let sheet_id = gc.sheet_ids()[0];
+
+        gc.paste_from_clipboard(
+            SheetPos {
+                x: 0,
+                y: 0,
+                sheet_id,
+            },
+            Some(String::from("name,amount\n\"Smith, J\",5")),
+            None,
+            PasteSpecial::None,
+            None,
+        );
+
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(
+            sheet.cell_value(Pos { x: 0, y: 1 }),
+            Some(CellValue::from("Smith, J"))
+        );
+        assert_eq!(
+            sheet.cell_value(Pos { x: 1, y: 1 }),
+            Some(CellValue::Number(BigDecimal::from(5)))
+        );
+    }
+
+    #[test]
+    fn copy_visible_skips_hidden_columns_and_rows() {
+        let mut gc = GridController::default();
+        let sheet_id = gc.sheet_ids()[0];
+        for (x, y, value) in [
+            (0, 0, "a"),
+            (1, 0, "b"),
+            (2, 0, "c"),
+            (0, 1, "d"),
+            (0, 2, "e"),
+        ] {
+            gc.set_cell_value(SheetPos { x, y, sheet_id }, value.into(), None);
+        }
+        gc.set_cell_bold(
+            SheetRect::single_pos(Pos { x: 2, y: 0 }, sheet_id),
+            Some(true),
+            None,
+        );
+        gc.set_columns_hidden(sheet_id, vec![1], true, None);
+        gc.set_rows_hidden(sheet_id, vec![1], true, None);
+
+        let sheet_rect = SheetRect::from_numbers(0, 0, 3, 3, sheet_id);
+        let (plain_text, _) = gc.copy_to_clipboard(sheet_rect);
+        assert_eq!(plain_text, "a\tb\tc\nd\t\t\ne\t\t");
+
+        let (plain_text, html) = gc.copy_visible_to_clipboard(sheet_rect);
+        assert_eq!(plain_text, "a\tc\ne\t");
+
+        gc.paste_from_clipboard(
+            SheetPos {
+                x: 0,
+                y: 5,
+                sheet_id,
+            },
+            None,
+            Some(html),
+            PasteSpecial::None,
+            None,
+        );
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(
+            sheet.cell_value(Pos { x: 1, y: 5 }),
+            Some(CellValue::from("c"))
+        );
+        assert_eq!(
+            sheet.cell_value(Pos { x: 0, y: 6 }),
+            Some(CellValue::from("e"))
+        );
+        assert_eq!(
+            sheet.get_formatting_value::<Bold>(Pos { x: 1, y: 5 }),
+            Some(true)
+        );
+    }
 }
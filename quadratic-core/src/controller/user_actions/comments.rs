@@ -0,0 +1,315 @@
+use crate::{
+    controller::{transaction_summary::TransactionSummary, GridController},
+    grid::CommentId,
+    SheetPos, SheetRect,
+};
+
+impl GridController {
+    /// Adds a comment to a cell, starting a thread if there is none.
+    pub fn add_comment(
+        &mut self,
+        sheet_pos: SheetPos,
+        author: String,
+        text: String,
+        cursor: Option<String>,
+    ) -> TransactionSummary {
+        let ops = self.add_comment_operations(sheet_pos, author, text);
+        self.start_user_transaction(ops, cursor)
+    }
+
+    /// Changes the text of a comment in a cell's thread.
+    pub fn edit_comment(
+        &mut self,
+        sheet_pos: SheetPos,
+        comment_id: CommentId,
+        text: String,
+        cursor: Option<String>,
+    ) -> TransactionSummary {
+        let ops = self.edit_comment_operations(sheet_pos, comment_id, text);
+        self.start_user_transaction(ops, cursor)
+    }
+
+    /// Deletes a comment from a cell's thread.
+    pub fn delete_comment(
+        &mut self,
+        sheet_pos: SheetPos,
+        comment_id: CommentId,
+        cursor: Option<String>,
+    ) -> TransactionSummary {
+        let ops = self.delete_comment_operations(sheet_pos, comment_id);
+        self.start_user_transaction(ops, cursor)
+    }
+
+    /// Resolves (or reopens) a cell's thread.
+    pub fn set_comment_resolved(
+        &mut self,
+        sheet_pos: SheetPos,
+        resolved: bool,
+        cursor: Option<String>,
+    ) -> TransactionSummary {
+        let ops = self.set_comment_resolved_operations(sheet_pos, resolved);
+        self.start_user_transaction(ops, cursor)
+    }
+
+    /// Deletes all comment threads within the sheet_rect.
+    pub fn delete_comment_threads(
+        &mut self,
+        sheet_rect: SheetRect,
+        cursor: Option<String>,
+    ) -> TransactionSummary {
+        let ops = self.delete_comment_threads_operations(sheet_rect);
+        self.start_user_transaction(ops, cursor)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use uuid::Uuid;
+
+    use crate::{
+        controller::{
+            operations::operation::Operation, transaction_summary::TransactionSummary,
+            user_actions::clipboard::PasteSpecial, GridController,
+        },
+        grid::CommentId,
+        Pos, SheetPos, SheetRect,
+    };
+
+    /// Returns the sorted texts of a cell's comments (comments written in
+    /// the same millisecond are ordered by their random ids).
+    fn texts(gc: &GridController, sheet_pos: SheetPos) -> Vec<String> {
+        let mut texts: Vec<String> = gc
+            .sheet(sheet_pos.sheet_id)
+            .comment_thread(sheet_pos.into())
+            .map(|thread| {
+                thread
+                    .comments
+                    .iter()
+                    .map(|comment| comment.text.clone())
+                    .collect()
+            })
+            .unwrap_or_default();
+        texts.sort();
+        texts
+    }
+
+    fn comment_id(gc: &GridController, sheet_pos: SheetPos, text: &str) -> CommentId {
+        gc.sheet(sheet_pos.sheet_id)
+            .comment_thread(sheet_pos.into())
+            .and_then(|thread| thread.comments.iter().find(|comment| comment.text == text))
+            .unwrap()
+            .id
+    }
+
+    /// Sends a transaction to every client, as the server would.
+    fn broadcast(gcs: &mut [GridController], sequence_num: u64, summary: TransactionSummary) {
+        let transaction_id = Uuid::from_str(&summary.transaction_id.unwrap()).unwrap();
+        let operations: Vec<Operation> =
+            serde_json::from_str(&summary.operations.unwrap()).unwrap();
+        for gc in gcs.iter_mut() {
+            gc.received_transaction(transaction_id, sequence_num, operations.clone());
+        }
+    }
+
+    #[test]
+    fn edits_comment_threads() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let sheet_pos = SheetPos::new(sheet_id, 1, 1);
+
+        let summary = gc.add_comment(sheet_pos, "Ada".into(), "Source?".into(), None);
+        assert!(summary.comments_modified.contains(&sheet_id));
+        gc.add_comment(sheet_pos, "Grace".into(), "The Q3 report".into(), None);
+        gc.edit_comment(
+            sheet_pos,
+            comment_id(&gc, sheet_pos, "The Q3 report"),
+            "The Q3 board report".into(),
+            None,
+        );
+        assert_eq!(
+            texts(&gc, sheet_pos),
+            vec!["Source?", "The Q3 board report"]
+        );
+        let thread = gc
+            .sheet(sheet_id)
+            .comment_thread(Pos { x: 1, y: 1 })
+            .unwrap();
+        assert!(thread
+            .comments
+            .iter()
+            .any(|comment| comment.author == "Grace" && comment.text == "The Q3 board report"));
+
+        gc.set_comment_resolved(sheet_pos, true, None);
+        assert!(
+            gc.sheet(sheet_id)
+                .comment_thread(Pos { x: 1, y: 1 })
+                .unwrap()
+                .resolved
+        );
+
+        gc.delete_comment(sheet_pos, comment_id(&gc, sheet_pos, "Source?"), None);
+        assert_eq!(texts(&gc, sheet_pos), vec!["The Q3 board report"]);
+        gc.delete_comment(
+            sheet_pos,
+            comment_id(&gc, sheet_pos, "The Q3 board report"),
+            None,
+        );
+        assert!(gc.sheet(sheet_id).comments.is_empty());
+
+        gc.undo(None);
+        assert_eq!(texts(&gc, sheet_pos), vec!["The Q3 board report"]);
+        assert!(
+            gc.sheet(sheet_id)
+                .comment_thread(Pos { x: 1, y: 1 })
+                .unwrap()
+                .resolved
+        );
+        gc.undo(None);
+        gc.undo(None);
+        assert!(
+            !gc.sheet(sheet_id)
+                .comment_thread(Pos { x: 1, y: 1 })
+                .unwrap()
+                .resolved
+        );
+        gc.redo(None);
+        assert!(
+            gc.sheet(sheet_id)
+                .comment_thread(Pos { x: 1, y: 1 })
+                .unwrap()
+                .resolved
+        );
+
+        // edits to missing comments do nothing
+        let summary = gc.edit_comment(
+            SheetPos::new(sheet_id, 5, 5),
+            CommentId::new(),
+            "x".into(),
+            None,
+        );
+        assert!(summary.comments_modified.is_empty());
+    }
+
+    #[test]
+    fn undoes_interleaved_comment_edits() {
+        let mut gcs = [GridController::test(), GridController::test()];
+        let sheet_id = gcs[0].sheet_ids()[0];
+        gcs[1].grid_mut().sheets_mut()[0].id = sheet_id;
+        let sheet_pos = SheetPos::new(sheet_id, 0, 0);
+
+        let summary = gcs[0].add_comment(sheet_pos, "Ada".into(), "Source?".into(), None);
+        broadcast(&mut gcs, 1, summary);
+        let summary = gcs[1].add_comment(sheet_pos, "Grace".into(), "Q3 report".into(), None);
+        broadcast(&mut gcs, 2, summary);
+        assert_eq!(texts(&gcs[0], sheet_pos), vec!["Q3 report", "Source?"]);
+
+        // each user edits their own comment
+        let id = comment_id(&gcs[0], sheet_pos, "Source?");
+        let summary = gcs[0].edit_comment(sheet_pos, id, "Source for Q3?".into(), None);
+        broadcast(&mut gcs, 3, summary);
+        let id = comment_id(&gcs[1], sheet_pos, "Q3 report");
+        let summary = gcs[1].edit_comment(sheet_pos, id, "The Q3 board report".into(), None);
+        broadcast(&mut gcs, 4, summary);
+
+        // undoing Ada's edit keeps Grace's
+        let summary = gcs[0].undo(None);
+        broadcast(&mut gcs, 5, summary);
+        for gc in gcs.iter() {
+            assert_eq!(texts(gc, sheet_pos), vec!["Source?", "The Q3 board report"]);
+        }
+
+        // undoing Ada's comment keeps Grace's
+        let summary = gcs[0].undo(None);
+        broadcast(&mut gcs, 6, summary);
+        for gc in gcs.iter() {
+            assert_eq!(texts(gc, sheet_pos), vec!["The Q3 board report"]);
+        }
+
+        // Grace's undo only reverts her own edit
+        let summary = gcs[1].undo(None);
+        broadcast(&mut gcs, 7, summary);
+        for gc in gcs.iter() {
+            assert_eq!(texts(gc, sheet_pos), vec!["Q3 report"]);
+        }
+    }
+
+    #[test]
+    fn syncs_comments_through_multiplayer() {
+        let mut gc1 = GridController::test();
+        let sheet_id = gc1.sheet_ids()[0];
+        let sheet_pos = SheetPos::new(sheet_id, 0, 0);
+        let summary = gc1.add_comment(sheet_pos, "Ada".into(), "Check this".into(), None);
+
+        let transaction_id = Uuid::from_str(&summary.transaction_id.unwrap()).unwrap();
+        let operations: Vec<Operation> =
+            serde_json::from_str(&summary.operations.unwrap()).unwrap();
+        let mut gc2 = GridController::test();
+        gc2.grid_mut().sheets_mut()[0].id = sheet_id;
+        gc2.received_transaction(transaction_id, 1, operations);
+        assert_eq!(
+            gc2.sheet(sheet_id).comment_thread(Pos { x: 0, y: 0 }),
+            gc1.sheet(sheet_id).comment_thread(Pos { x: 0, y: 0 })
+        );
+    }
+
+    #[test]
+    fn copies_and_moves_comments() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_cell_value(SheetPos::new(sheet_id, 1, 1), "revenue".into(), None);
+        gc.add_comment(
+            SheetPos::new(sheet_id, 1, 1),
+            "Ada".into(),
+            "Check this".into(),
+            None,
+        );
+
+        // copy and paste keeps the original
+        let (_, html) = gc.copy_to_clipboard(SheetRect::from_numbers(0, 0, 2, 2, sheet_id));
+        gc.paste_from_clipboard(
+            SheetPos::new(sheet_id, 5, 5),
+            None,
+            Some(html.clone()),
+            PasteSpecial::None,
+            None,
+        );
+        assert_eq!(
+            texts(&gc, SheetPos::new(sheet_id, 6, 6)),
+            vec!["Check this"]
+        );
+        assert_eq!(
+            texts(&gc, SheetPos::new(sheet_id, 1, 1)),
+            vec!["Check this"]
+        );
+
+        // pasting only values leaves comments behind
+        gc.paste_from_clipboard(
+            SheetPos::new(sheet_id, 10, 10),
+            None,
+            Some(html),
+            PasteSpecial::Values,
+            None,
+        );
+        assert!(texts(&gc, SheetPos::new(sheet_id, 11, 11)).is_empty());
+
+        // moving takes the comment with the cell
+        gc.move_cells(
+            SheetRect::from_numbers(1, 1, 1, 1, sheet_id),
+            SheetPos::new(sheet_id, 1, 20),
+            None,
+        );
+        assert!(texts(&gc, SheetPos::new(sheet_id, 1, 1)).is_empty());
+        assert_eq!(
+            texts(&gc, SheetPos::new(sheet_id, 1, 20)),
+            vec!["Check this"]
+        );
+        gc.undo(None);
+        assert_eq!(
+            texts(&gc, SheetPos::new(sheet_id, 1, 1)),
+            vec!["Check this"]
+        );
+        assert!(texts(&gc, SheetPos::new(sheet_id, 1, 20)).is_empty());
+    }
+}
@@ -0,0 +1,150 @@
+use crate::{
+    controller::{transaction_summary::TransactionSummary, GridController},
+    grid::{sheet::conditional_formats::ConditionalFormat, SheetId},
+    SheetRect,
+};
+
+impl GridController {
+    /// Adds a conditional format to a sheet. It takes precedence over the
+    /// sheet's existing conditional formats.
+    pub fn add_conditional_format(
+        &mut self,
+        sheet_id: SheetId,
+        conditional_format: ConditionalFormat,
+        cursor: Option<String>,
+    ) -> TransactionSummary {
+        let ops = self.add_conditional_format_operations(sheet_id, conditional_format);
+        self.start_user_transaction(ops, cursor)
+    }
+
+    /// Removes all conditional formats that intersect the sheet_rect.
+    pub fn remove_conditional_formats(
+        &mut self,
+        sheet_rect: SheetRect,
+        cursor: Option<String>,
+    ) -> TransactionSummary {
+        let ops = self.remove_conditional_formats_operations(sheet_rect);
+        self.start_user_transaction(ops, cursor)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        controller::{transaction_summary::CellSheetsModified, GridController},
+        grid::sheet::conditional_formats::{
+            ConditionalFormat, ConditionalFormatRule, ConditionalFormatStyle,
+        },
+        Pos, Rect, SheetPos, SheetRect,
+    };
+
+    #[test]
+    fn renders_conditional_formats() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        for y in 0..3 {
+            gc.set_cell_value(SheetPos::new(sheet_id, 0, y), (y * 10).to_string(), None);
+        }
+        let summary = gc.add_conditional_format(
+            sheet_id,
+            ConditionalFormat {
+                rect: Rect::from_numbers(0, 0, 1, 100),
+                rule: ConditionalFormatRule::TopN {
+                    count: 1,
+                    bottom: false,
+                },
+                style: ConditionalFormatStyle {
+                    bold: Some(true),
+                    fill_color: Some("#ff0000".into()),
+                    ..Default::default()
+                },
+            },
+            None,
+        );
+        assert!(summary.conditional_formats_modified.contains(&sheet_id));
+        assert!(summary.fill_sheets_modified.contains(&sheet_id));
+
+        let rect = Rect::from_numbers(0, 0, 1, 10);
+        let bold_rows = |gc: &GridController| {
+            let sheet = gc.sheet(sheet_id);
+            let mut cells = sheet.get_render_cells(rect);
+            sheet.apply_conditional_formats(gc.grid(), rect, &mut cells);
+            cells
+                .iter()
+                .filter(|cell| cell.bold == Some(true))
+                .map(|cell| cell.y)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(bold_rows(&gc), vec![2]);
+        let sheet = gc.sheet(sheet_id);
+        let fills = sheet.get_conditional_render_fills(gc.grid(), rect);
+        assert_eq!(fills.len(), 1);
+        assert_eq!((fills[0].x, fills[0].y), (0, 2));
+        assert_eq!(fills[0].color, "#ff0000");
+
+        // stored formats are not changed
+        assert!(sheet.get_render_cells(rect)[2].bold.is_none());
+
+        // changing another value in the range re-renders the whole range
+        let summary = gc.set_cell_value(SheetPos::new(sheet_id, 0, 40), "100".into(), None);
+        assert!(summary
+            .cell_sheets_modified
+            .contains(&CellSheetsModified::new(SheetPos::new(sheet_id, 0, 2))));
+        assert!(summary.fill_sheets_modified.contains(&sheet_id));
+        assert_eq!(bold_rows(&gc), Vec::<i64>::new());
+        let summary = gc.set_cell_value(SheetPos::new(sheet_id, 0, 5), "200".into(), None);
+        assert_eq!(bold_rows(&gc), vec![5]);
+        assert!(summary.fill_sheets_modified.contains(&sheet_id));
+
+        gc.remove_conditional_formats(SheetRect::single_pos(Pos { x: 0, y: 0 }, sheet_id), None);
+        assert!(gc.sheet(sheet_id).conditional_formats.is_empty());
+        gc.undo(None);
+        assert_eq!(gc.sheet(sheet_id).conditional_formats.len(), 1);
+    }
+
+    #[test]
+    fn renders_custom_formats_when_references_change() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.add_sheet_with_name(None, Some("Other".into()));
+        let other_id = gc.sheet_ids()[1];
+        for y in 0..3 {
+            gc.set_cell_value(SheetPos::new(sheet_id, 0, y), "1".into(), None);
+        }
+        gc.add_conditional_format(
+            sheet_id,
+            ConditionalFormat {
+                rect: Rect::from_numbers(0, 0, 1, 3),
+                rule: ConditionalFormatRule::Custom {
+                    formula: "OR(B50 > 5, 'Other'!$A$0 > 5)".into(),
+                },
+                style: ConditionalFormatStyle {
+                    bold: Some(true),
+                    ..Default::default()
+                },
+            },
+            None,
+        );
+        let format_cells = CellSheetsModified::new(SheetPos::new(sheet_id, 0, 0));
+
+        // cells the formula does not read do not re-render the format
+        for sheet_pos in [
+            SheetPos::new(sheet_id, 1, 100),
+            SheetPos::new(other_id, 0, 100),
+        ] {
+            let summary = gc.set_cell_value(sheet_pos, "10".into(), None);
+            assert!(!summary.cell_sheets_modified.contains(&format_cells));
+            assert!(!summary.fill_sheets_modified.contains(&sheet_id));
+        }
+
+        // cells the formula reads on this sheet or on another sheet do
+        for sheet_pos in [
+            SheetPos::new(sheet_id, 1, 52),
+            SheetPos::new(other_id, 0, 0),
+        ] {
+            let summary = gc.set_cell_value(sheet_pos, "10".into(), None);
+            assert!(summary.cell_sheets_modified.contains(&format_cells));
+            assert!(summary.fill_sheets_modified.contains(&sheet_id));
+        }
+    }
+}
@@ -0,0 +1,266 @@
+use crate::controller::{transaction_summary::TransactionSummary, GridController};
+use crate::grid::sheet::search::{SearchMatch, SearchOptions};
+
+impl GridController {
+    /// Replaces matches of a search query with `replace`. When `replace_all`
+    /// is false, only the `current` match (or the first match) is replaced.
+    /// All replacements are made in a single (undoable) transaction.
+    ///
+    /// Returns a [`TransactionSummary`], the number of replacements, and the
+    /// next match to replace.
+    pub fn find_replace(
+        &mut self,
+        query: &str,
+        replace: &str,
+        options: SearchOptions,
+        replace_all: bool,
+        current: Option<SearchMatch>,
+        cursor: Option<String>,
+    ) -> (TransactionSummary, usize, Option<SearchMatch>) {
+        let (ops, count, next) =
+            self.find_replace_operations(query, replace, options, replace_all, current);
+        if ops.is_empty() {
+            return (TransactionSummary::default(), 0, None);
+        }
+        (self.start_user_transaction(ops, cursor), count, next)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{grid::CodeCellLanguage, CellValue, Pos, SheetPos};
+    use bigdecimal::BigDecimal;
+
+    fn set_text(gc: &mut GridController, x: i64, y: i64, text: &str) {
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_cell_value(SheetPos { x, y, sheet_id }, text.to_string(), None);
+    }
+
+    fn text(gc: &GridController, x: i64, y: i64) -> Option<CellValue> {
+        gc.sheet(gc.sheet_ids()[0]).cell_value(Pos { x, y })
+    }
+
+    #[test]
+    fn replace_all() {
+        let mut gc = GridController::default();
+        set_text(&mut gc, 0, 0, "Hello hello");
+        set_text(&mut gc, 1, 0, "say hello");
+        set_text(&mut gc, 2, 0, "goodbye");
+        let sheet_id = gc.sheet_ids()[0];
+        gc.add_sheet(None);
+        let sheet_id2 = gc.sheet_ids()[1];
+        gc.set_cell_value(
+            SheetPos {
+                x: 0,
+                y: 0,
+                sheet_id: sheet_id2,
+            },
+            "hello there".into(),
+            None,
+        );
+
+        let (_, count, _) =
+            gc.find_replace("hello", "hi", SearchOptions::default(), true, None, None);
+        assert_eq!(count, 4);
+        assert_eq!(text(&gc, 0, 0), Some(CellValue::from("hi hi")));
+        assert_eq!(text(&gc, 1, 0), Some(CellValue::from("say hi")));
+        assert_eq!(text(&gc, 2, 0), Some(CellValue::from("goodbye")));
+        assert_eq!(
+            gc.sheet(sheet_id2).cell_value(Pos { x: 0, y: 0 }),
+            Some(CellValue::from("hi there"))
+        );
+
+        // a single undo reverts every replacement
+        gc.undo(None);
+        assert_eq!(text(&gc, 0, 0), Some(CellValue::from("Hello hello")));
+        assert_eq!(text(&gc, 1, 0), Some(CellValue::from("say hello")));
+        assert_eq!(
+            gc.sheet(sheet_id2).cell_value(Pos { x: 0, y: 0 }),
+            Some(CellValue::from("hello there"))
+        );
+
+        // scoped to a sheet
+        let (_, count, _) = gc.find_replace(
+            "hello",
+            "hi",
+            SearchOptions {
+                sheet_id: Some(sheet_id.to_string()),
+                ..Default::default()
+            },
+            true,
+            None,
+            None,
+        );
+        assert_eq!(count, 3);
+    }
+
+    #[test]
+    fn replace_one() {
+        let mut gc = GridController::default();
+        set_text(&mut gc, 0, 0, "abc");
+        set_text(&mut gc, 0, 1, "abc");
+
+        let (_, count, _) = gc.find_replace("b", "x", SearchOptions::default(), false, None, None);
+        assert_eq!(count, 1);
+        assert_eq!(text(&gc, 0, 0), Some(CellValue::from("axc")));
+        assert_eq!(text(&gc, 0, 1), Some(CellValue::from("abc")));
+
+        let (_, count, _) = gc.find_replace("b", "x", SearchOptions::default(), false, None, None);
+        assert_eq!(count, 1);
+        assert_eq!(text(&gc, 0, 1), Some(CellValue::from("axc")));
+
+        let (summary, count, _) =
+            gc.find_replace("b", "x", SearchOptions::default(), false, None, None);
+        assert_eq!(count, 0);
+        assert!(!summary.save);
+    }
+
+    #[test]
+    fn replace_one_steps_through_matches() {
+        let mut gc = GridController::default();
+        let sheet_id = gc.sheet_ids()[0];
+        set_text(&mut gc, 0, 0, "a b a");
+        set_text(&mut gc, 0, 1, "a");
+        let first = SheetPos {
+            x: 0,
+            y: 0,
+            sheet_id,
+        };
+        let second = SheetPos {
+            x: 0,
+            y: 1,
+            sheet_id,
+        };
+
+        let options = SearchOptions::default;
+        let (_, count, next) = gc.find_replace("a", "aa", options(), false, None, None);
+        assert_eq!(count, 1);
+        assert_eq!(text(&gc, 0, 0), Some(CellValue::from("aa b a")));
+        assert_eq!(
+            next,
+            Some(SearchMatch {
+                sheet_pos: first,
+                index: 2
+            })
+        );
+
+        let (_, _, next) = gc.find_replace("a", "aa", options(), false, next, None);
+        assert_eq!(text(&gc, 0, 0), Some(CellValue::from("aa b aa")));
+        assert_eq!(
+            next,
+            Some(SearchMatch {
+                sheet_pos: second,
+                index: 0
+            })
+        );
+
+        let (_, _, next) = gc.find_replace("a", "aa", options(), false, next, None);
+        assert_eq!(text(&gc, 0, 0), Some(CellValue::from("aa b aa")));
+        assert_eq!(text(&gc, 0, 1), Some(CellValue::from("aa")));
+        assert_eq!(next, None);
+
+        // a match that no longer exists moves on to the next cell
+        let stale = SearchMatch {
+            sheet_pos: first,
+            index: 10,
+        };
+        let (_, count, _) = gc.find_replace("b", "c", options(), false, Some(stale), None);
+        assert_eq!(count, 0);
+        assert_eq!(text(&gc, 0, 0), Some(CellValue::from("aa b aa")));
+    }
+
+    #[test]
+    fn replace_options() {
+        let mut gc = GridController::default();
+        set_text(&mut gc, 0, 0, "Cat");
+        set_text(&mut gc, 0, 1, "cat");
+        set_text(&mut gc, 0, 2, "cats");
+        set_text(&mut gc, 0, 3, "12");
+
+        let case_sensitive = SearchOptions {
+            case_sensitive: Some(true),
+            ..Default::default()
+        };
+        let (_, count, _) = gc.find_replace("Cat", "Dog", case_sensitive, true, None, None);
+        assert_eq!(count, 1);
+        assert_eq!(text(&gc, 0, 0), Some(CellValue::from("Dog")));
+        assert_eq!(text(&gc, 0, 1), Some(CellValue::from("cat")));
+
+        let whole_cell = SearchOptions {
+            whole_cell: Some(true),
+            ..Default::default()
+        };
+        let (_, count, _) = gc.find_replace("CAT", "dog", whole_cell, true, None, None);
+        assert_eq!(count, 1);
+        assert_eq!(text(&gc, 0, 1), Some(CellValue::from("dog")));
+        assert_eq!(text(&gc, 0, 2), Some(CellValue::from("cats")));
+
+        // numbers are not replaced
+        let (_, count, _) = gc.find_replace("12", "13", SearchOptions::default(), true, None, None);
+        assert_eq!(count, 0);
+        assert_eq!(
+            text(&gc, 0, 3),
+            Some(CellValue::Number(BigDecimal::from(12)))
+        );
+    }
+
+    #[test]
+    fn replace_in_code() {
+        let mut gc = GridController::default();
+        let sheet_id = gc.sheet_ids()[0];
+        let sheet_pos = SheetPos {
+            x: 0,
+            y: 0,
+            sheet_id,
+        };
+        gc.set_code_cell(sheet_pos, CodeCellLanguage::Formula, "1 + 2".into(), None);
+
+        let (_, count, _) = gc.find_replace("2", "3", SearchOptions::default(), true, None, None);
+        assert_eq!(count, 0);
+
+        let search_code = SearchOptions {
+            search_code: Some(true),
+            ..Default::default()
+        };
+        let (_, count, _) = gc.find_replace("2", "3", search_code, true, None, None);
+        assert_eq!(count, 1);
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(
+            sheet.display_value(sheet_pos.into()),
+            Some(CellValue::Number(BigDecimal::from(4)))
+        );
+    }
+
+    #[test]
+    fn replace_regex_and_criteria() {
+        let mut gc = GridController::default();
+        set_text(&mut gc, 0, 0, "Smith, John");
+        set_text(&mut gc, 0, 1, "Doe, Jane");
+        set_text(&mut gc, 0, 2, "apple");
+
+        let regex = SearchOptions {
+            regex: Some(true),
+            ..Default::default()
+        };
+        let (_, count, _) = gc.find_replace(r"(\w+), (\w+)", "$2 $1", regex, true, None, None);
+        assert_eq!(count, 2);
+        assert_eq!(text(&gc, 0, 0), Some(CellValue::from("John Smith")));
+        assert_eq!(text(&gc, 0, 1), Some(CellValue::from("Jane Doe")));
+
+        // without regex, $ is literal
+        let (_, count, _) =
+            gc.find_replace("apple", "$1", SearchOptions::default(), true, None, None);
+        assert_eq!(count, 1);
+        assert_eq!(text(&gc, 0, 2), Some(CellValue::from("$1")));
+
+        let criteria = SearchOptions {
+            criteria: Some(true),
+            ..Default::default()
+        };
+        let (_, count, _) = gc.find_replace("<>$1", "other", criteria, true, None, None);
+        assert_eq!(count, 2);
+        assert_eq!(text(&gc, 0, 0), Some(CellValue::from("other")));
+        assert_eq!(text(&gc, 0, 2), Some(CellValue::from("$1")));
+    }
+}
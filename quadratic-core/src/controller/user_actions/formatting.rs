@@ -3,8 +3,9 @@ use crate::controller::{
 };
 use crate::{
     grid::{
-        formatting::CellFmtArray, Bold, CellAlign, CellFmtAttr, CellWrap, FillColor, Italic,
-        NumericCommas, NumericDecimals, NumericFormat, RenderSize, TextColor,
+        formatting::CellFmtArray, Bold, CellAlign, CellFmtAttr, CellVerticalAlign, CellWrap,
+        FillColor, FontFamily, FontSize, Italic, NumericCommas, NumericDecimals, NumericFormat,
+        RenderSize, StrikeThrough, TextColor, TextRotation, Underline,
     },
     Pos, RunLengthEncoding, SheetPos, SheetRect,
 };
@@ -73,11 +74,17 @@ impl GridController {
         let mut cell_formats = vec![
             CellFmtArray::Align(RunLengthEncoding::new()),
             CellFmtArray::Wrap(RunLengthEncoding::new()),
+            CellFmtArray::VerticalAlign(RunLengthEncoding::new()),
+            CellFmtArray::TextRotation(RunLengthEncoding::new()),
             CellFmtArray::NumericFormat(RunLengthEncoding::new()),
             CellFmtArray::NumericDecimals(RunLengthEncoding::new()),
             CellFmtArray::NumericCommas(RunLengthEncoding::new()),
             CellFmtArray::Bold(RunLengthEncoding::new()),
             CellFmtArray::Italic(RunLengthEncoding::new()),
+            CellFmtArray::Underline(RunLengthEncoding::new()),
+            CellFmtArray::StrikeThrough(RunLengthEncoding::new()),
+            CellFmtArray::FontSize(RunLengthEncoding::new()),
+            CellFmtArray::FontFamily(RunLengthEncoding::new()),
             CellFmtArray::TextColor(RunLengthEncoding::new()),
             CellFmtArray::FillColor(RunLengthEncoding::new()),
         ];
@@ -91,6 +98,12 @@ impl GridController {
                     CellFmtArray::Wrap(array) => {
                         array.push(sheet.get_formatting_value::<CellWrap>(pos));
                     }
+                    CellFmtArray::VerticalAlign(array) => {
+                        array.push(sheet.get_formatting_value::<CellVerticalAlign>(pos));
+                    }
+                    CellFmtArray::TextRotation(array) => {
+                        array.push(sheet.get_formatting_value::<TextRotation>(pos));
+                    }
                     CellFmtArray::NumericFormat(array) => {
                         array.push(sheet.get_formatting_value::<NumericFormat>(pos));
                     }
@@ -106,6 +119,18 @@ impl GridController {
                     CellFmtArray::Italic(array) => {
                         array.push(sheet.get_formatting_value::<Italic>(pos));
                     }
+                    CellFmtArray::Underline(array) => {
+                        array.push(sheet.get_formatting_value::<Underline>(pos));
+                    }
+                    CellFmtArray::StrikeThrough(array) => {
+                        array.push(sheet.get_formatting_value::<StrikeThrough>(pos));
+                    }
+                    CellFmtArray::FontSize(array) => {
+                        array.push(sheet.get_formatting_value::<FontSize>(pos));
+                    }
+                    CellFmtArray::FontFamily(array) => {
+                        array.push(sheet.get_formatting_value::<FontFamily>(pos));
+                    }
                     CellFmtArray::TextColor(array) => {
                         array.push(sheet.get_formatting_value::<TextColor>(pos));
                     }
@@ -138,14 +163,38 @@ macro_rules! impl_set_cell_fmt_method {
             }
         }
     };
+    // values outside of the attribute's RANGE are clamped
+    ($method_name:ident<$cell_fmt_attr_type:ty>($cell_fmt_array_constructor:expr), clamp) => {
+        impl GridController {
+            pub fn $method_name(
+                &mut self,
+                sheet_rect: SheetRect,
+                value: Option<<$cell_fmt_attr_type as CellFmtAttr>::Value>,
+                cursor: Option<String>,
+            ) -> TransactionSummary {
+                let range = <$cell_fmt_attr_type>::RANGE;
+                let value = value.map(|value| value.clamp(*range.start(), *range.end()));
+                let attr =
+                    $cell_fmt_array_constructor(RunLengthEncoding::repeat(value, sheet_rect.len()));
+                let ops = vec![Operation::SetCellFormats { sheet_rect, attr }];
+                self.start_user_transaction(ops, cursor)
+            }
+        }
+    };
 }
 
 impl_set_cell_fmt_method!(set_cell_align<CellAlign>(CellFmtArray::Align));
 impl_set_cell_fmt_method!(set_cell_wrap<CellWrap>(CellFmtArray::Wrap));
+impl_set_cell_fmt_method!(set_cell_vertical_align<CellVerticalAlign>(CellFmtArray::VerticalAlign));
+impl_set_cell_fmt_method!(set_cell_text_rotation<TextRotation>(CellFmtArray::TextRotation), clamp);
 impl_set_cell_fmt_method!(set_cell_numeric_format<NumericFormat>(CellFmtArray::NumericFormat));
 impl_set_cell_fmt_method!(set_cell_numeric_decimals<NumericDecimals>(CellFmtArray::NumericDecimals));
 impl_set_cell_fmt_method!(set_cell_bold<Bold>(CellFmtArray::Bold));
 impl_set_cell_fmt_method!(set_cell_italic<Italic>(CellFmtArray::Italic));
+impl_set_cell_fmt_method!(set_cell_underline<Underline>(CellFmtArray::Underline));
+impl_set_cell_fmt_method!(set_cell_strike_through<StrikeThrough>(CellFmtArray::StrikeThrough));
+impl_set_cell_fmt_method!(set_cell_font_size<FontSize>(CellFmtArray::FontSize), clamp);
+impl_set_cell_fmt_method!(set_cell_font_family<FontFamily>(CellFmtArray::FontFamily));
 impl_set_cell_fmt_method!(set_cell_text_color<TextColor>(CellFmtArray::TextColor));
 impl_set_cell_fmt_method!(set_cell_fill_color<FillColor>(CellFmtArray::FillColor));
 impl_set_cell_fmt_method!(set_cell_render_size<RenderSize>(CellFmtArray::RenderSize));
@@ -153,8 +202,14 @@ impl_set_cell_fmt_method!(set_cell_render_size<RenderSize>(CellFmtArray::RenderS
 #[cfg(test)]
 mod test {
     use crate::{
-        controller::GridController,
-        grid::{RenderSize, SheetId, TextColor},
+        controller::{
+            operations::html_table::parse_html_table, user_actions::clipboard::PasteSpecial,
+            GridController,
+        },
+        grid::{
+            file::{export, import},
+            CellVerticalAlign, FontSize, RenderSize, SheetId, TextColor, TextRotation, Underline,
+        },
         Pos, Rect, SheetPos, SheetRect,
     };
 
@@ -574,4 +629,78 @@ mod test {
             None,
         );
     }
+
+    #[test]
+    fn sets_text_styles() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let pos = Pos { x: 1, y: 1 };
+        let sheet_rect = SheetRect::single_pos(pos, sheet_id);
+        gc.set_cell_value(pos.to_sheet_pos(sheet_id), "a".into(), None);
+        gc.set_cell_underline(sheet_rect, Some(true), None);
+        gc.set_cell_strike_through(sheet_rect, Some(true), None);
+        gc.set_cell_font_size(sheet_rect, Some(18), None);
+        gc.set_cell_font_family(sheet_rect, Some("Courier New".into()), None);
+        gc.set_cell_vertical_align(sheet_rect, Some(CellVerticalAlign::Top), None);
+        gc.set_cell_text_rotation(sheet_rect, Some(45), None);
+
+        let render_cell = gc.sheet(sheet_id).get_render_cells(Rect::single_pos(pos))[0].clone();
+        assert_eq!(render_cell.underline, Some(true));
+        assert_eq!(render_cell.strike_through, Some(true));
+        assert_eq!(render_cell.font_size, Some(18));
+        assert_eq!(render_cell.font_family, Some("Courier New".into()));
+        assert_eq!(render_cell.vertical_align, Some(CellVerticalAlign::Top));
+        assert_eq!(render_cell.text_rotation, Some(45));
+
+        let (_, html) = gc.copy_to_clipboard(sheet_rect);
+        let style =
+            "text-decoration:underline line-through;font-size:18pt;font-family:'Courier New';";
+        assert!(html.contains(&format!(
+            "<span style=\"{}\">a</span>",
+            htmlescape::encode_attribute(style)
+        )));
+
+        // other spreadsheets read the styles
+        let table = parse_html_table(&html).unwrap();
+        let copied = &table.cell(0, 0).unwrap().style;
+        assert_eq!(copied.underline, Some(true));
+        assert_eq!(copied.strike_through, Some(true));
+        assert_eq!(copied.font_size, Some(18));
+        assert_eq!(copied.font_family, Some("Courier New".into()));
+
+        // formats survive copy and paste, and saving the file
+        gc.paste_from_clipboard(
+            SheetPos::new(sheet_id, 3, 3),
+            None,
+            Some(html),
+            PasteSpecial::None,
+            None,
+        );
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(
+            sheet.get_formatting_value::<FontSize>(Pos { x: 3, y: 3 }),
+            Some(18)
+        );
+        let mut grid = gc.grid().clone();
+        let imported = import(&export(&mut grid).unwrap()).unwrap();
+        assert_eq!(imported, grid);
+
+        gc.clear_formatting(sheet_rect, None);
+        assert_eq!(
+            gc.sheet(sheet_id).get_formatting_value::<Underline>(pos),
+            None
+        );
+
+        gc.undo(None);
+        assert_eq!(
+            gc.sheet(sheet_id).get_formatting_value::<Underline>(pos),
+            Some(true)
+        );
+        // out of range sizes and rotations are clamped
+        gc.set_cell_font_size(sheet_rect, Some(0), None);
+        gc.set_cell_text_rotation(sheet_rect, Some(180), None);
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(sheet.get_formatting_value::<FontSize>(pos), Some(1));
+        assert_eq!(sheet.get_formatting_value::<TextRotation>(pos), Some(90));
+    }
 }
@@ -50,6 +50,7 @@ mod tests {
     use std::io::Read;
 
     use crate::{
+        grid::Locale,
         test_util::{assert_cell_value_row, print_table},
         Rect,
     };
@@ -111,6 +112,42 @@ Concord,NH,United States,42605
         );
     }
 
+    #[test]
+    fn imports_a_comma_csv_in_a_semicolon_locale() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.grid.sheets()[0].id;
+        gc.set_locale(Locale::from_tag("de").unwrap(), None)
+            .unwrap();
+
+        gc.import_csv(
+            sheet_id,
+            SIMPLE_CSV.as_bytes(),
+            "smallpop.csv",
+            Pos { x: 0, y: 0 },
+            None,
+        )
+        .unwrap();
+        assert_cell_value_row(
+            &gc,
+            sheet_id,
+            0,
+            3,
+            1,
+            vec!["Southborough", "MA", "United States", "9686"],
+        );
+
+        // the locale's delimiter is used for semicolon files
+        gc.import_csv(
+            sheet_id,
+            "a;b\n1,5;2".as_bytes(),
+            "semicolons.csv",
+            Pos { x: 10, y: 0 },
+            None,
+        )
+        .unwrap();
+        assert_cell_value_row(&gc, sheet_id, 10, 11, 1, vec!["1.5", "2"]);
+    }
+
     #[test]
     fn errors_on_an_empty_csv() {
         let mut grid_controller = GridController::test();
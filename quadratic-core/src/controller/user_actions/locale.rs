@@ -0,0 +1,123 @@
+use crate::{
+    controller::{
+        operations::operation::Operation, transaction_summary::TransactionSummary, GridController,
+    },
+    grid::Locale,
+};
+use anyhow::Result;
+
+impl GridController {
+    /// Sets the workbook's locale. Values and formulas are not changed, only
+    /// how they are parsed and displayed. Returns an error if the locale's
+    /// separators are ambiguous.
+    pub fn set_locale(
+        &mut self,
+        locale: Locale,
+        cursor: Option<String>,
+    ) -> Result<TransactionSummary> {
+        locale.validate()?;
+        let ops = vec![Operation::SetLocale { locale }];
+        Ok(self.start_user_transaction(ops, cursor))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        controller::GridController,
+        grid::{CodeCellLanguage, Locale, NumericFormat, NumericFormatKind},
+        CellValue, Pos, Rect, SheetPos,
+    };
+    use bigdecimal::BigDecimal;
+    use chrono::NaiveDate;
+    use std::str::FromStr;
+
+    #[test]
+    fn parses_and_displays_in_locale() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let german = Locale::from_tag("de-DE").unwrap();
+        let summary = gc.set_locale(german, None).unwrap();
+        assert!(summary.locale_modified);
+        assert_eq!(gc.sheet(sheet_id).locale, german);
+
+        gc.set_cell_value(SheetPos::new(sheet_id, 0, 0), "1.234,5".into(), None);
+        gc.set_cell_value(SheetPos::new(sheet_id, 0, 1), "12,50 €".into(), None);
+        gc.set_cell_value(SheetPos::new(sheet_id, 0, 2), "1/3/2024".into(), None);
+        gc.set_code_cell(
+            SheetPos::new(sheet_id, 1, 0),
+            CodeCellLanguage::Formula,
+            "SUM(1,5; 2)".into(),
+            None,
+        );
+
+        let sheet = gc.sheet(sheet_id);
+        let number = sheet.cell_value(Pos { x: 0, y: 0 }).unwrap();
+        assert_eq!(
+            number,
+            CellValue::Number(BigDecimal::from_str("1234.5").unwrap())
+        );
+        assert_eq!(sheet.locale.localize_edit(&number), "1234,5");
+        assert_eq!(
+            sheet.get_formatting_value::<NumericFormat>(Pos { x: 0, y: 1 }),
+            Some(NumericFormat {
+                kind: NumericFormatKind::Currency,
+                symbol: Some("€".into()),
+            })
+        );
+        let display = |x, y| {
+            gc.sheet(sheet_id)
+                .get_render_cells(Rect::single_pos(Pos { x, y }))
+                .first()
+                .map(|cell| cell.value.clone())
+        };
+        assert_eq!(display(0, 1), Some("12,50 €".to_string()));
+        assert_eq!(
+            sheet.cell_value(Pos { x: 0, y: 2 }),
+            Some(CellValue::Instant(
+                NaiveDate::from_ymd_opt(2024, 3, 1)
+                    .unwrap()
+                    .and_hms_opt(0, 0, 0)
+                    .unwrap()
+                    .into()
+            ))
+        );
+
+        // formulas are stored in the US form and edited in the locale
+        let Some(CellValue::Code(code_cell)) = sheet.cell_value(Pos { x: 1, y: 0 }) else {
+            panic!("expected a code cell");
+        };
+        assert_eq!(code_cell.code, "SUM(1.5, 2)");
+        assert_eq!(display(1, 0), Some("3,5".to_string()));
+        assert_eq!(
+            gc.sheet(sheet_id)
+                .edit_code_value(Pos { x: 1, y: 0 })
+                .map(|code| code.code_string),
+            Some("SUM(1,5; 2)".to_string())
+        );
+
+        for _ in 0..5 {
+            gc.undo(None);
+        }
+        assert_eq!(*gc.grid().locale(), Locale::default());
+        assert_eq!(gc.sheet(sheet_id).locale, Locale::default());
+    }
+
+    #[test]
+    fn rejects_ambiguous_locales() {
+        let mut gc = GridController::test();
+        let german = Locale::from_tag("de").unwrap();
+        let same_separators = Locale {
+            thousands_separator: ',',
+            ..german
+        };
+        assert!(gc.set_locale(same_separators, None).is_err());
+        let comma_arguments = Locale {
+            argument_separator: ',',
+            ..german
+        };
+        assert!(gc.set_locale(comma_arguments, None).is_err());
+        assert_eq!(*gc.grid().locale(), Locale::default());
+        assert!(!gc.has_undo());
+    }
+}
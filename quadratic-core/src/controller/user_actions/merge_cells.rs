@@ -0,0 +1,94 @@
+use crate::controller::{transaction_summary::TransactionSummary, GridController};
+use crate::SheetRect;
+
+impl GridController {
+    /// Merges the cells in a sheet_rect, keeping only the top-left value.
+    pub fn merge_cells(
+        &mut self,
+        sheet_rect: SheetRect,
+        cursor: Option<String>,
+    ) -> TransactionSummary {
+        let ops = self.merge_cells_operations(sheet_rect);
+        self.start_user_transaction(ops, cursor)
+    }
+
+    /// Unmerges any merged cells that intersect the sheet_rect.
+    pub fn unmerge_cells(
+        &mut self,
+        sheet_rect: SheetRect,
+        cursor: Option<String>,
+    ) -> TransactionSummary {
+        let ops = self.unmerge_cells_operations(sheet_rect);
+        self.start_user_transaction(ops, cursor)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        controller::GridController, grid::CodeCellLanguage, CellValue, Pos, Rect, SheetPos,
+        SheetRect,
+    };
+
+    #[test]
+    fn merge_cells_keeps_top_left_value() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_cell_value(SheetPos::new(sheet_id, 0, 0), "a".into(), None);
+        gc.set_cell_value(SheetPos::new(sheet_id, 1, 0), "b".into(), None);
+        gc.set_cell_value(SheetPos::new(sheet_id, 0, 1), "c".into(), None);
+
+        let sheet_rect = SheetRect::from_numbers(0, 0, 2, 2, sheet_id);
+        let summary = gc.merge_cells(sheet_rect, None);
+        assert!(summary.merged_cells_modified.contains(&sheet_id));
+
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(sheet.merged_cells, vec![Rect::from_numbers(0, 0, 2, 2)]);
+        assert_eq!(
+            sheet.cell_value(Pos { x: 0, y: 0 }),
+            Some(CellValue::Text("a".into()))
+        );
+        assert_eq!(sheet.cell_value(Pos { x: 1, y: 0 }), None);
+        assert_eq!(sheet.cell_value(Pos { x: 0, y: 1 }), None);
+
+        // only the anchor is rendered
+        assert_eq!(
+            sheet.get_render_cells(Rect::from_numbers(0, 0, 2, 2)).len(),
+            1
+        );
+
+        gc.undo(None);
+        let sheet = gc.sheet(sheet_id);
+        assert!(sheet.merged_cells.is_empty());
+        assert_eq!(
+            sheet.cell_value(Pos { x: 1, y: 0 }),
+            Some(CellValue::Text("b".into()))
+        );
+
+        gc.redo(None);
+        gc.unmerge_cells(SheetRect::single_pos(Pos { x: 1, y: 1 }, sheet_id), None);
+        assert!(gc.sheet(sheet_id).merged_cells.is_empty());
+        gc.undo(None);
+        assert_eq!(
+            gc.sheet(sheet_id).merged_cells,
+            vec![Rect::from_numbers(0, 0, 2, 2)]
+        );
+    }
+
+    #[test]
+    fn spill_into_merged_cells() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.merge_cells(SheetRect::from_numbers(0, 1, 2, 2, sheet_id), None);
+        gc.set_code_cell(
+            SheetPos::new(sheet_id, 0, 0),
+            CodeCellLanguage::Formula,
+            "{1;2}".into(),
+            None,
+        );
+        assert!(gc.sheet(sheet_id).code_runs[0].spill_error);
+
+        gc.unmerge_cells(SheetRect::from_numbers(0, 1, 2, 2, sheet_id), None);
+        assert!(!gc.sheet(sheet_id).code_runs[0].spill_error);
+    }
+}
@@ -3,9 +3,18 @@
 pub mod auto_complete;
 pub mod borders;
 pub mod cells;
+pub mod charts;
 pub mod clipboard;
 pub mod code;
+pub mod comments;
+pub mod conditional_formats;
+pub mod find_replace;
 pub mod formatting;
 pub mod import;
+pub mod locale;
+pub mod merge_cells;
+pub mod move_cells;
+pub mod protection;
 pub mod sheets;
 pub mod undo;
+pub mod validations;
@@ -0,0 +1,219 @@
+use crate::controller::{transaction_summary::TransactionSummary, GridController};
+use crate::{SheetPos, SheetRect};
+
+impl GridController {
+    /// Moves the cells in `source` so that its top-left cell is at `dest`,
+    /// updating any formulas that reference the moved cells.
+    pub fn move_cells(
+        &mut self,
+        source: SheetRect,
+        dest: SheetPos,
+        cursor: Option<String>,
+    ) -> TransactionSummary {
+        let ops = self.move_cells_operations(source, dest);
+        self.start_user_transaction(ops, cursor)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        controller::{operations::operation::Operation, GridController},
+        grid::{Bold, CodeCellLanguage},
+        CellValue, CodeCellValue, Pos, Rect, SheetPos, SheetRect,
+    };
+
+    fn code(gc: &GridController, sheet_pos: SheetPos) -> Option<String> {
+        match gc.sheet(sheet_pos.sheet_id).cell_value(sheet_pos.into()) {
+            Some(CellValue::Code(CodeCellValue { code, .. })) => Some(code),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn move_cells_with_formats_and_code() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_cell_value(SheetPos::new(sheet_id, 0, 0), "1".into(), None);
+        gc.set_cell_value(SheetPos::new(sheet_id, 0, 1), "2".into(), None);
+        gc.set_cell_bold(
+            SheetRect::single_pos(Pos { x: 0, y: 0 }, sheet_id),
+            Some(true),
+            None,
+        );
+        gc.set_code_cell(
+            SheetPos::new(sheet_id, 1, 0),
+            CodeCellLanguage::Formula,
+            "A0 + A1".into(),
+            None,
+        );
+        // references the moved cells from outside
+        gc.set_code_cell(
+            SheetPos::new(sheet_id, 5, 5),
+            CodeCellLanguage::Formula,
+            "SUM(A0:A1) + $B$0".into(),
+            None,
+        );
+        // references a cell that stays put
+        gc.set_cell_value(SheetPos::new(sheet_id, 3, 0), "10".into(), None);
+        gc.set_code_cell(
+            SheetPos::new(sheet_id, 1, 1),
+            CodeCellLanguage::Formula,
+            "D0 * 2".into(),
+            None,
+        );
+
+        gc.move_cells(
+            SheetRect::from_numbers(0, 0, 2, 2, sheet_id),
+            SheetPos::new(sheet_id, 0, 10),
+            None,
+        );
+
+        let sheet = gc.sheet(sheet_id);
+        assert!(sheet
+            .get_render_cells(Rect::from_numbers(0, 0, 2, 2))
+            .is_empty());
+        assert_eq!(
+            sheet.display_value(Pos { x: 0, y: 11 }),
+            Some(CellValue::Number(2.into()))
+        );
+        assert_eq!(
+            sheet.display_value(Pos { x: 1, y: 10 }),
+            Some(CellValue::Number(3.into()))
+        );
+        assert_eq!(
+            sheet.display_value(Pos { x: 1, y: 11 }),
+            Some(CellValue::Number(20.into()))
+        );
+        assert_eq!(
+            sheet.get_formatting_value::<Bold>(Pos { x: 0, y: 10 }),
+            Some(true)
+        );
+        assert_eq!(sheet.get_formatting_value::<Bold>(Pos { x: 0, y: 0 }), None);
+        assert_eq!(
+            code(&gc, SheetPos::new(sheet_id, 1, 10)),
+            Some("A10 + A11".into())
+        );
+        assert_eq!(
+            code(&gc, SheetPos::new(sheet_id, 1, 11)),
+            Some("D0 * 2".into())
+        );
+        assert_eq!(
+            code(&gc, SheetPos::new(sheet_id, 5, 5)),
+            Some("SUM(A10:A11) + $B$10".into())
+        );
+        assert_eq!(
+            gc.sheet(sheet_id).display_value(Pos { x: 5, y: 5 }),
+            Some(CellValue::Number(6.into()))
+        );
+
+        gc.undo(None);
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(
+            sheet.display_value(Pos { x: 1, y: 0 }),
+            Some(CellValue::Number(3.into()))
+        );
+        assert_eq!(sheet.display_value(Pos { x: 1, y: 10 }), None);
+        assert_eq!(
+            code(&gc, SheetPos::new(sheet_id, 5, 5)),
+            Some("SUM(A0:A1) + $B$0".into())
+        );
+    }
+
+    #[test]
+    fn move_cells_to_another_sheet() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.add_sheet(None);
+        let other_id = gc.sheet_ids()[1];
+        let other_name = gc.sheet(other_id).name.clone();
+
+        gc.set_cell_value(SheetPos::new(sheet_id, 0, 0), "5".into(), None);
+        gc.set_cell_value(SheetPos::new(sheet_id, 2, 2), "7".into(), None);
+        gc.set_code_cell(
+            SheetPos::new(sheet_id, 0, 1),
+            CodeCellLanguage::Formula,
+            "A0 + C2".into(),
+            None,
+        );
+        gc.set_code_cell(
+            SheetPos::new(sheet_id, 3, 3),
+            CodeCellLanguage::Formula,
+            "A0 * 2".into(),
+            None,
+        );
+
+        gc.move_cells(
+            SheetRect::from_numbers(0, 0, 1, 2, sheet_id),
+            SheetPos::new(other_id, 1, 1),
+            None,
+        );
+
+        let sheet_name = gc.sheet(sheet_id).name.clone();
+        assert_eq!(
+            code(&gc, SheetPos::new(other_id, 1, 2)),
+            Some(format!("B1 + {sheet_name:?}!C2"))
+        );
+        assert_eq!(
+            gc.sheet(other_id).display_value(Pos { x: 1, y: 2 }),
+            Some(CellValue::Number(12.into()))
+        );
+        assert_eq!(
+            code(&gc, SheetPos::new(sheet_id, 3, 3)),
+            Some(format!("{other_name:?}!B1 * 2"))
+        );
+        assert_eq!(
+            gc.sheet(sheet_id).display_value(Pos { x: 3, y: 3 }),
+            Some(CellValue::Number(10.into()))
+        );
+        assert_eq!(gc.sheet(sheet_id).display_value(Pos { x: 0, y: 0 }), None);
+    }
+
+    #[test]
+    fn move_two_code_cells() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.add_sheet(None);
+        let other_id = gc.sheet_ids()[1];
+        gc.set_code_cell(
+            SheetPos::new(sheet_id, 0, 0),
+            CodeCellLanguage::Formula,
+            "1 + 1".into(),
+            None,
+        );
+        gc.set_code_cell(
+            SheetPos::new(sheet_id, 0, 1),
+            CodeCellLanguage::Formula,
+            "2 + 2".into(),
+            None,
+        );
+
+        let source = SheetRect::from_numbers(0, 0, 1, 2, sheet_id);
+        let dest = SheetPos::new(other_id, 1, 1);
+        let indices: Vec<usize> = gc
+            .move_cells_operations(source, dest)
+            .into_iter()
+            .filter_map(|op| match op {
+                Operation::SetCodeRun { index, .. } => Some(index),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(indices, vec![0, 1]);
+
+        gc.move_cells(source, dest, None);
+        let sheet = gc.sheet(other_id);
+        assert_eq!(
+            sheet.code_runs.keys().copied().collect::<Vec<_>>(),
+            vec![Pos { x: 1, y: 1 }, Pos { x: 1, y: 2 }]
+        );
+        assert_eq!(
+            sheet.display_value(Pos { x: 1, y: 1 }),
+            Some(CellValue::Number(2.into()))
+        );
+        assert_eq!(
+            sheet.display_value(Pos { x: 1, y: 2 }),
+            Some(CellValue::Number(4.into()))
+        );
+        assert!(gc.sheet(sheet_id).code_runs.is_empty());
+    }
+}
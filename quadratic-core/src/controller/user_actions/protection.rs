@@ -0,0 +1,219 @@
+use crate::{
+    controller::{transaction_summary::TransactionSummary, GridController},
+    grid::SheetId,
+    SheetRect,
+};
+
+impl GridController {
+    /// Locks a sheet so only the editors (and the current user) can edit it.
+    pub fn lock_sheet(
+        &mut self,
+        sheet_id: SheetId,
+        editors: Vec<String>,
+        cursor: Option<String>,
+    ) -> TransactionSummary {
+        let ops = self.lock_sheet_operations(sheet_id, editors);
+        self.start_user_transaction(ops, cursor)
+    }
+
+    /// Unlocks a sheet.
+    pub fn unlock_sheet(
+        &mut self,
+        sheet_id: SheetId,
+        cursor: Option<String>,
+    ) -> TransactionSummary {
+        let ops = self.unlock_sheet_operations(sheet_id);
+        self.start_user_transaction(ops, cursor)
+    }
+
+    /// Protects a range so only the editors (and the current user) can edit
+    /// it.
+    pub fn add_protected_range(
+        &mut self,
+        sheet_rect: SheetRect,
+        editors: Vec<String>,
+        cursor: Option<String>,
+    ) -> TransactionSummary {
+        let ops = self.add_protected_range_operations(sheet_rect, editors);
+        self.start_user_transaction(ops, cursor)
+    }
+
+    /// Removes all protected ranges that intersect the sheet_rect.
+    pub fn remove_protected_ranges(
+        &mut self,
+        sheet_rect: SheetRect,
+        cursor: Option<String>,
+    ) -> TransactionSummary {
+        let ops = self.remove_protected_ranges_operations(sheet_rect);
+        self.start_user_transaction(ops, cursor)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        controller::{operations::operation::Operation, GridController},
+        error_core::CoreError,
+        grid::{
+            sheet::{
+                conditional_formats::{ConditionalFormat, ConditionalFormatRule},
+                validations::{Validation, ValidationAction, ValidationCriteria},
+            },
+            CodeCellLanguage,
+        },
+        CellValue, Pos, Rect, SheetPos, SheetRect,
+    };
+
+    #[test]
+    fn protects_ranges() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_user_id(Some("ada".into()));
+        let summary = gc.add_protected_range(
+            SheetRect::from_numbers(0, 0, 2, 2, sheet_id),
+            vec!["bob".into()],
+            None,
+        );
+        assert!(summary.protections_modified.contains(&sheet_id));
+        assert_eq!(
+            gc.sheet(sheet_id).protection.ranges[0].editors,
+            vec!["bob".to_string(), "ada".to_string()]
+        );
+
+        gc.set_user_id(Some("cy".into()));
+        let summary = gc.set_cell_value(SheetPos::new(sheet_id, 1, 1), "1".into(), None);
+        assert_eq!(
+            summary.error,
+            Some(CoreError::Protected("cells A0:B1 are protected".into()))
+        );
+        assert_eq!(gc.sheet(sheet_id).display_value(Pos { x: 1, y: 1 }), None);
+
+        // cells outside the range can be edited
+        gc.set_cell_value(SheetPos::new(sheet_id, 2, 2), "2".into(), None);
+        assert_eq!(
+            gc.sheet(sheet_id).display_value(Pos { x: 2, y: 2 }),
+            Some(CellValue::Number(2.into()))
+        );
+
+        // only editors can remove the range
+        let summary =
+            gc.remove_protected_ranges(SheetRect::single_pos(Pos::ORIGIN, sheet_id), None);
+        assert!(summary.error.is_some());
+        gc.set_user_id(Some("bob".into()));
+        gc.set_cell_value(SheetPos::new(sheet_id, 1, 1), "1".into(), None);
+        assert_eq!(
+            gc.sheet(sheet_id).display_value(Pos { x: 1, y: 1 }),
+            Some(CellValue::Number(1.into()))
+        );
+        gc.remove_protected_ranges(SheetRect::single_pos(Pos::ORIGIN, sheet_id), None);
+        assert!(gc.sheet(sheet_id).protection.is_empty());
+    }
+
+    #[test]
+    fn locks_sheets() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_user_id(Some("ada".into()));
+        gc.set_cell_value(SheetPos::new(sheet_id, 0, 0), "1".into(), None);
+        gc.lock_sheet(sheet_id, vec![], None);
+
+        gc.set_user_id(Some("bob".into()));
+        let locked = Some(CoreError::Protected("the sheet is locked".into()));
+        let summary = gc.set_cell_value(SheetPos::new(sheet_id, 5, 5), "1".into(), None);
+        assert_eq!(summary.error, locked);
+        assert_eq!(gc.delete_sheet(sheet_id, None).error, locked);
+        assert_eq!(gc.unlock_sheet(sheet_id, None).error, locked);
+
+        // undo keeps the transaction until the user can apply it
+        assert_eq!(gc.undo(None).error, locked);
+        assert!(gc.has_undo());
+
+        gc.set_user_id(Some("ada".into()));
+        gc.unlock_sheet(sheet_id, None);
+        gc.set_user_id(Some("bob".into()));
+        gc.set_cell_value(SheetPos::new(sheet_id, 5, 5), "1".into(), None);
+        assert_eq!(
+            gc.sheet(sheet_id).display_value(Pos { x: 5, y: 5 }),
+            Some(CellValue::Number(1.into()))
+        );
+    }
+
+    #[test]
+    fn protects_code_output_and_rules() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_user_id(Some("ada".into()));
+        gc.add_protected_range(SheetRect::from_numbers(0, 0, 2, 2, sheet_id), vec![], None);
+
+        // output cannot spill into a protected range from outside of it, even
+        // for its editors
+        gc.set_code_cell(
+            SheetPos::new(sheet_id, 0, -2),
+            CodeCellLanguage::Formula,
+            "{1;2;3}".into(),
+            None,
+        );
+        let sheet = gc.sheet(sheet_id);
+        assert!(sheet.code_run(Pos { x: 0, y: -2 }).unwrap().spill_error);
+        assert_eq!(sheet.display_value(Pos { x: 0, y: 0 }), None);
+
+        // ... but can spill inside of it
+        gc.set_code_cell(
+            SheetPos::new(sheet_id, 0, 0),
+            CodeCellLanguage::Formula,
+            "{1;2}".into(),
+            None,
+        );
+        let sheet = gc.sheet(sheet_id);
+        assert!(!sheet.code_run(Pos::ORIGIN).unwrap().spill_error);
+        assert_eq!(
+            sheet.display_value(Pos { x: 0, y: 1 }),
+            Some(CellValue::Number(2.into()))
+        );
+
+        // others cannot set a code run whose output overlaps the range
+        let code_run = gc.sheet(sheet_id).code_run(Pos::ORIGIN).unwrap().clone();
+        let set_code_run = Operation::SetCodeRun {
+            sheet_pos: SheetPos::new(sheet_id, 0, -1),
+            code_run: Some(code_run),
+            index: 0,
+        };
+        gc.set_user_id(Some("bob".into()));
+        assert!(matches!(
+            gc.check_protection(&[set_code_run]),
+            Err(CoreError::Protected(_))
+        ));
+
+        // or add rules to protected cells
+        let protected = Some(CoreError::Protected("cells A0:B1 are protected".into()));
+        let summary = gc.add_validation(
+            sheet_id,
+            Validation {
+                rect: Rect::from_numbers(1, 1, 3, 3),
+                criteria: ValidationCriteria::Number {
+                    min: Some(0.0),
+                    max: None,
+                },
+                action: ValidationAction::Reject,
+                ignore_blank: true,
+                message: None,
+            },
+            None,
+        );
+        assert_eq!(summary.error, protected);
+        let summary = gc.add_conditional_format(
+            sheet_id,
+            ConditionalFormat {
+                rect: Rect::from_numbers(1, 1, 3, 3),
+                rule: ConditionalFormatRule::TopN {
+                    count: 1,
+                    bottom: false,
+                },
+                style: Default::default(),
+            },
+            None,
+        );
+        assert_eq!(summary.error, protected);
+        assert!(gc.sheet(sheet_id).validations.is_empty());
+    }
+}
@@ -24,6 +24,18 @@ impl GridController {
         self.start_user_transaction(ops, cursor)
     }
 
+    /// Sets the number of frozen rows and columns for a sheet.
+    pub fn set_sheet_frozen(
+        &mut self,
+        sheet_id: SheetId,
+        rows: u32,
+        columns: u32,
+        cursor: Option<String>,
+    ) -> TransactionSummary {
+        let ops = self.set_sheet_frozen_operations(sheet_id, rows, columns);
+        self.start_user_transaction(ops, cursor)
+    }
+
     pub fn add_sheet(&mut self, cursor: Option<String>) -> TransactionSummary {
         self.add_sheet_with_name(cursor, None)
     }
@@ -124,6 +136,33 @@ mod test {
         assert_eq!(sheet.color, Some(String::from("red")));
     }
 
+    #[test]
+    fn test_set_sheet_frozen() {
+        let mut g = GridController::test();
+        let s1 = g.sheet_ids()[0];
+
+        g.set_sheet_frozen(s1, 2, 0, None);
+        g.set_sheet_frozen(s1, 2, 1, None);
+        let sheet = g.sheet(s1);
+        assert_eq!((sheet.frozen_rows, sheet.frozen_columns), (2, 1));
+
+        g.undo(None);
+        let sheet = g.sheet(s1);
+        assert_eq!((sheet.frozen_rows, sheet.frozen_columns), (2, 0));
+
+        g.undo(None);
+        let sheet = g.sheet(s1);
+        assert_eq!((sheet.frozen_rows, sheet.frozen_columns), (0, 0));
+
+        g.redo(None);
+        let sheet = g.sheet(s1);
+        assert_eq!((sheet.frozen_rows, sheet.frozen_columns), (2, 0));
+
+        g.set_sheet_frozen(SheetId::new(), 5, 5, None);
+        let sheet = g.sheet(s1);
+        assert_eq!((sheet.frozen_rows, sheet.frozen_columns), (2, 0));
+    }
+
     #[test]
     fn test_delete_sheet() {
         let mut g = GridController::test();
@@ -12,6 +12,12 @@ impl GridController {
         !self.redo_stack.is_empty()
     }
     pub fn undo(&mut self, cursor: Option<String>) -> TransactionSummary {
+        if let Some(transaction) = self.undo_stack.last() {
+            // keep the transaction so it can be undone once the user has access
+            if let Err(error) = self.check_protection(&transaction.operations) {
+                return TransactionSummary::error(error);
+            }
+        }
         if let Some(mut transaction) = self.undo_stack.pop() {
             // we need to assign the transaction a new id to avoid conflicts with the original transaction.
             transaction.id = Uuid::new_v4();
@@ -21,6 +27,11 @@ impl GridController {
         }
     }
     pub fn redo(&mut self, cursor: Option<String>) -> TransactionSummary {
+        if let Some(transaction) = self.redo_stack.last() {
+            if let Err(error) = self.check_protection(&transaction.operations) {
+                return TransactionSummary::error(error);
+            }
+        }
         if let Some(mut transaction) = self.redo_stack.pop() {
             // we need to assign the transaction a new id to avoid conflicts with the original transaction.
             transaction.id = Uuid::new_v4();
@@ -0,0 +1,273 @@
+use crate::{
+    controller::{transaction_summary::TransactionSummary, GridController},
+    grid::{sheet::validations::Validation, GridBounds, SheetId},
+    Pos, Rect, SheetPos, SheetRect,
+};
+
+impl GridController {
+    /// Adds a data validation to a sheet.
+    pub fn add_validation(
+        &mut self,
+        sheet_id: SheetId,
+        validation: Validation,
+        cursor: Option<String>,
+    ) -> TransactionSummary {
+        let ops = self.add_validation_operations(sheet_id, validation);
+        self.start_user_transaction(ops, cursor)
+    }
+
+    /// Removes all data validations that intersect the sheet_rect.
+    pub fn remove_validations(
+        &mut self,
+        sheet_rect: SheetRect,
+        cursor: Option<String>,
+    ) -> TransactionSummary {
+        let ops = self.remove_validations_operations(sheet_rect);
+        self.start_user_transaction(ops, cursor)
+    }
+
+    /// Returns the dropdown options for a cell with a list validation.
+    pub fn validation_list(&self, sheet_pos: SheetPos) -> Option<Vec<String>> {
+        let sheet = self.try_sheet(sheet_pos.sheet_id)?;
+        sheet
+            .validation_at(sheet_pos.into())?
+            .list_values(&self.grid)
+    }
+
+    /// Returns the cells in a sheet whose values fail their validation. Blank
+    /// cells are not included.
+    pub fn invalid_cells(&self, sheet_id: SheetId) -> Vec<Pos> {
+        let Some(sheet) = self.try_sheet(sheet_id) else {
+            return vec![];
+        };
+        let GridBounds::NonEmpty(bounds) = sheet.bounds(true) else {
+            return vec![];
+        };
+        let mut invalid = vec![];
+        for validation in &sheet.validations {
+            if !validation.rect.intersects(bounds) {
+                continue;
+            }
+            let rect = Rect::new_span(
+                Pos {
+                    x: validation.rect.min.x.max(bounds.min.x),
+                    y: validation.rect.min.y.max(bounds.min.y),
+                },
+                Pos {
+                    x: validation.rect.max.x.min(bounds.max.x),
+                    y: validation.rect.max.y.min(bounds.max.y),
+                },
+            );
+            for pos in rect.iter() {
+                // a later validation may take precedence for this cell
+                if !sheet
+                    .validation_at(pos)
+                    .is_some_and(|current| std::ptr::eq(current, validation))
+                {
+                    continue;
+                }
+                let Some(value) = sheet.display_value(pos) else {
+                    continue;
+                };
+                if !value.is_blank_or_empty_string()
+                    && !validation.is_valid(&self.grid, pos.to_sheet_pos(sheet_id), &value)
+                    && !invalid.contains(&pos)
+                {
+                    invalid.push(pos);
+                }
+            }
+        }
+        invalid
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        controller::{user_actions::clipboard::PasteSpecial, GridController},
+        error_core::CoreError,
+        grid::sheet::validations::{Validation, ValidationAction, ValidationCriteria},
+        CellValue, Pos, Rect, SheetPos, SheetRect,
+    };
+
+    fn number_validation(action: ValidationAction) -> Validation {
+        Validation {
+            rect: Rect::from_numbers(0, 0, 1, 3),
+            criteria: ValidationCriteria::Number {
+                min: Some(0.0),
+                max: Some(10.0),
+            },
+            action,
+            ignore_blank: true,
+            message: None,
+        }
+    }
+
+    #[test]
+    fn set_cell_value_rejects_invalid_values() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let summary =
+            gc.add_validation(sheet_id, number_validation(ValidationAction::Reject), None);
+        assert!(summary.validations_modified.contains(&sheet_id));
+
+        let summary = gc.set_cell_value(SheetPos::new(sheet_id, 0, 0), "5".into(), None);
+        assert_eq!(summary.error, None);
+        let summary = gc.set_cell_value(SheetPos::new(sheet_id, 0, 1), "11".into(), None);
+        assert_eq!(
+            summary.error,
+            Some(CoreError::ValidationRejected(
+                "Value must be a number between 0 and 10".into()
+            ))
+        );
+
+        // set_cell_values rejects all of the values if any is rejected
+        let summary = gc.set_cell_values(
+            SheetPos::new(sheet_id, 0, 1),
+            vec![vec!["7"], vec!["abc"]],
+            None,
+        );
+        assert!(matches!(
+            summary.error,
+            Some(CoreError::ValidationRejected(_))
+        ));
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(
+            sheet.cell_value(Pos { x: 0, y: 0 }),
+            Some(CellValue::Number(5.into()))
+        );
+        assert_eq!(sheet.cell_value(Pos { x: 0, y: 1 }), None);
+
+        gc.set_cell_values(SheetPos::new(sheet_id, 0, 1), vec![vec!["7"]], None);
+        assert_eq!(
+            gc.sheet(sheet_id).cell_value(Pos { x: 0, y: 1 }),
+            Some(CellValue::Number(7.into()))
+        );
+
+        // outside of the validation
+        gc.set_cell_value(SheetPos::new(sheet_id, 1, 0), "abc".into(), None);
+        assert_eq!(
+            gc.sheet(sheet_id).cell_value(Pos { x: 1, y: 0 }),
+            Some(CellValue::Text("abc".into()))
+        );
+    }
+
+    #[test]
+    fn paste_and_autocomplete_are_validated() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_cell_values(
+            SheetPos::new(sheet_id, 1, 0),
+            vec![vec!["abc"], vec!["def"]],
+            None,
+        );
+        gc.add_validation(sheet_id, number_validation(ValidationAction::Reject), None);
+
+        let summary = gc.paste_from_clipboard(
+            SheetPos::new(sheet_id, 0, 0),
+            Some("1\nabc".into()),
+            None,
+            PasteSpecial::None,
+            None,
+        );
+        assert!(matches!(
+            summary.error,
+            Some(CoreError::ValidationRejected(_))
+        ));
+        assert_eq!(gc.sheet(sheet_id).cell_value(Pos { x: 0, y: 0 }), None);
+
+        let summary = gc
+            .autocomplete(
+                sheet_id,
+                Rect::from_numbers(1, 0, 1, 2),
+                Rect::from_numbers(0, 0, 2, 2),
+                None,
+            )
+            .unwrap();
+        assert!(matches!(
+            summary.error,
+            Some(CoreError::ValidationRejected(_))
+        ));
+        assert_eq!(gc.sheet(sheet_id).cell_value(Pos { x: 0, y: 1 }), None);
+
+        // warnings are returned with the pasted values
+        gc.add_validation(sheet_id, number_validation(ValidationAction::Warn), None);
+        let summary = gc.paste_from_clipboard(
+            SheetPos::new(sheet_id, 0, 0),
+            Some("1\nabc".into()),
+            None,
+            PasteSpecial::None,
+            None,
+        );
+        assert_eq!(
+            summary.validation_warning,
+            Some("Value must be a number between 0 and 10".into())
+        );
+        assert_eq!(
+            gc.sheet(sheet_id).cell_value(Pos { x: 0, y: 1 }),
+            Some(CellValue::Text("abc".into()))
+        );
+    }
+
+    #[test]
+    fn set_cell_value_warns_and_lists_invalid_cells() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_cell_value(SheetPos::new(sheet_id, 0, 2), "20".into(), None);
+        gc.add_validation(sheet_id, number_validation(ValidationAction::Warn), None);
+
+        let summary = gc.set_cell_value(SheetPos::new(sheet_id, 0, 0), "abc".into(), None);
+        assert_eq!(summary.error, None);
+        assert_eq!(
+            summary.validation_warning,
+            Some("Value must be a number between 0 and 10".into())
+        );
+        let summary = gc.set_cell_value(SheetPos::new(sheet_id, 0, 1), "3".into(), None);
+        assert_eq!(summary.validation_warning, None);
+
+        assert_eq!(
+            gc.invalid_cells(sheet_id),
+            vec![Pos { x: 0, y: 0 }, Pos { x: 0, y: 2 }]
+        );
+
+        gc.remove_validations(SheetRect::single_pos(Pos { x: 0, y: 1 }, sheet_id), None);
+        assert!(gc.sheet(sheet_id).validations.is_empty());
+        assert!(gc.invalid_cells(sheet_id).is_empty());
+
+        gc.undo(None);
+        assert_eq!(gc.sheet(sheet_id).validations.len(), 1);
+    }
+
+    #[test]
+    fn validation_list() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let validation = Validation {
+            rect: Rect::from_numbers(0, 0, 1, 1),
+            criteria: ValidationCriteria::List {
+                values: vec!["Yes".into(), "No".into()],
+            },
+            action: ValidationAction::Reject,
+            ignore_blank: true,
+            message: Some("Pick yes or no".into()),
+        };
+        gc.add_validation(sheet_id, validation.clone(), None);
+        assert_eq!(
+            gc.validation_list(SheetPos::new(sheet_id, 0, 0)),
+            Some(vec!["Yes".to_string(), "No".to_string()])
+        );
+        assert_eq!(gc.validation_list(SheetPos::new(sheet_id, 1, 0)), None);
+
+        let summary = gc.set_cell_value(SheetPos::new(sheet_id, 0, 0), "maybe".into(), None);
+        assert_eq!(
+            summary.error,
+            Some(CoreError::ValidationRejected("Pick yes or no".into()))
+        );
+
+        // a validation covering an existing one replaces it
+        let mut replacement = validation;
+        replacement.rect = Rect::from_numbers(0, 0, 2, 2);
+        gc.add_validation(sheet_id, replacement.clone(), None);
+        assert_eq!(gc.sheet(sheet_id).validations, vec![replacement]);
+    }
+}
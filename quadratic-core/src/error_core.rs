@@ -27,6 +27,12 @@ pub enum CoreError {
 
     #[error("IndexedDb error: {0}")]
     IndexedDbError(String),
+
+    #[error("Invalid value: {0}")]
+    ValidationRejected(String),
+
+    #[error("Protected: {0}")]
+    Protected(String),
 }
 
 impl From<serde_json::Error> for CoreError {
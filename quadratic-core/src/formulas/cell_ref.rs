@@ -108,7 +108,7 @@ impl CellRef {
             None => String::new(),
         };
         let col = self.x.col_string(base.x);
-        let row = self.y.col_string(base.y);
+        let row = self.y.row_string(base.y);
         format!("{sheet_str}{col}{row}")
     }
 
@@ -240,7 +240,11 @@ impl CellRefCoord {
     /// a row coordinate.
     fn row_string(self, base: i64) -> String {
         let row = self.resolve_from(base);
-        format!("{}{row}", self.prefix())
+        if row < 0 {
+            format!("{}n{}", self.prefix(), -row)
+        } else {
+            format!("{}{row}", self.prefix())
+        }
     }
 
     /// Returns whether the coordinate is relative (i.e., no '$' prefix).
@@ -279,6 +283,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_a1_string() {
+        let base_pos = pos![C3];
+        for s in ["A0", "$B$2", "Cn4", "D$n1", "$nA5", "'Sheet 2'!E6"] {
+            let cell_ref = CellRef::parse_a1(s, base_pos).unwrap();
+            let a1 = cell_ref.a1_string(base_pos);
+            assert_eq!(CellRef::parse_a1(&a1, base_pos), Some(cell_ref));
+        }
+    }
+
     #[test]
     fn test_a1_sheet_parsing() {
         let pos = CellRef::parse_a1("'Sheet 2'!A0", crate::Pos::ORIGIN);
@@ -31,7 +31,9 @@ impl TryFrom<Spanned<&CellValue>> for Criterion {
             CellValue::Blank
             | CellValue::Number(_)
             | CellValue::Html(_)
+            | CellValue::Image(_)
             | CellValue::Code(_)
+            | CellValue::Link(_)
             | CellValue::Logical(_)
             | CellValue::Instant(_)
             | CellValue::Duration(_) => Ok(Criterion::Compare {
@@ -104,7 +106,12 @@ impl Criterion {
             },
             CellValue::Error(_) => false,
             CellValue::Html(_) => false,
+            CellValue::Image(_) => false,
             CellValue::Code(_) => false,
+            CellValue::Link(rhs) => compare_fn.compare(
+                &lhs.to_string().to_ascii_lowercase(),
+                &rhs.text.to_ascii_lowercase(),
+            ),
         }
     }
 
@@ -25,6 +25,22 @@ fn get_functions() -> Vec<FormulaFunction> {
                 ctx.get_cell(&pos, cellref_string.span)?.inner
             }
         ),
+        formula_fn!(
+            /// Returns a link that opens `url`, showing `text` or, if `text`
+            /// is omitted, the URL itself.
+            ///
+            /// To link to cells in the workbook, use `#` followed by a cell or
+            /// range, such as `"#B5"` or `"#'Sheet 2'!A1:C10"`.
+            #[examples(
+                "HYPERLINK(\"https://quadratichq.com\")",
+                "HYPERLINK(\"#'Sheet 2'!A1\", \"Totals\")"
+            )]
+            #[zip_map]
+            fn HYPERLINK([url]: String, [text]: (Option<String>)) {
+                let text = text.unwrap_or_else(|| url.clone());
+                CellValue::link(text, url)
+            }
+        ),
         formula_fn!(
             /// Searches for a value in the first vertical column of a range and
             /// return the corresponding cell in another vertical column, or an
@@ -463,7 +479,7 @@ mod tests {
     use lazy_static::lazy_static;
     use smallvec::smallvec;
 
-    use crate::{formulas::tests::*, Pos};
+    use crate::{formulas::tests::*, Link, Pos};
 
     lazy_static! {
         static ref NUMBERS_LOOKUP_ARRAY: Array = array![
@@ -511,6 +527,38 @@ mod tests {
         assert_eq!("35".to_string(), eval_to_string(&g, "INDIRECT(\"D5\")"));
     }
 
+    #[test]
+    fn test_formula_hyperlink() {
+        let g = Grid::new();
+        assert_eq!(
+            eval(&g, "HYPERLINK(\"https://quadratichq.com\")"),
+            Value::from(CellValue::Link(
+                Link::new("https://quadratichq.com", "https://quadratichq.com").unwrap()
+            )),
+        );
+        assert_eq!(
+            eval_to_string(&g, "HYPERLINK(\"#B5\", \"Totals\") & \"!\""),
+            "Totals!",
+        );
+        assert_eq!(
+            eval(&g, "HYPERLINK({\"#A1\", \"#A2\"}, \"cell\")").cell_values_slice(),
+            [
+                CellValue::Link(Link::new("cell", "#A1").unwrap()),
+                CellValue::Link(Link::new("cell", "#A2").unwrap()),
+            ],
+        );
+
+        // links that could run code are plain text
+        assert_eq!(
+            eval(&g, "HYPERLINK(\"javascript:alert(1)\")"),
+            Value::from(CellValue::Text("javascript:alert(1)".into())),
+        );
+        assert_eq!(
+            eval(&g, "HYPERLINK(\"JAVASCRIPT:alert(1)\", \"Click\")"),
+            Value::from(CellValue::Text("Click".into())),
+        );
+    }
+
     /// Test VLOOKUP error conditions.
     #[test]
     fn test_vlookup_errors() {
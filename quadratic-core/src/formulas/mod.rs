@@ -20,8 +20,9 @@ pub use cell_ref::*;
 pub use criteria::Criterion;
 pub use ctx::Ctx;
 use functions::FormulaFnArgs;
+pub(crate) use lexer::{tokenize, Token};
 use params::{Param, ParamKind};
-pub use parser::{find_cell_references, parse_formula};
+pub use parser::{find_cell_references, parse_formula, replace_cell_references};
 use wildcards::wildcard_pattern_to_regex;
 
 /// Escapes a formula string.
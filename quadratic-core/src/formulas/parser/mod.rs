@@ -43,6 +43,26 @@ pub fn find_cell_references(source: &str, pos: Pos) -> Vec<Spanned<RangeRef>> {
     ret
 }
 
+/// Replaces each cell reference in a formula with the string returned by `f`.
+/// References for which `f` returns `None` are left unchanged.
+pub fn replace_cell_references(
+    source: &str,
+    pos: Pos,
+    mut f: impl FnMut(&RangeRef) -> Option<String>,
+) -> String {
+    let mut ret = String::new();
+    let mut last = 0;
+    for Spanned { span, inner } in find_cell_references(source, pos) {
+        if let Some(replacement) = f(&inner) {
+            ret.push_str(&source[last..span.start as usize]);
+            ret.push_str(&replacement);
+            last = span.end as usize;
+        }
+    }
+    ret.push_str(&source[last..]);
+    ret
+}
+
 /// Token parser used to assemble an AST.
 #[derive(Debug, Copy, Clone)]
 pub struct Parser<'a> {
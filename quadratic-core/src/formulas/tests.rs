@@ -324,6 +324,18 @@ fn test_find_cell_references() {
     assert_eq!(cell_references_found.len(), test_cases.len());
 }
 
+#[test]
+fn test_replace_cell_references() {
+    let formula_string = "SUM(A1:B2) + 'Sheet 2'!$C3 * A1";
+    let replaced =
+        replace_cell_references(formula_string, Pos::ORIGIN, |range_ref| match range_ref {
+            RangeRef::Cell { pos } if pos.sheet.is_none() => Some("Z9".to_string()),
+            RangeRef::CellRange { .. } => Some("D4:E5".to_string()),
+            _ => None,
+        });
+    assert_eq!(replaced, "SUM(D4:E5) + 'Sheet 2'!$C3 * Z9");
+}
+
 #[test]
 fn test_sheet_references() {
     let mut g = Grid::new();
@@ -1,6 +1,50 @@
 use crate::grid::js_types::JsRenderBorder;
 use crate::grid::Sheet;
 
+/// Splits a border segment into the parts that are not inside a merged
+/// region. `line` is the column (for vertical borders) or row (for horizontal
+/// borders), and the segment covers `start..start + len` along it.
+fn split_at_merged_cells(
+    sheet: &Sheet,
+    vertical: bool,
+    line: i64,
+    start: i64,
+    len: i64,
+) -> Vec<(i64, i64)> {
+    let mut segments = vec![(start, start + len)];
+    for merged in &sheet.merged_cells {
+        // the line is interior if it is between the region's first and last cell
+        let (line_min, line_max, min, max) = if vertical {
+            (merged.min.x, merged.max.x, merged.min.y, merged.max.y)
+        } else {
+            (merged.min.y, merged.max.y, merged.min.x, merged.max.x)
+        };
+        if line <= line_min || line > line_max {
+            continue;
+        }
+        segments = segments
+            .into_iter()
+            .flat_map(|(start, end)| {
+                if end <= min || start > max {
+                    return vec![(start, end)];
+                }
+                let mut split = vec![];
+                if start < min {
+                    split.push((start, min));
+                }
+                if end > max + 1 {
+                    split.push((max + 1, end));
+                }
+                split
+            })
+            .collect();
+    }
+    segments
+        .into_iter()
+        .map(|(start, end)| (start, end - start))
+        .collect()
+}
+
 pub fn get_render_vertical_borders(sheet: &Sheet) -> Vec<JsRenderBorder> {
     sheet
         .borders
@@ -8,12 +52,16 @@ pub fn get_render_vertical_borders(sheet: &Sheet) -> Vec<JsRenderBorder> {
         .vertical
         .iter()
         .flat_map(|(&column_index, column)| {
-            column.blocks().map(move |block| JsRenderBorder {
-                x: column_index,
-                y: block.start(),
-                w: None,
-                h: Some(block.len()),
-                style: block.content().value,
+            column.blocks().flat_map(move |block| {
+                split_at_merged_cells(sheet, true, column_index, block.start(), block.len() as i64)
+                    .into_iter()
+                    .map(move |(y, h)| JsRenderBorder {
+                        x: column_index,
+                        y,
+                        w: None,
+                        h: Some(h as usize),
+                        style: block.content().value,
+                    })
             })
         })
         .collect()
@@ -26,12 +74,22 @@ pub fn get_render_horizontal_borders(sheet: &Sheet) -> Vec<JsRenderBorder> {
         .horizontal
         .iter()
         .flat_map(|(&column_index, column)| {
-            column.blocks().map(move |block| JsRenderBorder {
-                x: block.start(),
-                y: column_index,
-                w: Some(block.len()),
-                h: None,
-                style: block.content().value,
+            column.blocks().flat_map(move |block| {
+                split_at_merged_cells(
+                    sheet,
+                    false,
+                    column_index,
+                    block.start(),
+                    block.len() as i64,
+                )
+                .into_iter()
+                .map(move |(x, w)| JsRenderBorder {
+                    x,
+                    y: column_index,
+                    w: Some(w as usize),
+                    h: None,
+                    style: block.content().value,
+                })
             })
         })
         .collect()
@@ -77,6 +135,36 @@ mod tests {
     //     }
     // }
 
+    #[test]
+    fn merged_cells_hide_interior_borders() {
+        let mut sheet = Sheet::new(SheetId::new(), "Test Sheet".to_string(), "".to_string());
+        let rect = Rect::new_span(Pos { x: 0, y: 0 }, Pos { x: 2, y: 2 });
+        let style = BorderStyle {
+            color: Rgba::from_str("#000000").unwrap(),
+            line: CellBorderLine::Line1,
+        };
+        set_rect_border_selection(&mut sheet, &rect, vec![BorderSelection::All], Some(style));
+        sheet.merge_cells(Rect::new_span(Pos { x: 0, y: 0 }, Pos { x: 1, y: 1 }));
+
+        let vertical: HashSet<_> = get_render_vertical_borders(&sheet).into_iter().collect();
+        let expected = HashSet::from([
+            JsRenderBorder::new(0, 0, None, Some(3), style),
+            JsRenderBorder::new(1, 2, None, Some(1), style),
+            JsRenderBorder::new(2, 0, None, Some(3), style),
+            JsRenderBorder::new(3, 0, None, Some(3), style),
+        ]);
+        assert_eq!(vertical, expected);
+
+        let horizontal: HashSet<_> = get_render_horizontal_borders(&sheet).into_iter().collect();
+        let expected = HashSet::from([
+            JsRenderBorder::new(0, 0, Some(3), None, style),
+            JsRenderBorder::new(2, 1, Some(1), None, style),
+            JsRenderBorder::new(0, 2, Some(3), None, style),
+            JsRenderBorder::new(0, 3, Some(3), None, style),
+        ]);
+        assert_eq!(horizontal, expected);
+    }
+
     mod vertical {
         use crate::grid::set_rect_borders;
 
@@ -25,6 +25,23 @@ pub struct CodeRun {
 }
 
 impl CodeRun {
+    // creates a successful CodeRun with a single value for testing
+    #[cfg(test)]
+    pub fn test_ok(value: CellValue, output_type: Option<&str>) -> Self {
+        CodeRun {
+            formatted_code_string: None,
+            std_out: None,
+            std_err: None,
+            cells_accessed: HashSet::new(),
+            result: CodeRunResult::Ok(Value::Single(value)),
+            return_type: None,
+            spill_error: false,
+            line_number: None,
+            output_type: output_type.map(str::to_string),
+            last_modified: Utc::now(),
+        }
+    }
+
     /// Returns the output value of a code run at the relative location (ie, (0,0) is the top of the code run result).
     /// A spill or error returns CellValue::Blank. Note: this assumes a CellValue::Code exists at the location.
     pub fn cell_value_at(&self, x: u32, y: u32) -> Option<CellValue> {
@@ -18,11 +18,23 @@ pub struct Column {
     pub values: BTreeMap<i64, CellValue>,
     pub align: ColumnData<SameValue<CellAlign>>,
     pub wrap: ColumnData<SameValue<CellWrap>>,
+    #[serde(default)]
+    pub vertical_align: ColumnData<SameValue<CellVerticalAlign>>,
+    #[serde(default)]
+    pub text_rotation: ColumnData<SameValue<i16>>,
     pub numeric_format: ColumnData<SameValue<NumericFormat>>,
     pub numeric_decimals: ColumnData<SameValue<i16>>,
     pub numeric_commas: ColumnData<SameValue<bool>>,
     pub bold: ColumnData<SameValue<bool>>,
     pub italic: ColumnData<SameValue<bool>>,
+    #[serde(default)]
+    pub underline: ColumnData<SameValue<bool>>,
+    #[serde(default)]
+    pub strike_through: ColumnData<SameValue<bool>>,
+    #[serde(default)]
+    pub font_size: ColumnData<SameValue<i16>>,
+    #[serde(default)]
+    pub font_family: ColumnData<SameValue<String>>,
     pub text_color: ColumnData<SameValue<String>>,
     pub fill_color: ColumnData<SameValue<String>>,
     pub render_size: ColumnData<SameValue<RenderSize>>,
@@ -53,10 +65,16 @@ impl Column {
                 self.values_range(),
                 self.align.range(),
                 self.wrap.range(),
+                self.vertical_align.range(),
+                self.text_rotation.range(),
                 self.numeric_format.range(),
                 self.numeric_decimals.range(),
                 self.bold.range(),
                 self.italic.range(),
+                self.underline.range(),
+                self.strike_through.range(),
+                self.font_size.range(),
+                self.font_family.range(),
                 self.text_color.range(),
                 self.fill_color.range(),
             ])
@@ -70,10 +88,16 @@ impl Column {
         self.has_data_in_row(y)
             || self.align.get(y).is_some()
             || self.wrap.get(y).is_some()
+            || self.vertical_align.get(y).is_some()
+            || self.text_rotation.get(y).is_some()
             || self.numeric_format.get(y).is_some()
             || self.numeric_decimals.get(y).is_some()
             || self.bold.get(y).is_some()
             || self.italic.get(y).is_some()
+            || self.underline.get(y).is_some()
+            || self.strike_through.get(y).is_some()
+            || self.font_size.get(y).is_some()
+            || self.font_family.get(y).is_some()
             || self.text_color.get(y).is_some()
             || self.fill_color.get(y).is_some()
     }
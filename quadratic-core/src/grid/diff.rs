@@ -0,0 +1,526 @@
+//! Compares two versions of a [`Grid`] (e.g., two checkpoints of a file).
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Write};
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    block::SameValue, formatting::CellFmtAttr, Bold, CellAlign, CellVerticalAlign, CellWrap,
+    Column, ColumnData, FillColor, FontFamily, FontSize, Grid, Italic, NumericCommas,
+    NumericDecimals, NumericFormat, RenderSize, Sheet, SheetId, StrikeThrough, TextColor,
+    TextRotation, Underline,
+};
+use crate::{CellValue, Pos};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SheetDiffKind {
+    Added,
+    Deleted,
+    Modified,
+}
+
+/// A cell whose value changed. `None` means the cell was empty.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CellValueDiff {
+    pub pos: Pos,
+    pub old: Option<CellValue>,
+    pub new: Option<CellValue>,
+}
+
+/// A cell whose formatting changed, with the names of the changed attributes.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CellFormatDiff {
+    pub pos: Pos,
+    pub attributes: Vec<String>,
+}
+
+/// A column width or row height that changed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OffsetDiff {
+    pub index: i64,
+    pub old: f64,
+    pub new: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SheetDiff {
+    pub sheet_id: SheetId,
+    pub name: String,
+    pub kind: SheetDiffKind,
+
+    /// The previous name of the sheet if it was renamed.
+    pub old_name: Option<String>,
+
+    pub values: Vec<CellValueDiff>,
+    pub code: Vec<CellValueDiff>,
+    pub formats: Vec<CellFormatDiff>,
+    pub borders: Vec<Pos>,
+    pub column_widths: Vec<OffsetDiff>,
+    pub row_heights: Vec<OffsetDiff>,
+}
+
+impl SheetDiff {
+    pub fn is_empty(&self) -> bool {
+        self.kind == SheetDiffKind::Modified
+            && self.old_name.is_none()
+            && self.values.is_empty()
+            && self.code.is_empty()
+            && self.formats.is_empty()
+            && self.borders.is_empty()
+            && self.column_widths.is_empty()
+            && self.row_heights.is_empty()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct GridDiff {
+    pub sheets: Vec<SheetDiff>,
+}
+
+impl GridDiff {
+    pub fn is_empty(&self) -> bool {
+        self.sheets.is_empty()
+    }
+
+    /// Returns a human-readable summary of the diff, with one line per change.
+    pub fn summary(&self) -> String {
+        if self.is_empty() {
+            return "No changes\n".to_string();
+        }
+
+        let mut s = String::new();
+        for sheet in &self.sheets {
+            match (sheet.kind, &sheet.old_name) {
+                (SheetDiffKind::Added, _) => writeln!(s, "Added sheet '{}'", sheet.name),
+                (SheetDiffKind::Deleted, _) => writeln!(s, "Deleted sheet '{}'", sheet.name),
+                (SheetDiffKind::Modified, Some(old_name)) => {
+                    writeln!(s, "Sheet '{}' (renamed from '{old_name}')", sheet.name)
+                }
+                (SheetDiffKind::Modified, None) => writeln!(s, "Sheet '{}'", sheet.name),
+            }
+            .unwrap();
+
+            // newlines are escaped to keep one change per line
+            let display = |value: &Option<CellValue>| match value {
+                Some(CellValue::Code(code)) => {
+                    format!("{:?} `{}`", code.language, code.code.replace('\n', "\\n"))
+                }
+                Some(value) => format!("'{}'", value.to_string().replace('\n', "\\n")),
+                None => "(empty)".to_string(),
+            };
+            for diff in sheet.values.iter().chain(sheet.code.iter()) {
+                writeln!(
+                    s,
+                    "  {}: {} -> {}",
+                    diff.pos,
+                    display(&diff.old),
+                    display(&diff.new)
+                )
+                .unwrap();
+            }
+            for diff in &sheet.formats {
+                writeln!(s, "  {}: format {}", diff.pos, diff.attributes.join(", ")).unwrap();
+            }
+            for pos in &sheet.borders {
+                writeln!(s, "  {pos}: borders").unwrap();
+            }
+            for diff in &sheet.column_widths {
+                writeln!(
+                    s,
+                    "  column {}: width {} -> {}",
+                    diff.index, diff.old, diff.new
+                )
+                .unwrap();
+            }
+            for diff in &sheet.row_heights {
+                writeln!(
+                    s,
+                    "  row {}: height {} -> {}",
+                    diff.index, diff.old, diff.new
+                )
+                .unwrap();
+            }
+        }
+        s
+    }
+}
+
+impl Grid {
+    /// Compares this grid (the old version) to another grid (the new
+    /// version). Sheets are matched by id.
+    pub fn diff(&self, new: &Grid) -> GridDiff {
+        let mut sheets = vec![];
+        for old_sheet in &self.sheets {
+            match new.try_sheet(old_sheet.id) {
+                Some(new_sheet) => {
+                    let diff = diff_sheets(old_sheet, new_sheet, SheetDiffKind::Modified);
+                    if !diff.is_empty() {
+                        sheets.push(diff);
+                    }
+                }
+                None => {
+                    let empty = empty_sheet(old_sheet);
+                    sheets.push(diff_sheets(old_sheet, &empty, SheetDiffKind::Deleted));
+                }
+            }
+        }
+        for new_sheet in &new.sheets {
+            if self.try_sheet(new_sheet.id).is_none() {
+                let empty = empty_sheet(new_sheet);
+                sheets.push(diff_sheets(&empty, new_sheet, SheetDiffKind::Added));
+            }
+        }
+        GridDiff { sheets }
+    }
+}
+
+fn empty_sheet(sheet: &Sheet) -> Sheet {
+    Sheet::new(sheet.id, sheet.name.clone(), sheet.order.clone())
+}
+
+fn diff_sheets(old: &Sheet, new: &Sheet, kind: SheetDiffKind) -> SheetDiff {
+    let (values, code) = diff_values(old, new);
+    let (formats, borders) = diff_formats_and_borders(old, new);
+    let old_widths = old.offsets.export();
+    let new_widths = new.offsets.export();
+    SheetDiff {
+        sheet_id: new.id,
+        name: new.name.clone(),
+        kind,
+        old_name: (kind == SheetDiffKind::Modified && old.name != new.name)
+            .then(|| old.name.clone()),
+        values,
+        code,
+        formats,
+        borders,
+        column_widths: diff_offsets(&old_widths.0, &new_widths.0, |x| {
+            (old.offsets.column_width(x), new.offsets.column_width(x))
+        }),
+        row_heights: diff_offsets(&old_widths.1, &new_widths.1, |y| {
+            (old.offsets.row_height(y), new.offsets.row_height(y))
+        }),
+    }
+}
+
+/// Returns the changed values and the changed code cells.
+fn diff_values(old: &Sheet, new: &Sheet) -> (Vec<CellValueDiff>, Vec<CellValueDiff>) {
+    let columns = old
+        .columns
+        .keys()
+        .chain(new.columns.keys())
+        .collect::<BTreeSet<_>>();
+
+    let mut values = vec![];
+    let mut code = vec![];
+    for &x in columns {
+        let rows = [old, new]
+            .iter()
+            .filter_map(|sheet| sheet.columns.get(&x))
+            .flat_map(|column| column.values.keys())
+            .collect::<BTreeSet<_>>();
+        for &y in rows {
+            let pos = Pos { x, y };
+            let old_value = old
+                .cell_value(pos)
+                .filter(|v| !v.is_blank_or_empty_string());
+            let new_value = new
+                .cell_value(pos)
+                .filter(|v| !v.is_blank_or_empty_string());
+            if old_value == new_value {
+                continue;
+            }
+            let is_code = matches!(old_value, Some(CellValue::Code(_)))
+                || matches!(new_value, Some(CellValue::Code(_)));
+            let diff = CellValueDiff {
+                pos,
+                old: old_value,
+                new: new_value,
+            };
+            if is_code {
+                code.push(diff);
+            } else {
+                values.push(diff);
+            }
+        }
+    }
+    (values, code)
+}
+
+/// Returns the rows of a column where two versions of a format differ. Only
+/// the block boundaries are compared, since values are constant in between.
+fn changed_rows<T: Serialize + for<'d> Deserialize<'d> + fmt::Debug + Clone + PartialEq>(
+    old: Option<&ColumnData<SameValue<T>>>,
+    new: Option<&ColumnData<SameValue<T>>>,
+    same: impl Fn(Option<T>, Option<T>) -> bool,
+) -> Vec<Range<i64>> {
+    let mut breaks = old
+        .into_iter()
+        .chain(new)
+        .flat_map(|data| data.blocks().flat_map(|block| [block.start(), block.end()]))
+        .collect::<Vec<_>>();
+    breaks.sort_unstable();
+    breaks.dedup();
+
+    breaks
+        .windows(2)
+        .filter(|ys| {
+            let y = ys[0];
+            !same(
+                old.and_then(|data| data.get(y)),
+                new.and_then(|data| data.get(y)),
+            )
+        })
+        .map(|ys| ys[0]..ys[1])
+        .collect()
+}
+
+fn format_changed<A: CellFmtAttr>(old: Option<&Column>, new: Option<&Column>) -> Vec<Range<i64>> {
+    changed_rows(
+        old.map(A::column_data_ref),
+        new.map(A::column_data_ref),
+        |old, new| old == new,
+    )
+}
+
+/// Returns the cells with changed formatting and the cells with changed borders.
+fn diff_formats_and_borders(old: &Sheet, new: &Sheet) -> (Vec<CellFormatDiff>, Vec<Pos>) {
+    type FormatCheck = fn(Option<&Column>, Option<&Column>) -> Vec<Range<i64>>;
+    let checks: [(&str, FormatCheck); 16] = [
+        ("align", format_changed::<CellAlign>),
+        ("wrap", format_changed::<CellWrap>),
+        ("vertical_align", format_changed::<CellVerticalAlign>),
+        ("text_rotation", format_changed::<TextRotation>),
+        ("numeric_format", format_changed::<NumericFormat>),
+        ("numeric_decimals", format_changed::<NumericDecimals>),
+        ("numeric_commas", format_changed::<NumericCommas>),
+        ("bold", format_changed::<Bold>),
+        ("italic", format_changed::<Italic>),
+        ("underline", format_changed::<Underline>),
+        ("strike_through", format_changed::<StrikeThrough>),
+        ("font_size", format_changed::<FontSize>),
+        ("font_family", format_changed::<FontFamily>),
+        ("text_color", format_changed::<TextColor>),
+        ("fill_color", format_changed::<FillColor>),
+        ("render_size", format_changed::<RenderSize>),
+    ];
+
+    // keyed by (x, y) so the cells are listed column by column
+    let mut formats = BTreeMap::<(i64, i64), Vec<String>>::new();
+    let columns = old
+        .iter_columns()
+        .chain(new.iter_columns())
+        .map(|(x, _)| *x)
+        .collect::<BTreeSet<_>>();
+    for x in columns {
+        let (old_column, new_column) = (old.get_column(x), new.get_column(x));
+        for (name, changed) in checks.iter() {
+            for y in changed(old_column, new_column).into_iter().flatten() {
+                formats.entry((x, y)).or_default().push(name.to_string());
+            }
+        }
+    }
+    let formats = formats
+        .into_iter()
+        .map(|((x, y), attributes)| CellFormatDiff {
+            pos: Pos { x, y },
+            attributes,
+        })
+        .collect();
+
+    let (old_borders, new_borders) = (
+        &old.borders().per_cell.borders,
+        &new.borders().per_cell.borders,
+    );
+    let columns = old_borders
+        .keys()
+        .chain(new_borders.keys())
+        .copied()
+        .collect::<BTreeSet<_>>();
+    let mut borders = vec![];
+    for x in columns {
+        let changed = changed_rows(old_borders.get(&x), new_borders.get(&x), |old, new| {
+            old.unwrap_or_default() == new.unwrap_or_default()
+        });
+        borders.extend(changed.into_iter().flatten().map(|y| Pos { x, y }));
+    }
+
+    (formats, borders)
+}
+
+fn diff_offsets(
+    old: &[(i64, f64)],
+    new: &[(i64, f64)],
+    size: impl Fn(i64) -> (f64, f64),
+) -> Vec<OffsetDiff> {
+    old.iter()
+        .chain(new.iter())
+        .map(|(index, _)| *index)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .filter_map(|index| {
+            let (old, new) = size(index);
+            (old != new).then_some(OffsetDiff { index, old, new })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        color::Rgba,
+        controller::GridController,
+        grid::{generate_borders, set_rect_borders, BorderSelection, BorderStyle, CellBorderLine},
+        Rect, SheetPos,
+    };
+
+    #[test]
+    fn diff_identical_grids() {
+        let grid = Grid::new();
+        let diff = grid.diff(&grid.clone());
+        assert!(diff.is_empty());
+        assert_eq!(diff.summary(), "No changes\n");
+    }
+
+    #[test]
+    fn diff_values_formats_and_offsets() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_cell_value((0, 0, sheet_id).into(), "1".into(), None);
+        gc.set_cell_value((1, 0, sheet_id).into(), "keep".into(), None);
+        let old = gc.grid().clone();
+
+        gc.set_cell_value((0, 0, sheet_id).into(), "2".into(), None);
+        gc.set_code_cell(
+            SheetPos {
+                x: 0,
+                y: 1,
+                sheet_id,
+            },
+            crate::grid::CodeCellLanguage::Formula,
+            "A0 + 1".into(),
+            None,
+        );
+        gc.set_cell_bold(
+            Rect::single_pos(Pos { x: 1, y: 0 }).to_sheet_rect(sheet_id),
+            Some(true),
+            None,
+        );
+        gc.commit_single_resize(sheet_id, Some(2), None, 200.0, None);
+        gc.sheet_mut(sheet_id).name = "Renamed".into();
+
+        let sheet = gc.sheet_mut(sheet_id);
+        let rect = Rect::single_pos(Pos { x: 3, y: 3 });
+        let style = BorderStyle {
+            color: Rgba::from_str("#000000").unwrap(),
+            line: CellBorderLine::Line1,
+        };
+        let borders = generate_borders(sheet, &rect, vec![BorderSelection::Left], Some(style));
+        set_rect_borders(sheet, &rect, borders);
+
+        let diff = old.diff(gc.grid());
+        assert_eq!(diff.sheets.len(), 1);
+        let sheet = &diff.sheets[0];
+        assert_eq!(sheet.old_name, Some("Sheet 1".into()));
+        assert_eq!(
+            sheet.values,
+            vec![CellValueDiff {
+                pos: Pos { x: 0, y: 0 },
+                old: Some(CellValue::Number(1.into())),
+                new: Some(CellValue::Number(2.into())),
+            }]
+        );
+        assert_eq!(sheet.code.len(), 1);
+        assert_eq!(sheet.code[0].pos, Pos { x: 0, y: 1 });
+        assert_eq!(
+            sheet.formats,
+            vec![CellFormatDiff {
+                pos: Pos { x: 1, y: 0 },
+                attributes: vec!["bold".into()],
+            }]
+        );
+        assert_eq!(sheet.borders, vec![Pos { x: 3, y: 3 }]);
+        assert_eq!(sheet.column_widths.len(), 1);
+        assert_eq!(sheet.column_widths[0].new, 200.0);
+
+        let summary = diff.summary();
+        assert!(summary.starts_with("Sheet 'Renamed' (renamed from 'Sheet 1')\n"));
+        assert!(summary.contains("  (0, 0): '1' -> '2'\n"));
+        assert!(summary.contains("  (0, 1): (empty) -> Formula `A0 + 1`\n"));
+        assert!(summary.contains("  (1, 0): format bold\n"));
+        assert!(summary.contains("  column 2: width 100 -> 200\n"));
+    }
+
+    #[test]
+    fn diff_added_and_deleted_sheets() {
+        let mut old = Grid::new();
+        let deleted_id = old.sheets()[0].id;
+        let mut new = Grid::new();
+        let added_id = new.sheets()[0].id;
+        new.sheets_mut()[0].set_cell_value(Pos { x: 0, y: 0 }, CellValue::Text("hello".into()));
+        old.sheets_mut()[0].name = "Old".into();
+
+        let diff = old.diff(&new);
+        assert_eq!(diff.sheets.len(), 2);
+        assert_eq!(diff.sheets[0].sheet_id, deleted_id);
+        assert_eq!(diff.sheets[0].kind, SheetDiffKind::Deleted);
+        assert_eq!(diff.sheets[1].sheet_id, added_id);
+        assert_eq!(diff.sheets[1].kind, SheetDiffKind::Added);
+        assert_eq!(diff.sheets[1].values.len(), 1);
+        assert_eq!(
+            diff.summary(),
+            "Deleted sheet 'Old'\nAdded sheet 'Sheet 1'\n  (0, 0): (empty) -> 'hello'\n"
+        );
+    }
+
+    #[test]
+    fn diff_formats_far_apart() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let old = gc.grid().clone();
+
+        gc.set_cell_bold(
+            Rect::new_span(Pos { x: 0, y: 0 }, Pos { x: 0, y: 1 }).to_sheet_rect(sheet_id),
+            Some(true),
+            None,
+        );
+        gc.set_cell_italic(
+            Rect::single_pos(Pos { x: 0, y: 1 }).to_sheet_rect(sheet_id),
+            Some(true),
+            None,
+        );
+        gc.set_cell_bold(
+            Rect::single_pos(Pos {
+                x: 1_000_000,
+                y: -1_000_000,
+            })
+            .to_sheet_rect(sheet_id),
+            Some(true),
+            None,
+        );
+
+        let diff = old.diff(gc.grid());
+        assert_eq!(
+            diff.sheets[0].formats,
+            vec![
+                CellFormatDiff {
+                    pos: Pos { x: 0, y: 0 },
+                    attributes: vec!["bold".into()],
+                },
+                CellFormatDiff {
+                    pos: Pos { x: 0, y: 1 },
+                    attributes: vec!["bold".into(), "italic".into()],
+                },
+                CellFormatDiff {
+                    pos: Pos {
+                        x: 1_000_000,
+                        y: -1_000_000,
+                    },
+                    attributes: vec!["bold".into()],
+                },
+            ]
+        );
+    }
+}
@@ -3,11 +3,11 @@ use crate::grid::file::v1_5::schema::{self as current};
 use crate::grid::{
     block::SameValue, formatting::RenderSize, generate_borders, set_rect_borders,
     sheet::sheet_offsets::SheetOffsets, BorderSelection, BorderStyle, CellAlign, CellBorderLine,
-    CellWrap, CodeRun, Column, ColumnData, Grid, GridBounds, NumericFormat, NumericFormatKind,
-    Sheet, SheetBorders, SheetId,
+    CellVerticalAlign, CellWrap, CodeRun, Column, ColumnData, Grid, GridBounds, NumericFormat,
+    NumericFormatKind, Sheet, SheetBorders, SheetId,
 };
 use crate::grid::{CodeCellLanguage, CodeRunResult};
-use crate::{CellValue, CodeCellValue, Pos, Rect, Value};
+use crate::{CellValue, CodeCellValue, Link, Pos, Rect, Value};
 use anyhow::Result;
 use bigdecimal::BigDecimal;
 use chrono::Utc;
@@ -59,6 +59,26 @@ fn set_column_format_wrap(
     }
 }
 
+fn set_column_format_vertical_align(
+    column_data: &mut ColumnData<SameValue<CellVerticalAlign>>,
+    column: &HashMap<String, current::ColumnRepeat<current::CellVerticalAlign>>,
+) {
+    for (y, format) in column.iter() {
+        // there's probably a better way to do this...
+        let y = (*y).parse::<i64>().unwrap();
+        for y in y..(y + format.len as i64) {
+            column_data.set(
+                y,
+                Some(match format.value {
+                    current::CellVerticalAlign::Top => CellVerticalAlign::Top,
+                    current::CellVerticalAlign::Middle => CellVerticalAlign::Middle,
+                    current::CellVerticalAlign::Bottom => CellVerticalAlign::Bottom,
+                }),
+            );
+        }
+    }
+}
+
 fn set_column_format_numeric_format(
     column_data: &mut ColumnData<SameValue<NumericFormat>>,
     column: &HashMap<String, current::ColumnRepeat<current::NumericFormat>>,
@@ -70,11 +90,17 @@ fn set_column_format_numeric_format(
             column_data.set(
                 y,
                 Some(NumericFormat {
-                    kind: match format.value.kind {
+                    kind: match &format.value.kind {
                         current::NumericFormatKind::Number => NumericFormatKind::Number,
                         current::NumericFormatKind::Currency => NumericFormatKind::Currency,
                         current::NumericFormatKind::Percentage => NumericFormatKind::Percentage,
                         current::NumericFormatKind::Exponential => NumericFormatKind::Exponential,
+                        current::NumericFormatKind::Custom(code) => {
+                            NumericFormatKind::Custom(code.clone())
+                        }
+                        current::NumericFormatKind::DateTime(code) => {
+                            NumericFormatKind::DateTime(code.clone())
+                        }
                     },
                     symbol: format.value.symbol.to_owned(),
                 }),
@@ -148,11 +174,17 @@ fn import_column_builder(columns: &[(i64, current::Column)]) -> Result<BTreeMap<
             let mut col = Column::new(*x);
             set_column_format_align(&mut col.align, &column.align);
             set_column_format_wrap(&mut col.wrap, &column.wrap);
+            set_column_format_vertical_align(&mut col.vertical_align, &column.vertical_align);
+            set_column_format_i16(&mut col.text_rotation, &column.text_rotation);
             set_column_format_i16(&mut col.numeric_decimals, &column.numeric_decimals);
             set_column_format_numeric_format(&mut col.numeric_format, &column.numeric_format);
             set_column_format_bool(&mut col.numeric_commas, &column.numeric_commas);
             set_column_format_bool(&mut col.bold, &column.bold);
             set_column_format_bool(&mut col.italic, &column.italic);
+            set_column_format_bool(&mut col.underline, &column.underline);
+            set_column_format_bool(&mut col.strike_through, &column.strike_through);
+            set_column_format_i16(&mut col.font_size, &column.font_size);
+            set_column_format_string(&mut col.font_family, &column.font_family);
             set_column_format_string(&mut col.text_color, &column.text_color);
             set_column_format_string(&mut col.fill_color, &column.fill_color);
             set_column_format_render_size(&mut col.render_size, &column.render_size);
@@ -182,6 +214,10 @@ fn import_column_builder(columns: &[(i64, current::Column)]) -> Result<BTreeMap<
                     current::CellValue::Error(error) => {
                         CellValue::Error(Box::new((*error).clone().into()))
                     }
+                    current::CellValue::Link(link) => {
+                        CellValue::link(link.text.to_owned(), link.url.to_owned())
+                    }
+                    current::CellValue::Image(image) => CellValue::Image(image.try_into()?),
                 };
                 if let Ok(y) = y.parse::<i64>() {
                     col.values.insert(y, cell_value);
@@ -224,11 +260,35 @@ fn import_borders_builder(sheet: &mut Sheet, current_sheet: &mut current::Sheet)
     });
 }
 
+/// Returns a code output value as it is stored in the file. Instants,
+/// durations, links and images are stored as JSON so they can be read back.
+fn export_code_cell_output(cell_value: &CellValue) -> current::OutputValueValue {
+    let value = match cell_value {
+        CellValue::Instant(instant) => serde_json::to_string(instant).unwrap_or_default(),
+        CellValue::Duration(duration) => serde_json::to_string(duration).unwrap_or_default(),
+        CellValue::Link(link) => serde_json::to_string(link).unwrap_or_default(),
+        CellValue::Image(image) => serde_json::to_string(image).unwrap_or_default(),
+        _ => cell_value.to_string(),
+    };
+    current::OutputValueValue {
+        type_field: cell_value.type_name().into(),
+        value,
+    }
+}
+
 fn import_code_cell_output(type_field: &str, value: &str) -> CellValue {
     match type_field.to_lowercase().as_str() {
         "text" => CellValue::Text(value.to_owned()),
         "number" => CellValue::Number(BigDecimal::from_str(value).unwrap_or_default()),
         "html" => CellValue::Html(value.to_owned()),
+        "time instant" => serde_json::from_str(value).map_or(CellValue::Blank, CellValue::Instant),
+        "time duration" => {
+            serde_json::from_str(value).map_or(CellValue::Blank, CellValue::Duration)
+        }
+        "link" => serde_json::from_str::<Link>(value).map_or(CellValue::Blank, |link| {
+            CellValue::link(link.text, link.url)
+        }),
+        "image" => serde_json::from_str(value).map_or(CellValue::Blank, CellValue::Image),
         _ => CellValue::Blank,
     }
 }
@@ -286,7 +346,7 @@ fn import_code_cell_builder(sheet: &current::Sheet) -> Result<IndexMap<Pos, Code
 }
 
 pub fn import(file: current::GridSchema) -> Result<Grid> {
-    Ok(Grid {
+    let mut grid = Grid {
         sheets: file
             .sheets
             .into_iter()
@@ -303,13 +363,49 @@ pub fn import(file: current::GridSchema) -> Result<Grid> {
                     code_runs: import_code_cell_builder(&sheet)?,
                     data_bounds: GridBounds::Empty,
                     format_bounds: GridBounds::Empty,
+                    merged_cells: sheet
+                        .merged_cells
+                        .iter()
+                        .map(|rect| Rect::from(rect.clone()))
+                        .collect(),
+                    frozen_rows: sheet.frozen_rows.unwrap_or_default(),
+                    frozen_columns: sheet.frozen_columns.unwrap_or_default(),
+                    validations: sheet.validations.iter().map(Into::into).collect(),
+                    conditional_formats: sheet.conditional_formats.iter().map(Into::into).collect(),
+                    comments: sheet
+                        .comments
+                        .iter()
+                        .map(|(pos, thread)| (crate::Pos { x: pos.x, y: pos.y }, thread.into()))
+                        .collect(),
+                    protection: sheet
+                        .protection
+                        .as_ref()
+                        .map(Into::into)
+                        .unwrap_or_default(),
+                    charts: sheet
+                        .charts
+                        .iter()
+                        .map(|(pos, chart)| (crate::Pos { x: pos.x, y: pos.y }, chart.into()))
+                        .collect(),
+                    locale: Default::default(),
                 };
+                sheet.hidden_columns.iter().for_each(|x| {
+                    new_sheet.offsets.set_column_hidden(*x, true);
+                });
+                sheet.hidden_rows.iter().for_each(|y| {
+                    new_sheet.offsets.set_row_hidden(*y, true);
+                });
                 new_sheet.recalculate_bounds();
                 import_borders_builder(&mut new_sheet, &mut sheet);
                 Ok(new_sheet)
             })
             .collect::<Result<_>>()?,
-    })
+        locale: Default::default(),
+    };
+    if let Some(locale) = &file.locale {
+        grid.set_locale(locale.into());
+    }
+    Ok(grid)
 }
 
 fn export_column_data_bool(
@@ -373,13 +469,19 @@ fn export_column_data_numeric_format(
                 block.y.to_string(),
                 current::ColumnRepeat {
                     value: current::NumericFormat {
-                        kind: match block.content.value.kind {
+                        kind: match &block.content.value.kind {
                             NumericFormatKind::Number => current::NumericFormatKind::Number,
                             NumericFormatKind::Currency => current::NumericFormatKind::Currency,
                             NumericFormatKind::Percentage => current::NumericFormatKind::Percentage,
                             NumericFormatKind::Exponential => {
                                 current::NumericFormatKind::Exponential
                             }
+                            NumericFormatKind::Custom(code) => {
+                                current::NumericFormatKind::Custom(code.clone())
+                            }
+                            NumericFormatKind::DateTime(code) => {
+                                current::NumericFormatKind::DateTime(code.clone())
+                            }
                         },
                         symbol: block.content.value.symbol.clone(),
                     },
@@ -452,6 +554,27 @@ fn export_column_data_wrap(
         .collect()
 }
 
+fn export_column_data_vertical_align(
+    column_data: &ColumnData<SameValue<CellVerticalAlign>>,
+) -> HashMap<String, current::ColumnRepeat<current::CellVerticalAlign>> {
+    column_data
+        .blocks()
+        .map(|block| {
+            (
+                block.y.to_string(),
+                current::ColumnRepeat {
+                    value: match block.content.value {
+                        CellVerticalAlign::Top => current::CellVerticalAlign::Top,
+                        CellVerticalAlign::Middle => current::CellVerticalAlign::Middle,
+                        CellVerticalAlign::Bottom => current::CellVerticalAlign::Bottom,
+                    },
+                    len: block.len() as u32,
+                },
+            )
+        })
+        .collect()
+}
+
 fn export_column_builder(sheet: &Sheet) -> Vec<(i64, current::Column)> {
     sheet
         .columns
@@ -462,11 +585,17 @@ fn export_column_builder(sheet: &Sheet) -> Vec<(i64, current::Column)> {
                 current::Column {
                     align: export_column_data_align(&column.align),
                     wrap: export_column_data_wrap(&column.wrap),
+                    vertical_align: export_column_data_vertical_align(&column.vertical_align),
+                    text_rotation: export_column_data_i16(&column.text_rotation),
                     numeric_decimals: export_column_data_i16(&column.numeric_decimals),
                     numeric_format: export_column_data_numeric_format(&column.numeric_format),
                     numeric_commas: export_column_data_bool(&column.numeric_commas),
                     bold: export_column_data_bool(&column.bold),
                     italic: export_column_data_bool(&column.italic),
+                    underline: export_column_data_bool(&column.underline),
+                    strike_through: export_column_data_bool(&column.strike_through),
+                    font_size: export_column_data_i16(&column.font_size),
+                    font_family: export_column_data_string(&column.font_family),
                     text_color: export_column_data_string(&column.text_color),
                     fill_color: export_column_data_string(&column.fill_color),
                     render_size: export_column_data_render_size(&column.render_size),
@@ -500,15 +629,24 @@ fn export_column_builder(sheet: &Sheet) -> Vec<(i64, current::Column)> {
                                     CellValue::Logical(logical) => {
                                         current::CellValue::Logical(*logical)
                                     }
-                                    CellValue::Instant(instant) => {
-                                        current::CellValue::Instant(instant.to_string())
-                                    }
-                                    CellValue::Duration(duration) => {
-                                        current::CellValue::Duration(duration.to_string())
-                                    }
+                                    CellValue::Instant(instant) => current::CellValue::Instant(
+                                        serde_json::to_string(instant).unwrap_or_default(),
+                                    ),
+                                    CellValue::Duration(duration) => current::CellValue::Duration(
+                                        serde_json::to_string(duration).unwrap_or_default(),
+                                    ),
                                     CellValue::Error(error) => current::CellValue::Error(
                                         current::RunError::from_grid_run_error(error),
                                     ),
+                                    CellValue::Link(link) => {
+                                        current::CellValue::Link(current::Link {
+                                            text: link.text.clone(),
+                                            url: link.url.clone(),
+                                        })
+                                    }
+                                    CellValue::Image(image) => {
+                                        current::CellValue::Image(image.into())
+                                    }
                                     CellValue::Blank => current::CellValue::Blank,
                                 },
                             )
@@ -557,6 +695,7 @@ fn export_borders_builder(sheet: &Sheet) -> current::Borders {
 pub fn export(grid: &mut Grid) -> Result<current::GridSchema> {
     Ok(current::GridSchema {
         version: Some(CURRENT_VERSION.into()),
+        locale: (*grid.locale() != Default::default()).then(|| grid.locale().into()),
         sheets: grid
             .sheets()
             .iter()
@@ -576,12 +715,9 @@ pub fn export(grid: &mut Grid) -> Result<current::GridSchema> {
                     .map(|(pos, code_run)| {
                         let result = match &code_run.result {
                             CodeRunResult::Ok(output) => current::CodeRunResult::Ok(match output {
-                                Value::Single(cell_value) => {
-                                    current::OutputValue::Single(current::OutputValueValue {
-                                        type_field: cell_value.type_name().into(),
-                                        value: cell_value.to_string(),
-                                    })
-                                }
+                                Value::Single(cell_value) => current::OutputValue::Single(
+                                    export_code_cell_output(cell_value),
+                                ),
                                 Value::Array(array) => {
                                     current::OutputValue::Array(current::OutputArray {
                                         size: current::OutputSize {
@@ -590,12 +726,7 @@ pub fn export(grid: &mut Grid) -> Result<current::GridSchema> {
                                         },
                                         values: array
                                             .rows()
-                                            .flat_map(|row| {
-                                                row.iter().map(|cell| current::OutputValueValue {
-                                                    type_field: cell.type_name().into(),
-                                                    value: cell.to_string(),
-                                                })
-                                            })
+                                            .flat_map(|row| row.iter().map(export_code_cell_output))
                                             .collect(),
                                     })
                                 }
@@ -626,6 +757,28 @@ pub fn export(grid: &mut Grid) -> Result<current::GridSchema> {
                         )
                     })
                     .collect(),
+                merged_cells: sheet
+                    .merged_cells
+                    .iter()
+                    .map(|rect| current::Rect::from(*rect))
+                    .collect(),
+                hidden_columns: sheet.offsets.hidden_columns(),
+                hidden_rows: sheet.offsets.hidden_rows(),
+                frozen_rows: (sheet.frozen_rows > 0).then_some(sheet.frozen_rows),
+                frozen_columns: (sheet.frozen_columns > 0).then_some(sheet.frozen_columns),
+                validations: sheet.validations.iter().map(Into::into).collect(),
+                conditional_formats: sheet.conditional_formats.iter().map(Into::into).collect(),
+                comments: sheet
+                    .comments
+                    .iter()
+                    .map(|(pos, thread)| ((*pos).into(), thread.into()))
+                    .collect(),
+                protection: (!sheet.protection.is_empty()).then(|| (&sheet.protection).into()),
+                charts: sheet
+                    .charts
+                    .iter()
+                    .map(|(pos, chart)| ((*pos).into(), chart.into()))
+                    .collect(),
             })
             .collect(),
     })
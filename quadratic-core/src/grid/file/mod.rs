@@ -1,6 +1,8 @@
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
+use flate2::{read::DeflateDecoder, write::DeflateEncoder, Compression};
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
+use std::io::{Read, Write};
 
 use super::Grid;
 
@@ -8,9 +10,15 @@ pub mod current;
 mod v1_3;
 mod v1_4;
 mod v1_5;
+pub mod validate;
 
 pub static CURRENT_VERSION: &str = "1.5";
 
+/// Magic bytes at the start of a binary .grid file. A binary file is laid out
+/// as the magic bytes, one byte with the length of the version string, the
+/// version string, then the deflate-compressed MessagePack grid.
+pub static BINARY_MAGIC: &[u8] = b"QGRID";
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(tag = "version")]
 enum GridFile {
@@ -49,15 +57,97 @@ impl GridFile {
     }
 }
 
+fn parse(file_contents: &str) -> Result<v1_5::schema::GridSchema> {
+    serde_json::from_str::<GridFile>(file_contents)
+        .map_err(|e| anyhow!("Failed to parse grid file: {e}"))?
+        .into_latest()
+}
+
 pub fn import(file_contents: &str) -> Result<Grid> {
-    let file = serde_json::from_str::<GridFile>(file_contents)
-        .map_err(|e| {
-            dbg!(&e);
-            anyhow!(e)
-        })?
-        .into_latest()?;
+    current::import(parse(file_contents)?)
+}
+
+/// Validates a parsed file (repairing it if `repair` is true) and imports it.
+fn import_validated(
+    mut file: v1_5::schema::GridSchema,
+    repair: bool,
+) -> Result<(Grid, validate::ValidationReport)> {
+    let report = if repair {
+        validate::repair(&mut file)
+    } else {
+        validate::validate(&file)
+    };
+    Ok((current::import(file)?, report))
+}
+
+/// Imports a .grid file after validating it. When `repair` is true, the
+/// problems that were found are fixed before importing.
+pub fn import_with_report(
+    file_contents: &str,
+    repair: bool,
+) -> Result<(Grid, validate::ValidationReport)> {
+    import_validated(parse(file_contents)?, repair)
+}
+
+/// Parses a .grid file from bytes, detecting whether it is a binary or a
+/// JSON file.
+fn parse_vec(file_contents: &[u8]) -> Result<v1_5::schema::GridSchema> {
+    if is_binary(file_contents) {
+        return parse_binary(file_contents);
+    }
+    let file_contents = std::str::from_utf8(file_contents).map_err(|e| anyhow!(e))?;
+    parse(file_contents)
+}
+
+/// Imports a .grid file from bytes, detecting whether it is a binary or a
+/// JSON file.
+pub fn import_vec(file_contents: &[u8]) -> Result<Grid> {
+    current::import(parse_vec(file_contents)?)
+}
+
+/// Imports a binary or JSON .grid file from bytes after validating it. When
+/// `repair` is true, the problems that were found are fixed before
+/// importing. This is used when loading files, so damaged files still open.
+pub fn import_vec_with_report(
+    file_contents: &[u8],
+    repair: bool,
+) -> Result<(Grid, validate::ValidationReport)> {
+    import_validated(parse_vec(file_contents)?, repair)
+}
+
+/// Returns true if the file contents start with the binary .grid header.
+pub fn is_binary(file_contents: &[u8]) -> bool {
+    file_contents.starts_with(BINARY_MAGIC)
+}
 
-    current::import(file)
+/// Imports a binary .grid file.
+pub fn import_binary(file_contents: &[u8]) -> Result<Grid> {
+    current::import(parse_binary(file_contents)?)
+}
+
+fn parse_binary(file_contents: &[u8]) -> Result<v1_5::schema::GridSchema> {
+    let Some(header) = file_contents.strip_prefix(BINARY_MAGIC) else {
+        bail!("Missing binary .grid header");
+    };
+    let Some((&version_len, rest)) = header.split_first() else {
+        bail!("Missing binary .grid version");
+    };
+    if rest.len() < version_len as usize {
+        bail!("Truncated binary .grid version");
+    }
+    let (version, payload) = rest.split_at(version_len as usize);
+    let version = std::str::from_utf8(version).map_err(|e| anyhow!(e))?;
+
+    let mut decompressed = vec![];
+    DeflateDecoder::new(payload)
+        .read_to_end(&mut decompressed)
+        .map_err(|e| anyhow!(e))?;
+
+    match version {
+        "1.5" => rmp_serde::from_slice::<v1_5::schema::GridSchema>(&decompressed)
+            .map_err(|e| anyhow!(e)),
+        _ => bail!("Unsupported binary .grid version: {version}"),
+    }
 }
 
 pub fn export(grid: &mut Grid) -> Result<String> {
@@ -73,13 +163,43 @@ pub fn export_vec(grid: &mut Grid) -> Result<Vec<u8>> {
     Ok(serialized)
 }
 
+/// Exports a binary .grid file (see [`BINARY_MAGIC`] for the layout).
+pub fn export_binary(grid: &mut Grid) -> Result<Vec<u8>> {
+    let converted = current::export(grid)?;
+    let serialized = rmp_serde::to_vec_named(&converted).map_err(|e| anyhow!(e))?;
+
+    let mut output = BINARY_MAGIC.to_vec();
+    output.push(CURRENT_VERSION.len() as u8);
+    output.extend_from_slice(CURRENT_VERSION.as_bytes());
+
+    let mut encoder = DeflateEncoder::new(output, Compression::default());
+    encoder.write_all(&serialized).map_err(|e| anyhow!(e))?;
+    let output = encoder.finish().map_err(|e| anyhow!(e))?;
+
+    Ok(output)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
         color::Rgba,
-        grid::{generate_borders, set_rect_borders, BorderSelection, BorderStyle, CellBorderLine},
-        Pos, Rect,
+        grid::{
+            generate_borders,
+            sheet::{
+                charts::{Chart, ChartSeries, ChartType},
+                comments::{Comment, CommentThread},
+                conditional_formats::{
+                    CompareOperator, ConditionalFormat, ConditionalFormatRule,
+                    ConditionalFormatStyle,
+                },
+                protection::{ProtectedRange, SheetProtection},
+                validations::{Validation, ValidationAction, ValidationCriteria},
+            },
+            set_rect_borders, BorderSelection, BorderStyle, CellBorderLine, CodeCellLanguage,
+            CodeRun, CommentId, Locale, NumericFormat, NumericFormatKind,
+        },
+        CellValue, CodeCellValue, Duration, Image, Instant, Link, Pos, Rect, SheetRect,
     };
 
     const V1_3_FILE: &str = include_str!("../../../../quadratic-rust-shared/data/grid/v1_3.grid");
@@ -219,4 +339,340 @@ mod tests {
         let mut imported = import(V1_4_FILE).unwrap();
         export(&mut imported).unwrap();
     }
+
+    #[test]
+    fn imports_and_exports_a_binary_grid() {
+        let mut imported = import(V1_5_FILE).unwrap();
+        let binary = export_binary(&mut imported).unwrap();
+        assert!(is_binary(&binary));
+        assert!(binary.len() < V1_5_FILE.len());
+
+        let reimported = import_vec(&binary).unwrap();
+        assert_eq!(reimported, imported);
+
+        // code runs survive the round trip
+        let mut imported = import(V1_3_SINGLE_FORMULAS_CODE_CELL_FILE).unwrap();
+        let json = import(&export(&mut imported).unwrap()).unwrap();
+        let binary = export_binary(&mut imported).unwrap();
+        assert_eq!(import_vec(&binary).unwrap(), json);
+
+        // JSON files are still detected
+        let json = import_vec(V1_5_FILE.as_bytes()).unwrap();
+        assert_eq!(json, import(V1_5_FILE).unwrap());
+    }
+
+    #[test]
+    fn imports_and_exports_merged_cells() {
+        let mut grid = Grid::new();
+        let rect = Rect::new_span(Pos { x: 1, y: 2 }, Pos { x: 3, y: 4 });
+        grid.sheets_mut()[0].merge_cells(rect);
+
+        let imported = import(&export(&mut grid).unwrap()).unwrap();
+        assert_eq!(imported.sheets()[0].merged_cells, vec![rect]);
+    }
+
+    #[test]
+    fn imports_and_exports_hidden_columns_and_rows() {
+        let mut grid = Grid::new();
+        let offsets = &mut grid.sheets_mut()[0].offsets;
+        offsets.set_column_width(2, 50.0);
+        offsets.set_column_hidden(2, true);
+        offsets.set_row_hidden(-1, true);
+
+        let imported = import(&export(&mut grid).unwrap()).unwrap();
+        let offsets = &imported.sheets()[0].offsets;
+        assert_eq!(offsets.hidden_columns(), vec![2]);
+        assert_eq!(offsets.hidden_rows(), vec![-1]);
+        assert_eq!(imported, grid);
+    }
+
+    #[test]
+    fn imports_and_exports_frozen_rows_and_columns() {
+        let mut grid = Grid::new();
+        grid.sheets_mut()[0].frozen_rows = 1;
+        grid.sheets_mut()[0].frozen_columns = 3;
+
+        let imported = import(&export(&mut grid).unwrap()).unwrap();
+        assert_eq!(imported.sheets()[0].frozen_rows, 1);
+        assert_eq!(imported.sheets()[0].frozen_columns, 3);
+    }
+
+    #[test]
+    fn imports_and_exports_custom_number_formats() {
+        let mut grid = Grid::new();
+        let numeric_format = NumericFormat {
+            kind: NumericFormatKind::Custom("#,##0.00;[Red](#,##0.00)".into()),
+            symbol: None,
+        };
+        grid.sheets_mut()[0].set_formatting_value::<NumericFormat>(
+            Pos { x: 1, y: 2 },
+            Some(numeric_format.clone()),
+        );
+
+        let imported = import(&export(&mut grid).unwrap()).unwrap();
+        assert_eq!(
+            imported.sheets()[0].get_formatting_value::<NumericFormat>(Pos { x: 1, y: 2 }),
+            Some(numeric_format)
+        );
+    }
+
+    #[test]
+    fn imports_and_exports_dates_and_durations() {
+        let mut grid = Grid::new();
+        let sheet = &mut grid.sheets_mut()[0];
+        let instant = CellValue::Instant(Instant::new(1709301600.5));
+        let duration = CellValue::Duration(Duration {
+            years: 1,
+            months: 2,
+            seconds: 3.5,
+        });
+        let numeric_format = NumericFormat {
+            kind: NumericFormatKind::DateTime("mmm d, yyyy".into()),
+            symbol: None,
+        };
+        sheet.set_cell_value(Pos { x: 0, y: 0 }, instant.clone());
+        sheet.set_cell_value(Pos { x: 0, y: 1 }, duration.clone());
+        sheet.set_formatting_value::<NumericFormat>(
+            Pos { x: 0, y: 0 },
+            Some(numeric_format.clone()),
+        );
+
+        // python code may return instants
+        sheet.set_cell_value(
+            Pos { x: 1, y: 0 },
+            CellValue::Code(CodeCellValue {
+                language: CodeCellLanguage::Python,
+                code: "datetime.now()".into(),
+            }),
+        );
+        sheet.set_code_run(
+            Pos { x: 1, y: 0 },
+            Some(CodeRun::test_ok(instant.clone(), None)),
+        );
+
+        let imported = import(&export(&mut grid).unwrap()).unwrap();
+        let sheet = &imported.sheets()[0];
+        assert_eq!(sheet.cell_value(Pos { x: 0, y: 0 }), Some(instant.clone()));
+        assert_eq!(sheet.cell_value(Pos { x: 0, y: 1 }), Some(duration));
+        assert_eq!(sheet.display_value(Pos { x: 1, y: 0 }), Some(instant));
+        assert_eq!(
+            sheet.get_formatting_value::<NumericFormat>(Pos { x: 0, y: 0 }),
+            Some(numeric_format)
+        );
+    }
+
+    #[test]
+    fn imports_and_exports_links() {
+        let mut grid = Grid::new();
+        let sheet = &mut grid.sheets_mut()[0];
+        let link = CellValue::Link(Link::new("Quadratic", "https://quadratichq.com").unwrap());
+        sheet.set_cell_value(Pos { x: 0, y: 0 }, link.clone());
+
+        // formulas may return links
+        sheet.set_cell_value(
+            Pos { x: 1, y: 0 },
+            CellValue::Code(CodeCellValue {
+                language: CodeCellLanguage::Formula,
+                code: "HYPERLINK(\"#B5\", \"totals\")".into(),
+            }),
+        );
+        let output = CellValue::Link(Link::new("totals", "#B5").unwrap());
+        sheet.set_code_run(
+            Pos { x: 1, y: 0 },
+            Some(CodeRun::test_ok(output.clone(), None)),
+        );
+
+        let imported = import(&export(&mut grid).unwrap()).unwrap();
+        let sheet = &imported.sheets()[0];
+        assert_eq!(sheet.cell_value(Pos { x: 0, y: 0 }), Some(link));
+        assert_eq!(sheet.display_value(Pos { x: 1, y: 0 }), Some(output));
+    }
+
+    #[test]
+    fn imports_and_exports_images() {
+        let mut grid = Grid::new();
+        let sheet = &mut grid.sheets_mut()[0];
+        let image = CellValue::Image(Image::test());
+        sheet.set_cell_value(Pos { x: 0, y: 0 }, image.clone());
+        sheet.set_cell_value(
+            Pos { x: 1, y: 0 },
+            CellValue::Code(CodeCellValue {
+                language: CodeCellLanguage::Python,
+                code: "fig".into(),
+            }),
+        );
+        sheet.set_code_run(
+            Pos { x: 1, y: 0 },
+            Some(CodeRun::test_ok(image.clone(), Some("Image"))),
+        );
+
+        // images are stored as base64 rather than arrays of bytes
+        let exported = export(&mut grid).unwrap();
+        assert!(exported.contains("iVBORw0KGgo"));
+
+        let imported = import(&exported).unwrap();
+        let sheet = &imported.sheets()[0];
+        assert_eq!(sheet.cell_value(Pos { x: 0, y: 0 }), Some(image.clone()));
+        assert_eq!(sheet.display_value(Pos { x: 1, y: 0 }), Some(image.clone()));
+
+        let imported = import_binary(&export_binary(&mut grid).unwrap()).unwrap();
+        assert_eq!(
+            imported.sheets()[0].cell_value(Pos { x: 0, y: 0 }),
+            Some(image)
+        );
+    }
+
+    #[test]
+    fn imports_and_exports_validations() {
+        let mut grid = Grid::new();
+        let sheet_id = grid.sheets()[0].id;
+        grid.sheets_mut()[0].validations = vec![
+            Validation {
+                rect: Rect::from_numbers(0, 0, 1, 10),
+                criteria: ValidationCriteria::ListFromRange {
+                    range: SheetRect::from_numbers(5, 0, 1, 3, sheet_id),
+                },
+                action: ValidationAction::Reject,
+                ignore_blank: true,
+                message: None,
+            },
+            Validation {
+                rect: Rect::from_numbers(1, 0, 1, 10),
+                criteria: ValidationCriteria::Date {
+                    min: Some(Instant::new(1704067200.0)),
+                    max: None,
+                },
+                action: ValidationAction::Warn,
+                ignore_blank: false,
+                message: Some("Dates from 2024".into()),
+            },
+        ];
+
+        let imported = import(&export(&mut grid).unwrap()).unwrap();
+        assert_eq!(imported, grid);
+    }
+
+    #[test]
+    fn imports_and_exports_protection() {
+        let mut grid = Grid::new();
+        grid.sheets_mut()[0].protection = SheetProtection {
+            locked: Some(vec!["ada".into()]),
+            ranges: vec![ProtectedRange {
+                rect: Rect::from_numbers(0, 0, 2, 5),
+                editors: vec!["ada".into(), "bob".into()],
+            }],
+        };
+
+        let imported = import(&export(&mut grid).unwrap()).unwrap();
+        assert_eq!(imported, grid);
+    }
+
+    #[test]
+    fn imports_and_exports_conditional_formats() {
+        let mut grid = Grid::new();
+        grid.sheets_mut()[0].conditional_formats = vec![
+            ConditionalFormat {
+                rect: Rect::from_numbers(0, 0, 1, 10),
+                rule: ConditionalFormatRule::Compare {
+                    operator: CompareOperator::GreaterThanOrEqual,
+                    value: "10".into(),
+                },
+                style: ConditionalFormatStyle {
+                    bold: Some(true),
+                    text_color: Some("#ff0000".into()),
+                    ..Default::default()
+                },
+            },
+            ConditionalFormat {
+                rect: Rect::from_numbers(1, 0, 1, 10),
+                rule: ConditionalFormatRule::ColorScale {
+                    min_color: "#ffffff".into(),
+                    mid_color: None,
+                    max_color: "#00ff00".into(),
+                },
+                style: ConditionalFormatStyle::default(),
+            },
+        ];
+
+        let imported = import(&export(&mut grid).unwrap()).unwrap();
+        assert_eq!(imported, grid);
+    }
+
+    #[test]
+    fn imports_and_exports_comments() {
+        let mut grid = Grid::new();
+        grid.sheets_mut()[0].set_comment_thread(
+            Pos { x: 2, y: -3 },
+            Some(CommentThread {
+                comments: vec![
+                    Comment {
+                        id: CommentId::new(),
+                        author: "Ada".into(),
+                        timestamp: Instant::new(1704067200.5),
+                        text: "Where is this from?".into(),
+                    },
+                    Comment {
+                        id: CommentId::new(),
+                        author: "Grace".into(),
+                        timestamp: Instant::new(1704070800.0),
+                        text: "The 2023 filing".into(),
+                    },
+                ],
+                resolved: true,
+            }),
+        );
+
+        let imported = import(&export(&mut grid).unwrap()).unwrap();
+        assert_eq!(imported, grid);
+    }
+
+    #[test]
+    fn imports_and_exports_charts() {
+        let mut grid = Grid::new();
+        let sheet_id = grid.sheets()[0].id;
+        let mut chart = Chart::new(
+            ChartType::Area,
+            Some(SheetRect::from_numbers(0, 0, 1, 5, sheet_id)),
+            vec![ChartSeries {
+                name: Some("Revenue".into()),
+                values: SheetRect::from_numbers(1, 0, 1, 5, sheet_id),
+            }],
+        );
+        chart.title = Some("Quarterly".into());
+        chart.width = 640;
+        grid.sheets_mut()[0].set_chart(Pos { x: 3, y: 1 }, Some(chart));
+        grid.sheets_mut()[0].set_chart(
+            Pos { x: 3, y: 20 },
+            Some(Chart::new(ChartType::Pie, None, vec![])),
+        );
+
+        let imported = import(&export(&mut grid).unwrap()).unwrap();
+        assert_eq!(imported, grid);
+        let imported = import_binary(&export_binary(&mut grid).unwrap()).unwrap();
+        assert_eq!(imported, grid);
+    }
+
+    #[test]
+    fn imports_and_exports_a_locale() {
+        let mut grid = Grid::new();
+        let imported = import(&export(&mut grid).unwrap()).unwrap();
+        assert_eq!(*imported.locale(), Locale::default());
+
+        grid.set_locale(Locale::from_tag("fr-FR").unwrap());
+        let imported = import(&export(&mut grid).unwrap()).unwrap();
+        assert_eq!(imported.locale(), grid.locale());
+        assert_eq!(imported.sheets()[0].locale, *grid.locale());
+    }
+
+    #[test]
+    fn rejects_invalid_binary_grids() {
+        assert!(import_binary(b"QGRID").is_err());
+        assert!(import_binary(b"QGRID\x05").is_err());
+        assert!(import_binary(b"QGRID\x039.9payload").is_err());
+
+        let mut grid = Grid::new();
+        let mut binary = export_binary(&mut grid).unwrap();
+        binary.truncate(binary.len() - 4);
+        assert!(import_vec(&binary).is_err());
+    }
 }
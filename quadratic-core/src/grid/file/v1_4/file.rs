@@ -185,6 +185,12 @@ fn upgrade_column(sheet: &v1_4::Sheet, x: &i64, column: &v1_4::Column) -> (i64,
                     (k.clone(), value)
                 })
                 .collect(),
+            vertical_align: HashMap::new(),
+            text_rotation: HashMap::new(),
+            underline: HashMap::new(),
+            strike_through: HashMap::new(),
+            font_size: HashMap::new(),
+            font_family: HashMap::new(),
         },
     )
 }
@@ -313,6 +319,16 @@ fn upgrade_sheet(sheet: &v1_4::Sheet) -> v1_5::Sheet {
         columns: upgrade_columns(sheet),
         borders: upgrade_borders(sheet),
         code_runs: upgrade_code_runs(sheet),
+        merged_cells: vec![],
+        hidden_columns: vec![],
+        hidden_rows: vec![],
+        frozen_rows: None,
+        frozen_columns: None,
+        validations: vec![],
+        conditional_formats: vec![],
+        comments: vec![],
+        protection: None,
+        charts: vec![],
     }
 }
 
@@ -320,6 +336,7 @@ pub(crate) fn upgrade(schema: v1_4::GridSchema) -> Result<v1_5::GridSchema> {
     let schema = v1_5::GridSchema {
         version: Some("1.5".into()),
         sheets: schema.sheets.iter().map(upgrade_sheet).collect(),
+        locale: None,
     };
     Ok(schema)
 }
@@ -1,4 +1,10 @@
-use crate::grid::{file::v1_4::schema as v1_4, SheetId};
+use crate::grid::{
+    file::v1_4::schema as v1_4,
+    locale,
+    sheet::{charts, comments, conditional_formats, protection, validations},
+    CommentId, SheetId,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
 use chrono::{serde::ts_seconds_option, DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -12,6 +18,9 @@ pub use super::run_error::RunErrorMsg;
 pub struct GridSchema {
     pub sheets: Vec<Sheet>,
     pub version: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<Locale>,
 }
 
 pub type Id = v1_4::Id;
@@ -90,6 +99,548 @@ impl From<SheetRect> for crate::SheetRect {
     }
 }
 
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rect {
+    pub min: Pos,
+    pub max: Pos,
+}
+impl From<crate::Rect> for Rect {
+    fn from(rect: crate::Rect) -> Self {
+        Self {
+            min: rect.min.into(),
+            max: rect.max.into(),
+        }
+    }
+}
+
+impl From<Rect> for crate::Rect {
+    fn from(rect: Rect) -> Self {
+        Self {
+            min: crate::Pos {
+                x: rect.min.x,
+                y: rect.min.y,
+            },
+            max: crate::Pos {
+                x: rect.max.x,
+                y: rect.max.y,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ValidationCriteria {
+    List { values: Vec<String> },
+    ListFromRange { range: SheetRect },
+    Number { min: Option<f64>, max: Option<f64> },
+    // dates are stored as seconds since the epoch
+    Date { min: Option<f64>, max: Option<f64> },
+    TextLength { min: Option<u32>, max: Option<u32> },
+    Custom { formula: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ValidationAction {
+    Reject,
+    Warn,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Validation {
+    pub rect: Rect,
+    pub criteria: ValidationCriteria,
+    pub action: ValidationAction,
+    pub ignore_blank: bool,
+    pub message: Option<String>,
+}
+
+impl From<&ValidationCriteria> for validations::ValidationCriteria {
+    fn from(criteria: &ValidationCriteria) -> Self {
+        match criteria.clone() {
+            ValidationCriteria::List { values } => Self::List { values },
+            ValidationCriteria::ListFromRange { range } => Self::ListFromRange {
+                range: range.into(),
+            },
+            ValidationCriteria::Number { min, max } => Self::Number { min, max },
+            ValidationCriteria::Date { min, max } => Self::Date {
+                min: min.map(crate::Instant::new),
+                max: max.map(crate::Instant::new),
+            },
+            ValidationCriteria::TextLength { min, max } => Self::TextLength { min, max },
+            ValidationCriteria::Custom { formula } => Self::Custom { formula },
+        }
+    }
+}
+
+impl From<&validations::ValidationCriteria> for ValidationCriteria {
+    fn from(criteria: &validations::ValidationCriteria) -> Self {
+        use validations::ValidationCriteria as Criteria;
+
+        match criteria.clone() {
+            Criteria::List { values } => Self::List { values },
+            Criteria::ListFromRange { range } => Self::ListFromRange {
+                range: range.into(),
+            },
+            Criteria::Number { min, max } => Self::Number { min, max },
+            Criteria::Date { min, max } => Self::Date {
+                min: min.map(|instant| instant.seconds),
+                max: max.map(|instant| instant.seconds),
+            },
+            Criteria::TextLength { min, max } => Self::TextLength { min, max },
+            Criteria::Custom { formula } => Self::Custom { formula },
+        }
+    }
+}
+
+impl From<&Validation> for validations::Validation {
+    fn from(validation: &Validation) -> Self {
+        Self {
+            rect: validation.rect.clone().into(),
+            criteria: (&validation.criteria).into(),
+            action: match validation.action {
+                ValidationAction::Reject => validations::ValidationAction::Reject,
+                ValidationAction::Warn => validations::ValidationAction::Warn,
+            },
+            ignore_blank: validation.ignore_blank,
+            message: validation.message.clone(),
+        }
+    }
+}
+
+impl From<&validations::Validation> for Validation {
+    fn from(validation: &validations::Validation) -> Self {
+        Self {
+            rect: validation.rect.into(),
+            criteria: (&validation.criteria).into(),
+            action: match validation.action {
+                validations::ValidationAction::Reject => ValidationAction::Reject,
+                validations::ValidationAction::Warn => ValidationAction::Warn,
+            },
+            ignore_blank: validation.ignore_blank,
+            message: validation.message.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CompareOperator {
+    GreaterThan,
+    GreaterThanOrEqual,
+    LessThan,
+    LessThanOrEqual,
+    Equal,
+    NotEqual,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ConditionalFormatRule {
+    Compare {
+        operator: CompareOperator,
+        value: String,
+    },
+    Between {
+        min: f64,
+        max: f64,
+    },
+    TextContains {
+        text: String,
+    },
+    TopN {
+        count: u32,
+        bottom: bool,
+    },
+    AboveAverage {
+        below: bool,
+    },
+    Duplicates,
+    Custom {
+        formula: String,
+    },
+    ColorScale {
+        min_color: String,
+        mid_color: Option<String>,
+        max_color: String,
+    },
+    DataBar {
+        color: String,
+    },
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConditionalFormatStyle {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bold: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub italic: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub underline: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strike_through: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text_color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fill_color: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConditionalFormat {
+    pub rect: Rect,
+    pub rule: ConditionalFormatRule,
+    pub style: ConditionalFormatStyle,
+}
+
+impl From<&ConditionalFormatRule> for conditional_formats::ConditionalFormatRule {
+    fn from(rule: &ConditionalFormatRule) -> Self {
+        use conditional_formats::CompareOperator as Operator;
+
+        match rule.clone() {
+            ConditionalFormatRule::Compare { operator, value } => Self::Compare {
+                operator: match operator {
+                    CompareOperator::GreaterThan => Operator::GreaterThan,
+                    CompareOperator::GreaterThanOrEqual => Operator::GreaterThanOrEqual,
+                    CompareOperator::LessThan => Operator::LessThan,
+                    CompareOperator::LessThanOrEqual => Operator::LessThanOrEqual,
+                    CompareOperator::Equal => Operator::Equal,
+                    CompareOperator::NotEqual => Operator::NotEqual,
+                },
+                value,
+            },
+            ConditionalFormatRule::Between { min, max } => Self::Between { min, max },
+            ConditionalFormatRule::TextContains { text } => Self::TextContains { text },
+            ConditionalFormatRule::TopN { count, bottom } => Self::TopN { count, bottom },
+            ConditionalFormatRule::AboveAverage { below } => Self::AboveAverage { below },
+            ConditionalFormatRule::Duplicates => Self::Duplicates,
+            ConditionalFormatRule::Custom { formula } => Self::Custom { formula },
+            ConditionalFormatRule::ColorScale {
+                min_color,
+                mid_color,
+                max_color,
+            } => Self::ColorScale {
+                min_color,
+                mid_color,
+                max_color,
+            },
+            ConditionalFormatRule::DataBar { color } => Self::DataBar { color },
+        }
+    }
+}
+
+impl From<&conditional_formats::ConditionalFormatRule> for ConditionalFormatRule {
+    fn from(rule: &conditional_formats::ConditionalFormatRule) -> Self {
+        use conditional_formats::{CompareOperator as Operator, ConditionalFormatRule as Rule};
+
+        match rule.clone() {
+            Rule::Compare { operator, value } => Self::Compare {
+                operator: match operator {
+                    Operator::GreaterThan => CompareOperator::GreaterThan,
+                    Operator::GreaterThanOrEqual => CompareOperator::GreaterThanOrEqual,
+                    Operator::LessThan => CompareOperator::LessThan,
+                    Operator::LessThanOrEqual => CompareOperator::LessThanOrEqual,
+                    Operator::Equal => CompareOperator::Equal,
+                    Operator::NotEqual => CompareOperator::NotEqual,
+                },
+                value,
+            },
+            Rule::Between { min, max } => Self::Between { min, max },
+            Rule::TextContains { text } => Self::TextContains { text },
+            Rule::TopN { count, bottom } => Self::TopN { count, bottom },
+            Rule::AboveAverage { below } => Self::AboveAverage { below },
+            Rule::Duplicates => Self::Duplicates,
+            Rule::Custom { formula } => Self::Custom { formula },
+            Rule::ColorScale {
+                min_color,
+                mid_color,
+                max_color,
+            } => Self::ColorScale {
+                min_color,
+                mid_color,
+                max_color,
+            },
+            Rule::DataBar { color } => Self::DataBar { color },
+        }
+    }
+}
+
+impl From<&ConditionalFormat> for conditional_formats::ConditionalFormat {
+    fn from(conditional_format: &ConditionalFormat) -> Self {
+        let style = conditional_format.style.clone();
+        Self {
+            rect: conditional_format.rect.clone().into(),
+            rule: (&conditional_format.rule).into(),
+            style: conditional_formats::ConditionalFormatStyle {
+                bold: style.bold,
+                italic: style.italic,
+                underline: style.underline,
+                strike_through: style.strike_through,
+                text_color: style.text_color,
+                fill_color: style.fill_color,
+            },
+        }
+    }
+}
+
+impl From<&conditional_formats::ConditionalFormat> for ConditionalFormat {
+    fn from(conditional_format: &conditional_formats::ConditionalFormat) -> Self {
+        let style = conditional_format.style.clone();
+        Self {
+            rect: conditional_format.rect.into(),
+            rule: (&conditional_format.rule).into(),
+            style: ConditionalFormatStyle {
+                bold: style.bold,
+                italic: style.italic,
+                underline: style.underline,
+                strike_through: style.strike_through,
+                text_color: style.text_color,
+                fill_color: style.fill_color,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Comment {
+    // older files have no comment ids; comments get new ones on import
+    #[serde(default)]
+    pub id: Id,
+    pub author: String,
+    // seconds since the epoch
+    pub timestamp: f64,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommentThread {
+    pub comments: Vec<Comment>,
+    pub resolved: bool,
+}
+
+impl From<&CommentThread> for comments::CommentThread {
+    fn from(thread: &CommentThread) -> Self {
+        Self {
+            comments: thread
+                .comments
+                .iter()
+                .map(|comment| comments::Comment {
+                    id: CommentId::from_str(&comment.id.id).unwrap_or_default(),
+                    author: comment.author.clone(),
+                    timestamp: crate::Instant::new(comment.timestamp),
+                    text: comment.text.clone(),
+                })
+                .collect(),
+            resolved: thread.resolved,
+        }
+    }
+}
+
+impl From<&comments::CommentThread> for CommentThread {
+    fn from(thread: &comments::CommentThread) -> Self {
+        Self {
+            comments: thread
+                .comments
+                .iter()
+                .map(|comment| Comment {
+                    id: Id {
+                        id: comment.id.to_string(),
+                    },
+                    author: comment.author.clone(),
+                    timestamp: comment.timestamp.seconds,
+                    text: comment.text.clone(),
+                })
+                .collect(),
+            resolved: thread.resolved,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProtectedRange {
+    pub rect: Rect,
+    pub editors: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SheetProtection {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locked: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ranges: Vec<ProtectedRange>,
+}
+
+impl From<&SheetProtection> for protection::SheetProtection {
+    fn from(protection: &SheetProtection) -> Self {
+        Self {
+            locked: protection.locked.clone(),
+            ranges: protection
+                .ranges
+                .iter()
+                .map(|range| protection::ProtectedRange {
+                    rect: range.rect.clone().into(),
+                    editors: range.editors.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<&protection::SheetProtection> for SheetProtection {
+    fn from(protection: &protection::SheetProtection) -> Self {
+        Self {
+            locked: protection.locked.clone(),
+            ranges: protection
+                .ranges
+                .iter()
+                .map(|range| ProtectedRange {
+                    rect: range.rect.into(),
+                    editors: range.editors.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ChartType {
+    Line,
+    Bar,
+    Scatter,
+    Pie,
+    Area,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChartSeries {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub values: SheetRect,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Chart {
+    pub chart_type: ChartType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub categories: Option<SheetRect>,
+    pub series: Vec<ChartSeries>,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl From<&Chart> for charts::Chart {
+    fn from(chart: &Chart) -> Self {
+        Self {
+            chart_type: match chart.chart_type {
+                ChartType::Line => charts::ChartType::Line,
+                ChartType::Bar => charts::ChartType::Bar,
+                ChartType::Scatter => charts::ChartType::Scatter,
+                ChartType::Pie => charts::ChartType::Pie,
+                ChartType::Area => charts::ChartType::Area,
+            },
+            title: chart.title.clone(),
+            categories: chart.categories.clone().map(Into::into),
+            series: chart
+                .series
+                .iter()
+                .map(|series| charts::ChartSeries {
+                    name: series.name.clone(),
+                    values: series.values.clone().into(),
+                })
+                .collect(),
+            width: chart.width,
+            height: chart.height,
+        }
+    }
+}
+
+impl From<&charts::Chart> for Chart {
+    fn from(chart: &charts::Chart) -> Self {
+        Self {
+            chart_type: match chart.chart_type {
+                charts::ChartType::Line => ChartType::Line,
+                charts::ChartType::Bar => ChartType::Bar,
+                charts::ChartType::Scatter => ChartType::Scatter,
+                charts::ChartType::Pie => ChartType::Pie,
+                charts::ChartType::Area => ChartType::Area,
+            },
+            title: chart.title.clone(),
+            categories: chart.categories.map(Into::into),
+            series: chart
+                .series
+                .iter()
+                .map(|series| ChartSeries {
+                    name: series.name.clone(),
+                    values: series.values.into(),
+                })
+                .collect(),
+            width: chart.width,
+            height: chart.height,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CurrencyPosition {
+    Prefix,
+    Suffix,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DateOrder {
+    Mdy,
+    Dmy,
+    Ymd,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Locale {
+    pub decimal_separator: char,
+    pub thousands_separator: char,
+    pub currency_position: CurrencyPosition,
+    pub date_order: DateOrder,
+    pub argument_separator: char,
+}
+
+impl From<&Locale> for locale::Locale {
+    fn from(locale: &Locale) -> Self {
+        Self {
+            decimal_separator: locale.decimal_separator,
+            thousands_separator: locale.thousands_separator,
+            currency_position: match locale.currency_position {
+                CurrencyPosition::Prefix => locale::CurrencyPosition::Prefix,
+                CurrencyPosition::Suffix => locale::CurrencyPosition::Suffix,
+            },
+            date_order: match locale.date_order {
+                DateOrder::Mdy => locale::DateOrder::Mdy,
+                DateOrder::Dmy => locale::DateOrder::Dmy,
+                DateOrder::Ymd => locale::DateOrder::Ymd,
+            },
+            argument_separator: locale.argument_separator,
+        }
+    }
+}
+
+impl From<&locale::Locale> for Locale {
+    fn from(locale: &locale::Locale) -> Self {
+        Self {
+            decimal_separator: locale.decimal_separator,
+            thousands_separator: locale.thousands_separator,
+            currency_position: match locale.currency_position {
+                locale::CurrencyPosition::Prefix => CurrencyPosition::Prefix,
+                locale::CurrencyPosition::Suffix => CurrencyPosition::Suffix,
+            },
+            date_order: match locale.date_order {
+                locale::DateOrder::Mdy => DateOrder::Mdy,
+                locale::DateOrder::Dmy => DateOrder::Dmy,
+                locale::DateOrder::Ymd => DateOrder::Ymd,
+            },
+            argument_separator: locale.argument_separator,
+        }
+    }
+}
+
 pub type Offsets = v1_4::Offsets;
 
 pub type Borders = HashMap<String, Vec<(i64, Vec<Option<CellBorder>>)>>;
@@ -104,6 +655,26 @@ pub struct Sheet {
     pub columns: Vec<(i64, Column)>,
     pub borders: Borders,
     pub code_runs: Vec<(Pos, CodeRun)>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub merged_cells: Vec<Rect>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hidden_columns: Vec<i64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hidden_rows: Vec<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frozen_rows: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frozen_columns: Option<u32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub validations: Vec<Validation>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditional_formats: Vec<ConditionalFormat>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub comments: Vec<(Pos, CommentThread)>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protection: Option<SheetProtection>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub charts: Vec<(Pos, Chart)>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -142,11 +713,23 @@ pub struct Column {
     pub values: HashMap<String, CellValue>,
     pub align: HashMap<String, ColumnRepeat<CellAlign>>,
     pub wrap: HashMap<String, ColumnRepeat<CellWrap>>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub vertical_align: HashMap<String, ColumnRepeat<CellVerticalAlign>>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub text_rotation: HashMap<String, ColumnRepeat<i16>>,
     pub numeric_format: HashMap<String, ColumnRepeat<NumericFormat>>,
     pub numeric_decimals: HashMap<String, ColumnRepeat<i16>>,
     pub numeric_commas: HashMap<String, ColumnRepeat<bool>>,
     pub bold: HashMap<String, ColumnRepeat<bool>>,
     pub italic: HashMap<String, ColumnRepeat<bool>>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub underline: HashMap<String, ColumnRepeat<bool>>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub strike_through: HashMap<String, ColumnRepeat<bool>>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub font_size: HashMap<String, ColumnRepeat<i16>>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub font_family: HashMap<String, ColumnRepeat<String>>,
     pub text_color: HashMap<String, ColumnRepeat<String>>,
     pub fill_color: HashMap<String, ColumnRepeat<String>>,
     pub render_size: HashMap<String, ColumnRepeat<RenderSize>>,
@@ -163,6 +746,50 @@ pub enum CellValue {
     Instant(String),
     Duration(String),
     Error(RunError),
+    Link(Link),
+    Image(Image),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Link {
+    pub text: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Image {
+    pub mime_type: String,
+    // base64 encoded contents of the file
+    pub data: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl From<&crate::Image> for Image {
+    fn from(image: &crate::Image) -> Self {
+        Self {
+            mime_type: image.format.mime_type().into(),
+            data: STANDARD.encode(&image.data),
+            width: image.width,
+            height: image.height,
+        }
+    }
+}
+
+impl TryFrom<&Image> for crate::Image {
+    type Error = anyhow::Error;
+
+    fn try_from(image: &Image) -> Result<Self, Self::Error> {
+        Ok(Self {
+            format: crate::ImageFormat::from_mime_type(&image.mime_type)
+                .ok_or_else(|| anyhow::anyhow!("unknown image type {}", image.mime_type))?,
+            data: STANDARD
+                .decode(&image.data)
+                .map_err(|e| anyhow::anyhow!("invalid image data: {e}"))?,
+            width: image.width,
+            height: image.height,
+        })
+    }
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -178,6 +805,8 @@ pub enum NumericFormatKind {
     Currency,
     Percentage,
     Exponential,
+    Custom(String),
+    DateTime(String),
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -208,6 +837,13 @@ pub enum CellAlign {
     Right,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CellVerticalAlign {
+    Top,
+    Middle,
+    Bottom,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum CellWrap {
     Overflow,
@@ -0,0 +1,518 @@
+//! Validation and repair of .grid files before they are imported.
+//!
+//! [`validate`] reports problems with a parsed file without changing it, and
+//! [`repair`] fixes the problems it finds and reports what it changed. Invalid
+//! colors are only reported, never repaired.
+
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use lexicon_fractional_index::key_between;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::v1_5::schema::{
+    CellValue as CellValueSchema, CodeRunResult, GridSchema, OutputValue, Sheet as SheetSchema,
+};
+use crate::{grid::SheetId, Pos, Rect};
+
+/// A problem found in a .grid file.
+#[derive(Error, Debug, Serialize, Deserialize, PartialEq, Clone, Eq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub enum ValidationProblem {
+    #[error("Sheet id {sheet_id} is not a valid id")]
+    InvalidSheetId { sheet_id: String },
+
+    #[error("Sheet id {sheet_id} is used by more than one sheet")]
+    DuplicateSheetId { sheet_id: String },
+
+    #[error("Sheet name {name} is used by more than one sheet")]
+    DuplicateSheetName { sheet_id: String, name: String },
+
+    #[error("Sheet {sheet_id} has an invalid order key {order}")]
+    InvalidSheetOrder { sheet_id: String, order: String },
+
+    #[error("Code run at {pos} in sheet {sheet_id} does not have a code cell")]
+    OrphanCodeRun { sheet_id: String, pos: Pos },
+
+    #[error("Code run at {pos} in sheet {sheet_id} spills over the code run at {other}")]
+    OverlappingSpill {
+        sheet_id: String,
+        pos: Pos,
+        other: Pos,
+    },
+
+    #[error("Invalid color {color} in sheet {sheet_id}")]
+    InvalidColor {
+        sheet_id: String,
+        pos: Option<Pos>,
+        color: String,
+    },
+}
+
+/// The result of validating or repairing a .grid file.
+#[derive(Default, Debug, Serialize, Deserialize, PartialEq, Clone, Eq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub struct ValidationReport {
+    pub problems: Vec<ValidationProblem>,
+
+    /// Whether any of the problems were repaired.
+    pub repaired: bool,
+}
+
+impl ValidationProblem {
+    /// Whether [`repair`] fixes the problem.
+    pub fn is_repairable(&self) -> bool {
+        !matches!(self, ValidationProblem::InvalidColor { .. })
+    }
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Returns a report of the problems in a file without changing it.
+pub fn validate(file: &GridSchema) -> ValidationReport {
+    let mut file = file.clone();
+    ValidationReport {
+        problems: check(&mut file, false),
+        repaired: false,
+    }
+}
+
+/// Fixes the problems in a file and returns a report of what was repaired.
+pub fn repair(file: &mut GridSchema) -> ValidationReport {
+    let problems = check(file, true);
+    ValidationReport {
+        repaired: problems.iter().any(ValidationProblem::is_repairable),
+        problems,
+    }
+}
+
+/// Finds the problems in a file, fixing them if `fix` is true.
+fn check(file: &mut GridSchema, fix: bool) -> Vec<ValidationProblem> {
+    let mut problems = vec![];
+    check_sheet_ids(file, fix, &mut problems);
+    check_sheet_names(file, fix, &mut problems);
+    check_sheet_orders(file, fix, &mut problems);
+    for sheet in file.sheets.iter_mut() {
+        check_code_runs(sheet, fix, &mut problems);
+        check_spills(sheet, fix, &mut problems);
+        check_colors(sheet, &mut problems);
+    }
+    problems
+}
+
+/// Sheet ids must be valid and unique. Repaired by assigning a new id.
+fn check_sheet_ids(file: &mut GridSchema, fix: bool, problems: &mut Vec<ValidationProblem>) {
+    let mut ids = HashSet::new();
+    for sheet in file.sheets.iter_mut() {
+        let sheet_id = sheet.id.id.clone();
+        let problem = if SheetId::from_str(&sheet_id).is_err() {
+            ValidationProblem::InvalidSheetId { sheet_id }
+        } else if !ids.insert(sheet_id.clone()) {
+            ValidationProblem::DuplicateSheetId { sheet_id }
+        } else {
+            continue;
+        };
+        problems.push(problem);
+        if fix {
+            let new_id = SheetId::new().to_string();
+            ids.insert(new_id.clone());
+            sheet.id.id = new_id;
+        }
+    }
+}
+
+/// Sheet names must be unique. Repaired by appending a number to the name.
+fn check_sheet_names(file: &mut GridSchema, fix: bool, problems: &mut Vec<ValidationProblem>) {
+    let mut names: HashSet<String> = HashSet::new();
+    for sheet in file.sheets.iter_mut() {
+        if names.insert(sheet.name.to_lowercase()) {
+            continue;
+        }
+        problems.push(ValidationProblem::DuplicateSheetName {
+            sheet_id: sheet.id.id.clone(),
+            name: sheet.name.clone(),
+        });
+        if fix {
+            let mut count = 2;
+            while names.contains(&format!("{} ({count})", sheet.name).to_lowercase()) {
+                count += 1;
+            }
+            sheet.name = format!("{} ({count})", sheet.name);
+            names.insert(sheet.name.to_lowercase());
+        }
+    }
+}
+
+/// Returns true if the order is a usable fractional index key.
+fn is_valid_order(order: &str) -> bool {
+    !order.is_empty()
+        && order.chars().all(|c| c.is_ascii_alphanumeric())
+        && key_between(&Some(order.to_string()), &None).is_ok()
+}
+
+/// Sheet orders must be valid, unique fractional index keys. Repaired by
+/// regenerating the keys of all sheets, keeping the sheets with valid keys in
+/// their current order and moving the rest to the end.
+fn check_sheet_orders(file: &mut GridSchema, fix: bool, problems: &mut Vec<ValidationProblem>) {
+    let mut orders = HashSet::new();
+    let mut invalid = HashSet::new();
+    for (index, sheet) in file.sheets.iter().enumerate() {
+        if !is_valid_order(&sheet.order) || !orders.insert(sheet.order.clone()) {
+            invalid.insert(index);
+            problems.push(ValidationProblem::InvalidSheetOrder {
+                sheet_id: sheet.id.id.clone(),
+                order: sheet.order.clone(),
+            });
+        }
+    }
+    if !fix || invalid.is_empty() {
+        return;
+    }
+
+    let mut sheets = std::mem::take(&mut file.sheets)
+        .into_iter()
+        .enumerate()
+        .collect::<Vec<_>>();
+    sheets.sort_by(|(a_index, a), (b_index, b)| {
+        let a_invalid = invalid.contains(a_index);
+        let b_invalid = invalid.contains(b_index);
+        match (a_invalid, b_invalid) {
+            (false, false) => a.order.cmp(&b.order),
+            (a_invalid, b_invalid) => a_invalid.cmp(&b_invalid).then(a_index.cmp(b_index)),
+        }
+    });
+
+    let mut last_order = None;
+    for (_, mut sheet) in sheets {
+        if let Ok(order) = key_between(&last_order, &None) {
+            sheet.order = order;
+        }
+        last_order = Some(sheet.order.clone());
+        file.sheets.push(sheet);
+    }
+}
+
+/// Returns the cell value at a position in a sheet.
+fn cell_value(sheet: &SheetSchema, pos: Pos) -> Option<&CellValueSchema> {
+    sheet
+        .columns
+        .iter()
+        .find(|(x, _)| *x == pos.x)
+        .and_then(|(_, column)| column.values.get(&pos.y.to_string()))
+}
+
+/// Every code run must have a code cell. Repaired by removing the code run.
+fn check_code_runs(sheet: &mut SheetSchema, fix: bool, problems: &mut Vec<ValidationProblem>) {
+    let orphans = sheet
+        .code_runs
+        .iter()
+        .map(|(pos, _)| Pos { x: pos.x, y: pos.y })
+        .filter(|&pos| !matches!(cell_value(sheet, pos), Some(CellValueSchema::Code(_))))
+        .collect::<Vec<_>>();
+
+    for &pos in orphans.iter() {
+        problems.push(ValidationProblem::OrphanCodeRun {
+            sheet_id: sheet.id.id.clone(),
+            pos,
+        });
+    }
+    if fix && !orphans.is_empty() {
+        sheet
+            .code_runs
+            .retain(|(pos, _)| !orphans.contains(&Pos { x: pos.x, y: pos.y }));
+    }
+}
+
+/// Code runs may not spill over each other. Repaired by marking the code run
+/// that spills over another code run as a spill error. When only the spills
+/// overlap, the later code run is marked.
+fn check_spills(sheet: &mut SheetSchema, fix: bool, problems: &mut Vec<ValidationProblem>) {
+    let mut outputs = sheet
+        .code_runs
+        .iter()
+        .map(|(pos, code_run)| {
+            let pos = Pos { x: pos.x, y: pos.y };
+            let (w, h) = match &code_run.result {
+                CodeRunResult::Ok(OutputValue::Array(array)) if !code_run.spill_error => {
+                    (array.size.w.max(1), array.size.h.max(1))
+                }
+                _ => (1, 1),
+            };
+            let output = Rect::new_span(
+                pos,
+                Pos {
+                    x: pos.x + w - 1,
+                    y: pos.y + h - 1,
+                },
+            );
+            (pos, output)
+        })
+        .collect::<Vec<_>>();
+
+    let mut spill_errors = vec![];
+    for index in 0..outputs.len() {
+        while let Some(other) =
+            (0..index).find(|&other| outputs[other].1.intersects(outputs[index].1))
+        {
+            let (pos, _) = outputs[index];
+            let (other_pos, other_output) = outputs[other];
+            let (spilled, over) = if other_output.contains(pos) {
+                (other, pos)
+            } else {
+                (index, other_pos)
+            };
+            let single = Rect::single_pos(outputs[spilled].0);
+
+            // two code runs at the same position cannot be fixed by spilling
+            if outputs[spilled].1 == single {
+                break;
+            }
+            problems.push(ValidationProblem::OverlappingSpill {
+                sheet_id: sheet.id.id.clone(),
+                pos: outputs[spilled].0,
+                other: over,
+            });
+            outputs[spilled].1 = single;
+            spill_errors.push(spilled);
+        }
+    }
+
+    if fix {
+        for index in spill_errors {
+            sheet.code_runs[index].1.spill_error = true;
+        }
+    }
+}
+
+/// Returns true if a color looks like a css color: a hex color (#rgb, #rgba,
+/// #rrggbb, or #rrggbbaa), an rgb(), rgba(), hsl(), or hsla() color, or a
+/// named color.
+fn is_valid_color(color: &str) -> bool {
+    let color = color.trim();
+    if let Some(hex) = color.strip_prefix('#') {
+        matches!(hex.len(), 3 | 4 | 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit())
+    } else if let Some((function, args)) = color.split_once('(') {
+        matches!(
+            function.to_ascii_lowercase().as_str(),
+            "rgb" | "rgba" | "hsl" | "hsla"
+        ) && args.strip_suffix(')').is_some_and(|args| {
+            args.chars()
+                .all(|c| c.is_ascii_digit() || " ,./%-+degDEG".contains(c))
+        })
+    } else {
+        !color.is_empty() && color.chars().all(|c| c.is_ascii_alphabetic())
+    }
+}
+
+/// Sheet, text, fill, and border colors should be valid. Colors are only
+/// reported: the renderer accepts more colors than are checked here, so
+/// removing them could lose colors that display correctly.
+fn check_colors(sheet: &SheetSchema, problems: &mut Vec<ValidationProblem>) {
+    let sheet_id = &sheet.id.id;
+    let mut check = |pos: Option<Pos>, color: &str| {
+        if !is_valid_color(color) {
+            problems.push(ValidationProblem::InvalidColor {
+                sheet_id: sheet_id.clone(),
+                pos,
+                color: color.to_string(),
+            });
+        }
+    };
+
+    if let Some(color) = &sheet.color {
+        check(None, color);
+    }
+
+    for (x, column) in sheet.columns.iter() {
+        for colors in [&column.text_color, &column.fill_color] {
+            for (y, repeat) in colors.iter() {
+                let pos = y.parse().ok().map(|y| Pos { x: *x, y });
+                check(pos, &repeat.value);
+            }
+        }
+    }
+
+    // borders are keyed by column id, so they are reported without a position
+    // and only once per color
+    let mut border_colors = HashSet::new();
+    for (_, rows) in sheet.borders.iter() {
+        for (_, borders) in rows.iter() {
+            for cell_border in borders.iter().flatten() {
+                if border_colors.insert(cell_border.color.as_str()) {
+                    check(None, &cell_border.color);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controller::GridController;
+    use crate::grid::file::{current, import, CURRENT_VERSION};
+    use crate::grid::{CodeCellLanguage, Grid};
+    use crate::SheetPos;
+
+    fn schema(grid: &mut Grid) -> GridSchema {
+        current::export(grid).unwrap()
+    }
+
+    #[test]
+    fn valid_file_has_no_problems() {
+        let mut grid = Grid::new();
+        grid.add_sheet(None);
+        let report = validate(&schema(&mut grid));
+        assert!(report.is_valid());
+        assert!(!report.repaired);
+    }
+
+    #[test]
+    fn repairs_sheet_ids_names_and_orders() {
+        let mut grid = Grid::new();
+        grid.add_sheet(None);
+        grid.add_sheet(None);
+        let mut file = schema(&mut grid);
+        file.sheets[1].id = file.sheets[0].id.clone();
+        file.sheets[1].name = file.sheets[0].name.clone();
+        file.sheets[2].order = "!!".into();
+        file.sheets[2].color = Some("not a color".into());
+
+        let report = validate(&file);
+        assert_eq!(report.problems.len(), 4);
+        assert!(report
+            .problems
+            .contains(&ValidationProblem::DuplicateSheetId {
+                sheet_id: file.sheets[0].id.id.clone()
+            }));
+
+        let report = repair(&mut file);
+        assert!(report.repaired);
+        assert_eq!(report.problems.len(), 4);
+        assert_ne!(file.sheets[0].id, file.sheets[1].id);
+        assert_eq!(file.sheets[1].name, format!("{} (2)", file.sheets[0].name));
+        assert!(file.sheets.iter().all(|sheet| is_valid_order(&sheet.order)));
+        assert!(file.sheets[0].order < file.sheets[1].order);
+        assert!(file.sheets[1].order < file.sheets[2].order);
+
+        // invalid colors are reported but kept
+        assert_eq!(file.sheets[2].color, Some("not a color".into()));
+        assert_eq!(
+            validate(&file).problems,
+            vec![ValidationProblem::InvalidColor {
+                sheet_id: file.sheets[2].id.id.clone(),
+                pos: None,
+                color: "not a color".into(),
+            }]
+        );
+        file.version = Some(CURRENT_VERSION.into());
+        assert!(current::import(file).is_ok());
+    }
+
+    #[test]
+    fn accepts_css_colors() {
+        for color in [
+            "red",
+            "RebeccaPurple",
+            "#fff",
+            "#ffff",
+            "#00ff00",
+            "#00ff0080",
+            "rgb(0, 128, 255)",
+            "rgba(0, 128, 255, 0.5)",
+            "rgb(0 128 255 / 50%)",
+            "hsl(120, 100%, 50%)",
+            "hsla(120deg, 100%, 50%, 0.3)",
+        ] {
+            assert!(is_valid_color(color), "{color}");
+        }
+        for color in ["", "not a color", "#12345", "url(x)", "rgb(0, 0, 0", "red;"] {
+            assert!(!is_valid_color(color), "{color}");
+        }
+
+        // a file with only invalid colors is not changed
+        let mut grid = Grid::new();
+        let mut file = schema(&mut grid);
+        file.sheets[0].color = Some("rgb(".into());
+        let report = repair(&mut file);
+        assert!(!report.repaired);
+        assert_eq!(report.problems.len(), 1);
+        assert_eq!(file.sheets[0].color, Some("rgb(".into()));
+    }
+
+    #[test]
+    fn repairs_code_runs_and_spills() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        for (x, y, code) in [(0, 0, "{1; 2; 3}"), (0, 5, "{4; 5}"), (3, 0, "1")] {
+            gc.set_code_cell(
+                SheetPos { x, y, sheet_id },
+                CodeCellLanguage::Formula,
+                code.into(),
+                None,
+            );
+        }
+        let mut file = schema(gc.grid_mut());
+        let sheet = &mut file.sheets[0];
+
+        // move the code run at (0, 5) into the spill of the code run at (0, 0)
+        let (_, column) = sheet.columns.iter_mut().find(|(x, _)| *x == 0).unwrap();
+        let code_cell = column.values.remove("5").unwrap();
+        column.values.insert("1".into(), code_cell);
+        sheet.code_runs[1].0.y = 1;
+
+        // remove the code cell for the code run at (3, 0)
+        sheet.columns.retain(|(x, _)| *x != 3);
+
+        let sheet_id = sheet.id.id.clone();
+        let report = repair(&mut file);
+        assert_eq!(
+            report.problems,
+            vec![
+                ValidationProblem::OrphanCodeRun {
+                    sheet_id: sheet_id.clone(),
+                    pos: Pos { x: 3, y: 0 }
+                },
+                ValidationProblem::OverlappingSpill {
+                    sheet_id,
+                    pos: Pos { x: 0, y: 0 },
+                    other: Pos { x: 0, y: 1 }
+                },
+            ]
+        );
+        assert_eq!(file.sheets[0].code_runs.len(), 2);
+        assert!(file.sheets[0].code_runs[0].1.spill_error);
+        assert!(!file.sheets[0].code_runs[1].1.spill_error);
+        assert!(validate(&file).is_valid());
+    }
+
+    #[test]
+    fn imports_with_a_report() {
+        let mut grid = Grid::new();
+        grid.add_sheet(None);
+        let mut file = schema(&mut grid);
+        file.sheets[1].id = file.sheets[0].id.clone();
+        let contents = serde_json::to_string(&file).unwrap();
+
+        let (_, report) = super::super::import_with_report(&contents, false).unwrap();
+        assert!(!report.repaired);
+        assert_eq!(report.problems.len(), 1);
+
+        let (grid, report) = super::super::import_with_report(&contents, true).unwrap();
+        assert!(report.repaired);
+        assert_ne!(grid.sheets()[0].id, grid.sheets()[1].id);
+
+        // files loaded from bytes are repaired too
+        let (grid, report) =
+            super::super::import_vec_with_report(contents.as_bytes(), true).unwrap();
+        assert!(report.repaired);
+        assert_ne!(grid.sheets()[0].id, grid.sheets()[1].id);
+
+        assert!(import("not a grid").is_err());
+    }
+}
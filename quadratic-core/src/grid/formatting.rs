@@ -13,16 +13,74 @@ use super::{block::SameValue, Column, ColumnData};
 pub enum CellFmtArray {
     Align(RunLengthEncoding<Option<CellAlign>>),
     Wrap(RunLengthEncoding<Option<CellWrap>>),
+    VerticalAlign(RunLengthEncoding<Option<CellVerticalAlign>>),
+    TextRotation(RunLengthEncoding<Option<i16>>),
     NumericFormat(RunLengthEncoding<Option<NumericFormat>>),
     NumericDecimals(RunLengthEncoding<Option<i16>>),
     NumericCommas(RunLengthEncoding<Option<bool>>),
     Bold(RunLengthEncoding<Option<bool>>),
     Italic(RunLengthEncoding<Option<bool>>),
+    Underline(RunLengthEncoding<Option<bool>>),
+    StrikeThrough(RunLengthEncoding<Option<bool>>),
+    FontSize(RunLengthEncoding<Option<i16>>),
+    FontFamily(RunLengthEncoding<Option<String>>),
     TextColor(RunLengthEncoding<Option<String>>),
     FillColor(RunLengthEncoding<Option<String>>),
     RenderSize(RunLengthEncoding<Option<RenderSize>>),
 }
 
+impl CellFmtArray {
+    /// Transposes a row-major array that covers a `w` x `h` rect.
+    pub fn transpose(&self, w: usize, h: usize) -> Self {
+        let indexes = (0..w)
+            .flat_map(|x| (0..h).map(move |y| y * w + x))
+            .collect::<Vec<_>>();
+        self.reorder(&indexes)
+    }
+
+    /// Keeps only the given columns and rows of a row-major array that is `w`
+    /// wide.
+    pub fn select(&self, w: usize, columns: &[usize], rows: &[usize]) -> Self {
+        let indexes = rows
+            .iter()
+            .flat_map(|y| columns.iter().map(move |x| y * w + x))
+            .collect::<Vec<_>>();
+        self.reorder(&indexes)
+    }
+
+    /// Creates a new array from the values at `indexes`.
+    fn reorder(&self, indexes: &[usize]) -> Self {
+        fn reorder<T: Eq + Clone>(
+            rle: &RunLengthEncoding<T>,
+            indexes: &[usize],
+        ) -> RunLengthEncoding<T> {
+            let values = rle.iter_values().collect::<Vec<_>>();
+            indexes
+                .iter()
+                .filter_map(|i| values.get(*i).map(|value| (*value).clone()))
+                .collect()
+        }
+        match self {
+            Self::Align(rle) => Self::Align(reorder(rle, indexes)),
+            Self::Wrap(rle) => Self::Wrap(reorder(rle, indexes)),
+            Self::VerticalAlign(rle) => Self::VerticalAlign(reorder(rle, indexes)),
+            Self::TextRotation(rle) => Self::TextRotation(reorder(rle, indexes)),
+            Self::NumericFormat(rle) => Self::NumericFormat(reorder(rle, indexes)),
+            Self::NumericDecimals(rle) => Self::NumericDecimals(reorder(rle, indexes)),
+            Self::NumericCommas(rle) => Self::NumericCommas(reorder(rle, indexes)),
+            Self::Bold(rle) => Self::Bold(reorder(rle, indexes)),
+            Self::Italic(rle) => Self::Italic(reorder(rle, indexes)),
+            Self::Underline(rle) => Self::Underline(reorder(rle, indexes)),
+            Self::StrikeThrough(rle) => Self::StrikeThrough(reorder(rle, indexes)),
+            Self::FontSize(rle) => Self::FontSize(reorder(rle, indexes)),
+            Self::FontFamily(rle) => Self::FontFamily(reorder(rle, indexes)),
+            Self::TextColor(rle) => Self::TextColor(reorder(rle, indexes)),
+            Self::FillColor(rle) => Self::FillColor(reorder(rle, indexes)),
+            Self::RenderSize(rle) => Self::RenderSize(reorder(rle, indexes)),
+        }
+    }
+}
+
 /// Cell formatting attribute.
 pub trait CellFmtAttr {
     type Value: Serialize + for<'d> Deserialize<'d> + fmt::Debug + Clone + Eq;
@@ -48,6 +106,29 @@ impl CellFmtAttr for CellWrap {
         &mut column.wrap
     }
 }
+impl CellFmtAttr for CellVerticalAlign {
+    type Value = Self;
+    fn column_data_ref(column: &Column) -> &ColumnData<SameValue<Self::Value>> {
+        &column.vertical_align
+    }
+    fn column_data_mut(column: &mut Column) -> &mut ColumnData<SameValue<Self::Value>> {
+        &mut column.vertical_align
+    }
+}
+/// Text rotation in degrees counterclockwise, from -90 to 90.
+pub struct TextRotation;
+impl TextRotation {
+    pub const RANGE: std::ops::RangeInclusive<i16> = -90..=90;
+}
+impl CellFmtAttr for TextRotation {
+    type Value = i16;
+    fn column_data_ref(column: &Column) -> &ColumnData<SameValue<Self::Value>> {
+        &column.text_rotation
+    }
+    fn column_data_mut(column: &mut Column) -> &mut ColumnData<SameValue<Self::Value>> {
+        &mut column.text_rotation
+    }
+}
 impl CellFmtAttr for NumericFormat {
     type Value = Self;
     fn column_data_ref(column: &Column) -> &ColumnData<SameValue<Self::Value>> {
@@ -99,6 +180,50 @@ impl CellFmtAttr for Italic {
         &mut column.italic
     }
 }
+pub struct Underline;
+impl CellFmtAttr for Underline {
+    type Value = bool;
+    fn column_data_ref(column: &Column) -> &ColumnData<SameValue<Self::Value>> {
+        &column.underline
+    }
+    fn column_data_mut(column: &mut Column) -> &mut ColumnData<SameValue<Self::Value>> {
+        &mut column.underline
+    }
+}
+pub struct StrikeThrough;
+impl CellFmtAttr for StrikeThrough {
+    type Value = bool;
+    fn column_data_ref(column: &Column) -> &ColumnData<SameValue<Self::Value>> {
+        &column.strike_through
+    }
+    fn column_data_mut(column: &mut Column) -> &mut ColumnData<SameValue<Self::Value>> {
+        &mut column.strike_through
+    }
+}
+/// Font size in points.
+pub struct FontSize;
+impl FontSize {
+    pub const RANGE: std::ops::RangeInclusive<i16> = 1..=400;
+}
+impl CellFmtAttr for FontSize {
+    type Value = i16;
+    fn column_data_ref(column: &Column) -> &ColumnData<SameValue<Self::Value>> {
+        &column.font_size
+    }
+    fn column_data_mut(column: &mut Column) -> &mut ColumnData<SameValue<Self::Value>> {
+        &mut column.font_size
+    }
+}
+pub struct FontFamily;
+impl CellFmtAttr for FontFamily {
+    type Value = String;
+    fn column_data_ref(column: &Column) -> &ColumnData<SameValue<Self::Value>> {
+        &column.font_family
+    }
+    fn column_data_mut(column: &mut Column) -> &mut ColumnData<SameValue<Self::Value>> {
+        &mut column.font_family
+    }
+}
 pub struct TextColor;
 impl CellFmtAttr for TextColor {
     type Value = String;
@@ -139,6 +264,15 @@ pub enum CellAlign {
     Right,
 }
 
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash, Display, EnumString)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub enum CellVerticalAlign {
+    Top,
+    Middle,
+    Bottom,
+}
+
 #[derive(
     Serialize, Deserialize, Debug, Default, Copy, Clone, PartialEq, Eq, Hash, Display, EnumString,
 )]
@@ -168,7 +302,7 @@ pub struct RenderSize {
 }
 
 #[derive(
-    Default, Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, Display, EnumString, Copy,
+    Default, Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, Display, EnumString,
 )]
 #[cfg_attr(feature = "js", derive(ts_rs::TS))]
 #[serde(rename_all = "UPPERCASE")]
@@ -179,6 +313,13 @@ pub enum NumericFormatKind {
     Currency, // { symbol: String }, // TODO: would be nice if this were just a single char (and it could be)
     Percentage,
     Exponential,
+    /// An Excel-style format code, such as `#,##0.00` (see
+    /// [`super::number_format`]).
+    Custom(String),
+    /// A date/time format code, such as `yyyy-mm-dd` or `[h]:mm:ss`, for
+    /// instants and durations (see [`crate::Instant::format`]). The code
+    /// `relative` formats instants relative to now, e.g. "3 days ago".
+    DateTime(String),
 }
 
 /// Whether a set of booleans has any `true` values and/or any `false` values.
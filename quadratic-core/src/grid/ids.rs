@@ -43,6 +43,42 @@ impl Display for SheetId {
     }
 }
 
+/// Identifies a [`Comment`](super::sheet::comments::Comment) so that
+/// concurrent edits to the same thread do not overwrite each other.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub struct CommentId {
+    id: Uuid,
+}
+
+impl CommentId {
+    pub fn new() -> Self {
+        Self { id: Uuid::new_v4() }
+    }
+}
+
+impl Default for CommentId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FromStr for CommentId {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(CommentId {
+            id: Uuid::parse_str(s)?,
+        })
+    }
+}
+
+impl Display for CommentId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.id)
+    }
+}
+
 #[cfg(test)]
 impl SheetId {
     pub fn test() -> Self {
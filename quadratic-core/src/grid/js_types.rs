@@ -3,7 +3,10 @@ use std::ops::{BitOr, BitOrAssign};
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::wasm_bindgen;
 
-use super::formatting::{BoolSummary, CellAlign, CellWrap};
+use super::formatting::{BoolSummary, CellAlign, CellVerticalAlign, CellWrap};
+use super::sheet::charts::Chart;
+use super::sheet::comments::CommentThread;
+use super::sheet::search::SearchMatch;
 use super::CodeCellLanguage;
 use crate::controller::transaction_summary::TransactionSummary;
 use crate::grid::BorderStyle;
@@ -41,8 +44,29 @@ pub struct JsRenderCell {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub italic: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub underline: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strike_through: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub text_color: Option<String>,
 
+    /// Font size in points.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font_size: Option<i16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font_family: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vertical_align: Option<CellVerticalAlign>,
+
+    /// Text rotation in degrees counterclockwise.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_rotation: Option<i16>,
+
+    /// Target of a link cell: a URL, or `#` followed by a cell or range in
+    /// the workbook.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
+
     pub special: Option<JsRenderCellSpecial>,
 }
 
@@ -58,7 +82,14 @@ impl JsRenderCell {
             wrap: None,
             bold: None,
             italic: None,
+            underline: None,
+            strike_through: None,
             text_color: None,
+            font_size: None,
+            font_family: None,
+            vertical_align: None,
+            text_rotation: None,
+            link: None,
             special: None,
         }
     }
@@ -75,7 +106,14 @@ impl From<Pos> for JsRenderCell {
             wrap: None,
             bold: None,
             italic: None,
+            underline: None,
+            strike_through: None,
             text_color: None,
+            font_size: None,
+            font_family: None,
+            vertical_align: None,
+            text_rotation: None,
+            link: None,
             special: None,
         }
     }
@@ -90,6 +128,11 @@ pub struct JsRenderFill {
     pub h: u32,
 
     pub color: String,
+
+    /// Set for a conditional format data bar: the length of the bar as a
+    /// fraction of the cell's width.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_bar: Option<f32>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -241,6 +284,22 @@ pub enum JsRenderCodeCellState {
     Success,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub struct JsCommentThread {
+    pub x: i64,
+    pub y: i64,
+    pub thread: CommentThread,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub struct JsChart {
+    pub x: i64,
+    pub y: i64,
+    pub chart: Chart,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[cfg_attr(feature = "js", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
@@ -249,3 +308,12 @@ pub struct JsClipboard {
     pub plain_text: String,
     pub html: String,
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct JsFindReplace {
+    pub summary: TransactionSummary,
+    pub count: usize,
+    pub next: Option<SearchMatch>,
+}
@@ -0,0 +1,326 @@
+//! The workbook's locale: the conventions used to parse and display numbers,
+//! currencies, dates, and formulas.
+//!
+//! Values and formulas are always stored in the US form (`,` thousands, `.`
+//! decimal, `$` prefix, `,` between formula arguments). The locale converts
+//! input to that form and converts it back for display and editing.
+
+use anyhow::{bail, Result};
+use bigdecimal::BigDecimal;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::{
+    formulas::{tokenize, Token},
+    values::cellvalue::CURRENCY_SYMBOLS,
+    CellValue,
+};
+
+/// Where the currency symbol is written.
+#[derive(Serialize, Deserialize, Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub enum CurrencyPosition {
+    /// `$1,234.50`
+    #[default]
+    Prefix,
+    /// `1.234,50 €`
+    Suffix,
+}
+
+/// The order of the day, month, and year in typed dates such as `1/3/2024`.
+/// ISO dates (`2024-03-01`) are always accepted.
+#[derive(Serialize, Deserialize, Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub enum DateOrder {
+    #[default]
+    Mdy,
+    Dmy,
+    Ymd,
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub struct Locale {
+    pub decimal_separator: char,
+    pub thousands_separator: char,
+    pub currency_position: CurrencyPosition,
+    pub date_order: DateOrder,
+
+    /// Separates formula arguments, e.g. `;` in `SUM(1,5; 2)`.
+    pub argument_separator: char,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Locale {
+            decimal_separator: '.',
+            thousands_separator: ',',
+            currency_position: CurrencyPosition::Prefix,
+            date_order: DateOrder::Mdy,
+            argument_separator: ',',
+        }
+    }
+}
+
+impl Locale {
+    /// Returns the locale for a BCP 47 language tag, such as `de-DE`.
+    pub fn from_tag(tag: &str) -> Option<Locale> {
+        let us = Locale::default();
+        let european = Locale {
+            decimal_separator: ',',
+            thousands_separator: '.',
+            currency_position: CurrencyPosition::Suffix,
+            date_order: DateOrder::Dmy,
+            argument_separator: ';',
+        };
+        Some(match tag.to_ascii_lowercase().replace('_', "-").as_str() {
+            "en" | "en-us" => us,
+            "en-gb" | "en-au" | "en-nz" | "en-ie" => Locale {
+                date_order: DateOrder::Dmy,
+                ..us
+            },
+            "ja" | "ja-jp" | "zh" | "zh-cn" | "ko" | "ko-kr" => Locale {
+                date_order: DateOrder::Ymd,
+                ..us
+            },
+            "de" | "de-de" | "es" | "es-es" | "it" | "it-it" | "nl" | "nl-nl" | "pt-br" => european,
+            "fr" | "fr-fr" => Locale {
+                thousands_separator: ' ',
+                ..european
+            },
+            _ => return None,
+        })
+    }
+
+    /// Returns an error if numbers or formulas could not be read unambiguously
+    /// in this locale.
+    pub fn validate(&self) -> Result<()> {
+        if self.decimal_separator == self.thousands_separator {
+            bail!(
+                "The decimal and thousands separators cannot both be '{}'",
+                self.decimal_separator
+            );
+        }
+        if self.argument_separator == self.decimal_separator {
+            bail!(
+                "The argument and decimal separators cannot both be '{}'",
+                self.decimal_separator
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns true if the locale uses US conventions for numbers and formulas.
+    fn uses_us_numbers(&self) -> bool {
+        self.decimal_separator == '.'
+            && self.thousands_separator == ','
+            && self.currency_position == CurrencyPosition::Prefix
+            && self.argument_separator == ','
+    }
+
+    /// Returns true if the character is the thousands separator. A space also
+    /// matches non-breaking spaces.
+    fn is_thousands_separator(&self, c: char) -> bool {
+        c == self.thousands_separator
+            || (self.thousands_separator == ' ' && matches!(c, '\u{a0}' | '\u{202f}'))
+    }
+
+    /// Converts a number typed in this locale, such as `1.234,5` or
+    /// `12,50 €`, to the US form, such as `1,234.5` or `€12.50`. Returns
+    /// `None` if the value is not a number in this locale.
+    pub fn canonical_number(&self, value: &str) -> Option<String> {
+        if self.uses_us_numbers() {
+            return None;
+        }
+        let mut value = value.trim().to_string();
+
+        // a currency symbol after the number moves to the front
+        if let Some(symbol) = CURRENCY_SYMBOLS.chars().find(|c| value.ends_with(*c)) {
+            let number = value.trim_end_matches(symbol).trim_end();
+            value = format!("{symbol}{number}");
+        }
+        let canonical: String = value
+            .chars()
+            .map(|c| match c {
+                c if c == self.decimal_separator => '.',
+                c if self.is_thousands_separator(c) => ',',
+                c => c,
+            })
+            .collect();
+
+        // thousands separators must separate groups of three digits, so
+        // dates such as `1.3.2024` are not numbers
+        let number = CellValue::strip_percentage(CellValue::strip_currency(&canonical));
+        let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+        let mut groups = integer.trim_start_matches('-').split(',');
+        let first = groups.next().unwrap_or_default();
+        if integer.contains(',')
+            && (first.is_empty() || first.len() > 3 || groups.any(|group| group.len() != 3))
+            || fraction.contains([',', '.'])
+        {
+            return None;
+        }
+        BigDecimal::from_str(&CellValue::strip_commas(number))
+            .ok()
+            .map(|_| canonical)
+    }
+
+    /// Converts a number displayed in the US form, such as `$1,234.50`, to
+    /// this locale. `currency_symbol` is the symbol of a currency format.
+    pub fn localize_number(&self, display: &str, currency_symbol: Option<&str>) -> String {
+        if self.uses_us_numbers() {
+            return display.to_string();
+        }
+        let mut localized: String = display
+            .chars()
+            .map(|c| match c {
+                ',' => self.thousands_separator,
+                '.' => self.decimal_separator,
+                c => c,
+            })
+            .collect();
+        if self.currency_position == CurrencyPosition::Suffix {
+            if let Some(symbol) = currency_symbol.filter(|symbol| !symbol.is_empty()) {
+                if let Some(index) = localized.find(symbol) {
+                    localized.replace_range(index..index + symbol.len(), "");
+                    localized = format!("{localized} {symbol}");
+                }
+            }
+        }
+        localized
+    }
+
+    /// Returns the value as the user should edit it in this locale.
+    pub fn localize_edit(&self, value: &CellValue) -> String {
+        match value {
+            CellValue::Number(n) => self.localize_number(&n.to_string(), None),
+            _ => value.to_edit(),
+        }
+    }
+
+    /// The CSV delimiter used by this locale. Locales with a decimal comma
+    /// separate values with `;`.
+    pub fn csv_delimiter(&self) -> char {
+        if self.decimal_separator == ',' {
+            ';'
+        } else {
+            ','
+        }
+    }
+
+    /// Separates the rows of an array literal, e.g. `{1, 2; 3, 4}`. Locales
+    /// that separate arguments with `;` use `|`.
+    fn row_separator(&self) -> char {
+        if self.argument_separator == ';' {
+            '|'
+        } else {
+            ';'
+        }
+    }
+
+    /// Converts a formula written in this locale to the US form, e.g.
+    /// `SUM(1,5; A1)` to `SUM(1.5, A1)`. Strings and sheet names are not
+    /// changed, and neither is the length of the formula.
+    pub fn formula_to_canonical(&self, code: &str) -> String {
+        if self.uses_us_numbers() {
+            return code.to_string();
+        }
+        let decimal_comma =
+            self.decimal_separator == ',' && self.argument_separator != self.decimal_separator;
+        let tokens: Vec<_> = tokenize(code).collect();
+        let mut canonical = String::with_capacity(code.len());
+        for (i, token) in tokens.iter().enumerate() {
+            let text = token.span.of_str(code);
+            let is_number = |index: Option<usize>| {
+                index
+                    .and_then(|index| tokens.get(index))
+                    .is_some_and(|token| token.inner == Token::NumericLiteral)
+            };
+            let push = match token.inner {
+                // the lexer splits `1,5` into `1`, `,` and `5`
+                Token::ArgSep
+                    if decimal_comma && is_number(i.checked_sub(1)) && is_number(Some(i + 1)) =>
+                {
+                    Some('.')
+                }
+                Token::RowSep if self.argument_separator == ';' => Some(','),
+                Token::Unknown if text.starts_with(self.row_separator()) => Some(';'),
+                _ => None,
+            };
+            match push {
+                Some(c) => canonical.push(c),
+                None => canonical.push_str(text),
+            }
+        }
+        canonical
+    }
+
+    /// Converts a formula in the US form to this locale, e.g. `SUM(1.5, A1)`
+    /// to `SUM(1,5; A1)`. This is the inverse of
+    /// [`Self::formula_to_canonical`].
+    pub fn formula_to_localized(&self, code: &str) -> String {
+        if self.uses_us_numbers() {
+            return code.to_string();
+        }
+        let mut localized = String::with_capacity(code.len());
+        for token in tokenize(code) {
+            let text = token.span.of_str(code);
+            match token.inner {
+                Token::NumericLiteral => {
+                    localized.push_str(&text.replace('.', &self.decimal_separator.to_string()));
+                }
+                Token::ArgSep => localized.push(self.argument_separator),
+                Token::RowSep => localized.push(self.row_separator()),
+                _ => localized.push_str(text),
+            }
+        }
+        localized
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn converts_numbers() {
+        let german = Locale::from_tag("de-DE").unwrap();
+        assert_eq!(
+            german.canonical_number("1.234,5"),
+            Some("1,234.5".to_string())
+        );
+        assert_eq!(
+            german.canonical_number("-12,50 €"),
+            Some("€-12.50".to_string())
+        );
+        assert_eq!(german.canonical_number("12,5%"), Some("12.5%".to_string()));
+        assert_eq!(german.canonical_number("hello"), None);
+        assert_eq!(german.canonical_number("1.3.2024"), None);
+        assert_eq!(
+            german.localize_number("-€1,234.50", Some("€")),
+            "-1.234,50 €"
+        );
+
+        let french = Locale::from_tag("fr-FR").unwrap();
+        assert_eq!(
+            french.canonical_number("1\u{a0}234,5"),
+            Some("1,234.5".to_string())
+        );
+        assert_eq!(french.localize_number("1,234.5", None), "1 234,5");
+
+        let us = Locale::default();
+        assert_eq!(us.canonical_number("1,234.5"), None);
+        assert_eq!(us.localize_number("$1,234.50", Some("$")), "$1,234.50");
+        assert_eq!(Locale::from_tag("xx"), None);
+    }
+
+    #[test]
+    fn converts_formulas() {
+        let german = Locale::from_tag("de").unwrap();
+        let localized = "SUM(1,5; A1; \"a,b;c\") + {1; 2| 3; 4}";
+        let canonical = "SUM(1.5, A1, \"a,b;c\") + {1, 2; 3, 4}";
+        assert_eq!(german.formula_to_canonical(localized), canonical);
+        assert_eq!(german.formula_to_localized(canonical), localized);
+        assert_eq!(Locale::default().formula_to_canonical(canonical), canonical);
+    }
+}
@@ -13,10 +13,12 @@ pub use bounds::GridBounds;
 pub use code_run::*;
 pub use column::{Column, ColumnData};
 pub use formatting::{
-    Bold, BoolSummary, CellAlign, CellFmtAttr, CellWrap, FillColor, Italic, NumericCommas,
-    NumericDecimals, NumericFormat, NumericFormatKind, RenderSize, TextColor,
+    Bold, BoolSummary, CellAlign, CellFmtAttr, CellVerticalAlign, CellWrap, FillColor, FontFamily,
+    FontSize, Italic, NumericCommas, NumericDecimals, NumericFormat, NumericFormatKind,
+    RenderSize, StrikeThrough, TextColor, TextRotation, Underline,
 };
 pub use ids::*;
+pub use locale::Locale;
 use serde::{Deserialize, Serialize};
 pub use sheet::Sheet;
 #[cfg(feature = "js")]
@@ -27,10 +29,13 @@ mod borders;
 mod bounds;
 mod code_run;
 mod column;
+pub mod diff;
 pub mod file;
 pub mod formatting;
 mod ids;
 pub mod js_types;
+pub mod locale;
+pub mod number_format;
 mod offsets;
 pub mod search;
 pub mod series;
@@ -41,6 +46,9 @@ pub mod sheets;
 #[cfg_attr(feature = "js", wasm_bindgen)]
 pub struct Grid {
     sheets: Vec<Sheet>,
+
+    #[serde(default)]
+    locale: Locale,
 }
 impl Default for Grid {
     fn default() -> Self {
@@ -54,7 +62,22 @@ impl Grid {
         ret
     }
     pub fn new_blank() -> Self {
-        Grid { sheets: vec![] }
+        Grid {
+            sheets: vec![],
+            locale: Locale::default(),
+        }
+    }
+
+    pub fn locale(&self) -> &Locale {
+        &self.locale
+    }
+
+    /// Sets the workbook's locale. Each sheet keeps a copy for display.
+    pub fn set_locale(&mut self, locale: Locale) {
+        self.locale = locale;
+        self.sheets
+            .iter_mut()
+            .for_each(|sheet| sheet.locale = locale);
     }
 
     #[cfg(test)]
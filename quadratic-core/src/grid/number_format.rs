@@ -0,0 +1,646 @@
+//! Excel-style custom number formats, such as `#,##0.00`, `0.0%`,
+//! `0.00E+00`, `[Red]-0.00`, `"$"#,##0;("$"#,##0)` or `# ?/?`.
+//!
+//! A format has up to four `;`-separated sections: positive numbers, negative
+//! numbers, zero and text. Format codes are parsed once and cached.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use bigdecimal::{BigDecimal, RoundingMode, ToPrimitive, Zero};
+use lazy_static::lazy_static;
+
+/// Maximum number of parsed formats kept in the cache.
+const MAX_CACHED_FORMATS: usize = 1000;
+
+/// Colors that can be used in a section, e.g. `[Red]`.
+const COLORS: [&str; 8] = [
+    "black", "blue", "cyan", "green", "magenta", "red", "white", "yellow",
+];
+
+lazy_static! {
+    static ref CACHE: Mutex<HashMap<String, Arc<CustomNumberFormat>>> = Mutex::new(HashMap::new());
+}
+
+/// The part of a number that a digit placeholder belongs to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum Zone {
+    Integer,
+    Decimal,
+    Exponent,
+    Numerator,
+    Denominator,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Literal(String),
+    /// `0` (shows zeros), `#` (shows nothing) or `?` (shows a space).
+    Digit(char, Zone),
+    DecimalPoint,
+    Comma,
+    Percent,
+    Exponent {
+        plus: bool,
+    },
+    Slash,
+    FixedDenominator(u64),
+    Text,
+    General,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Section {
+    tokens: Vec<Token>,
+    color: Option<String>,
+    /// Whether the integer part has thousands separators.
+    grouping: bool,
+    /// Number of times the number is divided by 1,000 (trailing commas).
+    scale: u32,
+    /// Number of times the number is multiplied by 100.
+    percent: u32,
+    exponent: bool,
+    fraction: bool,
+    text: bool,
+}
+
+impl Section {
+    fn new(mut tokens: Vec<Token>, color: Option<String>) -> Self {
+        let mut section = Section {
+            color,
+            ..Default::default()
+        };
+
+        let mut zone = Zone::Integer;
+        for i in 0..tokens.len() {
+            let has_digits = tokens[..i].iter().any(|t| matches!(t, Token::Digit(..)));
+            match tokens[i].clone() {
+                Token::Digit(c, _) => tokens[i] = Token::Digit(c, zone),
+                Token::DecimalPoint if zone == Zone::Integer => zone = Zone::Decimal,
+                Token::DecimalPoint => tokens[i] = Token::Literal(".".into()),
+                Token::Exponent { .. }
+                    if matches!(zone, Zone::Integer | Zone::Decimal) && has_digits =>
+                {
+                    section.exponent = true;
+                    zone = Zone::Exponent;
+                }
+                Token::Exponent { plus } => {
+                    tokens[i] = Token::Literal(if plus { "E+" } else { "E-" }.into());
+                }
+                Token::Slash if zone == Zone::Integer && has_digits => {
+                    section.fraction = true;
+                    zone = Zone::Denominator;
+
+                    // the digits right before the slash are the numerator
+                    let mut j = i;
+                    while j > 0 {
+                        let Token::Digit(c, _) = tokens[j - 1] else {
+                            break;
+                        };
+                        j -= 1;
+                        tokens[j] = Token::Digit(c, Zone::Numerator);
+                    }
+                }
+                Token::Slash => tokens[i] = Token::Literal("/".into()),
+                Token::FixedDenominator(d) if !section.fraction => {
+                    tokens[i] = Token::Literal(d.to_string());
+                }
+                Token::Percent => section.percent += 1,
+                Token::Text => section.text = true,
+                _ => {}
+            }
+        }
+
+        // commas between integer digits group thousands; trailing commas scale
+        let mut i = 0;
+        while i < tokens.len() {
+            if tokens[i] == Token::Comma {
+                let before = tokens[..i].iter().any(|t| matches!(t, Token::Digit(..)));
+                let after = tokens[i + 1..]
+                    .iter()
+                    .any(|t| matches!(t, Token::Digit(..)));
+                let integer_after = tokens[i + 1..]
+                    .iter()
+                    .any(|t| matches!(t, Token::Digit(_, Zone::Integer)));
+                if before && integer_after {
+                    section.grouping = true;
+                    tokens.remove(i);
+                    continue;
+                }
+                if before && !after {
+                    section.scale += 1;
+                    tokens.remove(i);
+                    continue;
+                }
+                tokens[i] = Token::Literal(",".into());
+            }
+            i += 1;
+        }
+
+        section.tokens = tokens;
+        section
+    }
+
+    fn placeholders(&self, zone: Zone) -> Vec<char> {
+        self.tokens
+            .iter()
+            .filter_map(|token| match token {
+                Token::Digit(c, z) if *z == zone => Some(*c),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn fixed_denominator(&self) -> Option<u64> {
+        self.tokens.iter().find_map(|token| match token {
+            Token::FixedDenominator(d) => Some(*d),
+            _ => None,
+        })
+    }
+
+    /// Formats the absolute value of a number. A minus sign is added if
+    /// `negative` is set and the result is not zero.
+    fn format_number(&self, number: &BigDecimal, negative: bool) -> String {
+        let mut value = number.abs();
+        for _ in 0..self.percent {
+            value *= BigDecimal::from(100);
+        }
+        for _ in 0..self.scale {
+            value = value / BigDecimal::from(1000);
+        }
+
+        let mut filled: HashMap<Zone, Vec<String>> = HashMap::new();
+        let mut integer_overflow = String::new();
+        let mut exponent_negative = false;
+        let mut skip_fraction = false;
+        let mut is_zero = value.is_zero();
+
+        if self.exponent {
+            let x = value.to_f64().unwrap_or_default();
+            let integer = self.placeholders(Zone::Integer);
+
+            // formats like ##0.0E+0 use exponents that are multiples of the integer digits
+            let step = if integer.len() > 1 && integer[0] == '#' {
+                integer.len() as i32
+            } else {
+                1
+            };
+            let mut exponent = if x == 0.0 {
+                0
+            } else {
+                x.log10().floor() as i32
+            };
+            exponent -= exponent.rem_euclid(step);
+            let decimals = self.placeholders(Zone::Decimal);
+            let mut mantissa = format!("{:.*}", decimals.len(), x / 10f64.powi(exponent));
+            if step == 1 && mantissa.parse::<f64>().unwrap_or_default() >= 10.0 {
+                exponent += 1;
+                mantissa = format!("{:.*}", decimals.len(), x / 10f64.powi(exponent));
+            }
+            let (int, dec) = mantissa.split_once('.').unwrap_or((&mantissa, ""));
+            let int = if int == "0" { "" } else { int };
+            filled.insert(Zone::Integer, fill_right(int, &integer));
+            filled.insert(Zone::Decimal, fill_decimals(dec, &decimals));
+            filled.insert(
+                Zone::Exponent,
+                fill_right(
+                    &exponent.abs().to_string(),
+                    &self.placeholders(Zone::Exponent),
+                ),
+            );
+            exponent_negative = exponent < 0;
+        } else if self.fraction {
+            let x = value.to_f64().unwrap_or_default();
+            let integer = self.placeholders(Zone::Integer);
+            let (mut whole, fraction) = if integer.is_empty() {
+                (0.0, x)
+            } else {
+                (x.trunc(), x.fract())
+            };
+            let (mut numerator, denominator) = match self.fixed_denominator() {
+                Some(d) => ((fraction * d as f64).round() as u64, d),
+                None => {
+                    let digits = self.placeholders(Zone::Denominator).len().clamp(1, 4);
+                    best_fraction(fraction, 10u64.pow(digits as u32) - 1)
+                }
+            };
+            if !integer.is_empty() && numerator == denominator {
+                whole += 1.0;
+                numerator = 0;
+            }
+            skip_fraction = !integer.is_empty() && numerator == 0;
+            let whole = format!("{:.0}", whole);
+            let whole = if whole == "0" { "" } else { &whole };
+            filled.insert(Zone::Integer, fill_right(whole, &integer));
+            filled.insert(
+                Zone::Numerator,
+                fill_right(&numerator.to_string(), &self.placeholders(Zone::Numerator)),
+            );
+            filled.insert(
+                Zone::Denominator,
+                fill_left(
+                    &denominator.to_string(),
+                    &self.placeholders(Zone::Denominator),
+                ),
+            );
+        } else {
+            let decimals = self.placeholders(Zone::Decimal);
+            let rounded = value.with_scale_round(decimals.len() as i64, RoundingMode::HalfUp);
+            is_zero = rounded.is_zero();
+            let text = rounded.to_string();
+            let (int, dec) = text.split_once('.').unwrap_or((&text, ""));
+            let int = if int == "0" { "" } else { int };
+
+            // zero may be printed without its decimals
+            let dec = format!("{:0<width$}", dec, width = decimals.len());
+            let integer = self.placeholders(Zone::Integer);
+            if integer.is_empty() {
+                integer_overflow = int.to_string();
+            }
+            filled.insert(Zone::Integer, fill_right(int, &integer));
+            filled.insert(Zone::Decimal, fill_decimals(&dec, &decimals));
+        }
+
+        let integer_digits = filled.get(&Zone::Integer).map_or(0, |digits| {
+            digits
+                .iter()
+                .flat_map(|s| s.chars())
+                .filter(char::is_ascii_digit)
+                .count()
+        });
+        let mut digits_until_separator = match integer_digits % 3 {
+            0 => 3,
+            n => n,
+        };
+        let mut next: HashMap<Zone, usize> = HashMap::new();
+        let mut result = String::new();
+        for token in &self.tokens {
+            match token {
+                Token::Literal(s) => result.push_str(s),
+                Token::Percent => result.push('%'),
+                Token::DecimalPoint => {
+                    result.push_str(&integer_overflow);
+                    result.push('.');
+                }
+                Token::Exponent { plus } => {
+                    result.push('E');
+                    if exponent_negative {
+                        result.push('-');
+                    } else if *plus {
+                        result.push('+');
+                    }
+                }
+                Token::Slash => result.push('/'),
+                Token::FixedDenominator(d) => result.push_str(&d.to_string()),
+                Token::General => result.push_str(&number.abs().to_string()),
+                Token::Comma | Token::Text => {}
+                Token::Digit(_, zone) => {
+                    if *zone == Zone::Numerator && skip_fraction {
+                        break;
+                    }
+                    let index = next.entry(*zone).or_default();
+                    let digits = filled
+                        .get(zone)
+                        .and_then(|digits| digits.get(*index))
+                        .map_or("", |s| s.as_str());
+                    *index += 1;
+                    if *zone == Zone::Integer && self.grouping {
+                        for c in digits.chars() {
+                            if c.is_ascii_digit() {
+                                if digits_until_separator == 0 {
+                                    result.push(',');
+                                    digits_until_separator = 3;
+                                }
+                                digits_until_separator -= 1;
+                            }
+                            result.push(c);
+                        }
+                    } else {
+                        result.push_str(digits);
+                    }
+                }
+            }
+        }
+        if skip_fraction {
+            result = result.trim_end().to_string();
+            if result.is_empty() {
+                result = "0".to_string();
+            }
+        }
+        if negative && !is_zero {
+            result.insert(0, '-');
+        }
+        result
+    }
+
+    fn format_text(&self, text: &str) -> String {
+        self.tokens
+            .iter()
+            .map(|token| match token {
+                Token::Literal(s) => s.as_str(),
+                Token::Text => text,
+                _ => "",
+            })
+            .collect()
+    }
+}
+
+/// Fills right-aligned placeholders with digits. Extra digits go in the
+/// first placeholder.
+fn fill_right(digits: &str, placeholders: &[char]) -> Vec<String> {
+    let digits: Vec<char> = digits.chars().collect();
+    let (len, count) = (digits.len(), placeholders.len());
+    placeholders
+        .iter()
+        .enumerate()
+        .map(|(i, placeholder)| {
+            if i == 0 && len > count {
+                digits[..=len - count].iter().collect()
+            } else if i + len >= count {
+                digits[i + len - count].to_string()
+            } else {
+                pad(*placeholder)
+            }
+        })
+        .collect()
+}
+
+/// Fills left-aligned placeholders with digits. Extra digits go in the last
+/// placeholder.
+fn fill_left(digits: &str, placeholders: &[char]) -> Vec<String> {
+    let digits: Vec<char> = digits.chars().collect();
+    let (len, count) = (digits.len(), placeholders.len());
+    placeholders
+        .iter()
+        .enumerate()
+        .map(|(i, placeholder)| {
+            if i + 1 == count && len > count {
+                digits[i..].iter().collect()
+            } else if i < len {
+                digits[i].to_string()
+            } else {
+                pad(*placeholder)
+            }
+        })
+        .collect()
+}
+
+/// Fills decimal placeholders, where trailing zeros are only shown for `0`.
+fn fill_decimals(digits: &str, placeholders: &[char]) -> Vec<String> {
+    let significant = digits.trim_end_matches('0').len();
+    placeholders
+        .iter()
+        .zip(digits.chars())
+        .enumerate()
+        .map(|(i, (placeholder, digit))| {
+            if i < significant {
+                digit.to_string()
+            } else {
+                pad(*placeholder)
+            }
+        })
+        .collect()
+}
+
+/// What a placeholder shows when there is no digit for it.
+fn pad(placeholder: char) -> String {
+    match placeholder {
+        '0' => "0".into(),
+        '?' => " ".into(),
+        _ => String::new(),
+    }
+}
+
+/// Returns the closest fraction to x with a denominator of at most
+/// max_denominator.
+fn best_fraction(x: f64, max_denominator: u64) -> (u64, u64) {
+    let mut best = (x.round() as u64, 1);
+    let mut best_error = (x - x.round()).abs();
+    for denominator in 2..=max_denominator {
+        if best_error < 1e-9 {
+            break;
+        }
+        let numerator = (x * denominator as f64).round();
+        let error = (x - numerator / denominator as f64).abs();
+        if error < best_error - 1e-12 {
+            best = (numerator as u64, denominator);
+            best_error = error;
+        }
+    }
+    best
+}
+
+fn push_literal(tokens: &mut Vec<Token>, s: &str) {
+    match tokens.last_mut() {
+        Some(Token::Literal(literal)) => literal.push_str(s),
+        _ => tokens.push(Token::Literal(s.to_string())),
+    }
+}
+
+/// A parsed custom number format.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomNumberFormat {
+    sections: Vec<Section>,
+}
+
+impl CustomNumberFormat {
+    /// Parses a format code. Anything that is not understood is shown as-is.
+    pub fn parse(code: &str) -> Self {
+        let mut sections = vec![];
+        let mut tokens = vec![];
+        let mut color = None;
+        let mut chars = code.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                ';' => sections.push(Section::new(std::mem::take(&mut tokens), color.take())),
+                '"' => {
+                    let literal: String = chars.by_ref().take_while(|&c| c != '"').collect();
+                    push_literal(&mut tokens, &literal);
+                }
+                '\\' => {
+                    if let Some(c) = chars.next() {
+                        push_literal(&mut tokens, &c.to_string());
+                    }
+                }
+                // _x adds a space the width of x; *x repeats x to fill the cell
+                '_' => {
+                    chars.next();
+                    push_literal(&mut tokens, " ");
+                }
+                '*' => {
+                    chars.next();
+                }
+                '[' => {
+                    let bracket: String = chars.by_ref().take_while(|&c| c != ']').collect();
+                    let lower = bracket.to_ascii_lowercase();
+                    if COLORS.contains(&lower.as_str()) {
+                        color = Some(lower);
+                    } else if let Some(currency) = bracket.strip_prefix('$') {
+                        // [$€-407] is a currency symbol with a locale
+                        let symbol = currency.split('-').next().unwrap_or_default();
+                        push_literal(&mut tokens, symbol);
+                    }
+                    // conditions such as [>100] are not supported
+                }
+                '0' | '#' | '?' => tokens.push(Token::Digit(c, Zone::Integer)),
+                '.' => tokens.push(Token::DecimalPoint),
+                ',' => tokens.push(Token::Comma),
+                '%' => tokens.push(Token::Percent),
+                'E' | 'e' if matches!(chars.peek(), Some('+' | '-')) => {
+                    tokens.push(Token::Exponent {
+                        plus: chars.next() == Some('+'),
+                    });
+                }
+                '/' => tokens.push(Token::Slash),
+                '1'..='9' if tokens.last() == Some(&Token::Slash) => {
+                    let mut denominator = c.to_digit(10).unwrap_or_default() as u64;
+                    while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+                        denominator = denominator * 10 + digit as u64;
+                        chars.next();
+                    }
+                    tokens.push(Token::FixedDenominator(denominator));
+                }
+                '@' => tokens.push(Token::Text),
+                'G' | 'g' => {
+                    let rest: String = chars.clone().take(6).collect();
+                    if rest.eq_ignore_ascii_case("eneral") {
+                        chars.nth(5);
+                        tokens.push(Token::General);
+                    } else {
+                        push_literal(&mut tokens, &c.to_string());
+                    }
+                }
+                c => push_literal(&mut tokens, &c.to_string()),
+            }
+        }
+        sections.push(Section::new(tokens, color));
+        CustomNumberFormat { sections }
+    }
+
+    /// Returns the parsed format for a code, parsing it only the first time
+    /// it is used.
+    pub fn cached(code: &str) -> Arc<Self> {
+        let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(format) = cache.get(code) {
+            return format.clone();
+        }
+        if cache.len() >= MAX_CACHED_FORMATS {
+            cache.clear();
+        }
+        let format = Arc::new(Self::parse(code));
+        cache.insert(code.to_string(), format.clone());
+        format
+    }
+
+    /// Returns the section used for a number and whether it needs a minus
+    /// sign.
+    fn section(&self, number: &BigDecimal) -> Option<(&Section, bool)> {
+        let numeric: Vec<&Section> = self.sections.iter().filter(|s| !s.text).take(3).collect();
+        let negative = number < &BigDecimal::zero();
+        match numeric.len() {
+            0 => None,
+            1 => Some((numeric[0], negative)),
+            _ if negative => Some((numeric[1], false)),
+            3 if number.is_zero() => Some((numeric[2], false)),
+            _ => Some((numeric[0], false)),
+        }
+    }
+
+    /// Formats a number.
+    pub fn format_number(&self, number: &BigDecimal) -> String {
+        match self.section(number) {
+            Some((section, negative)) => section.format_number(number, negative),
+            None => number.to_string(),
+        }
+    }
+
+    /// Formats text using the text section (the fourth section, or a section
+    /// with `@`), if there is one.
+    pub fn format_text(&self, text: &str) -> Option<String> {
+        let section = match self.sections.get(3) {
+            Some(section) => Some(section),
+            None => self.sections.iter().find(|section| section.text),
+        };
+        section.map(|section| section.format_text(text))
+    }
+
+    /// Returns the color of the section used for a number, e.g. `red`.
+    pub fn color(&self, number: &BigDecimal) -> Option<&str> {
+        self.section(number)?.0.color.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn format(code: &str, number: &str) -> String {
+        CustomNumberFormat::parse(code).format_number(&BigDecimal::from_str(number).unwrap())
+    }
+
+    #[test]
+    fn formats_digits_and_separators() {
+        assert_eq!(format("#,##0.00", "1234.5"), "1,234.50");
+        assert_eq!(format("#,##0.00", "-1234567.891"), "-1,234,567.89");
+        assert_eq!(format("#,##0", "0"), "0");
+        assert_eq!(format("#.##", "0.5"), ".5");
+        assert_eq!(format("0.00", "-0.001"), "0.00");
+        assert_eq!(format("00000", "42"), "00042");
+        assert_eq!(format("000-00-0000", "123456789"), "123-45-6789");
+        assert_eq!(format("#,##0,", "12345678"), "12,346");
+        assert_eq!(format("0.0,,\"M\"", "12345678"), "12.3M");
+        assert_eq!(format("???.??", "1.5"), "  1.5 ");
+        assert_eq!(format("General", "-1.25"), "-1.25");
+    }
+
+    #[test]
+    fn formats_percentages_and_exponents() {
+        assert_eq!(format("0.0%", "0.256"), "25.6%");
+        assert_eq!(format("0%", "1"), "100%");
+        assert_eq!(format("0.00E+00", "12345"), "1.23E+04");
+        assert_eq!(format("0.00E+00", "0.00012"), "1.20E-04");
+        assert_eq!(format("0.00E+00", "99999"), "1.00E+05");
+        assert_eq!(format("0.0E-0", "1500"), "1.5E3");
+        assert_eq!(format("##0.0E+0", "12345"), "12.3E+3");
+    }
+
+    #[test]
+    fn formats_fractions() {
+        assert_eq!(format("# ?/?", "1.5"), "1 1/2");
+        assert_eq!(format("# ?/?", "3"), "3");
+        assert_eq!(format("?/?", "0.75"), "3/4");
+        assert_eq!(format("?/?", "2.5"), "5/2");
+        assert_eq!(format("# ??/??", "3.14159"), "3 14/99");
+        assert_eq!(format("# ?/8", "2.375"), "2 3/8");
+        assert_eq!(format("# ?/?", "-1.25"), "-1 1/4");
+    }
+
+    #[test]
+    fn formats_sections_and_colors() {
+        let currency = CustomNumberFormat::parse("\"$\"#,##0;(\"$\"#,##0)");
+        assert_eq!(currency.format_number(&BigDecimal::from(1234)), "$1,234");
+        assert_eq!(currency.format_number(&BigDecimal::from(-1234)), "($1,234)");
+
+        let red = CustomNumberFormat::parse("0.00;[Red]-0.00;\"zero\"");
+        assert_eq!(red.format_number(&BigDecimal::from(-5)), "-5.00");
+        assert_eq!(red.color(&BigDecimal::from(-5)), Some("red"));
+        assert_eq!(red.color(&BigDecimal::from(5)), None);
+        assert_eq!(red.format_number(&BigDecimal::zero()), "zero");
+
+        assert_eq!(format("[Red]-0.00", "1"), "-1.00");
+        assert_eq!(format("[$€-407] #,##0.00", "1000"), "€ 1,000.00");
+
+        let text = CustomNumberFormat::parse("0;-0;0;\"Name: \"@");
+        assert_eq!(text.format_text("Bob"), Some("Name: Bob".into()));
+        assert_eq!(CustomNumberFormat::parse("0.00").format_text("Bob"), None);
+
+        assert_eq!(
+            CustomNumberFormat::cached("0.0"),
+            CustomNumberFormat::cached("0.0")
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::ops::Range;
 use wasm_bindgen::prelude::wasm_bindgen;
 
@@ -13,6 +13,10 @@ pub struct Offsets {
     default: f64,
     #[serde(with = "crate::util::btreemap_serde")]
     sizes: BTreeMap<i64, f64>,
+
+    /// Hidden columns/rows have a size of 0 but keep their stored size.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    hidden: BTreeSet<i64>,
 }
 impl Offsets {
     /// Constructs an empty `Offsets` structure.
@@ -20,6 +24,7 @@ impl Offsets {
         Offsets {
             default,
             sizes: BTreeMap::new(),
+            hidden: BTreeSet::new(),
         }
     }
 
@@ -28,6 +33,7 @@ impl Offsets {
         Offsets {
             default,
             sizes: iter.into_iter().collect(),
+            hidden: BTreeSet::new(),
         }
     }
 
@@ -52,10 +58,36 @@ impl Offsets {
         }
     }
 
-    /// Returns the width/height of a column/row.
+    /// Returns the width/height of a column/row. Hidden columns/rows have a
+    /// size of 0.
     pub fn get_size(&self, index: i64) -> f64 {
+        if self.hidden.contains(&index) {
+            return 0.0;
+        }
+        self.get_unhidden_size(index)
+    }
+    /// Returns the width/height of a column/row, ignoring whether it is
+    /// hidden.
+    pub fn get_unhidden_size(&self, index: i64) -> f64 {
         *self.sizes.get(&index).unwrap_or(&self.default)
     }
+
+    /// Returns whether a column/row is hidden.
+    pub fn is_hidden(&self, index: i64) -> bool {
+        self.hidden.contains(&index)
+    }
+    /// Hides or unhides a column/row and returns whether it was hidden.
+    pub fn set_hidden(&mut self, index: i64, hidden: bool) -> bool {
+        if hidden {
+            !self.hidden.insert(index)
+        } else {
+            self.hidden.remove(&index)
+        }
+    }
+    /// Iterates over all hidden columns/rows.
+    pub fn iter_hidden(&self) -> impl '_ + Iterator<Item = i64> {
+        self.hidden.iter().copied()
+    }
     /// Sets the width/height of a column/row.
     pub fn set_size(&mut self, index: i64, value: f64) -> f64 {
         if value == self.default {
@@ -73,6 +105,12 @@ impl Offsets {
     /// Iterates over the pixel positions of a range of columns/rows.
     pub fn iter_offsets(&self, index_range: Range<i64>) -> impl '_ + Iterator<Item = f64> {
         let start = index_range.start;
+        let hidden_size = |range: Range<i64>| {
+            self.hidden
+                .range(range)
+                .map(|&index| self.get_unhidden_size(index))
+                .sum::<f64>()
+        };
         let mut current_position = if start < 0 {
             self.default * start as f64
                 - self
@@ -80,6 +118,7 @@ impl Offsets {
                     .range(start..0)
                     .map(|(_k, v)| v - self.default)
                     .sum::<f64>()
+                + hidden_size(start..0)
         } else {
             self.default * start as f64
                 + self
@@ -87,6 +126,7 @@ impl Offsets {
                     .range(0..start)
                     .map(|(_k, v)| v - self.default)
                     .sum::<f64>()
+                - hidden_size(0..start)
         };
         index_range.map(move |index| {
             let ret = current_position;
@@ -133,19 +173,21 @@ impl Offsets {
     pub fn changes(&self, offsets: &Offsets) -> Vec<(i64, f64)> {
         let mut changes = Vec::new();
 
-        // find all changes in the old offset structure compared to the new one
-        for (k, v) in &self.sizes {
-            if let Some(old_v) = offsets.sizes.get(k) {
-                if *v != *old_v {
-                    changes.push((*k, *old_v - *v));
-                }
-            } else if *v != offsets.default {
-                changes.push((*k, self.default - *v));
-            }
-        }
-        for (k, v) in &offsets.sizes {
-            if !self.sizes.contains_key(k) && *v != self.default {
-                changes.push((*k, *v - self.default));
+        // find all changes in the old offset structure compared to the new one,
+        // then the new sizes, then any columns/rows that were hidden or unhidden
+        let indexes = self
+            .sizes
+            .keys()
+            .chain(offsets.sizes.keys().filter(|k| !self.sizes.contains_key(k)))
+            .chain(
+                self.hidden
+                    .symmetric_difference(&offsets.hidden)
+                    .filter(|k| !self.sizes.contains_key(k) && !offsets.sizes.contains_key(k)),
+            );
+        for &k in indexes {
+            let delta = offsets.get_size(k) - self.get_size(k);
+            if delta != 0.0 {
+                changes.push((k, delta));
             }
         }
         changes
@@ -256,6 +298,32 @@ mod tests {
         assert_eq!(offsets.find_offset(-35.0), (-3, -40.0));
     }
 
+    #[test]
+    fn test_hidden() {
+        let mut offsets = Offsets::new(10.0);
+        offsets.set_size(1, 20.0);
+        assert!(!offsets.set_hidden(1, true));
+        assert!(offsets.set_hidden(1, true));
+        offsets.set_hidden(-2, true);
+
+        assert!(offsets.is_hidden(1));
+        assert_eq!(offsets.get_size(1), 0.0);
+        assert_eq!(offsets.get_unhidden_size(1), 20.0);
+        assert_eq!(
+            offsets.iter_offsets(-3..4).collect_vec(),
+            vec![-20.0, -10.0, -10.0, 0.0, 10.0, 10.0, 20.0],
+        );
+
+        // hidden entries are skipped when finding an offset
+        assert_eq!(offsets.find_offset(10.0), (2, 10.0));
+        assert_eq!(offsets.find_offset(-15.0), (-3, -20.0));
+
+        let mut unhidden = offsets.clone();
+        assert!(unhidden.set_hidden(1, false));
+        assert_eq!(unhidden.iter_hidden().collect_vec(), vec![-2]);
+        assert_eq!(offsets.changes(&unhidden), vec![(1, 20.0)]);
+    }
+
     #[test]
     fn test_changes() {
         let mut first = Offsets::new(10.0);
@@ -8,9 +8,14 @@ impl Grid {
     pub fn search(&self, query: &String, options: SearchOptions) -> Vec<SheetPos> {
         let mut result = Vec::new();
         if let Some(sheet_id) = options
-            .sheet_id
-            .as_ref()
-            .map(|id| SheetId::from_str(id).unwrap_or_default())
+            .selection
+            .map(|selection| selection.sheet_id)
+            .or_else(|| {
+                options
+                    .sheet_id
+                    .as_ref()
+                    .map(|id| SheetId::from_str(id).unwrap_or_default())
+            })
         {
             if let Some(sheet) = self.try_sheet(sheet_id) {
                 let sheet_result = sheet.search(query, &options);
@@ -28,7 +33,13 @@ impl Grid {
 
 #[cfg(test)]
 mod test {
-    use crate::{CellValue, Pos};
+    use crate::{
+        grid::{
+            sheet::comments::{Comment, CommentThread},
+            CommentId,
+        },
+        CellValue, Instant, Pos,
+    };
 
     use super::*;
 
@@ -132,4 +143,43 @@ mod test {
         );
         assert_eq!(result.len(), 0);
     }
+
+    #[test]
+    fn search_comments() {
+        let mut grid = Grid::new();
+        let sheet_id = grid.sheet_ids()[0];
+        let sheet = grid.try_sheet_mut(sheet_id).unwrap();
+        sheet.set_cell_value(Pos { x: 0, y: 0 }, "revenue".to_string());
+        let thread = |text: &str| CommentThread {
+            comments: vec![Comment {
+                id: CommentId::new(),
+                author: "Ada".into(),
+                timestamp: Instant::new(0.0),
+                text: text.into(),
+            }],
+            resolved: false,
+        };
+        sheet.set_comment_thread(
+            Pos { x: 0, y: 0 },
+            Some(thread("Revenue assumptions from Q3")),
+        );
+        sheet.set_comment_thread(Pos { x: 2, y: 3 }, Some(thread("Q3 growth")));
+
+        let result = grid.search(&"q3".to_string(), SearchOptions::default());
+        assert!(result.is_empty());
+
+        let options = SearchOptions {
+            search_comments: Some(true),
+            ..SearchOptions::default()
+        };
+        let result = grid.search(&"q3".to_string(), options.clone());
+        assert_eq!(
+            result,
+            vec![SheetPos::new(sheet_id, 0, 0), SheetPos::new(sheet_id, 2, 3)]
+        );
+
+        // a cell whose value and comment both match is found once
+        let result = grid.search(&"revenue".to_string(), options);
+        assert_eq!(result, vec![SheetPos::new(sheet_id, 0, 0)]);
+    }
 }
@@ -12,18 +12,24 @@ use super::column::Column;
 use super::formatting::{BoolSummary, CellFmtAttr};
 use super::ids::SheetId;
 use super::js_types::{CellFormatSummary, FormattingSummary};
-use super::{CodeRun, NumericFormat, NumericFormatKind};
+use super::{CodeRun, Locale, NumericFormat, NumericFormatKind};
 use crate::grid::{borders, SheetBorders};
 use crate::{Array, CellValue, IsBlank, Pos, Rect};
 
 pub mod bounds;
 pub mod cell_array;
 pub mod cell_values;
+pub mod charts;
 pub mod code;
+pub mod comments;
+pub mod conditional_formats;
 pub mod formatting;
+pub mod merged_cells;
+pub mod protection;
 pub mod rendering;
 pub mod search;
 pub mod sheet_offsets;
+pub mod validations;
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Sheet {
@@ -43,6 +49,36 @@ pub struct Sheet {
 
     pub(super) data_bounds: GridBounds,
     pub(super) format_bounds: GridBounds,
+
+    #[serde(default)]
+    pub merged_cells: Vec<Rect>,
+
+    /// Number of rows (starting at row 0) pinned while scrolling.
+    #[serde(default)]
+    pub frozen_rows: u32,
+
+    /// Number of columns (starting at column 0) pinned while scrolling.
+    #[serde(default)]
+    pub frozen_columns: u32,
+
+    #[serde(default)]
+    pub validations: Vec<validations::Validation>,
+
+    #[serde(default)]
+    pub conditional_formats: Vec<conditional_formats::ConditionalFormat>,
+
+    #[serde(default, with = "crate::util::btreemap_serde")]
+    pub comments: BTreeMap<Pos, comments::CommentThread>,
+
+    #[serde(default)]
+    pub protection: protection::SheetProtection,
+
+    #[serde(default, with = "crate::util::btreemap_serde")]
+    pub charts: BTreeMap<Pos, charts::Chart>,
+
+    /// The workbook's locale, kept in sync by [`super::Grid`].
+    #[serde(skip)]
+    pub locale: Locale,
 }
 impl Sheet {
     /// Constructs a new empty sheet.
@@ -61,6 +97,19 @@ impl Sheet {
             format_bounds: GridBounds::Empty,
 
             offsets: SheetOffsets::default(),
+
+            merged_cells: vec![],
+
+            frozen_rows: 0,
+            frozen_columns: 0,
+
+            validations: vec![],
+            conditional_formats: vec![],
+            comments: BTreeMap::new(),
+            protection: protection::SheetProtection::default(),
+            charts: BTreeMap::new(),
+
+            locale: Locale::default(),
         }
     }
 
@@ -179,6 +228,37 @@ impl Sheet {
         }
     }
 
+    /// Returns the display_value at a Pos as a String, applying any numeric formatting (i.e., what
+    /// the user sees in the cell). Returns `None` if there is no value at the Pos.
+    pub fn formatted_display_value(&self, pos: Pos) -> Option<String> {
+        let value = self.display_value(pos)?;
+        Some(self.format_value(pos, &value))
+    }
+
+    /// Formats a value as it would be displayed at the Pos.
+    pub fn format_value(&self, pos: Pos, value: &CellValue) -> String {
+        match (value, self.get_column(pos.x)) {
+            (CellValue::Number(_), Some(column)) => {
+                let numeric_format = column.numeric_format.get(pos.y);
+                let is_percentage = numeric_format
+                    .as_ref()
+                    .is_some_and(|format| format.kind == NumericFormatKind::Percentage);
+                let numeric_decimals = self.decimal_places(pos, is_percentage);
+                let numeric_commas = column.numeric_commas.get(pos.y);
+                let currency_symbol = rendering::currency_symbol(&numeric_format);
+                self.locale.localize_number(
+                    &value.to_display(numeric_format, numeric_decimals, numeric_commas),
+                    currency_symbol.as_deref(),
+                )
+            }
+            (CellValue::Instant(_) | CellValue::Duration(_), Some(column)) => {
+                value.to_display(column.numeric_format.get(pos.y), None, None)
+            }
+            (CellValue::Number(n), None) => self.locale.localize_number(&n.to_string(), None),
+            _ => value.to_string(),
+        }
+    }
+
     /// Returns the cell_value at the Pos in column.values. This does not check or return results within code_runs.
     pub fn cell_value(&self, pos: Pos) -> Option<CellValue> {
         let column = self.get_column(pos.x)?;
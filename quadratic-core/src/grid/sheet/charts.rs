@@ -0,0 +1,688 @@
+use std::f64::consts::PI;
+use std::fmt::Write;
+
+use bigdecimal::ToPrimitive;
+use serde::{Deserialize, Serialize};
+
+use super::Sheet;
+use crate::{
+    grid::{js_types::JsHtmlOutput, Grid, GridBounds},
+    util::{escape_xml, round},
+    CellValue, Pos, Rect, SheetRect,
+};
+
+pub const DEFAULT_CHART_WIDTH: u32 = 480;
+pub const DEFAULT_CHART_HEIGHT: u32 = 300;
+
+/// Colors of the series (or pie slices), in order.
+const PALETTE: [&str; 8] = [
+    "#2563eb", "#f97316", "#16a34a", "#dc2626", "#9333ea", "#0891b2", "#ca8a04", "#db2777",
+];
+const GRID_COLOR: &str = "#e5e7eb";
+const AXIS_COLOR: &str = "#9ca3af";
+const LABEL_COLOR: &str = "#6b7280";
+
+/// Approximate width of a character of a label, in pixels.
+const CHAR_WIDTH: f64 = 7.0;
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub enum ChartType {
+    Line,
+    Bar,
+    Scatter,
+    Pie,
+    Area,
+}
+
+/// A series of values plotted by a [`Chart`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub struct ChartSeries {
+    /// Name shown in the legend. Defaults to `Series 1`, `Series 2`, etc.
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// Cells holding the values. Cells that are not numbers are skipped.
+    pub values: SheetRect,
+}
+
+/// A chart anchored to a cell. Charts are drawn by core as SVG from the
+/// current values of their ranges.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub struct Chart {
+    pub chart_type: ChartType,
+
+    #[serde(default)]
+    pub title: Option<String>,
+
+    /// Cells holding the category labels (or, for a scatter chart, the x
+    /// values). Defaults to 1, 2, 3, etc.
+    #[serde(default)]
+    pub categories: Option<SheetRect>,
+
+    pub series: Vec<ChartSeries>,
+
+    /// Size of the chart in pixels.
+    pub width: u32,
+    pub height: u32,
+}
+
+/// A cell of a chart's range.
+struct ChartCell {
+    number: Option<f64>,
+    label: String,
+}
+
+/// Returns the cells of a range in row-major order, ignoring the rows and
+/// columns past the data of its sheet.
+fn range_cells(grid: &Grid, range: &SheetRect) -> Vec<ChartCell> {
+    let Some(sheet) = grid.try_sheet(range.sheet_id) else {
+        return vec![];
+    };
+    let GridBounds::NonEmpty(bounds) = sheet.bounds(true) else {
+        return vec![];
+    };
+    let max = Pos {
+        x: range.max.x.min(bounds.max.x),
+        y: range.max.y.min(bounds.max.y),
+    };
+    if max.x < range.min.x || max.y < range.min.y {
+        return vec![];
+    }
+    Rect::new_span(range.min, max)
+        .iter()
+        .map(|pos| {
+            let value = sheet.display_value(pos);
+            ChartCell {
+                number: match &value {
+                    Some(CellValue::Number(n)) => n.to_f64(),
+                    _ => None,
+                },
+                label: value
+                    .map(|value| sheet.format_value(pos, &value))
+                    .unwrap_or_default(),
+            }
+        })
+        .collect()
+}
+
+/// The values of a chart's ranges.
+struct ChartData {
+    categories: Vec<ChartCell>,
+    series: Vec<(String, Vec<Option<f64>>)>,
+
+    /// Number of values in the longest series.
+    len: usize,
+}
+
+impl ChartData {
+    fn new(chart: &Chart, grid: &Grid) -> Self {
+        let categories = chart
+            .categories
+            .as_ref()
+            .map_or_else(Vec::new, |range| range_cells(grid, range));
+        let series: Vec<_> = chart
+            .series
+            .iter()
+            .enumerate()
+            .map(|(i, series)| {
+                let name = series
+                    .name
+                    .clone()
+                    .filter(|name| !name.is_empty())
+                    .unwrap_or_else(|| format!("Series {}", i + 1));
+                let values: Vec<Option<f64>> = range_cells(grid, &series.values)
+                    .into_iter()
+                    .map(|cell| cell.number)
+                    .collect();
+                (name, values)
+            })
+            .collect();
+        let len = series
+            .iter()
+            .map(|(_, values)| values.len())
+            .max()
+            .unwrap_or(0);
+        ChartData {
+            categories,
+            series,
+            len,
+        }
+    }
+
+    fn category_label(&self, index: usize) -> String {
+        self.categories
+            .get(index)
+            .map(|cell| cell.label.clone())
+            .filter(|label| !label.is_empty())
+            .unwrap_or_else(|| (index + 1).to_string())
+    }
+
+    /// Returns the x value of a scatter chart's point.
+    fn x_value(&self, index: usize) -> Option<f64> {
+        if self.categories.is_empty() {
+            Some((index + 1) as f64)
+        } else {
+            self.categories.get(index)?.number
+        }
+    }
+
+    fn numbers(&self) -> impl Iterator<Item = f64> + '_ {
+        self.series
+            .iter()
+            .flat_map(|(_, values)| values.iter().flatten().copied())
+    }
+}
+
+/// Evenly spaced round numbers covering a range of values.
+#[derive(Debug, PartialEq)]
+struct Axis {
+    min: f64,
+    max: f64,
+    step: f64,
+}
+
+impl Axis {
+    fn new(values: impl Iterator<Item = f64>, include_zero: bool) -> Self {
+        let (mut min, mut max) = values
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), n| {
+                (min.min(n), max.max(n))
+            });
+        if include_zero {
+            min = min.min(0.0);
+            max = max.max(0.0);
+        }
+        if !min.is_finite() || !max.is_finite() {
+            (min, max) = (0.0, 1.0);
+        } else if min == max {
+            (min, max) = (min - 1.0, max + 1.0);
+        }
+        let raw_step = (max - min) / 5.0;
+        let magnitude = 10f64.powf(raw_step.log10().floor());
+        let step = [1.0, 2.0, 5.0, 10.0]
+            .into_iter()
+            .map(|multiple| multiple * magnitude)
+            .find(|&step| step >= raw_step)
+            .unwrap_or(10.0 * magnitude);
+        Axis {
+            min: (min / step).floor() * step,
+            max: (max / step).ceil() * step,
+            step,
+        }
+    }
+
+    fn ticks(&self) -> impl Iterator<Item = f64> + '_ {
+        let count = ((self.max - self.min) / self.step).round() as usize;
+        (0..=count).map(|i| self.min + i as f64 * self.step)
+    }
+
+    /// Returns how far along the axis the value is, from 0 to 1.
+    fn fraction(&self, value: f64) -> f64 {
+        (value - self.min) / (self.max - self.min)
+    }
+}
+
+/// The area of the chart the values are plotted in.
+struct PlotArea {
+    left: f64,
+    top: f64,
+    right: f64,
+    bottom: f64,
+}
+
+impl PlotArea {
+    fn width(&self) -> f64 {
+        self.right - self.left
+    }
+
+    fn x(&self, fraction: f64) -> f64 {
+        self.left + fraction * self.width()
+    }
+
+    fn y(&self, fraction: f64) -> f64 {
+        self.bottom - fraction * (self.bottom - self.top)
+    }
+}
+
+fn format_number(n: f64) -> String {
+    // adding 0 avoids printing -0
+    format!("{}", round(n, 9) + 0.0)
+}
+
+fn color(index: usize) -> &'static str {
+    PALETTE[index % PALETTE.len()]
+}
+
+impl Chart {
+    pub fn new(
+        chart_type: ChartType,
+        categories: Option<SheetRect>,
+        series: Vec<ChartSeries>,
+    ) -> Self {
+        Chart {
+            chart_type,
+            title: None,
+            categories,
+            series,
+            width: DEFAULT_CHART_WIDTH,
+            height: DEFAULT_CHART_HEIGHT,
+        }
+    }
+
+    /// Returns the ranges the chart reads.
+    pub fn ranges(&self) -> impl Iterator<Item = &SheetRect> {
+        self.categories
+            .iter()
+            .chain(self.series.iter().map(|series| &series.values))
+    }
+
+    /// Whether the chart reads any of the cells in the sheet_rect.
+    pub fn depends_on(&self, sheet_rect: &SheetRect) -> bool {
+        self.ranges().any(|range| range.intersects(*sheet_rect))
+    }
+
+    /// Draws the chart as an SVG image from the current values of its
+    /// ranges.
+    pub fn to_svg(&self, grid: &Grid) -> String {
+        let data = ChartData::new(self, grid);
+        let width = self.width.max(100) as f64;
+        let height = self.height.max(100) as f64;
+        let mut svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="sans-serif" font-size="12"><rect width="{width}" height="{height}" fill="white"/>"#
+        );
+
+        let mut top = 16.0;
+        if let Some(title) = self.title.as_ref().filter(|title| !title.is_empty()) {
+            let _ = write!(
+                svg,
+                r#"<text x="{:.1}" y="24" text-anchor="middle" font-size="16" font-weight="bold">{}</text>"#,
+                width / 2.0,
+                escape_xml(title)
+            );
+            top = 40.0;
+        }
+
+        let legend: Vec<String> = match self.chart_type {
+            ChartType::Pie => pie_slices(&data)
+                .into_iter()
+                .map(|(index, _)| data.category_label(index))
+                .collect(),
+            _ => data.series.iter().map(|(name, _)| name.clone()).collect(),
+        };
+        let mut bottom = height - 12.0;
+        if legend.len() > 1 || (self.chart_type == ChartType::Pie && !legend.is_empty()) {
+            draw_legend(&mut svg, &legend, width, height - 12.0);
+            bottom -= 24.0;
+        }
+
+        match self.chart_type {
+            ChartType::Pie => {
+                let plot = PlotArea {
+                    left: 12.0,
+                    top,
+                    right: width - 12.0,
+                    bottom,
+                };
+                draw_pie(&mut svg, &data, &plot);
+            }
+            chart_type => {
+                let axis = Axis::new(data.numbers(), chart_type != ChartType::Line);
+                let label_chars = axis
+                    .ticks()
+                    .map(|tick| format_number(tick).len())
+                    .max()
+                    .unwrap_or(1);
+                let plot = PlotArea {
+                    left: 12.0 + label_chars as f64 * CHAR_WIDTH,
+                    top,
+                    right: width - 16.0,
+                    bottom: bottom - 20.0,
+                };
+                draw_value_axis(&mut svg, &plot, &axis);
+                if chart_type == ChartType::Scatter {
+                    draw_scatter(&mut svg, &data, &plot, &axis);
+                } else {
+                    draw_categories(&mut svg, chart_type, &data, &plot, &axis);
+                }
+            }
+        }
+
+        svg.push_str("</svg>");
+        svg
+    }
+}
+
+fn draw_legend(svg: &mut String, labels: &[String], width: f64, y: f64) {
+    let mut x = 12.0;
+    for (index, label) in labels.iter().enumerate() {
+        let item_width = 18.0 + label.chars().count() as f64 * CHAR_WIDTH;
+        if x + item_width > width && index > 0 {
+            break;
+        }
+        let _ = write!(
+            svg,
+            r#"<rect x="{x:.1}" y="{:.1}" width="10" height="10" fill="{}"/><text x="{:.1}" y="{y:.1}">{}</text>"#,
+            y - 9.0,
+            color(index),
+            x + 14.0,
+            escape_xml(label)
+        );
+        x += item_width + 12.0;
+    }
+}
+
+/// Draws the horizontal grid lines and their labels.
+fn draw_value_axis(svg: &mut String, plot: &PlotArea, axis: &Axis) {
+    for tick in axis.ticks() {
+        let y = plot.y(axis.fraction(tick));
+        let _ = write!(
+            svg,
+            r#"<line x1="{:.1}" y1="{y:.1}" x2="{:.1}" y2="{y:.1}" stroke="{GRID_COLOR}"/><text x="{:.1}" y="{:.1}" text-anchor="end" fill="{LABEL_COLOR}">{}</text>"#,
+            plot.left,
+            plot.right,
+            plot.left - 6.0,
+            y + 4.0,
+            format_number(tick)
+        );
+    }
+}
+
+/// Draws a line, bar or area chart, with the values of each category
+/// spaced evenly along the x axis.
+fn draw_categories(
+    svg: &mut String,
+    chart_type: ChartType,
+    data: &ChartData,
+    plot: &PlotArea,
+    axis: &Axis,
+) {
+    let count = data.len.max(1);
+    let slot = plot.width() / count as f64;
+    let center = |index: usize| plot.left + (index as f64 + 0.5) * slot;
+    let baseline = plot.y(axis.fraction(0f64.clamp(axis.min, axis.max)));
+
+    // only label as many categories as fit
+    let label_every = (count as f64 * 60.0 / plot.width()).ceil().max(1.0) as usize;
+    for index in (0..data.len).step_by(label_every) {
+        let _ = write!(
+            svg,
+            r#"<text x="{:.1}" y="{:.1}" text-anchor="middle" fill="{LABEL_COLOR}">{}</text>"#,
+            center(index),
+            plot.bottom + 16.0,
+            escape_xml(&data.category_label(index))
+        );
+    }
+
+    let bar_width = slot * 0.8 / data.series.len().max(1) as f64;
+    for (series_index, (_, values)) in data.series.iter().enumerate() {
+        let color = color(series_index);
+        let points: Vec<(f64, f64)> = values
+            .iter()
+            .enumerate()
+            .filter_map(|(index, value)| Some((index, (*value)?)))
+            .map(|(index, value)| (index as f64, plot.y(axis.fraction(value))))
+            .collect();
+        match chart_type {
+            ChartType::Bar => {
+                for &(index, y) in &points {
+                    let x = plot.left + index * slot + slot * 0.1 + series_index as f64 * bar_width;
+                    let _ = write!(
+                        svg,
+                        r#"<rect x="{x:.1}" y="{:.1}" width="{bar_width:.1}" height="{:.1}" fill="{color}"/>"#,
+                        y.min(baseline),
+                        (baseline - y).abs()
+                    );
+                }
+            }
+            _ => {
+                let mut line = String::new();
+                let mut previous = None;
+                for &(index, y) in &points {
+                    let command = if previous == Some(index - 1.0) {
+                        'L'
+                    } else {
+                        'M'
+                    };
+                    let _ = write!(line, "{command}{:.1} {y:.1}", center(index as usize));
+                    previous = Some(index);
+                }
+                if line.is_empty() {
+                    continue;
+                }
+                if let (ChartType::Area, Some(first), Some(last)) =
+                    (chart_type, points.first(), points.last())
+                {
+                    let mut area = format!("M{:.1} {baseline:.1}", center(first.0 as usize));
+                    for &(index, y) in &points {
+                        let _ = write!(area, "L{:.1} {y:.1}", center(index as usize));
+                    }
+                    let _ = write!(area, "L{:.1} {baseline:.1}Z", center(last.0 as usize));
+                    let _ = write!(
+                        svg,
+                        r#"<path d="{area}" fill="{color}" fill-opacity="0.3"/>"#
+                    );
+                }
+                let _ = write!(
+                    svg,
+                    r#"<path d="{line}" fill="none" stroke="{color}" stroke-width="2"/>"#
+                );
+            }
+        }
+    }
+
+    let _ = write!(
+        svg,
+        r#"<line x1="{:.1}" y1="{baseline:.1}" x2="{:.1}" y2="{baseline:.1}" stroke="{AXIS_COLOR}"/>"#,
+        plot.left, plot.right
+    );
+}
+
+/// Draws a scatter chart, using the categories as the x values.
+fn draw_scatter(svg: &mut String, data: &ChartData, plot: &PlotArea, y_axis: &Axis) {
+    let x_axis = Axis::new((0..data.len).filter_map(|index| data.x_value(index)), false);
+    for tick in x_axis.ticks() {
+        let _ = write!(
+            svg,
+            r#"<text x="{:.1}" y="{:.1}" text-anchor="middle" fill="{LABEL_COLOR}">{}</text>"#,
+            plot.x(x_axis.fraction(tick)),
+            plot.bottom + 16.0,
+            format_number(tick)
+        );
+    }
+    for (series_index, (_, values)) in data.series.iter().enumerate() {
+        for (index, value) in values.iter().enumerate() {
+            let (Some(x), Some(y)) = (data.x_value(index), value) else {
+                continue;
+            };
+            let _ = write!(
+                svg,
+                r#"<circle cx="{:.1}" cy="{:.1}" r="3.5" fill="{}"/>"#,
+                plot.x(x_axis.fraction(x)),
+                plot.y(y_axis.fraction(*y)),
+                color(series_index)
+            );
+        }
+    }
+    let _ = write!(
+        svg,
+        r#"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="{AXIS_COLOR}"/>"#,
+        plot.left, plot.bottom, plot.right, plot.bottom
+    );
+}
+
+/// Returns the index and value of the positive values of the first series,
+/// which are the slices of a pie chart.
+fn pie_slices(data: &ChartData) -> Vec<(usize, f64)> {
+    data.series.first().map_or_else(Vec::new, |(_, values)| {
+        values
+            .iter()
+            .enumerate()
+            .filter_map(|(index, value)| Some((index, value.filter(|value| *value > 0.0)?)))
+            .collect()
+    })
+}
+
+fn draw_pie(svg: &mut String, data: &ChartData, plot: &PlotArea) {
+    let slices = pie_slices(data);
+    let total: f64 = slices.iter().map(|(_, value)| value).sum();
+    let cx = (plot.left + plot.right) / 2.0;
+    let cy = (plot.top + plot.bottom) / 2.0;
+    let r = ((plot.right - plot.left).min(plot.bottom - plot.top) / 2.0 - 4.0).max(1.0);
+    let point = |angle: f64| (cx + r * angle.cos(), cy + r * angle.sin());
+
+    let mut angle = -PI / 2.0;
+    for (slice_index, (_, value)) in slices.iter().enumerate() {
+        let fraction = value / total;
+        let color = color(slice_index);
+        if fraction > 0.9999 {
+            let _ = write!(
+                svg,
+                r#"<circle cx="{cx:.1}" cy="{cy:.1}" r="{r:.1}" fill="{color}"/>"#
+            );
+            continue;
+        }
+        let (x1, y1) = point(angle);
+        angle += fraction * 2.0 * PI;
+        let (x2, y2) = point(angle);
+        let large_arc = u8::from(fraction > 0.5);
+        let _ = write!(
+            svg,
+            r#"<path d="M{cx:.1} {cy:.1}L{x1:.1} {y1:.1}A{r:.1} {r:.1} 0 {large_arc} 1 {x2:.1} {y2:.1}Z" fill="{color}" stroke="white"/>"#
+        );
+    }
+}
+
+impl Sheet {
+    /// Returns the chart anchored to the cell, if any.
+    pub fn chart(&self, pos: Pos) -> Option<&Chart> {
+        self.charts.get(&pos)
+    }
+
+    /// Sets (or removes) the chart anchored to the cell.
+    ///
+    /// Returns the old chart.
+    pub fn set_chart(&mut self, pos: Pos, chart: Option<Chart>) -> Option<Chart> {
+        match chart {
+            Some(chart) => self.charts.insert(pos, chart),
+            None => self.charts.remove(&pos),
+        }
+    }
+
+    /// Returns the charts as HTML outputs, which the client displays like the
+    /// HTML outputs of code cells.
+    pub fn get_chart_html_output(&self, grid: &Grid) -> Vec<JsHtmlOutput> {
+        self.charts
+            .iter()
+            .map(|(pos, chart)| JsHtmlOutput {
+                sheet_id: self.id.to_string(),
+                x: pos.x,
+                y: pos.y,
+                html: format!(
+                    r#"<html><body style="margin:0">{}</body></html>"#,
+                    chart.to_svg(grid)
+                ),
+                w: Some(chart.width.to_string()),
+                h: Some(chart.height.to_string()),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{controller::GridController, SheetPos};
+
+    fn chart(gc: &mut GridController, chart_type: ChartType) -> String {
+        let sheet_id = gc.sheet_ids()[0];
+        for (y, (label, value)) in [("a", "3"), ("b", "47"), ("c", "")].into_iter().enumerate() {
+            gc.set_cell_value(SheetPos::new(sheet_id, 0, y as i64), label.into(), None);
+            gc.set_cell_value(SheetPos::new(sheet_id, 1, y as i64), value.into(), None);
+        }
+        let mut chart = Chart::new(
+            chart_type,
+            Some(SheetRect::from_numbers(0, 0, 1, 3, sheet_id)),
+            vec![ChartSeries {
+                name: None,
+                values: SheetRect::from_numbers(1, 0, 1, 3, sheet_id),
+            }],
+        );
+        chart.title = Some("Sales & costs".into());
+        chart.to_svg(gc.grid())
+    }
+
+    #[test]
+    fn chooses_axis_ticks() {
+        let axis = Axis::new([3.0, 47.0].into_iter(), true);
+        assert_eq!(
+            axis,
+            Axis {
+                min: 0.0,
+                max: 50.0,
+                step: 10.0
+            }
+        );
+        assert_eq!(axis.ticks().count(), 6);
+
+        let axis = Axis::new([-0.3, 0.25].into_iter(), false);
+        assert_eq!(
+            axis.ticks().map(format_number).collect::<Vec<_>>(),
+            vec!["-0.4", "-0.2", "0", "0.2", "0.4"]
+        );
+
+        assert_eq!(Axis::new(std::iter::empty(), false).ticks().count(), 6);
+    }
+
+    #[test]
+    fn draws_charts() {
+        let mut gc = GridController::test();
+
+        let svg = chart(&mut gc, ChartType::Bar);
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="480""#));
+        assert!(svg.contains(">Sales &amp; costs</text>"));
+        assert!(svg.contains(r##"text-anchor="middle" fill="#6b7280">b</text>"##));
+        assert!(svg.contains(r##"text-anchor="end" fill="#6b7280">50</text>"##));
+        assert_eq!(svg.matches(r##"fill="#2563eb"/>"##).count(), 2);
+
+        let svg = chart(&mut gc, ChartType::Line);
+        assert_eq!(svg.matches("<path").count(), 1);
+        assert_eq!(svg.matches('L').count(), 1);
+
+        let svg = chart(&mut gc, ChartType::Area);
+        assert_eq!(svg.matches("<path").count(), 2);
+
+        // the labels are not numbers, so the points have no x values
+        let svg = chart(&mut gc, ChartType::Scatter);
+        assert_eq!(svg.matches("<circle").count(), 0);
+
+        let svg = chart(&mut gc, ChartType::Pie);
+        assert_eq!(svg.matches("<path").count(), 2);
+        assert!(svg.contains(">a</text>"));
+    }
+
+    #[test]
+    fn draws_scatter_charts() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_cell_value(SheetPos::new(sheet_id, 0, 0), "1".into(), None);
+        gc.set_cell_value(SheetPos::new(sheet_id, 0, 1), "2".into(), None);
+        let chart = Chart::new(
+            ChartType::Scatter,
+            None,
+            vec![ChartSeries {
+                name: None,
+                values: SheetRect::from_numbers(0, 0, 1, 10, sheet_id),
+            }],
+        );
+        let svg = chart.to_svg(gc.grid());
+        assert_eq!(svg.matches("<circle").count(), 2);
+
+        // deleted sheets have no values
+        gc.add_sheet(None);
+        gc.delete_sheet(sheet_id, None);
+        let svg = chart.to_svg(gc.grid());
+        assert_eq!(svg.matches("<circle").count(), 0);
+    }
+}
@@ -4,7 +4,7 @@ use super::Sheet;
 use crate::{
     grid::{
         js_types::{JsCodeCell, JsReturnInfo},
-        CodeRun, RenderSize,
+        CodeCellLanguage, CodeRun, RenderSize,
     },
     CellValue, Pos, Rect,
 };
@@ -142,7 +142,11 @@ impl Sheet {
         let code_cell = code_cell?;
 
         match code_cell {
-            CellValue::Code(code_cell) => {
+            CellValue::Code(mut code_cell) => {
+                // formulas are edited in the workbook's locale
+                if code_cell.language == CodeCellLanguage::Formula {
+                    code_cell.code = self.locale.formula_to_localized(&code_cell.code);
+                }
                 if let Some(code_run) = self.code_run(code_pos) {
                     let evaluation_result =
                         serde_json::to_string(&code_run.result).unwrap_or("".into());
@@ -0,0 +1,203 @@
+use serde::{Deserialize, Serialize};
+
+use super::Sheet;
+use crate::{grid::CommentId, Instant, Pos, Rect};
+
+/// A note or reply in a [`CommentThread`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub struct Comment {
+    #[serde(default)]
+    pub id: CommentId,
+
+    pub author: String,
+
+    /// When the comment was written.
+    pub timestamp: Instant,
+
+    pub text: String,
+}
+
+/// The comments anchored to a cell. A note is a thread with a single
+/// comment.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub struct CommentThread {
+    pub comments: Vec<Comment>,
+
+    #[serde(default)]
+    pub resolved: bool,
+}
+
+impl Comment {
+    /// Creates a comment written now.
+    pub fn new(author: String, text: String) -> Self {
+        Self {
+            id: CommentId::new(),
+            author,
+            timestamp: Instant::now(),
+            text,
+        }
+    }
+}
+
+impl Sheet {
+    /// Returns the comment thread anchored to the cell, if any.
+    pub fn comment_thread(&self, pos: Pos) -> Option<&CommentThread> {
+        self.comments.get(&pos)
+    }
+
+    /// Sets (or removes) the comment thread anchored to the cell. Empty
+    /// threads are removed.
+    ///
+    /// Returns the old thread.
+    pub fn set_comment_thread(
+        &mut self,
+        pos: Pos,
+        thread: Option<CommentThread>,
+    ) -> Option<CommentThread> {
+        match thread {
+            Some(thread) if !thread.comments.is_empty() => self.comments.insert(pos, thread),
+            _ => self.comments.remove(&pos),
+        }
+    }
+
+    /// Adds a comment to the cell's thread, starting a thread if there is
+    /// none. Comments are kept in the order they were written (ties are
+    /// broken by id so every client ends up with the same order).
+    ///
+    /// Returns false if the thread already has a comment with the same id.
+    pub fn add_comment(&mut self, pos: Pos, comment: Comment) -> bool {
+        let thread = self.comments.entry(pos).or_default();
+        if thread.comments.iter().any(|c| c.id == comment.id) {
+            return false;
+        }
+        let index = thread
+            .comments
+            .iter()
+            .position(|c| (c.timestamp.seconds, c.id) > (comment.timestamp.seconds, comment.id))
+            .unwrap_or(thread.comments.len());
+        thread.comments.insert(index, comment);
+        true
+    }
+
+    /// Changes the text of a comment in the cell's thread.
+    ///
+    /// Returns the old text, or None if there is no such comment.
+    pub fn edit_comment(&mut self, pos: Pos, id: CommentId, text: String) -> Option<String> {
+        let comment = self
+            .comments
+            .get_mut(&pos)?
+            .comments
+            .iter_mut()
+            .find(|comment| comment.id == id)?;
+        Some(std::mem::replace(&mut comment.text, text))
+    }
+
+    /// Deletes a comment from the cell's thread. The thread is removed with
+    /// its last comment.
+    ///
+    /// Returns the deleted comment.
+    pub fn delete_comment(&mut self, pos: Pos, id: CommentId) -> Option<Comment> {
+        let thread = self.comments.get_mut(&pos)?;
+        let index = thread.comments.iter().position(|c| c.id == id)?;
+        let comment = thread.comments.remove(index);
+        if thread.comments.is_empty() {
+            self.comments.remove(&pos);
+        }
+        Some(comment)
+    }
+
+    /// Resolves (or reopens) the cell's thread.
+    ///
+    /// Returns the old value, or None if there is no thread.
+    pub fn set_comment_resolved(&mut self, pos: Pos, resolved: bool) -> Option<bool> {
+        let thread = self.comments.get_mut(&pos)?;
+        Some(std::mem::replace(&mut thread.resolved, resolved))
+    }
+
+    /// Returns the comment threads anchored to cells within the rect.
+    pub fn comment_threads_in_rect(&self, rect: Rect) -> Vec<(Pos, &CommentThread)> {
+        self.comments
+            .range(
+                Pos {
+                    x: rect.min.x,
+                    y: i64::MIN,
+                }..=Pos {
+                    x: rect.max.x,
+                    y: i64::MAX,
+                },
+            )
+            .filter(|(pos, _)| rect.contains(**pos))
+            .map(|(pos, thread)| (*pos, thread))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::grid::SheetId;
+
+    #[test]
+    fn sets_and_finds_comment_threads() {
+        let mut sheet = Sheet::new(SheetId::new(), "Sheet 1".into(), "a0".into());
+        let thread = CommentThread {
+            comments: vec![Comment {
+                id: CommentId::new(),
+                author: "Ada".into(),
+                timestamp: Instant::new(1704067200.0),
+                text: "Check this".into(),
+            }],
+            resolved: false,
+        };
+        assert_eq!(
+            sheet.set_comment_thread(Pos { x: 1, y: 2 }, Some(thread.clone())),
+            None
+        );
+        sheet.set_comment_thread(Pos { x: 5, y: 0 }, Some(thread.clone()));
+        assert_eq!(sheet.comment_thread(Pos { x: 1, y: 2 }), Some(&thread));
+        assert_eq!(
+            sheet.comment_threads_in_rect(Rect::from_numbers(0, 0, 3, 3)),
+            vec![(Pos { x: 1, y: 2 }, &thread)]
+        );
+
+        // empty threads are removed
+        sheet.set_comment_thread(Pos { x: 1, y: 2 }, Some(CommentThread::default()));
+        assert_eq!(sheet.comment_thread(Pos { x: 1, y: 2 }), None);
+        assert_eq!(sheet.comments.len(), 1);
+    }
+
+    #[test]
+    fn edits_comments_by_id() {
+        let mut sheet = Sheet::new(SheetId::new(), "Sheet 1".into(), "a0".into());
+        let pos = Pos { x: 0, y: 0 };
+        let comment = |text: &str, seconds: f64| Comment {
+            id: CommentId::new(),
+            author: "Ada".into(),
+            timestamp: Instant::new(seconds),
+            text: text.into(),
+        };
+        let first = comment("first", 1.0);
+        let second = comment("second", 2.0);
+        assert!(sheet.add_comment(pos, second.clone()));
+        assert!(sheet.add_comment(pos, first.clone()));
+        assert!(!sheet.add_comment(pos, first.clone()));
+        assert_eq!(
+            sheet.comment_thread(pos).unwrap().comments,
+            vec![first.clone(), second.clone()]
+        );
+
+        assert_eq!(
+            sheet.edit_comment(pos, second.id, "2nd".into()),
+            Some("second".into())
+        );
+        assert_eq!(sheet.edit_comment(pos, CommentId::new(), "x".into()), None);
+        assert_eq!(sheet.set_comment_resolved(pos, true), Some(false));
+
+        assert_eq!(sheet.delete_comment(pos, first.id), Some(first));
+        assert!(sheet.delete_comment(pos, second.id).is_some());
+        assert!(sheet.comments.is_empty());
+        assert_eq!(sheet.set_comment_resolved(pos, true), None);
+    }
+}
@@ -0,0 +1,585 @@
+use std::collections::HashMap;
+
+use bigdecimal::ToPrimitive;
+use serde::{Deserialize, Serialize};
+
+use super::{validations::custom_formula_is_valid, Sheet};
+use crate::{
+    color::Rgba,
+    formulas::{find_cell_references, CellRef, RangeRef},
+    grid::{Grid, GridBounds},
+    CellValue, Pos, Rect, SheetRect,
+};
+
+/// How a cell's value is compared in a [`ConditionalFormatRule::Compare`]
+/// rule.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub enum CompareOperator {
+    GreaterThan,
+    GreaterThanOrEqual,
+    LessThan,
+    LessThanOrEqual,
+    Equal,
+    NotEqual,
+}
+
+/// Which cells a [`ConditionalFormat`] applies to.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+#[serde(tag = "type")]
+pub enum ConditionalFormatRule {
+    /// Values that compare to the value. Numbers are compared numerically;
+    /// text may only be compared for (case-insensitive) equality.
+    Compare {
+        operator: CompareOperator,
+        value: String,
+    },
+    /// Numbers between the (inclusive) bounds.
+    Between { min: f64, max: f64 },
+    /// Text that contains the text (case-insensitive).
+    TextContains { text: String },
+    /// The largest (or smallest) `count` numbers in the range.
+    TopN { count: u32, bottom: bool },
+    /// Numbers above (or below) the average of the range.
+    AboveAverage { below: bool },
+    /// Values that appear more than once in the range (case-insensitive).
+    Duplicates,
+    /// A formula that evaluates to TRUE. The formula is written relative to
+    /// the top-left cell of the conditional format.
+    Custom { formula: String },
+    /// Fills numbers with a color between `min_color` and `max_color`
+    /// according to where they fall in the range. The style is not used.
+    ColorScale {
+        min_color: String,
+        mid_color: Option<String>,
+        max_color: String,
+    },
+    /// Draws a bar whose length is proportional to the number. The style is
+    /// not used.
+    DataBar { color: String },
+}
+
+/// The style applied to cells that match a [`ConditionalFormatRule`]. Unset
+/// values keep the cell's own format.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub struct ConditionalFormatStyle {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bold: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub italic: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub underline: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strike_through: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text_color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fill_color: Option<String>,
+}
+
+impl ConditionalFormatStyle {
+    /// Overrides this style with the values set in other.
+    pub fn merge(&mut self, other: &ConditionalFormatStyle) {
+        self.bold = other.bold.or(self.bold);
+        self.italic = other.italic.or(self.italic);
+        self.underline = other.underline.or(self.underline);
+        self.strike_through = other.strike_through.or(self.strike_through);
+        if other.text_color.is_some() {
+            self.text_color.clone_from(&other.text_color);
+        }
+        if other.fill_color.is_some() {
+            self.fill_color.clone_from(&other.fill_color);
+        }
+    }
+}
+
+/// A conditional format rule that applies to a rect of cells.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub struct ConditionalFormat {
+    pub rect: Rect,
+    pub rule: ConditionalFormatRule,
+
+    #[serde(default)]
+    pub style: ConditionalFormatStyle,
+}
+
+/// How a [`ConditionalFormat`] changes a cell.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConditionalFormatEffect {
+    Style(ConditionalFormatStyle),
+    /// A data bar with the length as a fraction of the cell's width.
+    DataBar {
+        color: String,
+        fraction: f32,
+    },
+}
+
+/// The numbers and values in a conditional format's range, for rules that
+/// compare a cell to the rest of the range.
+#[derive(Default)]
+struct RangeValues {
+    /// Sorted ascending.
+    numbers: Vec<f64>,
+    counts: HashMap<String, usize>,
+}
+
+impl RangeValues {
+    fn new(sheet: &Sheet, rect: Rect) -> Self {
+        let mut values = RangeValues::default();
+        for pos in rect.iter() {
+            let Some(value) = sheet.display_value(pos) else {
+                continue;
+            };
+            if let Some(n) = number(&value) {
+                values.numbers.push(n);
+            }
+            if !value.is_blank_or_empty_string() {
+                *values.counts.entry(duplicate_key(&value)).or_default() += 1;
+            }
+        }
+        values.numbers.sort_by(f64::total_cmp);
+        values
+    }
+
+    fn min(&self) -> Option<f64> {
+        self.numbers.first().copied()
+    }
+
+    fn max(&self) -> Option<f64> {
+        self.numbers.last().copied()
+    }
+
+    fn average(&self) -> Option<f64> {
+        (!self.numbers.is_empty())
+            .then(|| self.numbers.iter().sum::<f64>() / self.numbers.len() as f64)
+    }
+}
+
+fn number(value: &CellValue) -> Option<f64> {
+    match value {
+        CellValue::Number(n) => n.to_f64(),
+        _ => None,
+    }
+}
+
+fn duplicate_key(value: &CellValue) -> String {
+    value.to_string().to_lowercase()
+}
+
+/// Parses a `#rrggbb`, `#rrggbbaa`, or `rgb(r, g, b)` color.
+fn parse_color(color: &str) -> Option<Rgba> {
+    if color.starts_with('#') && matches!(color.len(), 7 | 9) && color.is_ascii() {
+        Rgba::from_str(color).ok()
+    } else {
+        Rgba::from_css_str(color).ok()
+    }
+}
+
+/// Returns the color a fraction of the way from one color to another.
+fn interpolate_color(from: &str, to: &str, fraction: f64) -> Option<String> {
+    let (from, to) = (parse_color(from)?, parse_color(to)?);
+    let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * fraction).round() as u8;
+    Some(
+        Rgba::new(
+            mix(from.red, to.red),
+            mix(from.green, to.green),
+            mix(from.blue, to.blue),
+            mix(from.alpha, to.alpha),
+        )
+        .as_string(),
+    )
+}
+
+impl ConditionalFormat {
+    /// Returns true if whether a cell matches depends on cells other than
+    /// itself, so the format must be re-evaluated when any cell in its rect
+    /// changes.
+    pub fn depends_on_other_cells(&self) -> bool {
+        !matches!(
+            self.rule,
+            ConditionalFormatRule::Compare { .. }
+                | ConditionalFormatRule::Between { .. }
+                | ConditionalFormatRule::TextContains { .. }
+        )
+    }
+
+    /// Returns the cells of the format within the sheet's data bounds, if any.
+    pub fn bounded_rect(&self, sheet: &Sheet) -> Option<Rect> {
+        match sheet.bounds(true) {
+            GridBounds::NonEmpty(bounds) => self.rect.intersection(&bounds),
+            GridBounds::Empty => None,
+        }
+    }
+
+    /// Returns the cells read by a custom formula when it is evaluated for
+    /// every cell of the format. Returns None if the references can't be
+    /// bounded (e.g., whole rows or columns). References to missing sheets
+    /// are skipped.
+    pub fn custom_references(&self, grid: &Grid, sheet: &Sheet) -> Option<Vec<SheetRect>> {
+        let ConditionalFormatRule::Custom { formula } = &self.rule else {
+            return Some(vec![]);
+        };
+
+        // references are relative to the top-left cell, so the first and last
+        // cells of the format bound where each reference resolves
+        let bounds = |cell_ref: &CellRef| {
+            Rect::new_span(
+                cell_ref.resolve_from(self.rect.min),
+                cell_ref.resolve_from(self.rect.max),
+            )
+        };
+        let mut references = vec![];
+        for range_ref in find_cell_references(formula, self.rect.min) {
+            let (rect, sheet_name) = match &range_ref.inner {
+                RangeRef::Cell { pos } => (bounds(pos), &pos.sheet),
+                RangeRef::CellRange { start, end } => {
+                    (bounds(start).union(&bounds(end)), &start.sheet)
+                }
+                RangeRef::RowRange { .. } | RangeRef::ColRange { .. } => return None,
+            };
+            let sheet_id = match sheet_name {
+                Some(name) => match grid.try_sheet_from_name(name.clone()) {
+                    Some(sheet) => sheet.id,
+                    None => continue,
+                },
+                None => sheet.id,
+            };
+            references.push(rect.to_sheet_rect(sheet_id));
+        }
+        Some(references)
+    }
+
+    /// Evaluates the format for the cells within region, returning the
+    /// effect on each cell that matches.
+    pub fn evaluate(
+        &self,
+        grid: &Grid,
+        sheet: &Sheet,
+        region: Rect,
+    ) -> Vec<(Pos, ConditionalFormatEffect)> {
+        let Some(bounded) = self.bounded_rect(sheet) else {
+            return vec![];
+        };
+        let Some(rect) = bounded.intersection(&region) else {
+            return vec![];
+        };
+
+        if let ConditionalFormatRule::Custom { formula } = &self.rule {
+            return rect
+                .iter()
+                .filter(|&pos| {
+                    let value = sheet.display_value(pos).unwrap_or(CellValue::Blank);
+                    custom_formula_is_valid(
+                        grid,
+                        formula,
+                        self.rect.min,
+                        pos.to_sheet_pos(sheet.id),
+                        &value,
+                    )
+                })
+                .map(|pos| (pos, ConditionalFormatEffect::Style(self.style.clone())))
+                .collect();
+        }
+
+        let range = if self.depends_on_other_cells() {
+            RangeValues::new(sheet, bounded)
+        } else {
+            RangeValues::default()
+        };
+        rect.iter()
+            .filter_map(|pos| {
+                let value = sheet.display_value(pos)?;
+                Some((pos, self.effect(&value, &range)?))
+            })
+            .collect()
+    }
+
+    /// Returns the effect of a (non-custom) format on a value.
+    fn effect(&self, value: &CellValue, range: &RangeValues) -> Option<ConditionalFormatEffect> {
+        let matches = match &self.rule {
+            ConditionalFormatRule::Compare {
+                operator,
+                value: compare_to,
+            } => compare(value, *operator, compare_to),
+            ConditionalFormatRule::Between { min, max } => {
+                number(value).is_some_and(|n| n >= *min && n <= *max)
+            }
+            ConditionalFormatRule::TextContains { text } => match value {
+                CellValue::Text(value) => value.to_lowercase().contains(&text.to_lowercase()),
+                _ => false,
+            },
+            ConditionalFormatRule::TopN { count, bottom } => {
+                let (n, count) = (number(value)?, *count as usize);
+                let len = range.numbers.len();
+                if count == 0 || len == 0 {
+                    false
+                } else if *bottom {
+                    n <= range.numbers[count.min(len) - 1]
+                } else {
+                    n >= range.numbers[len.saturating_sub(count)]
+                }
+            }
+            ConditionalFormatRule::AboveAverage { below } => {
+                let (n, average) = (number(value)?, range.average()?);
+                if *below {
+                    n < average
+                } else {
+                    n > average
+                }
+            }
+            ConditionalFormatRule::Duplicates => {
+                !value.is_blank_or_empty_string()
+                    && range
+                        .counts
+                        .get(&duplicate_key(value))
+                        .is_some_and(|count| *count > 1)
+            }
+            ConditionalFormatRule::ColorScale {
+                min_color,
+                mid_color,
+                max_color,
+            } => {
+                let (n, min, max) = (number(value)?, range.min()?, range.max()?);
+                let fraction = if max > min {
+                    (n - min) / (max - min)
+                } else {
+                    0.0
+                };
+                let fill_color = match mid_color {
+                    Some(mid_color) if fraction < 0.5 => {
+                        interpolate_color(min_color, mid_color, fraction * 2.0)
+                    }
+                    Some(mid_color) => {
+                        interpolate_color(mid_color, max_color, (fraction - 0.5) * 2.0)
+                    }
+                    None => interpolate_color(min_color, max_color, fraction),
+                }?;
+                return Some(ConditionalFormatEffect::Style(ConditionalFormatStyle {
+                    fill_color: Some(fill_color),
+                    ..Default::default()
+                }));
+            }
+            ConditionalFormatRule::DataBar { color } => {
+                // bars start at zero, or at the smallest number if it is negative
+                let n = number(value)?;
+                let min = range.min()?.min(0.0);
+                let max = range.max()?;
+                let fraction = if max > min {
+                    (n - min) / (max - min)
+                } else {
+                    1.0
+                };
+                return Some(ConditionalFormatEffect::DataBar {
+                    color: color.clone(),
+                    fraction: fraction as f32,
+                });
+            }
+            ConditionalFormatRule::Custom { .. } => false,
+        };
+        matches.then(|| ConditionalFormatEffect::Style(self.style.clone()))
+    }
+}
+
+fn compare(value: &CellValue, operator: CompareOperator, compare_to: &str) -> bool {
+    match (number(value), compare_to.trim().parse::<f64>()) {
+        (Some(n), Ok(compare_to)) => match operator {
+            CompareOperator::GreaterThan => n > compare_to,
+            CompareOperator::GreaterThanOrEqual => n >= compare_to,
+            CompareOperator::LessThan => n < compare_to,
+            CompareOperator::LessThanOrEqual => n <= compare_to,
+            CompareOperator::Equal => n == compare_to,
+            CompareOperator::NotEqual => n != compare_to,
+        },
+        _ => {
+            let equal = value.to_string().eq_ignore_ascii_case(compare_to);
+            match operator {
+                CompareOperator::Equal => equal,
+                CompareOperator::NotEqual => !value.is_blank_or_empty_string() && !equal,
+                _ => false,
+            }
+        }
+    }
+}
+
+/// The combined effect of a sheet's conditional formats on a cell.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ConditionalCellFormat {
+    pub style: ConditionalFormatStyle,
+
+    /// The color and length (as a fraction of the cell's width) of a data bar.
+    pub data_bar: Option<(String, f32)>,
+}
+
+impl Sheet {
+    /// Evaluates the sheet's conditional formats for the cells within
+    /// region. Later formats take precedence over earlier ones.
+    pub fn conditional_cell_formats(
+        &self,
+        grid: &Grid,
+        region: Rect,
+    ) -> HashMap<Pos, ConditionalCellFormat> {
+        let mut formats: HashMap<Pos, ConditionalCellFormat> = HashMap::new();
+        for conditional_format in &self.conditional_formats {
+            if !conditional_format.rect.intersects(region) {
+                continue;
+            }
+            for (pos, effect) in conditional_format.evaluate(grid, self, region) {
+                let format = formats.entry(pos).or_default();
+                match effect {
+                    ConditionalFormatEffect::Style(style) => format.style.merge(&style),
+                    ConditionalFormatEffect::DataBar { color, fraction } => {
+                        format.data_bar = Some((color, fraction));
+                    }
+                }
+            }
+        }
+        formats
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{controller::GridController, SheetPos};
+
+    fn format(rule: ConditionalFormatRule) -> ConditionalFormat {
+        ConditionalFormat {
+            rect: Rect::from_numbers(0, 0, 1, 5),
+            rule,
+            style: ConditionalFormatStyle {
+                bold: Some(true),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn matching(gc: &GridController, rule: ConditionalFormatRule) -> Vec<i64> {
+        let sheet = gc.sheet(gc.sheet_ids()[0]);
+        let mut rows: Vec<i64> = format(rule)
+            .evaluate(gc.grid(), sheet, Rect::from_numbers(0, 0, 10, 10))
+            .into_iter()
+            .map(|(pos, _)| pos.y)
+            .collect();
+        rows.sort();
+        rows
+    }
+
+    #[test]
+    fn evaluates_rules() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        for (y, value) in ["10", "3", "apple", "7", "3"].iter().enumerate() {
+            gc.set_cell_value(
+                SheetPos::new(sheet_id, 0, y as i64),
+                value.to_string(),
+                None,
+            );
+        }
+
+        let compare = |operator, value: &str| ConditionalFormatRule::Compare {
+            operator,
+            value: value.into(),
+        };
+        assert_eq!(
+            matching(&gc, compare(CompareOperator::GreaterThan, "5")),
+            vec![0, 3]
+        );
+        assert_eq!(
+            matching(&gc, compare(CompareOperator::Equal, "APPLE")),
+            vec![2]
+        );
+        assert_eq!(
+            matching(&gc, ConditionalFormatRule::Between { min: 3.0, max: 7.0 }),
+            vec![1, 3, 4]
+        );
+        assert_eq!(
+            matching(
+                &gc,
+                ConditionalFormatRule::TextContains { text: "PP".into() }
+            ),
+            vec![2]
+        );
+        assert_eq!(
+            matching(
+                &gc,
+                ConditionalFormatRule::TopN {
+                    count: 2,
+                    bottom: false
+                }
+            ),
+            vec![0, 3]
+        );
+        assert_eq!(
+            matching(
+                &gc,
+                ConditionalFormatRule::TopN {
+                    count: 1,
+                    bottom: true
+                }
+            ),
+            vec![1, 4]
+        );
+        // average of 10, 3, 7, 3 is 5.75
+        assert_eq!(
+            matching(&gc, ConditionalFormatRule::AboveAverage { below: false }),
+            vec![0, 3]
+        );
+        assert_eq!(matching(&gc, ConditionalFormatRule::Duplicates), vec![1, 4]);
+        assert_eq!(
+            matching(
+                &gc,
+                ConditionalFormatRule::Custom {
+                    formula: "A0 = 3".into()
+                }
+            ),
+            vec![1, 4]
+        );
+    }
+
+    #[test]
+    fn evaluates_color_scales_and_data_bars() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        for (y, value) in ["0", "5", "10"].iter().enumerate() {
+            gc.set_cell_value(
+                SheetPos::new(sheet_id, 0, y as i64),
+                value.to_string(),
+                None,
+            );
+        }
+        let sheet = gc.sheet(sheet_id);
+        let region = Rect::from_numbers(0, 0, 1, 3);
+
+        let scale = format(ConditionalFormatRule::ColorScale {
+            min_color: "#000000".into(),
+            mid_color: None,
+            max_color: "#ffffff".into(),
+        });
+        let fills: Vec<_> = scale
+            .evaluate(gc.grid(), sheet, region)
+            .into_iter()
+            .map(|(_, effect)| match effect {
+                ConditionalFormatEffect::Style(style) => style.fill_color.unwrap(),
+                _ => panic!("expected a fill"),
+            })
+            .collect();
+        assert_eq!(fills, vec!["#000000ff", "#808080ff", "#ffffffff"]);
+
+        let bar = format(ConditionalFormatRule::DataBar {
+            color: "#0000ff".into(),
+        });
+        assert_eq!(
+            bar.evaluate(gc.grid(), sheet, Rect::single_pos(Pos { x: 0, y: 1 })),
+            vec![(
+                Pos { x: 0, y: 1 },
+                ConditionalFormatEffect::DataBar {
+                    color: "#0000ff".into(),
+                    fraction: 0.5
+                }
+            )]
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use crate::{Pos, Rect};
+
+use super::Sheet;
+
+impl Sheet {
+    /// Returns the merged region that contains the position.
+    pub fn merged_rect(&self, pos: Pos) -> Option<Rect> {
+        self.merged_cells
+            .iter()
+            .find(|rect| rect.contains(pos))
+            .copied()
+    }
+
+    /// Returns all merged regions that intersect the rect.
+    pub fn merged_cells_in_rect(&self, rect: Rect) -> Vec<Rect> {
+        self.merged_cells
+            .iter()
+            .filter(|merged| merged.intersects(rect))
+            .copied()
+            .collect()
+    }
+
+    /// Returns true if the position is inside a merged region but is not its
+    /// top-left (anchor) cell.
+    pub fn is_merged_cell_hidden(&self, pos: Pos) -> bool {
+        self.merged_rect(pos).is_some_and(|rect| rect.min != pos)
+    }
+
+    /// Returns true if a code output in the rect would spill into a merged
+    /// region. A single-cell output may sit in the anchor of a merged region.
+    pub fn has_merged_cells_in_rect(&self, rect: Rect) -> bool {
+        rect.len() > 1
+            && self
+                .merged_cells
+                .iter()
+                .any(|merged| merged.intersects(rect))
+    }
+
+    /// Merges the cells in the rect. Any merged regions that overlap the rect
+    /// are removed and returned.
+    pub fn merge_cells(&mut self, rect: Rect) -> Vec<Rect> {
+        let removed = self.unmerge_cells(rect);
+        if rect.len() > 1 {
+            self.merged_cells.push(rect);
+        }
+        removed
+    }
+
+    /// Removes all merged regions that intersect the rect and returns them.
+    pub fn unmerge_cells(&mut self, rect: Rect) -> Vec<Rect> {
+        let mut removed = vec![];
+        self.merged_cells.retain(|merged| {
+            if merged.intersects(rect) {
+                removed.push(*merged);
+                false
+            } else {
+                true
+            }
+        });
+        removed
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{grid::Sheet, Pos, Rect};
+
+    #[test]
+    fn merge_and_unmerge() {
+        let mut sheet = Sheet::test();
+        let rect = Rect::from_numbers(1, 1, 2, 3);
+        assert!(sheet.merge_cells(rect).is_empty());
+        assert_eq!(sheet.merged_rect(Pos { x: 2, y: 3 }), Some(rect));
+        assert_eq!(sheet.merged_rect(Pos { x: 3, y: 3 }), None);
+        assert!(!sheet.is_merged_cell_hidden(Pos { x: 1, y: 1 }));
+        assert!(sheet.is_merged_cell_hidden(Pos { x: 1, y: 2 }));
+
+        // merging an overlapping region replaces the old one
+        let overlap = Rect::from_numbers(2, 3, 2, 2);
+        assert_eq!(sheet.merge_cells(overlap), vec![rect]);
+        assert_eq!(
+            sheet.merged_cells_in_rect(Rect::from_numbers(0, 0, 10, 10)),
+            vec![overlap]
+        );
+
+        assert_eq!(
+            sheet.unmerge_cells(Rect::single_pos(Pos { x: 3, y: 4 })),
+            vec![overlap]
+        );
+        assert!(sheet.merged_cells.is_empty());
+
+        // a single cell is never merged
+        sheet.merge_cells(Rect::single_pos(Pos { x: 0, y: 0 }));
+        assert!(sheet.merged_cells.is_empty());
+    }
+
+    #[test]
+    fn has_merged_cells_in_rect() {
+        let mut sheet = Sheet::test();
+        sheet.merge_cells(Rect::from_numbers(2, 2, 2, 2));
+        assert!(sheet.has_merged_cells_in_rect(Rect::from_numbers(0, 0, 3, 3)));
+        assert!(!sheet.has_merged_cells_in_rect(Rect::from_numbers(0, 0, 2, 5)));
+        assert!(!sheet.has_merged_cells_in_rect(Rect::from_numbers(2, 2, 1, 1)));
+    }
+}
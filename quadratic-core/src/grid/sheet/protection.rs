@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{Pos, Rect};
+
+/// A range of cells that only some users can edit.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub struct ProtectedRange {
+    pub rect: Rect,
+
+    /// Ids of the users that can edit the range.
+    pub editors: Vec<String>,
+}
+
+/// Who can edit a sheet. A locked sheet can only be edited by the users it
+/// lists, and a protected range only by the users the range lists.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub struct SheetProtection {
+    /// Ids of the users that can edit the sheet if it is locked, or `None`
+    /// if the sheet is not locked.
+    #[serde(default)]
+    pub locked: Option<Vec<String>>,
+
+    #[serde(default)]
+    pub ranges: Vec<ProtectedRange>,
+}
+
+fn is_editor(editors: &[String], user_id: Option<&str>) -> bool {
+    user_id.is_some_and(|user_id| editors.iter().any(|editor| editor == user_id))
+}
+
+impl ProtectedRange {
+    pub fn can_edit(&self, user_id: Option<&str>) -> bool {
+        is_editor(&self.editors, user_id)
+    }
+}
+
+impl SheetProtection {
+    pub fn is_empty(&self) -> bool {
+        self.locked.is_none() && self.ranges.is_empty()
+    }
+
+    /// Whether the user can edit the sheet's settings and its cells outside
+    /// of protected ranges.
+    pub fn can_edit_sheet(&self, user_id: Option<&str>) -> bool {
+        match &self.locked {
+            Some(editors) => is_editor(editors, user_id),
+            None => true,
+        }
+    }
+
+    /// Returns the first protected range overlapping the rect that the user
+    /// cannot edit.
+    pub fn protected_range_in_rect(
+        &self,
+        rect: Rect,
+        user_id: Option<&str>,
+    ) -> Option<&ProtectedRange> {
+        self.ranges
+            .iter()
+            .find(|range| range.rect.intersects(rect) && !range.can_edit(user_id))
+    }
+
+    /// Whether a protected range keeps the output of the code cell at `pos`
+    /// from spilling into `output`. Output can only spill into the protected
+    /// ranges that contain its code cell, so it is blocked for every user
+    /// alike.
+    pub fn blocks_output(&self, pos: Pos, output: Rect) -> bool {
+        self.ranges
+            .iter()
+            .any(|range| range.rect.intersects(output) && !range.rect.contains(pos))
+    }
+
+    /// Whether the user can replace this protection with `protection`. The
+    /// user must be able to edit the sheet, and can only change or remove
+    /// ranges they can edit.
+    pub fn can_change_to(&self, protection: &SheetProtection, user_id: Option<&str>) -> bool {
+        self.can_edit_sheet(user_id)
+            && self
+                .ranges
+                .iter()
+                .all(|range| range.can_edit(user_id) || protection.ranges.contains(range))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn checks_editors() {
+        let range = ProtectedRange {
+            rect: Rect::from_numbers(0, 0, 2, 2),
+            editors: vec!["ada".into()],
+        };
+        let mut protection = SheetProtection {
+            locked: None,
+            ranges: vec![range.clone()],
+        };
+        assert!(protection.can_edit_sheet(None));
+        assert_eq!(
+            protection.protected_range_in_rect(Rect::from_numbers(1, 1, 5, 5), Some("bob")),
+            Some(&range)
+        );
+        assert_eq!(
+            protection.protected_range_in_rect(Rect::from_numbers(1, 1, 5, 5), Some("ada")),
+            None
+        );
+        assert_eq!(
+            protection.protected_range_in_rect(Rect::from_numbers(2, 0, 5, 5), Some("bob")),
+            None
+        );
+
+        // only editors of a range can remove it
+        assert!(!protection.can_change_to(&SheetProtection::default(), Some("bob")));
+        assert!(protection.can_change_to(&SheetProtection::default(), Some("ada")));
+
+        // others can add ranges
+        let mut more = protection.clone();
+        more.ranges.push(ProtectedRange {
+            rect: Rect::from_numbers(5, 5, 1, 1),
+            editors: vec!["bob".into()],
+        });
+        assert!(protection.can_change_to(&more, Some("bob")));
+
+        protection.locked = Some(vec!["ada".into()]);
+        assert!(!protection.can_edit_sheet(Some("bob")));
+        assert!(!protection.can_edit_sheet(None));
+        assert!(protection.can_edit_sheet(Some("ada")));
+        assert!(!protection.can_change_to(&more, Some("bob")));
+    }
+
+    #[test]
+    fn blocks_output() {
+        let protection = SheetProtection {
+            locked: None,
+            ranges: vec![ProtectedRange {
+                rect: Rect::from_numbers(0, 0, 2, 2),
+                editors: vec!["ada".into()],
+            }],
+        };
+        assert!(protection.blocks_output(Pos { x: 0, y: 3 }, Rect::from_numbers(0, 0, 1, 4)));
+        assert!(!protection.blocks_output(Pos { x: 0, y: 0 }, Rect::from_numbers(0, 0, 3, 3)));
+        assert!(!protection.blocks_output(Pos { x: 5, y: 5 }, Rect::from_numbers(5, 5, 2, 2)));
+    }
+}
@@ -8,7 +8,9 @@ use crate::{
             JsHtmlOutput, JsRenderBorder, JsRenderCell, JsRenderCellSpecial, JsRenderCodeCell,
             JsRenderCodeCellState, JsRenderFill,
         },
-        CellAlign, CodeCellLanguage, CodeRun, Column, NumericFormatKind,
+        number_format::CustomNumberFormat,
+        CellAlign, CodeCellLanguage, CodeRun, Column, Grid, GridBounds, NumericFormat,
+        NumericFormatKind,
     },
     CellValue, Pos, Rect, RunError, RunErrorMsg,
 };
@@ -35,7 +37,7 @@ impl Sheet {
         value: CellValue,
         language: Option<CodeCellLanguage>,
     ) -> JsRenderCell {
-        if let CellValue::Html(_) = value {
+        if let CellValue::Html(_) | CellValue::Image(_) = value {
             return JsRenderCell {
                 x,
                 y,
@@ -45,7 +47,14 @@ impl Sheet {
                 wrap: None,
                 bold: None,
                 italic: None,
+                underline: None,
+                strike_through: None,
                 text_color: None,
+                font_size: None,
+                font_family: None,
+                vertical_align: None,
+                text_rotation: None,
+                link: None,
                 special: Some(JsRenderCellSpecial::Chart),
             };
         } else if let CellValue::Error(error) = value {
@@ -59,7 +68,14 @@ impl Sheet {
                 wrap: None,
                 bold: None,
                 italic: None,
+                underline: None,
+                strike_through: None,
                 text_color: None,
+                font_size: None,
+                font_family: None,
+                vertical_align: None,
+                text_rotation: None,
+                link: None,
                 special: Some(if spill_error {
                     JsRenderCellSpecial::SpillError
                 } else {
@@ -68,23 +84,43 @@ impl Sheet {
             };
         }
 
+        let link = match &value {
+            CellValue::Link(link) => Some(link.url.clone()),
+            _ => None,
+        };
         match column {
             None => {
-                let align = if matches!(value, CellValue::Number(_)) {
+                let align = if matches!(
+                    value,
+                    CellValue::Number(_) | CellValue::Instant(_) | CellValue::Duration(_)
+                ) {
                     Some(CellAlign::Right)
                 } else {
                     None
                 };
+                let value = match &value {
+                    CellValue::Number(_) => self
+                        .locale
+                        .localize_number(&value.to_display(None, None, None), None),
+                    _ => value.to_display(None, None, None),
+                };
                 JsRenderCell {
                     x,
                     y,
-                    value: value.to_display(None, None, None),
+                    value,
                     language,
                     align,
                     wrap: None,
                     bold: None,
                     italic: None,
+                    underline: None,
+                    strike_through: None,
                     text_color: None,
+                    font_size: None,
+                    font_family: None,
+                    vertical_align: None,
+                    text_rotation: None,
+                    link,
                     special: None,
                 }
             }
@@ -94,9 +130,15 @@ impl Sheet {
                 let wrap = column.wrap.get(y);
                 let bold = column.bold.get(y);
                 let italic = column.italic.get(y);
-                let text_color = column.text_color.get(y);
+                let underline = column.underline.get(y);
+                let strike_through = column.strike_through.get(y);
+                let mut text_color = column.text_color.get(y);
+                let font_size = column.font_size.get(y);
+                let font_family = column.font_family.get(y);
+                let vertical_align = column.vertical_align.get(y);
+                let text_rotation = column.text_rotation.get(y);
                 let value = match &value {
-                    CellValue::Number(_) => {
+                    CellValue::Number(n) => {
                         // get numeric_format and numeric_decimal to turn number into a string
                         let numeric_format = column.numeric_format.get(y);
                         let is_percentage = numeric_format.as_ref().is_some_and(|numeric_format| {
@@ -108,7 +150,22 @@ impl Sheet {
                         // if align is not set, set it to right only for numbers
                         align = align.or(Some(CellAlign::Right));
 
-                        value.to_display(numeric_format, numeric_decimals, numeric_commas)
+                        // custom formats may set a color, e.g. [Red]
+                        if let Some(NumericFormat {
+                            kind: NumericFormatKind::Custom(code),
+                            ..
+                        }) = &numeric_format
+                        {
+                            if let Some(color) = CustomNumberFormat::cached(code).color(n) {
+                                text_color = Some(color.to_string());
+                            }
+                        }
+
+                        let currency_symbol = currency_symbol(&numeric_format);
+                        self.locale.localize_number(
+                            &value.to_display(numeric_format, numeric_decimals, numeric_commas),
+                            currency_symbol.as_deref(),
+                        )
                     }
                     CellValue::Logical(bool) => {
                         special = match bool {
@@ -117,6 +174,13 @@ impl Sheet {
                         };
                         "".to_string()
                     }
+                    CellValue::Text(_) => {
+                        value.to_display(column.numeric_format.get(y), None, None)
+                    }
+                    CellValue::Instant(_) | CellValue::Duration(_) => {
+                        align = align.or(Some(CellAlign::Right));
+                        value.to_display(column.numeric_format.get(y), None, None)
+                    }
                     _ => value.to_display(None, None, None),
                 };
                 JsRenderCell {
@@ -128,7 +192,14 @@ impl Sheet {
                     wrap,
                     bold,
                     italic,
+                    underline,
+                    strike_through,
                     text_color,
+                    font_size,
+                    font_family,
+                    vertical_align,
+                    text_rotation,
+                    link,
                     special,
                 }
             }
@@ -244,27 +315,51 @@ impl Sheet {
                     render_cells.extend(self.get_code_cells(&code, code_run, &rect, &code_rect));
                 }
             });
+
+        // cells hidden by a merged region are not rendered
+        if !self.merged_cells.is_empty() {
+            render_cells.retain(|cell| {
+                !self.is_merged_cell_hidden(Pos {
+                    x: cell.x,
+                    y: cell.y,
+                })
+            });
+        }
+
+        // nor are cells in hidden columns or rows
+        render_cells.retain(|cell| {
+            !self.offsets.is_column_hidden(cell.x) && !self.offsets.is_row_hidden(cell.y)
+        });
         render_cells
     }
 
+    /// Returns the html and images output by code cells, which float above
+    /// the grid. Images are sized to their pixel size until they are resized.
     pub fn get_html_output(&self) -> Vec<JsHtmlOutput> {
         self.code_runs
             .iter()
             .filter_map(|(pos, run)| {
-                let output = run.cell_value_at(0, 0)?;
-                if !matches!(output, CellValue::Html(_)) {
-                    return None;
-                }
-                let (w, h) = if let Some(render_size) = self.render_size(*pos) {
-                    (Some(render_size.w), Some(render_size.h))
-                } else {
-                    (None, None)
+                let (html, size) = match run.cell_value_at(0, 0)? {
+                    CellValue::Html(html) => (html, None),
+                    CellValue::Image(image) => (
+                        format!(
+                            r#"<html><body style="margin:0"><img src="{}" style="width:100%;height:100%;object-fit:contain"></body></html>"#,
+                            image.to_data_url()
+                        ),
+                        Some((image.width.to_string(), image.height.to_string())),
+                    ),
+                    _ => return None,
+                };
+                let (w, h) = match (self.render_size(*pos), size) {
+                    (Some(render_size), _) => (Some(render_size.w), Some(render_size.h)),
+                    (None, Some((w, h))) => (Some(w), Some(h)),
+                    (None, None) => (None, None),
                 };
                 Some(JsHtmlOutput {
                     sheet_id: self.id.to_string(),
                     x: pos.x,
                     y: pos.y,
-                    html: output.to_display(None, None, None),
+                    html,
                     w,
                     h,
                 })
@@ -276,6 +371,9 @@ impl Sheet {
     pub fn get_all_render_fills(&self) -> Vec<JsRenderFill> {
         let mut ret = vec![];
         for (&x, column) in self.columns.iter() {
+            if self.offsets.is_column_hidden(x) {
+                continue;
+            }
             for block in column.fill_color.blocks() {
                 ret.push(JsRenderFill {
                     x,
@@ -283,6 +381,7 @@ impl Sheet {
                     w: 1,
                     h: block.len() as u32,
                     color: block.content().value.clone(),
+                    data_bar: None,
                 });
             }
         }
@@ -292,6 +391,9 @@ impl Sheet {
     pub fn get_render_fills(&self, region: Rect) -> Vec<JsRenderFill> {
         let mut ret = vec![];
         for (&x, column) in self.columns.range(region.x_range()) {
+            if self.offsets.is_column_hidden(x) {
+                continue;
+            }
             for block in column.fill_color.blocks_covering_range(region.y_range()) {
                 ret.push(JsRenderFill {
                     x,
@@ -299,12 +401,87 @@ impl Sheet {
                     w: 1,
                     h: block.len() as u32,
                     color: block.content().value.clone(),
+                    data_bar: None,
                 });
             }
         }
         ret
     }
 
+    /// Applies the sheet's conditional formats to render cells within rect.
+    /// The stored formats are not changed.
+    pub fn apply_conditional_formats(
+        &self,
+        grid: &Grid,
+        rect: Rect,
+        render_cells: &mut [JsRenderCell],
+    ) {
+        if self.conditional_formats.is_empty() {
+            return;
+        }
+        let formats = self.conditional_cell_formats(grid, rect);
+        for cell in render_cells.iter_mut() {
+            let Some(format) = formats.get(&Pos {
+                x: cell.x,
+                y: cell.y,
+            }) else {
+                continue;
+            };
+            let style = &format.style;
+            cell.bold = style.bold.or(cell.bold);
+            cell.italic = style.italic.or(cell.italic);
+            cell.underline = style.underline.or(cell.underline);
+            cell.strike_through = style.strike_through.or(cell.strike_through);
+            if style.text_color.is_some() {
+                cell.text_color.clone_from(&style.text_color);
+            }
+        }
+    }
+
+    /// Returns fills from the sheet's conditional formats (including color
+    /// scales and data bars) within region. These are drawn over the cells'
+    /// own fills.
+    pub fn get_conditional_render_fills(&self, grid: &Grid, region: Rect) -> Vec<JsRenderFill> {
+        if self.conditional_formats.is_empty() {
+            return vec![];
+        }
+        let mut formats: Vec<_> = self
+            .conditional_cell_formats(grid, region)
+            .into_iter()
+            .filter(|(pos, _)| {
+                !self.offsets.is_column_hidden(pos.x) && !self.offsets.is_row_hidden(pos.y)
+            })
+            .collect();
+        formats.sort_by_key(|(pos, _)| (pos.y, pos.x));
+
+        let mut ret = vec![];
+        for (pos, format) in formats {
+            let fill = |color: String, data_bar: Option<f32>| JsRenderFill {
+                x: pos.x,
+                y: pos.y,
+                w: 1,
+                h: 1,
+                color,
+                data_bar,
+            };
+            if let Some(color) = format.style.fill_color {
+                ret.push(fill(color, None));
+            }
+            if let Some((color, fraction)) = format.data_bar {
+                ret.push(fill(color, Some(fraction)));
+            }
+        }
+        ret
+    }
+
+    /// Returns all fills from the sheet's conditional formats.
+    pub fn get_all_conditional_render_fills(&self, grid: &Grid) -> Vec<JsRenderFill> {
+        match self.bounds(true) {
+            GridBounds::NonEmpty(bounds) => self.get_conditional_render_fills(grid, bounds),
+            GridBounds::Empty => vec![],
+        }
+    }
+
     /// Returns data for all rendering code cells
     pub fn get_all_render_code_cells(&self) -> Vec<JsRenderCodeCell> {
         self.code_runs
@@ -366,6 +543,14 @@ impl Sheet {
     }
 }
 
+/// Returns the symbol of a currency format.
+pub(crate) fn currency_symbol(numeric_format: &Option<NumericFormat>) -> Option<String> {
+    numeric_format
+        .as_ref()
+        .filter(|format| format.kind == NumericFormatKind::Currency)
+        .and_then(|format| format.symbol.clone())
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashSet;
@@ -378,7 +563,7 @@ mod tests {
             js_types::{JsHtmlOutput, JsRenderCell, JsRenderCellSpecial},
             Bold, CellAlign, CodeCellLanguage, CodeRun, CodeRunResult, Italic, RenderSize, Sheet,
         },
-        CellValue, CodeCellValue, Pos, Rect, RunError, RunErrorMsg, SheetPos, Value,
+        CellValue, CodeCellValue, Image, Pos, Rect, RunError, RunErrorMsg, SheetPos, Value,
     };
 
     #[test]
@@ -481,7 +666,14 @@ mod tests {
                 wrap: None,
                 bold: Some(true),
                 italic: None,
+                underline: None,
+                strike_through: None,
                 text_color: None,
+                font_size: None,
+                font_family: None,
+                vertical_align: None,
+                text_rotation: None,
+                link: None,
                 special: None,
             },
         );
@@ -496,7 +688,14 @@ mod tests {
                 wrap: None,
                 bold: None,
                 italic: Some(true),
+                underline: None,
+                strike_through: None,
                 text_color: None,
+                font_size: None,
+                font_family: None,
+                vertical_align: None,
+                text_rotation: None,
+                link: None,
                 special: None,
             },
         );
@@ -511,7 +710,14 @@ mod tests {
                 wrap: None,
                 bold: None,
                 italic: None,
+                underline: None,
+                strike_through: None,
                 text_color: None,
+                font_size: None,
+                font_family: None,
+                vertical_align: None,
+                text_rotation: None,
+                link: None,
                 special: Some(JsRenderCellSpecial::Chart),
             },
         );
@@ -526,7 +732,14 @@ mod tests {
                 wrap: None,
                 bold: None,
                 italic: None,
+                underline: None,
+                strike_through: None,
                 text_color: None,
+                font_size: None,
+                font_family: None,
+                vertical_align: None,
+                text_rotation: None,
+                link: None,
                 special: Some(JsRenderCellSpecial::True),
             },
         );
@@ -541,7 +754,14 @@ mod tests {
                 wrap: None,
                 bold: None,
                 italic: None,
+                underline: None,
+                strike_through: None,
                 text_color: None,
+                font_size: None,
+                font_family: None,
+                vertical_align: None,
+                text_rotation: None,
+                link: None,
                 special: Some(JsRenderCellSpecial::SpillError),
             },
         );
@@ -556,7 +776,14 @@ mod tests {
                 wrap: None,
                 bold: None,
                 italic: None,
+                underline: None,
+                strike_through: None,
                 text_color: None,
+                font_size: None,
+                font_family: None,
+                vertical_align: None,
+                text_rotation: None,
+                link: None,
                 special: Some(JsRenderCellSpecial::RunError),
             },
         );
@@ -633,6 +860,65 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_get_image_output() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let sheet_pos = SheetPos {
+            x: 1,
+            y: 2,
+            sheet_id,
+        };
+        gc.set_code_cell(sheet_pos, CodeCellLanguage::Python, "fig".to_string(), None);
+        let transaction_id = gc.async_transactions()[0].id;
+        let image = Image::test();
+        gc.calculation_complete(JsCodeResult::new(
+            transaction_id.to_string(),
+            true,
+            None,
+            None,
+            None,
+            Some(vec![image.to_data_url(), "image".into()]),
+            None,
+            None,
+            None,
+            None,
+        ))
+        .ok();
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(
+            sheet.display_value(sheet_pos.into()),
+            Some(CellValue::Image(image.clone()))
+        );
+        assert_eq!(
+            sheet.get_render_cells(Rect::single_pos(sheet_pos.into()))[0].special,
+            Some(JsRenderCellSpecial::Chart)
+        );
+
+        // images start at their pixel size
+        let output = sheet.get_html_output();
+        assert_eq!(output.len(), 1);
+        assert!(output[0].html.contains(&image.to_data_url()));
+        assert_eq!(
+            (output[0].w.as_deref(), output[0].h.as_deref()),
+            (Some("2"), Some("3"))
+        );
+
+        gc.set_cell_render_size(
+            sheet_pos.into(),
+            Some(RenderSize {
+                w: "200".into(),
+                h: "300".into(),
+            }),
+            None,
+        );
+        let output = gc.sheet(sheet_id).get_html_output();
+        assert_eq!(
+            (output[0].w.as_deref(), output[0].h.as_deref()),
+            (Some("200"), Some("300"))
+        );
+    }
+
     #[test]
     fn test_get_code_cells() {
         let sheet = Sheet::test();
@@ -693,6 +979,39 @@ mod tests {
         assert_eq!(code_cells[0].language, Some(CodeCellLanguage::Python));
     }
 
+    #[test]
+    fn test_get_render_cells_link() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_cell_value(
+            SheetPos {
+                x: 0,
+                y: 0,
+                sheet_id,
+            },
+            "https://quadratichq.com".into(),
+            None,
+        );
+        gc.set_code_cell(
+            SheetPos {
+                x: 1,
+                y: 0,
+                sheet_id,
+            },
+            CodeCellLanguage::Formula,
+            "HYPERLINK(\"#A5\", \"totals\")".into(),
+            None,
+        );
+
+        let render_cells = gc
+            .sheet(sheet_id)
+            .get_render_cells(Rect::from_numbers(0, 0, 2, 1));
+        assert_eq!(render_cells[0].value, "https://quadratichq.com");
+        assert_eq!(render_cells[0].link, Some("https://quadratichq.com".into()));
+        assert_eq!(render_cells[1].value, "totals");
+        assert_eq!(render_cells[1].link, Some("#A5".into()));
+    }
+
     #[test]
     fn test_get_render_cells_code() {
         let mut gc = GridController::test();
@@ -720,7 +1039,14 @@ mod tests {
                 wrap: None,
                 bold: None,
                 italic: None,
+                underline: None,
+                strike_through: None,
                 text_color: None,
+                font_size: None,
+                font_family: None,
+                vertical_align: None,
+                text_rotation: None,
+                link: None,
                 special: None,
             }]
         );
@@ -1,11 +1,36 @@
+use regex::{Regex, RegexBuilder};
+
 use crate::{
-    grid::{CodeRunResult, Column},
-    CellValue, Pos, SheetPos, Value,
+    formulas::Criterion, grid::CodeRunResult, CellValue, Pos, SheetPos, SheetRect, Spanned, Value,
 };
 
 use super::Sheet;
 use serde::{Deserialize, Serialize};
 
+/// Which value of a number cell is compared to the query.
+#[derive(Default, Debug, Serialize, Deserialize, PartialEq, Clone, Copy, Eq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub enum SearchValueMode {
+    /// matches either the raw number or its formatted display value
+    #[default]
+    Both,
+
+    /// matches the raw number (eg, 0.1)
+    Raw,
+
+    /// matches the formatted display value (eg, 10%)
+    Display,
+}
+
+/// A single match of a search: the cell and which match within the cell's
+/// text (0 for the first).
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy, Eq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub struct SearchMatch {
+    pub sheet_pos: SheetPos,
+    pub index: usize,
+}
+
 #[derive(Default, Debug, Serialize, Deserialize, PartialEq, Clone, Eq)]
 #[cfg_attr(feature = "js", derive(ts_rs::TS))]
 pub struct SearchOptions {
@@ -15,83 +40,131 @@ pub struct SearchOptions {
     pub whole_cell: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub search_code: Option<bool>,
+
+    /// also matches the text of cell comments
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub search_comments: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sheet_id: Option<String>,
+
+    /// treats the query as a regular expression
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub regex: Option<bool>,
+
+    /// treats the query as a criteria (eg, `>1000` or `<>apple`), the same as
+    /// the criteria in SUMIF
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub criteria: Option<bool>,
+
+    /// limits the search to a selection (this overrides sheet_id)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selection: Option<SheetRect>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_mode: Option<SearchValueMode>,
+}
+
+/// A query compiled from a search string and SearchOptions.
+#[derive(Debug, Clone)]
+pub struct SearchQuery {
+    /// matches cell text (anchored when searching whole cells)
+    pub regex: Regex,
+
+    /// matches anywhere within code
+    pub code_regex: Regex,
+
+    /// matches the entire cell (used for logical values)
+    whole_regex: Regex,
+
+    pub criterion: Option<Criterion>,
+    pub search_code: bool,
+    pub search_comments: bool,
+    pub value_mode: SearchValueMode,
+}
+
+impl SearchQuery {
+    /// Compiles a query. Returns None if the query is not a valid regex or
+    /// criteria.
+    pub fn new(query: &str, options: &SearchOptions) -> Option<Self> {
+        let case_sensitive = options.case_sensitive.unwrap_or(false);
+        let whole_cell = options.whole_cell.unwrap_or(false);
+        let pattern = if options.regex.unwrap_or(false) {
+            query.to_string()
+        } else {
+            regex::escape(query)
+        };
+        let build = |pattern: &str| {
+            RegexBuilder::new(pattern)
+                .case_insensitive(!case_sensitive)
+                .build()
+                .ok()
+        };
+        let whole_pattern = format!("^(?:{pattern})$");
+        let criterion = if options.criteria.unwrap_or(false) {
+            let value = CellValue::Text(query.to_string());
+            Some(Criterion::try_from(Spanned::new(0, 0, &value)).ok()?)
+        } else {
+            None
+        };
+        Some(SearchQuery {
+            regex: build(if whole_cell { &whole_pattern } else { &pattern })?,
+            code_regex: build(&pattern)?,
+            whole_regex: build(&whole_pattern)?,
+            criterion,
+            search_code: options.search_code.unwrap_or(false),
+            search_comments: options.search_comments.unwrap_or(false),
+            value_mode: options.value_mode.unwrap_or_default(),
+        })
+    }
 }
 
 impl Sheet {
     /// Compares a CellValue to a query.
-    /// Note: Column and y are necessary to compare display value for CellValue::Number (regrettably).
+    /// Note: pos is necessary to compare display value for CellValue::Number (regrettably).
     ///
     /// Returns true if the cell value matches the query.
-    #[allow(clippy::too_many_arguments)]
     fn compare_cell_value(
         &self,
         cell_value: &CellValue,
-        query: &String,
-        column: Option<&Column>,
+        query: &SearchQuery,
         pos: Pos,
-        case_sensitive: bool,
-        whole_cell: bool,
         search_code: bool,
     ) -> bool {
+        if let Some(criterion) = &query.criterion {
+            return match cell_value {
+                CellValue::Code(_)
+                | CellValue::Html(_)
+                | CellValue::Image(_)
+                | CellValue::Blank => false,
+                _ => criterion.matches(cell_value),
+            };
+        }
         match cell_value {
-            CellValue::Text(text) => {
-                if (case_sensitive && text == query)
-                    || (!case_sensitive && text.to_lowercase() == *query)
-                {
-                    true
-                } else if !whole_cell {
-                    (!case_sensitive && text.to_lowercase().contains(&query.to_lowercase()))
-                        || (case_sensitive && text.contains(query))
-                } else {
-                    false
-                }
-            }
+            CellValue::Text(text) => query.regex.is_match(text),
+            CellValue::Link(link) => query.regex.is_match(&link.text),
             CellValue::Number(n) => {
                 // first test against unformatted number
-                if n.to_string() == *query || (!whole_cell && n.to_string().contains(query)) {
+                if query.value_mode != SearchValueMode::Display
+                    && query.regex.is_match(&n.to_string())
+                {
                     true
-                } else {
-                    // test against any formatting applied to the number
-                    if let Some(column) = column.map_or(self.get_column(pos.x), Some) {
-                        // compare the number using its display value (eg, $ or % or commas)
-                        let numeric_format = column.numeric_format.get(pos.y);
-                        let numeric_decimals = column.numeric_decimals.get(pos.y);
-                        let numeric_commas = column.numeric_commas.get(pos.y);
-                        let display =
-                            cell_value.to_display(numeric_format, numeric_decimals, numeric_commas);
-                        display == *query || (!whole_cell && display.contains(query))
-                    } else {
-                        false
-                    }
-                }
-            }
-            CellValue::Logical(b) => {
-                let query = query.to_lowercase();
-                (*b && query == "true") || (!(*b) && query == "false")
-            }
-            CellValue::Code(code) => {
-                if search_code {
-                    let code = &code.code;
-                    (case_sensitive && code.contains(query))
-                        || (!case_sensitive && code.to_lowercase().contains(query))
-                } else {
+                } else if query.value_mode == SearchValueMode::Raw {
                     false
+                } else {
+                    // compare the number using its display value (eg, $ or % or commas)
+                    query.regex.is_match(&self.format_value(pos, cell_value))
                 }
             }
+            CellValue::Logical(b) => query
+                .whole_regex
+                .is_match(if *b { "true" } else { "false" }),
+            CellValue::Code(code) => search_code && query.code_regex.is_match(&code.code),
             _ => false,
         }
     }
 
     /// Searches the column.values for a match to the query string.
-    fn search_cell_values(
-        &self,
-        query: &String,
-        case_sensitive: bool,
-        whole_cell: bool,
-        search_code: bool,
-    ) -> Vec<SheetPos> {
+    fn search_cell_values(&self, query: &SearchQuery) -> Vec<SheetPos> {
         self.columns
             .iter()
             .flat_map(|(x, column)| {
@@ -99,11 +172,8 @@ impl Sheet {
                     if self.compare_cell_value(
                         cell_value,
                         query,
-                        Some(column),
                         Pos { x: *x, y: *y },
-                        case_sensitive,
-                        whole_cell,
-                        search_code,
+                        query.search_code,
                     ) {
                         Some(SheetPos {
                             x: *x,
@@ -118,12 +188,7 @@ impl Sheet {
             .collect::<Vec<_>>()
     }
 
-    fn search_code_runs(
-        &self,
-        query: &String,
-        case_sensitive: bool,
-        whole_cell: bool,
-    ) -> Vec<SheetPos> {
+    fn search_code_runs(&self, query: &SearchQuery) -> Vec<SheetPos> {
         let mut results = vec![];
         self.code_runs
             .iter()
@@ -131,12 +196,7 @@ impl Sheet {
                 CodeRunResult::Ok(value) => match value {
                     Value::Single(v) => {
                         if self.compare_cell_value(
-                            v,
-                            query,
-                            None,
-                            *pos,
-                            case_sensitive,
-                            whole_cell,
+                            v, query, *pos,
                             false, // code_runs can never have code within them (although that would be cool if they did ;)
                         ) {
                             results.push(pos.to_sheet_pos(self.id));
@@ -149,13 +209,10 @@ impl Sheet {
                                 if self.compare_cell_value(
                                     cell_value,
                                     query,
-                                    None,
                                     Pos {
                                         x: pos.x + x as i64,
                                         y: pos.y + y as i64,
                                     },
-                                    case_sensitive,
-                                    whole_cell,
                                     false, // code_runs can never have code within them (although that would be cool if they did ;)
                                 ) {
                                     results.push(SheetPos {
@@ -173,21 +230,39 @@ impl Sheet {
         results
     }
 
+    /// Searches the text of comment threads for a match to the query string.
+    /// Comments are not compared to criteria.
+    fn search_comments(&self, query: &SearchQuery) -> Vec<SheetPos> {
+        if !query.search_comments || query.criterion.is_some() {
+            return vec![];
+        }
+        self.comments
+            .iter()
+            .filter(|(_, thread)| {
+                thread
+                    .comments
+                    .iter()
+                    .any(|comment| query.code_regex.is_match(&comment.text))
+            })
+            .map(|(pos, _)| pos.to_sheet_pos(self.id))
+            .collect()
+    }
+
     /// Searches the Sheet for a match to the query string.
     /// Returns the resulting SheetPos sorted by x and then y.
     ///
-    /// Returns `Vec<SheetPos>` for all cells that match.
+    /// Returns `Vec<SheetPos>` for all cells that match (or none if the
+    /// query is an invalid regex or criteria).
     pub fn search(&self, query: &String, options: &SearchOptions) -> Vec<SheetPos> {
-        let case_sensitive = options.case_sensitive.unwrap_or(false);
-        let query = if case_sensitive {
-            query.to_owned()
-        } else {
-            query.to_lowercase()
+        let Some(query) = SearchQuery::new(query, options) else {
+            return vec![];
         };
-        let whole_cell = options.whole_cell.unwrap_or(false);
-        let search_code = options.search_code.unwrap_or(false);
-        let mut results = self.search_cell_values(&query, case_sensitive, whole_cell, search_code);
-        results.extend(self.search_code_runs(&query, case_sensitive, whole_cell));
+        let mut results = self.search_cell_values(&query);
+        results.extend(self.search_code_runs(&query));
+        results.extend(self.search_comments(&query));
+        if let Some(selection) = options.selection {
+            results.retain(|sheet_pos| selection.contains(*sheet_pos));
+        }
         results.sort_by(|a, b| {
             let order = a.x.cmp(&b.x);
             if order == std::cmp::Ordering::Equal {
@@ -196,6 +271,7 @@ impl Sheet {
                 order
             }
         });
+        results.dedup();
         results
     }
 }
@@ -550,4 +626,124 @@ mod test {
         assert_eq!(results.len(), 1);
         assert_eq!(results[0], SheetPos::new(sheet.id, 3, 3));
     }
+
+    #[test]
+    fn regex_search() {
+        let mut sheet = Sheet::test();
+        sheet.set_cell_value(Pos { x: 0, y: 0 }, CellValue::Text("abc-123".into()));
+        sheet.set_cell_value(Pos { x: 0, y: 1 }, CellValue::Text("ABC-4".into()));
+        sheet.set_cell_value(Pos { x: 0, y: 2 }, CellValue::Text("x abc-5".into()));
+        sheet.set_cell_value(Pos { x: 0, y: 3 }, CellValue::Number(456.into()));
+
+        let regex = SearchOptions {
+            regex: Some(true),
+            ..Default::default()
+        };
+        let results = sheet.search(&r"abc-\d+".into(), &regex);
+        assert_eq!(results.len(), 3);
+
+        let results = sheet.search(
+            &r"abc-\d+".into(),
+            &SearchOptions {
+                case_sensitive: Some(true),
+                whole_cell: Some(true),
+                ..regex.clone()
+            },
+        );
+        assert_eq!(results, vec![SheetPos::new(sheet.id, 0, 0)]);
+
+        let results = sheet.search(&r"^\d{3}$".into(), &regex);
+        assert_eq!(results, vec![SheetPos::new(sheet.id, 0, 3)]);
+
+        // invalid regex finds nothing
+        assert!(sheet.search(&"abc(".into(), &regex).is_empty());
+
+        // without regex, the query is literal
+        assert!(sheet
+            .search(&r"abc-\d+".into(), &SearchOptions::default())
+            .is_empty());
+    }
+
+    #[test]
+    fn criteria_search() {
+        let mut sheet = Sheet::test();
+        sheet.set_cell_value(Pos { x: 0, y: 0 }, CellValue::Number(500.into()));
+        sheet.set_cell_value(Pos { x: 0, y: 1 }, CellValue::Number(1500.into()));
+        sheet.set_cell_value(Pos { x: 0, y: 2 }, CellValue::Text("2000".into()));
+        sheet.set_cell_value(Pos { x: 0, y: 3 }, CellValue::Text("apple".into()));
+
+        let criteria = SearchOptions {
+            criteria: Some(true),
+            ..Default::default()
+        };
+        let results = sheet.search(&">1000".into(), &criteria);
+        assert_eq!(results, vec![SheetPos::new(sheet.id, 0, 1)]);
+
+        let results = sheet.search(&"<=1500".into(), &criteria);
+        assert_eq!(results.len(), 2);
+
+        let results = sheet.search(&"APPLE".into(), &criteria);
+        assert_eq!(results, vec![SheetPos::new(sheet.id, 0, 3)]);
+
+        let results = sheet.search(&"a*".into(), &criteria);
+        assert_eq!(results, vec![SheetPos::new(sheet.id, 0, 3)]);
+    }
+
+    #[test]
+    fn selection_search() {
+        let mut sheet = Sheet::test();
+        sheet.set_cell_value(Pos { x: 0, y: 0 }, CellValue::Text("hello".into()));
+        sheet.set_cell_value(Pos { x: 5, y: 5 }, CellValue::Text("hello".into()));
+        sheet.set_cell_value(Pos { x: 10, y: 10 }, CellValue::Text("hello".into()));
+
+        let options = SearchOptions {
+            selection: Some(SheetRect::new_pos_span(
+                Pos { x: 1, y: 1 },
+                Pos { x: 10, y: 9 },
+                sheet.id,
+            )),
+            ..Default::default()
+        };
+        let results = sheet.search(&"hello".into(), &options);
+        assert_eq!(results, vec![SheetPos::new(sheet.id, 5, 5)]);
+    }
+
+    #[test]
+    fn value_mode_search() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_cell_value(
+            SheetPos {
+                x: 0,
+                y: 0,
+                sheet_id,
+            },
+            "10%".to_string(),
+            None,
+        );
+        let sheet = gc.sheet(sheet_id);
+
+        let raw = SearchOptions {
+            value_mode: Some(SearchValueMode::Raw),
+            ..Default::default()
+        };
+        assert_eq!(sheet.search(&"0.1".into(), &raw).len(), 1);
+        assert_eq!(sheet.search(&"10%".into(), &raw).len(), 0);
+
+        let display = SearchOptions {
+            value_mode: Some(SearchValueMode::Display),
+            ..Default::default()
+        };
+        assert_eq!(sheet.search(&"0.1".into(), &display).len(), 0);
+        assert_eq!(sheet.search(&"10%".into(), &display).len(), 1);
+
+        assert_eq!(
+            sheet.search(&"0.1".into(), &SearchOptions::default()).len(),
+            1
+        );
+        assert_eq!(
+            sheet.search(&"10%".into(), &SearchOptions::default()).len(),
+            1
+        );
+    }
 }
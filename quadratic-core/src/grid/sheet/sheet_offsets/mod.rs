@@ -107,6 +107,35 @@ impl SheetOffsets {
         old
     }
 
+    /// Hides or unhides a column and returns whether it was hidden.
+    pub fn set_column_hidden(&mut self, x: i64, hidden: bool) -> bool {
+        let old = self.column_widths.set_hidden(x, hidden);
+        self.calculate_thumbnail();
+        old
+    }
+    /// Hides or unhides a row and returns whether it was hidden.
+    pub fn set_row_hidden(&mut self, y: i64, hidden: bool) -> bool {
+        let old = self.row_heights.set_hidden(y, hidden);
+        self.calculate_thumbnail();
+        old
+    }
+
+    pub fn is_column_hidden(&self, x: i64) -> bool {
+        self.column_widths.is_hidden(x)
+    }
+
+    pub fn is_row_hidden(&self, y: i64) -> bool {
+        self.row_heights.is_hidden(y)
+    }
+
+    pub fn hidden_columns(&self) -> Vec<i64> {
+        self.column_widths.iter_hidden().collect()
+    }
+
+    pub fn hidden_rows(&self) -> Vec<i64> {
+        self.row_heights.iter_hidden().collect()
+    }
+
     pub fn column_width(&self, x: i64) -> f64 {
         self.column_widths.get_size(x)
     }
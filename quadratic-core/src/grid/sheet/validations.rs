@@ -0,0 +1,304 @@
+use bigdecimal::ToPrimitive;
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use super::Sheet;
+use crate::{
+    formulas::{escape_string, parse_formula, replace_cell_references, Ctx, RangeRef},
+    grid::Grid,
+    CellValue, Instant, Pos, Rect, SheetPos, SheetRect, Value,
+};
+
+/// What a value must satisfy to pass a [`Validation`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+#[serde(tag = "type")]
+pub enum ValidationCriteria {
+    /// One of a literal list of values (case-insensitive).
+    List { values: Vec<String> },
+    /// One of the values displayed in a range.
+    ListFromRange { range: SheetRect },
+    /// A number between the (inclusive) bounds.
+    Number { min: Option<f64>, max: Option<f64> },
+    /// A date between the (inclusive) bounds.
+    Date {
+        min: Option<Instant>,
+        max: Option<Instant>,
+    },
+    /// Text whose length in characters is between the (inclusive) bounds.
+    TextLength { min: Option<u32>, max: Option<u32> },
+    /// A formula that evaluates to TRUE. The formula is written relative to
+    /// the top-left cell of the validation.
+    Custom { formula: String },
+}
+
+/// What happens when a value fails a [`Validation`].
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub enum ValidationAction {
+    /// The value is not set.
+    Reject,
+    /// The value is set, but the user is warned.
+    Warn,
+}
+
+/// A data validation rule that applies to a rect of cells.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub struct Validation {
+    pub rect: Rect,
+    pub criteria: ValidationCriteria,
+    pub action: ValidationAction,
+
+    /// Blank values always pass.
+    #[serde(default)]
+    pub ignore_blank: bool,
+
+    /// Message shown when a value fails the rule.
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+impl Validation {
+    /// Returns the values allowed by a list validation.
+    pub fn list_values(&self, grid: &Grid) -> Option<Vec<String>> {
+        match &self.criteria {
+            ValidationCriteria::List { values } => Some(values.clone()),
+            ValidationCriteria::ListFromRange { range } => {
+                let sheet = grid.try_sheet(range.sheet_id)?;
+                Some(
+                    range
+                        .iter()
+                        .filter_map(|sheet_pos| sheet.display_value(sheet_pos.into()))
+                        .filter(|value| !value.is_blank_or_empty_string())
+                        .map(|value| value.to_string())
+                        .collect(),
+                )
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns true if the value passes the validation at sheet_pos.
+    pub fn is_valid(&self, grid: &Grid, sheet_pos: SheetPos, value: &CellValue) -> bool {
+        if value.is_blank_or_empty_string() && self.ignore_blank {
+            return true;
+        }
+        let within = |n: f64, min: Option<f64>, max: Option<f64>| {
+            !min.is_some_and(|min| n < min) && !max.is_some_and(|max| n > max)
+        };
+        match &self.criteria {
+            ValidationCriteria::List { .. } | ValidationCriteria::ListFromRange { .. } => {
+                let value = value.to_string();
+                self.list_values(grid)
+                    .is_some_and(|values| values.iter().any(|v| v.eq_ignore_ascii_case(&value)))
+            }
+            ValidationCriteria::Number { min, max } => match value {
+                CellValue::Number(n) => n.to_f64().is_some_and(|n| within(n, *min, *max)),
+                _ => false,
+            },
+            ValidationCriteria::Date { min, max } => date_seconds(value).is_some_and(|seconds| {
+                within(seconds, min.map(|i| i.seconds), max.map(|i| i.seconds))
+            }),
+            ValidationCriteria::TextLength { min, max } => match value {
+                CellValue::Text(text) => within(
+                    text.chars().count() as f64,
+                    min.map(f64::from),
+                    max.map(f64::from),
+                ),
+                _ => false,
+            },
+            ValidationCriteria::Custom { formula } => {
+                custom_formula_is_valid(grid, formula, self.rect.min, sheet_pos, value)
+            }
+        }
+    }
+
+    /// Returns the message shown when a value fails the validation.
+    pub fn error_message(&self) -> String {
+        if let Some(message) = &self.message {
+            return message.clone();
+        }
+        let bounds = |min: Option<String>, max: Option<String>| match (min, max) {
+            (Some(min), Some(max)) => format!(" between {min} and {max}"),
+            (Some(min), None) => format!(" of at least {min}"),
+            (None, Some(max)) => format!(" of at most {max}"),
+            (None, None) => String::new(),
+        };
+        match &self.criteria {
+            ValidationCriteria::List { values } => {
+                format!("Value must be one of: {}", values.join(", "))
+            }
+            ValidationCriteria::ListFromRange { .. } => {
+                "Value must be one of the values in the list".to_string()
+            }
+            ValidationCriteria::Number { min, max } => format!(
+                "Value must be a number{}",
+                bounds(min.map(|n| n.to_string()), max.map(|n| n.to_string()))
+            ),
+            ValidationCriteria::Date { min, max } => format!(
+                "Value must be a date{}",
+                bounds(min.map(|i| i.to_string()), max.map(|i| i.to_string()))
+            ),
+            ValidationCriteria::TextLength { min, max } => format!(
+                "Value must be text with a length{}",
+                bounds(min.map(|n| n.to_string()), max.map(|n| n.to_string()))
+            ),
+            ValidationCriteria::Custom { formula } => {
+                format!("Value must satisfy the formula: {formula}")
+            }
+        }
+    }
+}
+
+/// Returns the value as seconds since the epoch, if it is a date.
+fn date_seconds(value: &CellValue) -> Option<f64> {
+    match value {
+        CellValue::Instant(instant) => Some(instant.seconds),
+        CellValue::Text(text) => {
+            let text = text.trim();
+            let datetime = NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S")
+                .ok()
+                .or_else(|| {
+                    ["%Y-%m-%d", "%m/%d/%Y"]
+                        .iter()
+                        .find_map(|format| NaiveDate::parse_from_str(text, format).ok())
+                        .and_then(|date| date.and_hms_opt(0, 0, 0))
+                })?;
+            Some(Instant::from(datetime).seconds)
+        }
+        _ => None,
+    }
+}
+
+/// Evaluates a custom validation formula for the value at sheet_pos. The
+/// value may not be in the grid yet, so references to the cell itself are
+/// replaced by the value. Also used by custom conditional formats.
+pub(super) fn custom_formula_is_valid(
+    grid: &Grid,
+    formula: &str,
+    origin: Pos,
+    sheet_pos: SheetPos,
+    value: &CellValue,
+) -> bool {
+    let pos: Pos = sheet_pos.into();
+    let literal = match value {
+        CellValue::Number(n) => n.to_string(),
+        CellValue::Logical(true) => "TRUE".to_string(),
+        CellValue::Logical(false) => "FALSE".to_string(),
+        _ => escape_string(&value.to_string()),
+    };
+    let formula = replace_cell_references(formula, origin, |range_ref| match range_ref {
+        RangeRef::Cell { pos: cell_ref }
+            if cell_ref.sheet.is_none() && cell_ref.resolve_from(pos) == pos =>
+        {
+            Some(literal.clone())
+        }
+        _ => None,
+    });
+
+    // references are relative to the origin, so evaluating at sheet_pos shifts them to this cell
+    let Ok(parsed) = parse_formula(&formula, origin) else {
+        return false;
+    };
+    match parsed.eval(&mut Ctx::new(grid, sheet_pos)) {
+        Ok(Value::Single(result)) => bool::try_from(&result).unwrap_or(false),
+        _ => false,
+    }
+}
+
+impl Sheet {
+    /// Returns the validation that applies to the position. Later validations
+    /// take precedence over earlier ones.
+    pub fn validation_at(&self, pos: Pos) -> Option<&Validation> {
+        self.validations
+            .iter()
+            .rev()
+            .find(|validation| validation.rect.contains(pos))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::controller::GridController;
+
+    fn validation(criteria: ValidationCriteria) -> Validation {
+        Validation {
+            rect: Rect::from_numbers(0, 0, 2, 2),
+            criteria,
+            action: ValidationAction::Reject,
+            ignore_blank: true,
+            message: None,
+        }
+    }
+
+    #[test]
+    fn validates_criteria() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_cell_value(SheetPos::new(sheet_id, 5, 0), "apple".into(), None);
+        gc.set_cell_value(SheetPos::new(sheet_id, 5, 1), "pear".into(), None);
+        gc.set_cell_value(SheetPos::new(sheet_id, 6, 0), "10".into(), None);
+        gc.set_cell_value(SheetPos::new(sheet_id, 7, 1), "0".into(), None);
+        let grid = gc.grid();
+        let sheet_pos = SheetPos::new(sheet_id, 1, 1);
+        let text = |s: &str| CellValue::Text(s.into());
+        let number = |n: i64| CellValue::Number(n.into());
+
+        let list = validation(ValidationCriteria::List {
+            values: vec!["Yes".into(), "No".into()],
+        });
+        assert!(list.is_valid(grid, sheet_pos, &text("yes")));
+        assert!(!list.is_valid(grid, sheet_pos, &text("maybe")));
+        assert!(list.is_valid(grid, sheet_pos, &CellValue::Blank));
+
+        let range = validation(ValidationCriteria::ListFromRange {
+            range: SheetRect::from_numbers(5, 0, 1, 3, sheet_id),
+        });
+        assert_eq!(
+            range.list_values(grid),
+            Some(vec!["apple".to_string(), "pear".to_string()])
+        );
+        assert!(range.is_valid(grid, sheet_pos, &text("Pear")));
+        assert!(!range.is_valid(grid, sheet_pos, &text("plum")));
+
+        let numbers = validation(ValidationCriteria::Number {
+            min: Some(1.0),
+            max: Some(10.0),
+        });
+        assert!(numbers.is_valid(grid, sheet_pos, &number(10)));
+        assert!(!numbers.is_valid(grid, sheet_pos, &number(11)));
+        assert!(!numbers.is_valid(grid, sheet_pos, &text("5")));
+
+        let dates = validation(ValidationCriteria::Date {
+            min: date_seconds(&text("2024-01-01")).map(Instant::new),
+            max: None,
+        });
+        assert!(dates.is_valid(grid, sheet_pos, &text("2024-02-29")));
+        assert!(dates.is_valid(grid, sheet_pos, &text("03/01/2024")));
+        assert!(!dates.is_valid(grid, sheet_pos, &text("2023-12-31")));
+        assert!(!dates.is_valid(grid, sheet_pos, &text("soon")));
+
+        let length = validation(ValidationCriteria::TextLength {
+            min: None,
+            max: Some(3),
+        });
+        assert!(length.is_valid(grid, sheet_pos, &text("abc")));
+        assert!(!length.is_valid(grid, sheet_pos, &text("abcd")));
+
+        // A0 is the cell itself; G0 shifts to H1 for the cell at B1
+        let custom = validation(ValidationCriteria::Custom {
+            formula: "A0 > G0".into(),
+        });
+        assert!(custom.is_valid(grid, SheetPos::new(sheet_id, 0, 0), &number(11)));
+        assert!(!custom.is_valid(grid, SheetPos::new(sheet_id, 0, 0), &number(9)));
+        assert!(custom.is_valid(grid, sheet_pos, &number(1)));
+        assert!(!custom.is_valid(grid, sheet_pos, &number(-1)));
+
+        assert_eq!(
+            numbers.error_message(),
+            "Value must be a number between 1 and 10"
+        );
+    }
+}
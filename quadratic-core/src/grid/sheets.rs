@@ -112,6 +112,7 @@ impl Grid {
                 break;
             }
         }
+        sheet.locale = self.locale;
         self.sheets.push(sheet);
         self.sort_sheets();
         id
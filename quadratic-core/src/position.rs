@@ -221,6 +221,20 @@ impl Rect {
             max: Pos { x: max_x, y: max_y },
         }
     }
+
+    /// Returns the overlap of two rectangles, if any.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        self.intersects(*other).then(|| Rect {
+            min: Pos {
+                x: self.min.x.max(other.min.x),
+                y: self.min.y.max(other.min.y),
+            },
+            max: Pos {
+                x: self.max.x.min(other.max.x),
+                y: self.max.y.min(other.max.y),
+            },
+        })
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Default, Copy, Clone)]
@@ -256,6 +270,7 @@ impl From<(i64, i64, SheetId)> for SheetPos {
 
 /// Used for referencing a range during computation.
 #[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
 pub struct SheetRect {
     /// Upper-left corner.
     pub min: Pos,
@@ -704,6 +719,17 @@ mod test {
         assert_eq!(rect.max, Pos { x: 5, y: 7 });
     }
 
+    #[test]
+    fn test_rect_intersection() {
+        let rect1 = Rect::from_numbers(1, 2, 3, 4);
+        let rect2 = Rect::from_numbers(2, 3, 4, 5);
+        assert_eq!(
+            rect1.intersection(&rect2),
+            Some(Rect::from_numbers(2, 3, 2, 3))
+        );
+        assert_eq!(rect1.intersection(&Rect::from_numbers(10, 10, 1, 1)), None);
+    }
+
     #[test]
     fn test_sheet_rect_union() {
         let sheet_id = SheetId::new();
@@ -263,6 +263,18 @@ pub fn unused_name(prefix: &str, already_used: &[&str]) -> String {
     }
 }
 
+/// Escapes text for XML content and attribute values. Control characters
+/// other than tab, newline and carriage return are not allowed in XML 1.0
+/// (even escaped), so they are removed.
+pub fn escape_xml(value: &str) -> String {
+    value
+        .replace(|c: char| c < ' ' && !matches!(c, '\t' | '\n' | '\r'), "")
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
 pub fn maybe_reverse_range(
     range: Range<i64>,
     rev: bool,
@@ -337,6 +349,16 @@ pub(crate) fn assert_f64_approx_eq(expected: f64, actual: &str) {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_escape_xml() {
+        assert_eq!(
+            escape_xml("<a href=\"x\">&</a>"),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;"
+        );
+        assert_eq!(escape_xml("a\u{0}b\u{8}\u{b}\u{c}\u{e}\u{1f}c"), "abc");
+        assert_eq!(escape_xml("a\tb\nc\rd"), "a\tb\nc\rd");
+    }
+
     #[test]
     fn test_column_names() {
         // Test near 0
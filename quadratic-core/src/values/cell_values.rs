@@ -54,6 +54,15 @@ impl CellValues {
         Self { columns, w, h }
     }
 
+    /// Swaps rows and columns.
+    pub fn transpose(&self) -> Self {
+        let mut transposed = Self::new(self.h, self.w);
+        for (x, y, value) in self.into_iter() {
+            transposed.set(y, x, value.clone());
+        }
+        transposed
+    }
+
     pub fn into_iter(&self) -> impl Iterator<Item = (u32, u32, &CellValue)> {
         self.columns.iter().enumerate().flat_map(|(x, col)| {
             col.iter()
@@ -129,6 +138,17 @@ mod test {
         assert_eq!(cell_values.columns[1].len(), 0);
     }
 
+    #[test]
+    fn transpose() {
+        let cell_values = CellValues::from(vec![vec!["a", "b", "c"], vec!["d"]]);
+        let transposed = cell_values.transpose();
+        assert_eq!((transposed.w, transposed.h), (3, 2));
+        assert_eq!(transposed.get(0, 0), Some(&CellValue::from("a")));
+        assert_eq!(transposed.get(2, 0), Some(&CellValue::from("c")));
+        assert_eq!(transposed.get(0, 1), Some(&CellValue::from("d")));
+        assert_eq!(transposed.get(1, 1), None);
+    }
+
     #[test]
     fn get_set_remove() {
         let mut cell_values = CellValues::new(2, 3);
@@ -2,21 +2,21 @@ use std::{fmt, str::FromStr};
 
 use anyhow::{bail, Result};
 use bigdecimal::{BigDecimal, Signed, ToPrimitive, Zero};
-use chrono::{TimeZone, Utc};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
 
-use super::{Duration, Instant, IsBlank};
+use super::{Duration, Image, Instant, IsBlank, Link};
 use crate::{
     controller::operations::operation::Operation,
     grid::{
-        formatting::CellFmtArray, CodeCellLanguage, NumericDecimals, NumericFormat,
-        NumericFormatKind, Sheet,
+        formatting::CellFmtArray, locale::DateOrder, number_format::CustomNumberFormat,
+        CodeCellLanguage, NumericDecimals, NumericFormat, NumericFormatKind, Sheet,
     },
     CodeResult, Pos, RunError, RunLengthEncoding, SheetRect,
 };
 
 // todo: fill this out
-const CURRENCY_SYMBOLS: &str = "$€£¥";
+pub(crate) const CURRENCY_SYMBOLS: &str = "$€£¥";
 const PERCENTAGE_SYMBOL: char = '%';
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -52,6 +52,12 @@ pub enum CellValue {
     Html(String),
     #[cfg_attr(test, proptest(skip))]
     Code(CodeCellValue),
+    /// Hyperlink to a URL or to cells in the workbook.
+    #[cfg_attr(test, proptest(skip))]
+    Link(Link),
+    /// PNG, JPEG or SVG image.
+    #[cfg_attr(test, proptest(skip))]
+    Image(Image),
 }
 impl fmt::Display for CellValue {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -66,6 +72,8 @@ impl fmt::Display for CellValue {
             CellValue::Error(e) => write!(f, "{}", e.msg),
             CellValue::Html(s) => write!(f, "{}", s),
             CellValue::Code(code) => write!(f, "{:?}", code),
+            CellValue::Link(link) => write!(f, "{link}"),
+            CellValue::Image(image) => write!(f, "{image}"),
         }
     }
 }
@@ -90,6 +98,8 @@ impl CellValue {
             CellValue::Error(_) => "error",
             CellValue::Html(_) => "html",
             CellValue::Code(_) => "python",
+            CellValue::Link(_) => "link",
+            CellValue::Image(_) => "image",
         }
     }
     /// Returns a formula-source-code representation of the value.
@@ -100,11 +110,13 @@ impl CellValue {
             CellValue::Number(n) => n.to_string(),
             CellValue::Logical(true) => "TRUE".to_string(),
             CellValue::Logical(false) => "FALSE".to_string(),
-            CellValue::Instant(_) => todo!("repr of Instant"),
-            CellValue::Duration(_) => todo!("repr of Duration"),
+            CellValue::Instant(i) => format!("{:?}", i.to_string()),
+            CellValue::Duration(d) => format!("{:?}", d.to_string()),
             CellValue::Error(_) => "[error]".to_string(),
             CellValue::Html(s) => s.clone(),
             CellValue::Code(_) => todo!("repr of python"),
+            CellValue::Link(link) => format!("HYPERLINK({:?}, {:?})", link.url, link.text),
+            CellValue::Image(_) => "[image]".to_string(),
         }
     }
 
@@ -146,10 +158,24 @@ impl CellValue {
     ) -> String {
         match self {
             CellValue::Blank => String::new(),
-            CellValue::Text(s) => s.to_string(),
+            CellValue::Text(s) => match numeric_format {
+                Some(NumericFormat {
+                    kind: NumericFormatKind::Custom(code),
+                    ..
+                }) => CustomNumberFormat::cached(&code)
+                    .format_text(s)
+                    .unwrap_or_else(|| s.to_string()),
+                _ => s.to_string(),
+            },
             CellValue::Html(s) => s.to_string(),
+            CellValue::Link(link) => link.text.clone(),
+            // images are displayed by the client, not as text
+            CellValue::Image(_) => String::new(),
             CellValue::Number(n) => {
                 let numeric_format = numeric_format.unwrap_or_default();
+                if let NumericFormatKind::Custom(code) = &numeric_format.kind {
+                    return CustomNumberFormat::cached(code).format_number(n);
+                }
                 let use_commas = numeric_commas.is_some_and(|c| c)
                     || (numeric_commas.is_none()
                         && numeric_format.kind == NumericFormatKind::Currency);
@@ -205,12 +231,28 @@ impl CellValue {
                     }
                     NumericFormatKind::Number => number,
                     NumericFormatKind::Exponential => number,
+                    NumericFormatKind::Custom(_) | NumericFormatKind::DateTime(_) => number,
                 }
             }
             CellValue::Logical(true) => "true".to_string(),
             CellValue::Logical(false) => "false".to_string(),
-            CellValue::Instant(_) => todo!("repr of Instant"),
-            CellValue::Duration(_) => todo!("repr of Duration"),
+            CellValue::Instant(instant) => match numeric_format.map(|format| format.kind) {
+                Some(NumericFormatKind::DateTime(code))
+                    if code.eq_ignore_ascii_case("relative") =>
+                {
+                    instant.format_relative(Instant::now())
+                }
+                Some(NumericFormatKind::DateTime(code)) => instant.format(&code),
+                _ => instant.to_human(),
+            },
+            CellValue::Duration(duration) => match numeric_format.map(|format| format.kind) {
+                Some(NumericFormatKind::DateTime(code))
+                    if !code.eq_ignore_ascii_case("relative") =>
+                {
+                    duration.format_elapsed(&code)
+                }
+                _ => duration.to_human(),
+            },
             CellValue::Error(_) => "[error]".to_string(),
 
             // this should not render
@@ -223,11 +265,13 @@ impl CellValue {
             CellValue::Blank => String::new(),
             CellValue::Text(s) => s.to_string(),
             CellValue::Html(_) => String::new(),
+            CellValue::Image(_) => String::new(),
+            CellValue::Link(link) => link.text.clone(),
             CellValue::Number(n) => n.to_string(),
             CellValue::Logical(true) => "true".to_string(),
             CellValue::Logical(false) => "false".to_string(),
-            CellValue::Instant(_) => todo!("repr of Instant"),
-            CellValue::Duration(_) => todo!("repr of Duration"),
+            CellValue::Instant(i) => i.to_string(),
+            CellValue::Duration(d) => d.to_string(),
             CellValue::Error(_) => "[error]".to_string(),
 
             // this should not be editable
@@ -247,6 +291,15 @@ impl CellValue {
         None
     }
 
+    /// Returns a link, or the text alone if the URL is not allowed (see
+    /// [`Link::is_allowed_url`]).
+    pub fn link(text: String, url: String) -> CellValue {
+        match Link::new(text.clone(), url) {
+            Some(link) => CellValue::Link(link),
+            None => CellValue::Text(text),
+        }
+    }
+
     pub fn unpack_boolean(s: &str) -> Option<CellValue> {
         match s.to_ascii_lowercase().as_str() {
             "true" => Some(CellValue::Logical(true)),
@@ -285,20 +338,105 @@ impl CellValue {
         })
     }
 
+    /// Parses typed dates and times such as `2024-03-01`, `3/1/2024 14:00`,
+    /// `Mar 1, 2024` or `14:00`, with the day, month, and year of numeric
+    /// dates in `date_order`. Returns the instant and the date/time format
+    /// code that displays it the way it was typed. Times without a date are
+    /// on 1970-01-01.
+    pub fn unpack_date_time(value: &str, date_order: DateOrder) -> Option<(Instant, &'static str)> {
+        const ISO_DATE_TIMES: [(&str, &str); 3] = [
+            ("%Y-%m-%d %H:%M:%S", "yyyy-mm-dd hh:mm:ss"),
+            ("%Y-%m-%dT%H:%M:%S", "yyyy-mm-dd hh:mm:ss"),
+            ("%Y-%m-%d %H:%M", "yyyy-mm-dd hh:mm"),
+        ];
+        const MDY_DATE_TIMES: [(&str, &str); 4] = [
+            ("%m/%d/%Y %H:%M:%S", "m/d/yyyy hh:mm:ss"),
+            ("%m/%d/%Y %H:%M", "m/d/yyyy hh:mm"),
+            ("%m/%d/%Y %I:%M %p", "m/d/yyyy h:mm AM/PM"),
+            ("%b %d, %Y %H:%M", "mmm d, yyyy hh:mm"),
+        ];
+        const DMY_DATE_TIMES: [(&str, &str); 4] = [
+            ("%d/%m/%Y %H:%M:%S", "d/m/yyyy hh:mm:ss"),
+            ("%d/%m/%Y %H:%M", "d/m/yyyy hh:mm"),
+            ("%d.%m.%Y %H:%M:%S", "d.m.yyyy hh:mm:ss"),
+            ("%d.%m.%Y %H:%M", "d.m.yyyy hh:mm"),
+        ];
+        const YMD_DATE_TIMES: [(&str, &str); 2] = [
+            ("%Y/%m/%d %H:%M:%S", "yyyy/m/d hh:mm:ss"),
+            ("%Y/%m/%d %H:%M", "yyyy/m/d hh:mm"),
+        ];
+        const MDY_DATES: [(&str, &str); 3] = [
+            ("%m/%d/%Y", "m/d/yyyy"),
+            ("%b %d, %Y", "mmm d, yyyy"),
+            ("%B %d, %Y", "mmmm d, yyyy"),
+        ];
+        const DMY_DATES: [(&str, &str); 4] = [
+            ("%d/%m/%Y", "d/m/yyyy"),
+            ("%d.%m.%Y", "d.m.yyyy"),
+            ("%d %b %Y", "d mmm yyyy"),
+            ("%d %B %Y", "d mmmm yyyy"),
+        ];
+        const YMD_DATES: [(&str, &str); 1] = [("%Y/%m/%d", "yyyy/m/d")];
+        const TIMES: [(&str, &str); 3] = [
+            ("%H:%M:%S", "hh:mm:ss"),
+            ("%H:%M", "hh:mm"),
+            ("%I:%M %p", "h:mm AM/PM"),
+        ];
+
+        let value = value.trim();
+        if value.is_empty() || !value.chars().any(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        // pairs of a chrono format and the matching number format code
+        type Formats = &'static [(&'static str, &'static str)];
+        let (date_times, dates): (Formats, Formats) = match date_order {
+            DateOrder::Mdy => (&MDY_DATE_TIMES, &MDY_DATES),
+            DateOrder::Dmy => (&DMY_DATE_TIMES, &DMY_DATES),
+            DateOrder::Ymd => (&YMD_DATE_TIMES, &YMD_DATES),
+        };
+        let date_time = ISO_DATE_TIMES
+            .iter()
+            .chain(date_times)
+            .find_map(|(format, code)| {
+                NaiveDateTime::parse_from_str(value, format)
+                    .ok()
+                    .map(|date_time| (date_time, *code))
+            });
+        let date = || {
+            [("%Y-%m-%d", "yyyy-mm-dd")]
+                .iter()
+                .chain(dates)
+                .find_map(|(format, code)| {
+                    NaiveDate::parse_from_str(value, format)
+                        .ok()
+                        .map(|date| (date.and_time(NaiveTime::default()), *code))
+                })
+        };
+        let time = || {
+            TIMES.iter().find_map(|(format, code)| {
+                NaiveTime::parse_from_str(value, format)
+                    .ok()
+                    .map(|time| (NaiveDate::default().and_time(time), *code))
+            })
+        };
+        date_time
+            .or_else(date)
+            .or_else(time)
+            .map(|(date_time, code)| (Instant::from(date_time), code))
+    }
+
     pub fn unpack_str_unix_timestamp(value: &str) -> anyhow::Result<CellValue> {
         let parsed: i64 = value.parse()?;
         Self::unpack_unix_timestamp(parsed)
     }
 
     pub fn unpack_unix_timestamp(value: i64) -> anyhow::Result<CellValue> {
-        let timestamp = match Utc.timestamp_opt(value, 0) {
-            chrono::LocalResult::Single(timestamp) => timestamp,
+        match Utc.timestamp_opt(value, 0) {
+            chrono::LocalResult::Single(timestamp) => {
+                Ok(CellValue::Instant(timestamp.naive_utc().into()))
+            }
             _ => bail!("Could not parse timestamp: {}", value),
-        };
-        // TODO(ddimaria): convert to Instant when they're implement
-        Ok(CellValue::Text(
-            timestamp.format("%Y-%m-%d %H:%M:%S").to_string(),
-        ))
+        }
     }
 
     pub fn unpack_str_float(value: &str, default: CellValue) -> CellValue {
@@ -340,6 +478,11 @@ impl CellValue {
                 let b = b.to_ascii_uppercase();
                 a.cmp(&b)
             }
+            (CellValue::Link(a), CellValue::Link(b)) => {
+                let a = a.text.to_ascii_uppercase();
+                let b = b.text.to_ascii_uppercase();
+                a.cmp(&b)
+            }
             (CellValue::Logical(a), CellValue::Logical(b)) => a.cmp(b),
             (CellValue::Instant(a), CellValue::Instant(b)) => a.cmp(b),
             (CellValue::Duration(a), CellValue::Duration(b)) => a.cmp(b),
@@ -352,6 +495,8 @@ impl CellValue {
             | (CellValue::Duration(_), _)
             | (CellValue::Html(_), _)
             | (CellValue::Code(_), _)
+            | (CellValue::Link(_), _)
+            | (CellValue::Image(_), _)
             | (CellValue::Blank, _) => return Ok(None),
         }))
     }
@@ -374,6 +519,8 @@ impl CellValue {
                 CellValue::Blank => 6,
                 CellValue::Html(_) => 7,
                 CellValue::Code(_) => 8,
+                CellValue::Link(_) => 9,
+                CellValue::Image(_) => 10,
             }
         }
 
@@ -438,7 +585,10 @@ impl CellValue {
         match (number, is_bool) {
             (Ok(number), false) => CellValue::Number(number),
             (_, true) => CellValue::Logical(is_true),
-            _ => CellValue::Text(String::from(value)),
+            _ => match CellValue::unpack_date_time(value, DateOrder::default()) {
+                Some((instant, _)) => CellValue::Instant(instant),
+                None => CellValue::Text(String::from(value)),
+            },
         }
     }
 
@@ -520,6 +670,9 @@ impl CellValue {
                 CellValue::Logical(is_true)
             }
             "instant" => CellValue::unpack_str_unix_timestamp(value)?,
+            // images that cannot be read are left out rather than shown as
+            // (possibly very long) data URLs
+            "image" => Image::from_data_url(value).map_or(CellValue::Blank, CellValue::Image),
             "duration" => CellValue::Text("not implemented".into()),
             _ => CellValue::Text(value.into()),
         };
@@ -539,8 +692,8 @@ mod test {
     use bigdecimal::BigDecimal;
 
     use crate::{
-        grid::{NumericFormat, NumericFormatKind},
-        CellValue,
+        grid::{locale::DateOrder, NumericFormat, NumericFormatKind, Sheet},
+        CellValue, Image, Pos,
     };
 
     #[test]
@@ -733,6 +886,91 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_custom_display() {
+        let custom = |code: &str| {
+            Some(NumericFormat {
+                kind: NumericFormatKind::Custom(code.into()),
+                symbol: None,
+            })
+        };
+        let value = CellValue::Number(BigDecimal::from_str("-1234.5").unwrap());
+        assert_eq!(
+            value.to_display(custom("#,##0.00;(#,##0.00)"), Some(4), None),
+            "(1,234.50)"
+        );
+        assert_eq!(
+            value.to_display(custom("0.00E+00"), None, None),
+            "-1.23E+03"
+        );
+
+        let value = CellValue::Text("abc".into());
+        assert_eq!(
+            value.to_display(custom("0;-0;0;\"[\"@\"]\""), None, None),
+            "[abc]"
+        );
+        assert_eq!(value.to_display(custom("0.00"), None, None), "abc");
+    }
+
+    #[test]
+    fn test_date_time_input_and_display() {
+        let date_time = |s: &str| {
+            CellValue::unpack_date_time(s, DateOrder::Mdy)
+                .map(|(instant, code)| (instant.to_human(), code))
+        };
+        assert_eq!(
+            date_time("2024-03-01"),
+            Some(("2024-03-01".to_string(), "yyyy-mm-dd"))
+        );
+        assert_eq!(
+            date_time("3/1/2024 14:00"),
+            Some(("2024-03-01 14:00:00".to_string(), "m/d/yyyy hh:mm"))
+        );
+        assert_eq!(
+            date_time("Mar 1, 2024"),
+            Some(("2024-03-01".to_string(), "mmm d, yyyy"))
+        );
+        assert_eq!(
+            date_time("14:30:15"),
+            Some(("1970-01-01 14:30:15".to_string(), "hh:mm:ss"))
+        );
+        assert_eq!(date_time("13/45/2024"), None);
+        assert_eq!(
+            CellValue::unpack_date_time("1.3.2024", DateOrder::Dmy)
+                .map(|(instant, code)| (instant.to_human(), code)),
+            Some(("2024-03-01".to_string(), "d.m.yyyy"))
+        );
+        assert_eq!(date_time("March"), None);
+
+        let value = CellValue::to_cell_value("2024-03-01");
+        assert!(matches!(value, CellValue::Instant(_)));
+        assert_eq!(CellValue::to_cell_value("12"), CellValue::Number(12.into()));
+
+        let date_format = |code: &str| {
+            Some(NumericFormat {
+                kind: NumericFormatKind::DateTime(code.into()),
+                symbol: None,
+            })
+        };
+        assert_eq!(value.to_display(None, None, None), "2024-03-01");
+        assert_eq!(
+            value.to_display(date_format("mmmm d, yyyy"), None, None),
+            "March 1, 2024"
+        );
+        assert_eq!(value.to_edit(), "2024-03-01 00:00:00");
+
+        let duration = CellValue::Duration(crate::Duration {
+            years: 0,
+            months: 0,
+            seconds: 90_000.0,
+        });
+        assert_eq!(duration.to_display(None, None, None), "1 day, 1 hour");
+        assert_eq!(
+            duration.to_display(date_format("[h]:mm:ss"), None, None),
+            "25:00:00"
+        );
+    }
+
     #[test]
     fn test_with_commas() {
         let value = BigDecimal::from_str("123123123");
@@ -747,4 +985,22 @@ mod test {
             "-123,123,123.123456"
         );
     }
+
+    #[test]
+    fn test_image_from_js() {
+        let mut sheet = Sheet::test();
+        let url = Image::test().to_data_url();
+        let (value, _) = CellValue::from_js(&url, "image", Pos::ORIGIN, &mut sheet).unwrap();
+        assert_eq!(value, CellValue::Image(Image::test()));
+
+        // images that cannot be read are blank rather than text
+        for url in [
+            "data:image/png;base64,not base64!",
+            "data:image/png;base64,AAAA",
+        ] {
+            let (value, _) =
+                CellValue::from_js(&url.to_string(), "image", Pos::ORIGIN, &mut sheet).unwrap();
+            assert_eq!(value, CellValue::Blank);
+        }
+    }
 }
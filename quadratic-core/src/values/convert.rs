@@ -93,6 +93,8 @@ impl<'a> TryFrom<&'a CellValue> for String {
             CellValue::Duration(d) => Ok(d.to_string()),
             CellValue::Error(e) => Err(e.msg.clone()),
             CellValue::Html(s) => Ok(s.clone()),
+            CellValue::Link(link) => Ok(link.text.clone()),
+            CellValue::Image(image) => Ok(image.to_data_url()),
             CellValue::Code(_) => Ok(String::new()),
         }
     }
@@ -126,7 +128,11 @@ impl<'a> TryFrom<&'a CellValue> for f64 {
                 got: Some(value.type_name().into()),
             }),
             CellValue::Error(e) => Err(e.msg.clone()),
-            CellValue::Html(_) => Ok(0.0),
+            CellValue::Html(_) | CellValue::Image(_) => Ok(0.0),
+            CellValue::Link(_) => Err(RunErrorMsg::Expected {
+                expected: "number".into(),
+                got: Some(value.type_name().into()),
+            }),
             CellValue::Code(_) => Ok(0.0),
         }
     }
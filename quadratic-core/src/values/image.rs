@@ -0,0 +1,274 @@
+use std::fmt;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use base64::{
+    alphabet,
+    engine::{general_purpose::STANDARD, DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+    Engine,
+};
+
+lazy_static! {
+    static ref SVG_TAG: Regex = Regex::new(r"(?s)<svg\b[^>]*>").unwrap();
+    static ref SVG_WIDTH: Regex =
+        Regex::new(r#"\swidth\s*=\s*["']\s*([\d.]+)(px)?\s*["']"#).unwrap();
+    static ref SVG_HEIGHT: Regex =
+        Regex::new(r#"\sheight\s*=\s*["']\s*([\d.]+)(px)?\s*["']"#).unwrap();
+    static ref SVG_VIEW_BOX: Regex = Regex::new(
+        r#"\sviewBox\s*=\s*["']\s*[-\d.]+[\s,]+[-\d.]+[\s,]+([\d.]+)[\s,]+([\d.]+)\s*["']"#
+    )
+    .unwrap();
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    Svg,
+}
+impl ImageFormat {
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            ImageFormat::Png => "image/png",
+            ImageFormat::Jpeg => "image/jpeg",
+            ImageFormat::Svg => "image/svg+xml",
+        }
+    }
+
+    pub fn from_mime_type(mime_type: &str) -> Option<ImageFormat> {
+        match mime_type.to_ascii_lowercase().as_str() {
+            "image/png" => Some(ImageFormat::Png),
+            "image/jpeg" | "image/jpg" => Some(ImageFormat::Jpeg),
+            "image/svg+xml" => Some(ImageFormat::Svg),
+            _ => None,
+        }
+    }
+
+    /// File extension used when the image is exported.
+    pub fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Jpeg => "jpeg",
+            ImageFormat::Svg => "svg",
+        }
+    }
+}
+
+/// An image file, such as a chart returned by Python.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub struct Image {
+    pub format: ImageFormat,
+
+    /// Contents of the file, serialized as base64.
+    #[serde(with = "base64_data")]
+    #[cfg_attr(feature = "js", ts(type = "string"))]
+    pub data: Vec<u8>,
+
+    /// Size of the image in pixels.
+    pub width: u32,
+    pub height: u32,
+}
+impl fmt::Display for Image {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_data_url())
+    }
+}
+
+impl Image {
+    /// Constructs an image from the contents of a file, reading its size.
+    /// Returns `None` if the contents are not a valid image.
+    pub fn new(format: ImageFormat, data: Vec<u8>) -> Option<Image> {
+        let (width, height) = match format {
+            ImageFormat::Png => png_size(&data)?,
+            ImageFormat::Jpeg => jpeg_size(&data)?,
+            ImageFormat::Svg => svg_size(std::str::from_utf8(&data).ok()?)?,
+        };
+        Some(Image {
+            format,
+            data,
+            width,
+            height,
+        })
+    }
+
+    /// Constructs an image from a data URL, such as
+    /// `data:image/png;base64,iVBORw0...` or `data:image/svg+xml,<svg...`.
+    pub fn from_data_url(url: &str) -> Option<Image> {
+        let (header, contents) = url.trim().strip_prefix("data:")?.split_once(',')?;
+        let mut params = header.split(';');
+        let format = ImageFormat::from_mime_type(params.next()?.trim())?;
+        let data = if params.any(|param| param.trim().eq_ignore_ascii_case("base64")) {
+            let contents = contents.split_ascii_whitespace().collect::<String>();
+            DATA_URL_BASE64.decode(contents).ok()?
+        } else {
+            percent_decode(contents)
+        };
+        Image::new(format, data)
+    }
+
+    // creates a 2x3 PNG for testing
+    #[cfg(test)]
+    pub fn test() -> Self {
+        let mut png = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+        png.extend([0, 0, 0, 13]);
+        png.extend(b"IHDR");
+        png.extend(2u32.to_be_bytes());
+        png.extend(3u32.to_be_bytes());
+        png.extend([8, 6, 0, 0, 0]);
+        Image::new(ImageFormat::Png, png).unwrap()
+    }
+
+    pub fn to_data_url(&self) -> String {
+        format!(
+            "data:{};base64,{}",
+            self.format.mime_type(),
+            STANDARD.encode(&self.data)
+        )
+    }
+}
+
+/// Decodes the base64 in data URLs, where padding is optional.
+const DATA_URL_BASE64: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// Serializes image data as a base64 string.
+mod base64_data {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&STANDARD.encode(bytes))
+    }
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<u8>, D::Error> {
+        let encoded = String::deserialize(d)?;
+        STANDARD.decode(encoded).map_err(de::Error::custom)
+    }
+}
+
+fn png_size(data: &[u8]) -> Option<(u32, u32)> {
+    const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+    if data.get(..8)? != SIGNATURE || data.get(12..16)? != b"IHDR" {
+        return None;
+    }
+    let width = u32::from_be_bytes(data.get(16..20)?.try_into().ok()?);
+    let height = u32::from_be_bytes(data.get(20..24)?.try_into().ok()?);
+    Some((width, height))
+}
+
+fn jpeg_size(data: &[u8]) -> Option<(u32, u32)> {
+    if data.get(..2)? != [0xff, 0xd8] {
+        return None;
+    }
+    let u16_at = |i: usize| -> Option<u32> {
+        Some(u16::from_be_bytes(data.get(i..i + 2)?.try_into().ok()?) as u32)
+    };
+    let mut i = 2;
+    loop {
+        if *data.get(i)? != 0xff {
+            return None;
+        }
+        let marker = *data.get(i + 1)?;
+        match marker {
+            // padding before a marker
+            0xff => i += 1,
+            // markers without a length
+            0x01 | 0xd0..=0xd9 => i += 2,
+            // start of frame, which holds the size
+            0xc0..=0xcf if !matches!(marker, 0xc4 | 0xc8 | 0xcc) => {
+                return Some((u16_at(i + 7)?, u16_at(i + 5)?));
+            }
+            _ => i += 2 + u16_at(i + 2)? as usize,
+        }
+    }
+}
+
+fn svg_size(svg: &str) -> Option<(u32, u32)> {
+    let tag = SVG_TAG.find(svg)?.as_str();
+    let attribute = |regex: &Regex, index: usize| -> Option<f64> {
+        regex.captures(tag)?.get(index)?.as_str().parse().ok()
+    };
+    let width = attribute(&SVG_WIDTH, 1).or_else(|| attribute(&SVG_VIEW_BOX, 1))?;
+    let height = attribute(&SVG_HEIGHT, 1).or_else(|| attribute(&SVG_VIEW_BOX, 2))?;
+    Some((width.round() as u32, height.round() as u32))
+}
+
+/// Decodes the `%XX` escapes of a data URL.
+fn percent_decode(text: &str) -> Vec<u8> {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok())
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    decoded
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reads_image_sizes() {
+        let png = Image::test();
+        assert_eq!((png.width, png.height), (2, 3));
+        assert_eq!(Image::new(ImageFormat::Png, b"not a png".to_vec()), None);
+
+        // SOI, an APP0 segment, then SOF0 with a height of 40 and width of 30
+        let jpeg = vec![
+            0xff, 0xd8, 0xff, 0xe0, 0, 4, 0, 0, 0xff, 0xc0, 0, 11, 8, 0, 40, 0, 30, 1, 1, 0x11, 0,
+        ];
+        let jpeg = Image::new(ImageFormat::Jpeg, jpeg).unwrap();
+        assert_eq!((jpeg.width, jpeg.height), (30, 40));
+
+        let svg = r#"<svg xmlns="http://www.w3.org/2000/svg" width="120px" height="80"></svg>"#;
+        let svg = Image::new(ImageFormat::Svg, svg.into()).unwrap();
+        assert_eq!((svg.width, svg.height), (120, 80));
+        let svg = r#"<svg viewBox="0 0 64.4 32"><rect width="10" height="10"/></svg>"#;
+        let svg = Image::new(ImageFormat::Svg, svg.into()).unwrap();
+        assert_eq!((svg.width, svg.height), (64, 32));
+    }
+
+    #[test]
+    fn converts_data_urls() {
+        let png = Image::test();
+        let url = png.to_data_url();
+        assert!(url.starts_with("data:image/png;base64,iVBORw0KGgo"));
+        assert_eq!(Image::from_data_url(&url), Some(png));
+
+        let svg = Image::from_data_url(
+            "data:image/svg+xml;charset=utf-8,%3Csvg%20width='4'%20height='5'%3E%3C/svg%3E",
+        )
+        .unwrap();
+        assert_eq!(svg.format, ImageFormat::Svg);
+        assert_eq!(svg.data, b"<svg width='4' height='5'></svg>");
+        assert_eq!((svg.width, svg.height), (4, 5));
+
+        // data URLs may be wrapped and unpadded
+        let (start, end) = url.split_at(40);
+        let wrapped = format!("{start}\n {}", end.trim_end_matches('='));
+        assert_eq!(Image::from_data_url(&wrapped), Some(Image::test()));
+
+        assert_eq!(Image::from_data_url("data:image/png;base64,iVBO*"), None);
+        assert_eq!(Image::from_data_url("data:text/plain,hello"), None);
+        assert_eq!(Image::from_data_url("https://example.com/a.png"), None);
+    }
+}
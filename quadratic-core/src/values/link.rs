@@ -0,0 +1,171 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{formulas::CellRef, grid::Grid, grid::SheetId, Pos, Rect, SheetRect};
+
+/// The URL schemes links may open. Other schemes (such as `javascript:`)
+/// could run code when the link is clicked, so they are never linked.
+const URL_SCHEMES: [&str; 4] = ["http://", "https://", "ftp://", "mailto:"];
+
+/// A hyperlink: the text shown in the cell and the target it opens.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub struct Link {
+    pub text: String,
+
+    /// A URL, or `#` followed by a cell or range in the workbook, such as
+    /// `#'Sheet 2'!A1:B5`.
+    pub url: String,
+}
+impl fmt::Display for Link {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+impl Link {
+    /// Returns a link, or None if the URL is not allowed (see
+    /// [`Link::is_allowed_url`]).
+    pub fn new(text: impl Into<String>, url: impl Into<String>) -> Option<Self> {
+        let url = url.into();
+        Self::is_allowed_url(&url).then(|| Self {
+            text: text.into(),
+            url,
+        })
+    }
+
+    /// Returns whether a link may open the URL: a web, ftp or mailto URL, or
+    /// a `#` reference to cells in the workbook.
+    pub fn is_allowed_url(url: &str) -> bool {
+        let lowercase = url.to_ascii_lowercase();
+        url.starts_with('#')
+            || URL_SCHEMES
+                .iter()
+                .any(|scheme| lowercase.starts_with(scheme))
+    }
+
+    /// Returns a link to the text if it is a URL, e.g. `https://…`,
+    /// `mailto:…` or `www.…`. Addresses starting with `www.` open over https.
+    pub fn detect(value: &str) -> Option<Link> {
+        let value = value.trim();
+        if value.is_empty() || value.contains(char::is_whitespace) {
+            return None;
+        }
+        let lowercase = value.to_ascii_lowercase();
+        let is_url = URL_SCHEMES
+            .iter()
+            .any(|scheme| lowercase.len() > scheme.len() && lowercase.starts_with(scheme));
+        if is_url {
+            Link::new(value, value)
+        } else if lowercase.len() > 4 && lowercase.starts_with("www.") {
+            Link::new(value, format!("https://{value}"))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the sheet name (if given) and range of a link to a cell or
+    /// range in the workbook.
+    pub fn internal_reference(&self) -> Option<(Option<String>, Rect)> {
+        let reference = self.url.strip_prefix('#')?;
+        let (start, end) = match reference.rsplit_once(':') {
+            Some((start, end)) if !end.contains('!') => (start, Some(end)),
+            _ => (reference, None),
+        };
+        let start = CellRef::parse_a1(start, Pos::ORIGIN)?;
+        let end = match end {
+            Some(end) => CellRef::parse_a1(end, Pos::ORIGIN)?.resolve_from(Pos::ORIGIN),
+            None => start.resolve_from(Pos::ORIGIN),
+        };
+        Some((
+            start.sheet.clone(),
+            Rect::new_span(start.resolve_from(Pos::ORIGIN), end),
+        ))
+    }
+
+    /// Returns the cells a link to the workbook opens. References without a
+    /// sheet name are on `sheet_id`, the sheet of the linking cell.
+    pub fn internal_target(&self, grid: &Grid, sheet_id: SheetId) -> Option<SheetRect> {
+        let (sheet_name, rect) = self.internal_reference()?;
+        let sheet_id = match sheet_name {
+            Some(name) => grid.try_sheet_from_name(name)?.id,
+            None => sheet_id,
+        };
+        Some(rect.to_sheet_rect(sheet_id))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn detects_urls() {
+        assert_eq!(
+            Link::detect("https://quadratichq.com"),
+            Link::new("https://quadratichq.com", "https://quadratichq.com")
+        );
+        assert_eq!(
+            Link::detect("www.example.com/a?b=c"),
+            Link::new("www.example.com/a?b=c", "https://www.example.com/a?b=c")
+        );
+        assert_eq!(
+            Link::detect("MAILTO:ada@example.com").map(|link| link.url),
+            Some("MAILTO:ada@example.com".into())
+        );
+        assert_eq!(Link::detect("https://"), None);
+        assert_eq!(Link::detect("see https://example.com"), None);
+        assert_eq!(Link::detect("example.com"), None);
+    }
+
+    #[test]
+    fn rejects_unsafe_urls() {
+        assert!(Link::new("x", "HTTPS://example.com").is_some());
+        assert!(Link::new("x", "mailto:ada@example.com").is_some());
+        assert!(Link::new("x", "ftp://example.com/file").is_some());
+        assert!(Link::new("x", "#B5").is_some());
+        assert_eq!(Link::new("x", "javascript:alert(1)"), None);
+        assert_eq!(Link::new("x", "JavaScript:alert(1)"), None);
+        assert_eq!(Link::new("x", " javascript:alert(1)"), None);
+        assert_eq!(Link::new("x", "data:text/html,<script>"), None);
+        assert_eq!(Link::new("x", "example.com"), None);
+        assert_eq!(Link::detect("javascript:alert(1)"), None);
+    }
+
+    #[test]
+    fn resolves_internal_references() {
+        let mut grid = Grid::new();
+        let sheet_id = grid.sheets()[0].id;
+        let other_id = grid.add_sheet(None);
+        grid.try_sheet_mut(other_id).unwrap().name = "Sheet 2".into();
+
+        let link = Link::new("totals", "#'Sheet 2'!B3:C5").unwrap();
+        assert_eq!(
+            link.internal_reference(),
+            Some((Some("Sheet 2".into()), Rect::from_numbers(1, 3, 2, 3)))
+        );
+        assert_eq!(
+            link.internal_target(&grid, sheet_id),
+            Some(SheetRect::from_numbers(1, 3, 2, 3, other_id))
+        );
+        assert_eq!(
+            Link::new("here", "#A1")
+                .unwrap()
+                .internal_target(&grid, sheet_id),
+            Some(SheetRect::from_numbers(0, 1, 1, 1, sheet_id))
+        );
+        assert_eq!(
+            Link::new("missing", "#Nope!A1")
+                .unwrap()
+                .internal_target(&grid, sheet_id),
+            None
+        );
+        assert_eq!(
+            Link::new("web", "https://example.com")
+                .unwrap()
+                .internal_reference(),
+            None
+        );
+    }
+}
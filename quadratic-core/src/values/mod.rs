@@ -9,7 +9,9 @@ mod arrow;
 pub mod cell_values;
 pub mod cellvalue;
 mod convert;
+mod image;
 mod isblank;
+mod link;
 mod time;
 
 pub use array::Array;
@@ -17,7 +19,9 @@ pub use array_size::{ArraySize, Axis};
 pub use cellvalue::CellValue;
 pub use cellvalue::CodeCellValue;
 pub use convert::CoerceInto;
+pub use image::{Image, ImageFormat};
 pub use isblank::IsBlank;
+pub use link::Link;
 pub use time::{Duration, Instant};
 
 use crate::{CodeResult, CodeResultExt, RunErrorMsg, SpannableIterExt, Spanned};
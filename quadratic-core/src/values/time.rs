@@ -1,9 +1,156 @@
 use std::fmt;
 
-use chrono::NaiveDateTime;
+use chrono::{DateTime, Datelike, NaiveDateTime, TimeZone, Timelike, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::CellValue;
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+const WEEKDAYS: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+/// Months and years have no fixed length, so durations use these to convert
+/// them to elapsed time.
+const DAYS_PER_MONTH: f64 = 30.0;
+const DAYS_PER_YEAR: f64 = 365.0;
+
+/// One piece of a date/time format code such as `mmm d, yyyy h:mm AM/PM`.
+/// The `usize` is the number of repeated letters in the code.
+#[derive(Debug, Clone, PartialEq)]
+enum DatePart {
+    Literal(String),
+    Year(usize),
+    Month(usize),
+    Day(usize),
+    Hour(usize),
+    Minute(usize),
+    Second(usize),
+    /// Fractional seconds, e.g. `.00` after `ss`.
+    Fraction(usize),
+    /// `AM/PM` (false) or `A/P` (true).
+    AmPm(bool),
+    /// `[h]`, `[m]` or `[s]`: the total elapsed hours, minutes or seconds.
+    Elapsed(char),
+}
+
+/// Parses an Excel-style date/time format code.
+fn parse_date_format(code: &str) -> Vec<DatePart> {
+    let chars: Vec<char> = code.chars().collect();
+    let mut parts = vec![];
+    let mut i = 0;
+    let literal = |parts: &mut Vec<DatePart>, s: &str| match parts.last_mut() {
+        Some(DatePart::Literal(last)) => last.push_str(s),
+        _ => parts.push(DatePart::Literal(s.to_string())),
+    };
+    while i < chars.len() {
+        let c = chars[i];
+        let lower = c.to_ascii_lowercase();
+        let rest: String = chars[i..].iter().collect::<String>().to_ascii_lowercase();
+        if rest.starts_with("am/pm") {
+            parts.push(DatePart::AmPm(false));
+            i += 5;
+        } else if rest.starts_with("a/p") {
+            parts.push(DatePart::AmPm(true));
+            i += 3;
+        } else if matches!(lower, 'y' | 'm' | 'd' | 'h' | 's') {
+            let count = chars[i..]
+                .iter()
+                .take_while(|other| other.to_ascii_lowercase() == lower)
+                .count();
+            parts.push(match lower {
+                'y' => DatePart::Year(count),
+                'm' => DatePart::Month(count),
+                'd' => DatePart::Day(count),
+                'h' => DatePart::Hour(count),
+                _ => DatePart::Second(count),
+            });
+            i += count;
+        } else if c == '.' && matches!(parts.last(), Some(DatePart::Second(_))) {
+            let count = chars[i + 1..].iter().take_while(|c| **c == '0').count();
+            if count > 0 {
+                parts.push(DatePart::Fraction(count));
+            } else {
+                literal(&mut parts, ".");
+            }
+            i += count + 1;
+        } else if c == '"' {
+            let text: String = chars[i + 1..].iter().take_while(|c| **c != '"').collect();
+            literal(&mut parts, &text);
+            i += text.chars().count() + 2;
+        } else if c == '\\' {
+            if let Some(next) = chars.get(i + 1) {
+                literal(&mut parts, &next.to_string());
+            }
+            i += 2;
+        } else if c == '[' {
+            let inner: String = chars[i + 1..].iter().take_while(|c| **c != ']').collect();
+            let unit = inner.to_ascii_lowercase();
+            if let Some(unit) = unit
+                .chars()
+                .next()
+                .filter(|u| matches!(u, 'h' | 'm' | 's') && unit.chars().all(|other| other == *u))
+            {
+                parts.push(DatePart::Elapsed(unit));
+            }
+            // other brackets (colors, locales) are ignored
+            i += inner.chars().count() + 2;
+        } else {
+            literal(&mut parts, &c.to_string());
+            i += 1;
+        }
+    }
+
+    // `m` and `mm` are minutes when they follow hours or come before seconds
+    let units: Vec<usize> = (0..parts.len())
+        .filter(|i| !matches!(parts[*i], DatePart::Literal(_)))
+        .collect();
+    for (index, i) in units.iter().enumerate() {
+        if let DatePart::Month(count) = parts[*i] {
+            let previous = index.checked_sub(1).map(|index| &parts[units[index]]);
+            let next = units.get(index + 1).map(|next| &parts[*next]);
+            if count <= 2
+                && (matches!(previous, Some(DatePart::Hour(_) | DatePart::Elapsed('h')))
+                    || matches!(next, Some(DatePart::Second(_) | DatePart::Elapsed('s'))))
+            {
+                parts[*i] = DatePart::Minute(count);
+            }
+        }
+    }
+    parts
+}
+
+/// Formats a number with at least `width` digits.
+fn pad(n: impl fmt::Display, width: usize) -> String {
+    format!("{n:0>width$}", width = width.min(2))
+}
+
+/// Formats the fraction of a second with `digits` digits.
+fn fraction(seconds: f64, digits: usize) -> String {
+    let fraction = format!("{:.digits$}", seconds.rem_euclid(1.0));
+    fraction
+        .split_once('.')
+        .map(|(_, fraction)| format!(".{fraction}"))
+        .unwrap_or_default()
+}
 
 #[cfg_attr(test, derive(proptest_derive::Arbitrary))]
 #[derive(Serialize, Deserialize, Debug, Copy, Clone)]
@@ -17,6 +164,107 @@ impl Instant {
     pub fn new(seconds: f64) -> Self {
         Self { seconds }
     }
+
+    /// Returns the current time.
+    pub fn now() -> Self {
+        Self::new(Utc::now().timestamp_millis() as f64 / 1000.0)
+    }
+
+    /// Returns the instant as a UTC date and time.
+    pub fn to_datetime(&self) -> Option<DateTime<Utc>> {
+        let seconds = self.seconds.floor();
+        let nanos = ((self.seconds - seconds) * 1e9) as u32;
+        Utc.timestamp_opt(seconds as i64, nanos).single()
+    }
+
+    /// Formats the instant for people when it has no date/time format. Dates
+    /// at midnight are written without a time.
+    pub fn to_human(&self) -> String {
+        if self.seconds.rem_euclid(SECONDS_PER_DAY) == 0.0 {
+            self.format("yyyy-mm-dd")
+        } else {
+            self.format("yyyy-mm-dd hh:mm:ss")
+        }
+    }
+
+    /// Formats the instant using an Excel-style date/time format code, e.g.
+    /// `yyyy-mm-dd`, `m/d/yyyy`, `hh:mm:ss` or `mmm d, yyyy h:mm AM/PM`.
+    pub fn format(&self, code: &str) -> String {
+        let Some(datetime) = self.to_datetime() else {
+            return self.seconds.to_string();
+        };
+        let parts = parse_date_format(code);
+        let twelve_hour = parts.iter().any(|part| matches!(part, DatePart::AmPm(_)));
+        let hour = match datetime.hour() {
+            hour if !twelve_hour => hour,
+            0 => 12,
+            hour if hour > 12 => hour - 12,
+            hour => hour,
+        };
+        parts
+            .iter()
+            .map(|part| match part {
+                DatePart::Literal(s) => s.clone(),
+                DatePart::Year(1 | 2) => pad(datetime.year().rem_euclid(100), 2),
+                DatePart::Year(_) => datetime.year().to_string(),
+                DatePart::Month(count @ (1 | 2)) => pad(datetime.month(), *count),
+                DatePart::Month(3) => MONTHS[datetime.month0() as usize][..3].to_string(),
+                DatePart::Month(5) => MONTHS[datetime.month0() as usize][..1].to_string(),
+                DatePart::Month(_) => MONTHS[datetime.month0() as usize].to_string(),
+                DatePart::Day(count @ (1 | 2)) => pad(datetime.day(), *count),
+                DatePart::Day(3) => {
+                    WEEKDAYS[datetime.weekday().num_days_from_monday() as usize][..3].to_string()
+                }
+                DatePart::Day(_) => {
+                    WEEKDAYS[datetime.weekday().num_days_from_monday() as usize].to_string()
+                }
+                DatePart::Hour(count) => pad(hour, *count),
+                DatePart::Minute(count) => pad(datetime.minute(), *count),
+                DatePart::Second(count) => pad(datetime.second(), *count),
+                DatePart::Fraction(digits) => fraction(self.seconds, *digits),
+                DatePart::AmPm(short) => match (datetime.hour() < 12, short) {
+                    (true, false) => "AM".to_string(),
+                    (false, false) => "PM".to_string(),
+                    (true, true) => "A".to_string(),
+                    (false, true) => "P".to_string(),
+                },
+                DatePart::Elapsed(unit) => {
+                    let divisor = match unit {
+                        'h' => 3600.0,
+                        'm' => 60.0,
+                        _ => 1.0,
+                    };
+                    ((self.seconds / divisor).floor() as i64).to_string()
+                }
+            })
+            .collect()
+    }
+
+    /// Formats the instant relative to `now`, e.g. "3 days ago" or "in 2
+    /// hours".
+    pub fn format_relative(&self, now: Instant) -> String {
+        let difference = self.seconds - now.seconds;
+        let units = [
+            (DAYS_PER_YEAR * SECONDS_PER_DAY, "year"),
+            (DAYS_PER_MONTH * SECONDS_PER_DAY, "month"),
+            (7.0 * SECONDS_PER_DAY, "week"),
+            (SECONDS_PER_DAY, "day"),
+            (3600.0, "hour"),
+            (60.0, "minute"),
+        ];
+        let Some((count, unit)) = units.iter().find_map(|(seconds, unit)| {
+            let count = (difference.abs() / seconds).floor() as i64;
+            (count > 0).then_some((count, unit))
+        }) else {
+            return "just now".to_string();
+        };
+        let plural = if count == 1 { "" } else { "s" };
+        if difference < 0.0 {
+            format!("{count} {unit}{plural} ago")
+        } else {
+            format!("in {count} {unit}{plural}")
+        }
+    }
 }
 
 impl From<NaiveDateTime> for Instant {
@@ -29,12 +277,7 @@ impl From<NaiveDateTime> for Instant {
 
 impl fmt::Display for Instant {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            CellValue::unpack_unix_timestamp(self.seconds as i64).unwrap_or_default()
-        )
-        // write!(f, "{s} seconds", s = self.seconds)
+        write!(f, "{}", self.format("yyyy-mm-dd hh:mm:ss"))
     }
 }
 
@@ -65,6 +308,77 @@ pub struct Duration {
     pub seconds: f64,
 }
 
+impl Duration {
+    /// Returns the total length of the duration in seconds.
+    pub fn total_seconds(&self) -> f64 {
+        (self.years as f64 * DAYS_PER_YEAR + self.months as f64 * DAYS_PER_MONTH) * SECONDS_PER_DAY
+            + self.seconds
+    }
+
+    /// Formats the duration for people, e.g. "1 year, 2 months, 3 days, 4
+    /// hours".
+    pub fn to_human(&self) -> String {
+        let sign = if self.total_seconds() < 0.0 { "-" } else { "" };
+        let seconds = self.seconds.abs();
+        let units = [
+            (self.years.unsigned_abs() as f64, "year"),
+            (self.months.unsigned_abs() as f64, "month"),
+            ((seconds / SECONDS_PER_DAY).floor(), "day"),
+            (
+                (seconds.rem_euclid(SECONDS_PER_DAY) / 3600.0).floor(),
+                "hour",
+            ),
+            ((seconds.rem_euclid(3600.0) / 60.0).floor(), "minute"),
+            (seconds.rem_euclid(60.0), "second"),
+        ];
+        let human = units
+            .iter()
+            .filter(|(count, _)| *count > 0.0)
+            .map(|(count, unit)| {
+                let plural = if *count == 1.0 { "" } else { "s" };
+                format!("{count} {unit}{plural}")
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        match human.is_empty() {
+            true => "0 seconds".to_string(),
+            false => format!("{sign}{human}"),
+        }
+    }
+
+    /// Formats the duration as elapsed time using a format code such as
+    /// `[h]:mm:ss`, `[m]:ss` or `mm:ss.0`. The bracketed unit holds the total,
+    /// so `[h]` may exceed 24. Without one, the largest unit wraps like a time
+    /// of day.
+    pub fn format_elapsed(&self, code: &str) -> String {
+        let total = self.total_seconds();
+        let sign = if total < 0.0 { "-" } else { "" };
+        let total = total.abs();
+        let parts = parse_date_format(code);
+        let formatted: String = parts
+            .iter()
+            .map(|part| match part {
+                DatePart::Literal(s) => s.clone(),
+                DatePart::Elapsed('h') => ((total / 3600.0).floor() as i64).to_string(),
+                DatePart::Elapsed('m') => ((total / 60.0).floor() as i64).to_string(),
+                DatePart::Elapsed(_) => (total.floor() as i64).to_string(),
+                DatePart::Day(count) => pad((total / SECONDS_PER_DAY).floor() as i64, *count),
+                DatePart::Hour(count) => pad(
+                    (total.rem_euclid(SECONDS_PER_DAY) / 3600.0).floor() as i64,
+                    *count,
+                ),
+                DatePart::Minute(count) | DatePart::Month(count) => {
+                    pad((total.rem_euclid(3600.0) / 60.0).floor() as i64, *count)
+                }
+                DatePart::Second(count) => pad(total.rem_euclid(60.0).floor() as i64, *count),
+                DatePart::Fraction(digits) => fraction(total, *digits),
+                DatePart::Year(_) | DatePart::AmPm(_) => String::new(),
+            })
+            .collect();
+        format!("{sign}{formatted}")
+    }
+}
+
 impl fmt::Display for Duration {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -104,3 +418,78 @@ impl Ord for Duration {
         ret
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn instant(y: i32, m: u32, d: u32, h: u32, min: u32, s: u32) -> Instant {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .and_then(|date| date.and_hms_opt(h, min, s))
+            .unwrap()
+            .into()
+    }
+
+    #[test]
+    fn formats_instants() {
+        let date = instant(2024, 3, 1, 0, 0, 0);
+        let time = instant(2024, 3, 1, 14, 5, 9);
+        assert_eq!(date.to_string(), "2024-03-01 00:00:00");
+        assert_eq!(date.to_human(), "2024-03-01");
+        assert_eq!(time.to_string(), "2024-03-01 14:05:09");
+        assert_eq!(time.to_human(), "2024-03-01 14:05:09");
+        assert_eq!(date.format("m/d/yyyy"), "3/1/2024");
+        assert_eq!(date.format("mmm d, yyyy"), "Mar 1, 2024");
+        assert_eq!(date.format("dddd, mmmm dd yy"), "Friday, March 01 24");
+        assert_eq!(time.format("hh:mm:ss"), "14:05:09");
+        assert_eq!(time.format("h:mm AM/PM"), "2:05 PM");
+        assert_eq!(time.format("yyyy-mm-dd\\Thh\"h\""), "2024-03-01T14h");
+        assert_eq!(
+            Instant::new(time.seconds + 0.25).format("mm:ss.00"),
+            "05:09.25"
+        );
+    }
+
+    #[test]
+    fn formats_relative_instants() {
+        let now = instant(2024, 3, 10, 12, 0, 0);
+        let relative = |seconds: f64| Instant::new(now.seconds + seconds).format_relative(now);
+        assert_eq!(relative(-3.0 * SECONDS_PER_DAY), "3 days ago");
+        assert_eq!(relative(2.0 * 3600.0 + 5.0), "in 2 hours");
+        assert_eq!(relative(-60.0), "1 minute ago");
+        assert_eq!(relative(10.0), "just now");
+    }
+
+    #[test]
+    fn formats_durations() {
+        let duration = Duration {
+            years: 1,
+            months: 2,
+            seconds: 3.0 * SECONDS_PER_DAY + 4.0 * 3600.0,
+        };
+        assert_eq!(duration.to_human(), "1 year, 2 months, 3 days, 4 hours");
+        assert_eq!(duration.to_string(), "1 years, 2 months, 273600 seconds");
+        let duration = Duration {
+            years: 0,
+            months: 0,
+            seconds: 26.0 * 3600.0 + 3.0 * 60.0 + 4.5,
+        };
+        assert_eq!(duration.format_elapsed("[h]:mm:ss"), "26:03:04");
+        assert_eq!(duration.format_elapsed("h:mm:ss.0"), "2:03:04.5");
+        assert_eq!(duration.format_elapsed("[m]:ss"), "1563:04");
+        let negative = Duration {
+            years: 0,
+            months: 0,
+            seconds: -90.0,
+        };
+        assert_eq!(negative.format_elapsed("[m]:ss"), "-1:30");
+        assert_eq!(negative.to_human(), "-1 minute, 30 seconds");
+        let zero = Duration {
+            years: 0,
+            months: 0,
+            seconds: 0.0,
+        };
+        assert_eq!(zero.to_human(), "0 seconds");
+    }
+}
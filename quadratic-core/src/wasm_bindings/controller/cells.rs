@@ -2,7 +2,7 @@ use std::str::FromStr;
 
 use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
 
-use crate::{controller::GridController, grid::SheetId, Pos, Rect};
+use crate::{controller::GridController, grid::SheetId, Link, Pos, Rect};
 
 #[wasm_bindgen]
 impl GridController {
@@ -59,12 +59,28 @@ impl GridController {
             return String::from("");
         };
         if let Some(value) = sheet.cell_value(pos) {
-            value.to_edit()
+            sheet.locale.localize_edit(&value)
         } else {
             String::from("")
         }
     }
 
+    /// Returns the cells a link to the workbook opens (e.g. `#'Sheet 2'!A1`),
+    /// relative to the linking cell's sheet.
+    ///
+    /// Returns a stringified [`SheetRect`], or an empty string if the URL is
+    /// not a link to cells in the workbook.
+    #[wasm_bindgen(js_name = "getLinkTarget")]
+    pub fn js_get_link_target(&self, sheet_id: String, url: String) -> String {
+        let Ok(sheet_id) = SheetId::from_str(&sheet_id) else {
+            return String::new();
+        };
+        Link::new("", url)
+            .and_then(|link| link.internal_target(self.grid(), sheet_id))
+            .and_then(|target| serde_json::to_string(&target).ok())
+            .unwrap_or_default()
+    }
+
     /// Deletes a region of cells.
     ///
     /// Returns a [`TransactionSummary`].
@@ -0,0 +1,73 @@
+use super::*;
+use crate::grid::sheet::charts::Chart;
+
+#[wasm_bindgen]
+impl GridController {
+    /// Adds (or replaces) the chart (a [`Chart`]) anchored to a cell.
+    ///
+    /// Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "setChart")]
+    pub fn js_set_chart(
+        &mut self,
+        sheet_id: String,
+        pos: Pos,
+        chart: JsValue,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|e| e.to_string())?;
+        let chart: Chart = serde_wasm_bindgen::from_value(chart)?;
+        Ok(serde_wasm_bindgen::to_value(&self.set_chart(
+            pos.to_sheet_pos(sheet_id),
+            chart,
+            cursor,
+        ))?)
+    }
+
+    /// Deletes the chart anchored to a cell.
+    ///
+    /// Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "deleteChart")]
+    pub fn js_delete_chart(
+        &mut self,
+        sheet_id: String,
+        pos: Pos,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|e| e.to_string())?;
+        Ok(serde_wasm_bindgen::to_value(
+            &self.delete_chart(pos.to_sheet_pos(sheet_id), cursor),
+        )?)
+    }
+
+    /// Returns the charts in a sheet as a string containing a JSON array of
+    /// [`JsChart`].
+    #[wasm_bindgen(js_name = "getCharts")]
+    pub fn js_get_charts(&self, sheet_id: String) -> Result<String, JsValue> {
+        let Some(sheet) = self.try_sheet_from_string_id(sheet_id) else {
+            return Result::Err("Sheet not found".into());
+        };
+        let charts: Vec<JsChart> = sheet
+            .charts
+            .iter()
+            .map(|(pos, chart)| JsChart {
+                x: pos.x,
+                y: pos.y,
+                chart: chart.clone(),
+            })
+            .collect();
+        Ok(serde_json::to_string(&charts).map_err(|e| e.to_string())?)
+    }
+
+    /// Returns the chart anchored to a cell drawn as an SVG image, for
+    /// exporting it or drawing it in a thumbnail.
+    #[wasm_bindgen(js_name = "getChartSvg")]
+    pub fn js_get_chart_svg(&self, sheet_id: String, pos: Pos) -> Result<String, JsValue> {
+        let Some(sheet) = self.try_sheet_from_string_id(sheet_id) else {
+            return Result::Err("Sheet not found".into());
+        };
+        let Some(chart) = sheet.chart(pos) else {
+            return Result::Err("Chart not found".into());
+        };
+        Ok(chart.to_svg(self.grid()))
+    }
+}
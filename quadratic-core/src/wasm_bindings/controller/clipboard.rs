@@ -11,9 +11,19 @@ use crate::{
 impl GridController {
     /// Returns the clipboard [`JsClipboard`]
     #[wasm_bindgen(js_name = "copyToClipboard")]
-    pub fn js_copy_to_clipboard(&self, sheet_id: String, rect: &Rect) -> Result<JsValue, JsValue> {
+    pub fn js_copy_to_clipboard(
+        &self,
+        sheet_id: String,
+        rect: &Rect,
+        skip_hidden: Option<bool>,
+    ) -> Result<JsValue, JsValue> {
         let sheet_id = SheetId::from_str(&sheet_id).unwrap();
-        let (plain_text, html) = self.copy_to_clipboard(rect.to_sheet_rect(sheet_id));
+        let sheet_rect = rect.to_sheet_rect(sheet_id);
+        let (plain_text, html) = if skip_hidden.unwrap_or(false) {
+            self.copy_visible_to_clipboard(sheet_rect)
+        } else {
+            self.copy_to_clipboard(sheet_rect)
+        };
         let output = JsClipboard {
             plain_text,
             html,
@@ -0,0 +1,122 @@
+use super::*;
+
+#[wasm_bindgen]
+impl GridController {
+    /// Adds a comment to a cell, starting a thread if there is none.
+    ///
+    /// Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "addComment")]
+    pub fn js_add_comment(
+        &mut self,
+        sheet_id: String,
+        pos: Pos,
+        author: String,
+        text: String,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|e| e.to_string())?;
+        Ok(serde_wasm_bindgen::to_value(&self.add_comment(
+            pos.to_sheet_pos(sheet_id),
+            author,
+            text,
+            cursor,
+        ))?)
+    }
+
+    /// Changes the text of a comment in a cell's thread.
+    ///
+    /// Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "editComment")]
+    pub fn js_edit_comment(
+        &mut self,
+        sheet_id: String,
+        pos: Pos,
+        comment_id: String,
+        text: String,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|e| e.to_string())?;
+        let comment_id = CommentId::from_str(&comment_id).map_err(|e| e.to_string())?;
+        Ok(serde_wasm_bindgen::to_value(&self.edit_comment(
+            pos.to_sheet_pos(sheet_id),
+            comment_id,
+            text,
+            cursor,
+        ))?)
+    }
+
+    /// Deletes a comment from a cell's thread.
+    ///
+    /// Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "deleteComment")]
+    pub fn js_delete_comment(
+        &mut self,
+        sheet_id: String,
+        pos: Pos,
+        comment_id: String,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|e| e.to_string())?;
+        let comment_id = CommentId::from_str(&comment_id).map_err(|e| e.to_string())?;
+        Ok(serde_wasm_bindgen::to_value(&self.delete_comment(
+            pos.to_sheet_pos(sheet_id),
+            comment_id,
+            cursor,
+        ))?)
+    }
+
+    /// Resolves (or reopens) a cell's thread.
+    ///
+    /// Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "setCommentResolved")]
+    pub fn js_set_comment_resolved(
+        &mut self,
+        sheet_id: String,
+        pos: Pos,
+        resolved: bool,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|e| e.to_string())?;
+        Ok(serde_wasm_bindgen::to_value(&self.set_comment_resolved(
+            pos.to_sheet_pos(sheet_id),
+            resolved,
+            cursor,
+        ))?)
+    }
+
+    /// Deletes all comment threads within a rectangle.
+    ///
+    /// Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "deleteCommentThreads")]
+    pub fn js_delete_comment_threads(
+        &mut self,
+        sheet_id: String,
+        rect: Rect,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|e| e.to_string())?;
+        Ok(serde_wasm_bindgen::to_value(&self.delete_comment_threads(
+            rect.to_sheet_rect(sheet_id),
+            cursor,
+        ))?)
+    }
+
+    /// Returns the comment threads in a sheet as a string containing a JSON
+    /// array of [`JsCommentThread`].
+    #[wasm_bindgen(js_name = "getCommentThreads")]
+    pub fn js_get_comment_threads(&self, sheet_id: String) -> Result<String, JsValue> {
+        let Some(sheet) = self.try_sheet_from_string_id(sheet_id) else {
+            return Result::Err("Sheet not found".into());
+        };
+        let threads: Vec<JsCommentThread> = sheet
+            .comments
+            .iter()
+            .map(|(pos, thread)| JsCommentThread {
+                x: pos.x,
+                y: pos.y,
+                thread: thread.clone(),
+            })
+            .collect();
+        Ok(serde_json::to_string(&threads).map_err(|e| e.to_string())?)
+    }
+}
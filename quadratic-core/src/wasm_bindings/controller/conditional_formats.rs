@@ -0,0 +1,51 @@
+use super::*;
+use crate::grid::sheet::conditional_formats::ConditionalFormat;
+
+#[wasm_bindgen]
+impl GridController {
+    /// Adds a conditional format (a [`ConditionalFormat`]) to a sheet.
+    ///
+    /// Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "addConditionalFormat")]
+    pub fn js_add_conditional_format(
+        &mut self,
+        sheet_id: String,
+        conditional_format: JsValue,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|e| e.to_string())?;
+        let conditional_format: ConditionalFormat =
+            serde_wasm_bindgen::from_value(conditional_format)?;
+        Ok(serde_wasm_bindgen::to_value(&self.add_conditional_format(
+            sheet_id,
+            conditional_format,
+            cursor,
+        ))?)
+    }
+
+    /// Removes all conditional formats that intersect a rectangle.
+    ///
+    /// Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "removeConditionalFormats")]
+    pub fn js_remove_conditional_formats(
+        &mut self,
+        sheet_id: String,
+        rect: Rect,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|e| e.to_string())?;
+        Ok(serde_wasm_bindgen::to_value(
+            &self.remove_conditional_formats(rect.to_sheet_rect(sheet_id), cursor),
+        )?)
+    }
+
+    /// Returns the conditional formats in a sheet as a string containing a
+    /// JSON array of [`ConditionalFormat`].
+    #[wasm_bindgen(js_name = "getConditionalFormats")]
+    pub fn js_get_conditional_formats(&self, sheet_id: String) -> Result<String, JsValue> {
+        let Some(sheet) = self.try_sheet_from_string_id(sheet_id) else {
+            return Result::Err("Sheet not found".into());
+        };
+        Ok(serde_json::to_string(&sheet.conditional_formats).map_err(|e| e.to_string())?)
+    }
+}
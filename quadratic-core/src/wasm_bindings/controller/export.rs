@@ -1,7 +1,14 @@
 use std::str::FromStr;
 use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
 
-use crate::{controller::GridController, grid::SheetId, Rect};
+use crate::{
+    controller::{
+        export::{CsvExportOptions, JsonExportShape},
+        GridController,
+    },
+    grid::SheetId,
+    Rect,
+};
 
 #[wasm_bindgen]
 impl GridController {
@@ -14,8 +21,69 @@ impl GridController {
     ) -> Result<String, JsValue> {
         let sheet_id = SheetId::from_str(sheet_id).map_err(|e| e.to_string())?;
         let output = self
-            .export_csv_selection(sheet_id, selection)
+            .export_csv_selection(sheet_id, selection, CsvExportOptions::default())
             .map_err(|e| e.to_string())?;
         Ok(output)
     }
+
+    /// Exports the selection as CSV using `options` (a [`CsvExportOptions`]).
+    #[wasm_bindgen(js_name = "exportCsvSelectionWithOptions")]
+    pub fn js_export_csv_selection_with_options(
+        &self,
+        sheet_id: &str,
+        selection: &Rect,
+        options: JsValue,
+    ) -> Result<String, JsValue> {
+        let sheet_id = SheetId::from_str(sheet_id).map_err(|e| e.to_string())?;
+        let options = serde_wasm_bindgen::from_value(options)?;
+        let output = self
+            .export_csv_selection(sheet_id, selection, options)
+            .map_err(|e| e.to_string())?;
+        Ok(output)
+    }
+
+    #[wasm_bindgen(js_name = "exportCsvSheet")]
+    pub fn js_export_csv_sheet(&self, sheet_id: &str, options: JsValue) -> Result<String, JsValue> {
+        let sheet_id = SheetId::from_str(sheet_id).map_err(|e| e.to_string())?;
+        let options = serde_wasm_bindgen::from_value(options)?;
+        let output = self
+            .export_csv_sheet(sheet_id, options)
+            .map_err(|e| e.to_string())?;
+        Ok(output)
+    }
+
+    #[wasm_bindgen(js_name = "exportJsonSelection")]
+    pub fn js_export_json_selection(
+        &self,
+        sheet_id: &str,
+        selection: &Rect,
+        shape: JsonExportShape,
+        formatted: bool,
+    ) -> Result<String, JsValue> {
+        let sheet_id = SheetId::from_str(sheet_id).map_err(|e| e.to_string())?;
+        let output = self
+            .export_json_selection(sheet_id, selection, shape, formatted)
+            .map_err(|e| e.to_string())?;
+        Ok(output)
+    }
+
+    #[wasm_bindgen(js_name = "exportMarkdownSelection")]
+    pub fn js_export_markdown_selection(
+        &self,
+        sheet_id: &str,
+        selection: &Rect,
+        formatted: bool,
+    ) -> Result<String, JsValue> {
+        let sheet_id = SheetId::from_str(sheet_id).map_err(|e| e.to_string())?;
+        let output = self
+            .export_markdown_selection(sheet_id, selection, formatted)
+            .map_err(|e| e.to_string())?;
+        Ok(output)
+    }
+
+    #[wasm_bindgen(js_name = "exportXlsx")]
+    pub fn js_export_xlsx(&self) -> Result<Vec<u8>, JsValue> {
+        let output = self.export_xlsx().map_err(|e| e.to_string())?;
+        Ok(output)
+    }
 }
@@ -145,6 +145,49 @@ impl GridController {
         )?)
     }
 
+    /// Sets cells numeric_format to a custom format code, such as `#,##0.00`
+    ///
+    /// Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "setCellCustomNumberFormat")]
+    pub fn js_set_cell_custom_number_format(
+        &mut self,
+        sheet_id: String,
+        rect: &Rect,
+        code: String,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).unwrap();
+        let custom = NumericFormat {
+            kind: NumericFormatKind::Custom(code),
+            symbol: None,
+        };
+        Ok(serde_wasm_bindgen::to_value(
+            &self.set_cell_numeric_format(rect.to_sheet_rect(sheet_id), Some(custom), cursor),
+        )?)
+    }
+
+    /// Sets cells numeric_format to a date/time format code, such as
+    /// `yyyy-mm-dd`, `[h]:mm:ss` or `relative`
+    ///
+    /// Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "setCellDateTimeFormat")]
+    pub fn js_set_cell_date_time_format(
+        &mut self,
+        sheet_id: String,
+        rect: &Rect,
+        code: String,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).unwrap();
+        let date_time = NumericFormat {
+            kind: NumericFormatKind::DateTime(code),
+            symbol: None,
+        };
+        Ok(serde_wasm_bindgen::to_value(
+            &self.set_cell_numeric_format(rect.to_sheet_rect(sheet_id), Some(date_time), cursor),
+        )?)
+    }
+
     /// Sets cells numeric_commas
     ///
     /// Returns a [`TransactionSummary`].
@@ -203,6 +246,134 @@ impl GridController {
         ))?)
     }
 
+    /// Sets cell underline formatting given as an optional [`bool`].
+    ///
+    /// Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "setCellUnderline")]
+    pub fn js_set_cell_underline(
+        &mut self,
+        sheet_id: String,
+        rect: &Rect,
+        underline: JsValue,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let Ok(sheet_id) = SheetId::from_str(&sheet_id) else {
+            return Result::Err("Invalid sheet id".into());
+        };
+        let value: Option<bool> = serde_wasm_bindgen::from_value(underline)?;
+        Ok(serde_wasm_bindgen::to_value(&self.set_cell_underline(
+            rect.to_sheet_rect(sheet_id),
+            value,
+            cursor,
+        ))?)
+    }
+
+    /// Sets cell strikethrough formatting given as an optional [`bool`].
+    ///
+    /// Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "setCellStrikeThrough")]
+    pub fn js_set_cell_strike_through(
+        &mut self,
+        sheet_id: String,
+        rect: &Rect,
+        strike_through: JsValue,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let Ok(sheet_id) = SheetId::from_str(&sheet_id) else {
+            return Result::Err("Invalid sheet id".into());
+        };
+        let value: Option<bool> = serde_wasm_bindgen::from_value(strike_through)?;
+        Ok(serde_wasm_bindgen::to_value(
+            &self.set_cell_strike_through(rect.to_sheet_rect(sheet_id), value, cursor),
+        )?)
+    }
+
+    /// Sets cell font size in points given as an optional [`i16`].
+    ///
+    /// Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "setCellFontSize")]
+    pub fn js_set_cell_font_size(
+        &mut self,
+        sheet_id: String,
+        rect: &Rect,
+        font_size: JsValue,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let Ok(sheet_id) = SheetId::from_str(&sheet_id) else {
+            return Result::Err("Invalid sheet id".into());
+        };
+        let value: Option<i16> = serde_wasm_bindgen::from_value(font_size)?;
+        Ok(serde_wasm_bindgen::to_value(&self.set_cell_font_size(
+            rect.to_sheet_rect(sheet_id),
+            value,
+            cursor,
+        ))?)
+    }
+
+    /// Sets cell font family given as an optional [`String`].
+    ///
+    /// Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "setCellFontFamily")]
+    pub fn js_set_cell_font_family(
+        &mut self,
+        sheet_id: String,
+        rect: &Rect,
+        font_family: JsValue,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let Ok(sheet_id) = SheetId::from_str(&sheet_id) else {
+            return Result::Err("Invalid sheet id".into());
+        };
+        let value: Option<String> = serde_wasm_bindgen::from_value(font_family)?;
+        Ok(serde_wasm_bindgen::to_value(&self.set_cell_font_family(
+            rect.to_sheet_rect(sheet_id),
+            value,
+            cursor,
+        ))?)
+    }
+
+    /// Sets cell vertical alignment given as an optional [`CellVerticalAlign`].
+    ///
+    /// Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "setCellVerticalAlign")]
+    pub fn js_set_cell_vertical_align(
+        &mut self,
+        sheet_id: String,
+        rect: &Rect,
+        vertical_align: JsValue,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let Ok(sheet_id) = SheetId::from_str(&sheet_id) else {
+            return Result::Err("Invalid sheet id".into());
+        };
+        let value: Option<CellVerticalAlign> = serde_wasm_bindgen::from_value(vertical_align)?;
+        Ok(serde_wasm_bindgen::to_value(
+            &self.set_cell_vertical_align(rect.to_sheet_rect(sheet_id), value, cursor),
+        )?)
+    }
+
+    /// Sets cell text rotation in degrees counterclockwise (-90 to 90) given as an optional [`i16`].
+    ///
+    /// Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "setCellTextRotation")]
+    pub fn js_set_cell_text_rotation(
+        &mut self,
+        sheet_id: String,
+        rect: &Rect,
+        text_rotation: JsValue,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let Ok(sheet_id) = SheetId::from_str(&sheet_id) else {
+            return Result::Err("Invalid sheet id".into());
+        };
+        let value: Option<i16> = serde_wasm_bindgen::from_value(text_rotation)?;
+        Ok(serde_wasm_bindgen::to_value(&self.set_cell_text_rotation(
+            rect.to_sheet_rect(sheet_id),
+            value,
+            cursor,
+        ))?)
+    }
+
     /// Sets cell text color given as an optional [`String`].
     ///
     /// Returns a [`TransactionSummary`].
@@ -0,0 +1,26 @@
+use super::*;
+use crate::grid::Locale;
+
+#[wasm_bindgen]
+impl GridController {
+    /// Sets the workbook's [`Locale`].
+    ///
+    /// Returns a [`TransactionSummary`], or an error if the locale's
+    /// separators are ambiguous.
+    #[wasm_bindgen(js_name = "setLocale")]
+    pub fn js_set_locale(
+        &mut self,
+        locale: JsValue,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let locale: Locale = serde_wasm_bindgen::from_value(locale)?;
+        let summary = self.set_locale(locale, cursor).map_err(|e| e.to_string())?;
+        Ok(serde_wasm_bindgen::to_value(&summary)?)
+    }
+
+    /// Returns the workbook's [`Locale`] as a string containing JSON.
+    #[wasm_bindgen(js_name = "getLocale")]
+    pub fn js_get_locale(&self) -> Result<String, JsValue> {
+        Ok(serde_json::to_string(self.grid().locale()).map_err(|e| e.to_string())?)
+    }
+}
@@ -0,0 +1,46 @@
+use super::*;
+
+#[wasm_bindgen]
+impl GridController {
+    /// Merges the cells in a rectangle, keeping only the top-left value.
+    ///
+    /// Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "mergeCells")]
+    pub fn js_merge_cells(
+        &mut self,
+        sheet_id: String,
+        rect: Rect,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|e| e.to_string())?;
+        Ok(serde_wasm_bindgen::to_value(
+            &self.merge_cells(rect.to_sheet_rect(sheet_id), cursor),
+        )?)
+    }
+
+    /// Unmerges all merged cells that intersect a rectangle.
+    ///
+    /// Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "unmergeCells")]
+    pub fn js_unmerge_cells(
+        &mut self,
+        sheet_id: String,
+        rect: Rect,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|e| e.to_string())?;
+        Ok(serde_wasm_bindgen::to_value(
+            &self.unmerge_cells(rect.to_sheet_rect(sheet_id), cursor),
+        )?)
+    }
+
+    /// Returns the merged regions in a sheet as a string containing a JSON
+    /// array of [`Rect`].
+    #[wasm_bindgen(js_name = "getMergedCells")]
+    pub fn js_get_merged_cells(&self, sheet_id: String) -> Result<String, JsValue> {
+        let Some(sheet) = self.try_sheet_from_string_id(sheet_id) else {
+            return Result::Err("Sheet not found".into());
+        };
+        Ok(serde_json::to_string(&sheet.merged_cells).map_err(|e| e.to_string())?)
+    }
+}
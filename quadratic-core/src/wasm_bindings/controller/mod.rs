@@ -7,29 +7,58 @@ pub mod auto_complete;
 pub mod borders;
 pub mod bounds;
 pub mod cells;
+pub mod charts;
 pub mod clipboard;
 pub mod code;
+pub mod comments;
+pub mod conditional_formats;
 pub mod export;
 pub mod formatting;
 pub mod import;
+pub mod locale;
+pub mod merge_cells;
+pub mod move_cells;
+pub mod protection;
 pub mod render;
 pub mod search;
 pub mod sheet_offsets;
 pub mod sheets;
 pub mod summarize;
 pub mod transactions;
+pub mod validations;
 
 #[wasm_bindgen]
 impl GridController {
-    /// Imports a [`GridController`] from a JSON string.
+    /// Imports a [`GridController`] from a JSON string, repairing any
+    /// problems in the file.
     #[wasm_bindgen(js_name = "newFromFile")]
     pub fn js_new_from_file(file: &str, last_sequence_num: u32) -> Result<GridController, JsValue> {
-        Ok(GridController::from_grid(
-            file::import(file).map_err(|e| e.to_string())?,
+        Self::js_new_from_file_bytes(file.as_bytes(), last_sequence_num)
+    }
+
+    /// Imports a [`GridController`] from the bytes of a binary or JSON file,
+    /// repairing any problems in the file.
+    #[wasm_bindgen(js_name = "newFromFileBytes")]
+    pub fn js_new_from_file_bytes(
+        file: &[u8],
+        last_sequence_num: u32,
+    ) -> Result<GridController, JsValue> {
+        let (grid, report) = file::import_vec_with_report(file, true).map_err(|e| e.to_string())?;
+        Ok(GridController::from_file(
+            grid,
             last_sequence_num as u64,
+            report,
         ))
     }
 
+    /// Returns the problems found when the file was loaded as a string
+    /// containing a JSON
+    /// [`ValidationReport`](crate::grid::file::validate::ValidationReport).
+    #[wasm_bindgen(js_name = "getFileReport")]
+    pub fn js_file_report(&self) -> Result<String, JsValue> {
+        Ok(serde_json::to_string(self.file_report()).map_err(|e| e.to_string())?)
+    }
+
     #[wasm_bindgen(js_name = "test")]
     pub fn js_test() -> GridController {
         GridController::test()
@@ -41,6 +70,12 @@ impl GridController {
         Ok(file::export(self.grid_mut()).map_err(|e| e.to_string())?)
     }
 
+    /// Exports a [`GridController`] to a binary file. Returns a `Uint8Array`.
+    #[wasm_bindgen(js_name = "exportToBinaryFile")]
+    pub fn js_export_to_binary_file(&mut self) -> Result<Vec<u8>, JsValue> {
+        Ok(file::export_binary(self.grid_mut()).map_err(|e| e.to_string())?)
+    }
+
     /// Exports a [`string`]
     #[wasm_bindgen(js_name = "getVersion")]
     pub fn js_file_version(&self) -> String {
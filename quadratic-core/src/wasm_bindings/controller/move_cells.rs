@@ -0,0 +1,26 @@
+use super::*;
+
+#[wasm_bindgen]
+impl GridController {
+    /// Moves the cells in a rectangle so that its top-left cell is at `dest`
+    /// (possibly on another sheet), updating formulas that reference them.
+    ///
+    /// Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "moveCells")]
+    pub fn js_move_cells(
+        &mut self,
+        sheet_id: String,
+        rect: Rect,
+        dest_sheet_id: String,
+        dest: Pos,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|e| e.to_string())?;
+        let dest_sheet_id = SheetId::from_str(&dest_sheet_id).map_err(|e| e.to_string())?;
+        Ok(serde_wasm_bindgen::to_value(&self.move_cells(
+            rect.to_sheet_rect(sheet_id),
+            dest.to_sheet_pos(dest_sheet_id),
+            cursor,
+        ))?)
+    }
+}
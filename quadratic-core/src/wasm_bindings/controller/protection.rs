@@ -0,0 +1,91 @@
+use super::*;
+
+#[wasm_bindgen]
+impl GridController {
+    /// Sets the id of the user making changes, which is checked against
+    /// locked sheets and protected ranges.
+    #[wasm_bindgen(js_name = "setUserId")]
+    pub fn js_set_user_id(&mut self, user_id: Option<String>) {
+        self.set_user_id(user_id);
+    }
+
+    /// Locks a sheet so only the editors (an array of user ids) and the
+    /// current user can edit it.
+    ///
+    /// Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "lockSheet")]
+    pub fn js_lock_sheet(
+        &mut self,
+        sheet_id: String,
+        editors: JsValue,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|e| e.to_string())?;
+        let editors: Vec<String> = serde_wasm_bindgen::from_value(editors)?;
+        Ok(serde_wasm_bindgen::to_value(
+            &self.lock_sheet(sheet_id, editors, cursor),
+        )?)
+    }
+
+    /// Unlocks a sheet.
+    ///
+    /// Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "unlockSheet")]
+    pub fn js_unlock_sheet(
+        &mut self,
+        sheet_id: String,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|e| e.to_string())?;
+        Ok(serde_wasm_bindgen::to_value(
+            &self.unlock_sheet(sheet_id, cursor),
+        )?)
+    }
+
+    /// Protects a rectangle so only the editors (an array of user ids) and
+    /// the current user can edit it.
+    ///
+    /// Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "addProtectedRange")]
+    pub fn js_add_protected_range(
+        &mut self,
+        sheet_id: String,
+        rect: Rect,
+        editors: JsValue,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|e| e.to_string())?;
+        let editors: Vec<String> = serde_wasm_bindgen::from_value(editors)?;
+        Ok(serde_wasm_bindgen::to_value(&self.add_protected_range(
+            rect.to_sheet_rect(sheet_id),
+            editors,
+            cursor,
+        ))?)
+    }
+
+    /// Removes all protected ranges that intersect a rectangle.
+    ///
+    /// Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "removeProtectedRanges")]
+    pub fn js_remove_protected_ranges(
+        &mut self,
+        sheet_id: String,
+        rect: Rect,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|e| e.to_string())?;
+        Ok(serde_wasm_bindgen::to_value(
+            &self.remove_protected_ranges(rect.to_sheet_rect(sheet_id), cursor),
+        )?)
+    }
+
+    /// Returns the protection of a sheet as a string containing a JSON
+    /// [`SheetProtection`](crate::grid::sheet::protection::SheetProtection).
+    #[wasm_bindgen(js_name = "getSheetProtection")]
+    pub fn js_get_sheet_protection(&self, sheet_id: String) -> Result<String, JsValue> {
+        let Some(sheet) = self.try_sheet_from_string_id(sheet_id) else {
+            return Result::Err("Sheet not found".into());
+        };
+        Ok(serde_json::to_string(&sheet.protection).map_err(|e| e.to_string())?)
+    }
+}
@@ -11,7 +11,8 @@ impl GridController {
         let Some(sheet) = self.try_sheet_from_string_id(sheet_id) else {
             return Result::Err("Sheet not found".into());
         };
-        let output = sheet.get_render_cells(rect);
+        let mut output = sheet.get_render_cells(rect);
+        sheet.apply_conditional_formats(self.grid(), rect, &mut output);
         Ok(serde_json::to_string::<[JsRenderCell]>(&output).map_err(|e| e.to_string())?)
     }
 
@@ -31,7 +32,8 @@ impl GridController {
         let Some(sheet) = self.try_sheet_from_string_id(sheet_id) else {
             return Result::Err("Sheet not found".into());
         };
-        let output = sheet.get_render_fills(*region);
+        let mut output = sheet.get_render_fills(*region);
+        output.extend(sheet.get_conditional_render_fills(self.grid(), *region));
         Ok(serde_json::to_string::<[JsRenderFill]>(&output).map_err(|e| e.to_string())?)
     }
 
@@ -42,7 +44,8 @@ impl GridController {
         let Some(sheet) = self.try_sheet_from_string_id(sheet_id) else {
             return Result::Err("Sheet not found".into());
         };
-        let output = sheet.get_all_render_fills();
+        let mut output = sheet.get_all_render_fills();
+        output.extend(sheet.get_all_conditional_render_fills(self.grid()));
         Ok(serde_json::to_string::<[JsRenderFill]>(&output).map_err(|e| e.to_string())?)
     }
 
@@ -52,7 +55,8 @@ impl GridController {
         let Some(sheet) = self.try_sheet_from_string_id(sheet_id) else {
             return Result::Err("Sheet not found".into());
         };
-        let output = sheet.get_html_output();
+        let mut output = sheet.get_html_output();
+        output.extend(sheet.get_chart_html_output(self.grid()));
         Ok(serde_json::to_string::<[JsHtmlOutput]>(&output).map_err(|e| e.to_string())?)
     }
 
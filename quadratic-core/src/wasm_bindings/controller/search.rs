@@ -7,4 +7,28 @@ impl GridController {
         let search = self.grid().search(&query, options);
         Ok(serde_wasm_bindgen::to_value(&search)?)
     }
+
+    /// Returns [`JsFindReplace`] with the summary, number of replacements,
+    /// and the next match to replace
+    #[wasm_bindgen(js_name = "findReplace")]
+    pub fn js_find_replace(
+        &mut self,
+        query: String,
+        replace: String,
+        options: JsValue,
+        replace_all: bool,
+        current: JsValue,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let options = serde_wasm_bindgen::from_value(options)?;
+        let current = serde_wasm_bindgen::from_value(current)?;
+        let (summary, count, next) =
+            self.find_replace(&query, &replace, options, replace_all, current, cursor);
+        let output = JsFindReplace {
+            summary,
+            count,
+            next,
+        };
+        Ok(serde_wasm_bindgen::to_value(&output).map_err(|e| e.to_string())?)
+    }
 }
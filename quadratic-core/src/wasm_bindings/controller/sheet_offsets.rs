@@ -44,4 +44,36 @@ impl GridController {
             sheet_id, column, row, size, cursor,
         ))?)
     }
+
+    /// Hides or unhides a list of columns. Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "setColumnsHidden")]
+    pub fn js_set_columns_hidden(
+        &mut self,
+        sheet_id: String,
+        columns: JsValue,
+        hidden: bool,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).unwrap();
+        let columns: Vec<i64> = serde_wasm_bindgen::from_value(columns)?;
+        Ok(serde_wasm_bindgen::to_value(
+            &self.set_columns_hidden(sheet_id, columns, hidden, cursor),
+        )?)
+    }
+
+    /// Hides or unhides a list of rows. Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "setRowsHidden")]
+    pub fn js_set_rows_hidden(
+        &mut self,
+        sheet_id: String,
+        rows: JsValue,
+        hidden: bool,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).unwrap();
+        let rows: Vec<i64> = serde_wasm_bindgen::from_value(rows)?;
+        Ok(serde_wasm_bindgen::to_value(
+            &self.set_rows_hidden(sheet_id, rows, hidden, cursor),
+        )?)
+    }
 }
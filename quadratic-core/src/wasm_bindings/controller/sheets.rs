@@ -96,6 +96,24 @@ impl GridController {
         sheet.color.clone().unwrap_or_default()
     }
 
+    #[wasm_bindgen(js_name = "getSheetFrozenRows")]
+    pub fn js_sheet_frozen_rows(&self, sheet_id: String) -> u32 {
+        // todo: should return a Result
+        let Some(sheet) = self.try_sheet_from_string_id(sheet_id) else {
+            return 0;
+        };
+        sheet.frozen_rows
+    }
+
+    #[wasm_bindgen(js_name = "getSheetFrozenColumns")]
+    pub fn js_sheet_frozen_columns(&self, sheet_id: String) -> u32 {
+        // todo: should return a Result
+        let Some(sheet) = self.try_sheet_from_string_id(sheet_id) else {
+            return 0;
+        };
+        sheet.frozen_columns
+    }
+
     /// Returns a code cell as a [`TransactionSummary`].
     #[wasm_bindgen(js_name = "setSheetName")]
     pub fn js_set_sheet_name(
@@ -123,4 +141,19 @@ impl GridController {
             &self.set_sheet_color(sheet_id, color, cursor),
         )?)
     }
+
+    /// Sets the frozen rows and columns of a sheet. Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "setSheetFrozen")]
+    pub fn js_set_sheet_frozen(
+        &mut self,
+        sheet_id: String,
+        rows: u32,
+        columns: u32,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).unwrap();
+        Ok(serde_wasm_bindgen::to_value(
+            &self.set_sheet_frozen(sheet_id, rows, columns, cursor),
+        )?)
+    }
 }
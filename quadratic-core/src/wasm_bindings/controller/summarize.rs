@@ -21,6 +21,7 @@ impl GridController {
         sheet_id: String,
         rect: &Rect,
         max_decimals: i64,
+        skip_hidden: Option<bool>,
     ) -> Option<SummarizeSelectionResult> {
         // don't allow too large of a selection
         if rect.len() > MAX_SUMMARIZE_SELECTION_SIZE {
@@ -32,8 +33,15 @@ impl GridController {
         // sum and count
         let mut count = 0;
         let mut sum = BigDecimal::zero();
+        let skip_hidden = skip_hidden.unwrap_or(false);
         for x in rect.x_range() {
+            if skip_hidden && sheet.offsets.is_column_hidden(x) {
+                continue;
+            }
             for y in rect.y_range() {
+                if skip_hidden && sheet.offsets.is_row_hidden(y) {
+                    continue;
+                }
                 if let Some(cell) = sheet.display_value(Pos { x, y }) {
                     // if value is not an error or blank, count it
                     match cell {
@@ -90,7 +98,7 @@ mod tests {
         // span of 10 cells, 3 have numeric values
         let rect = Rect::new_span(Pos { x: 1, y: 1 }, Pos { x: 1, y: 10 });
         let result = gc
-            .js_summarize_selection(sheet_id.to_string(), &rect, 9)
+            .js_summarize_selection(sheet_id.to_string(), &rect, 9, None)
             .unwrap();
         assert_eq!(result.count, 3);
         assert_eq!(result.sum, Some(12325.12));
@@ -99,7 +107,7 @@ mod tests {
         // returns zeros for an empty selection
         let rect = Rect::new_span(Pos { x: 100, y: 100 }, Pos { x: 1000, y: 105 });
         let result = gc
-            .js_summarize_selection(sheet_id.to_string(), &rect, 9)
+            .js_summarize_selection(sheet_id.to_string(), &rect, 9, None)
             .unwrap();
         assert_eq!(result.count, 0);
         assert_eq!(result.sum, Some(0.0));
@@ -107,14 +115,14 @@ mod tests {
 
         // returns none if selection is too large (MAX_SUMMARIZE_SELECTION_SIZE)
         let rect = Rect::new_span(Pos { x: 100, y: 100 }, Pos { x: 10000, y: 10000 });
-        let result = gc.js_summarize_selection(sheet_id.to_string(), &rect, 9);
+        let result = gc.js_summarize_selection(sheet_id.to_string(), &rect, 9, None);
         assert!(result.is_none());
 
         // rounding
         set_value(&mut gc, 1, 1, "9.1234567891");
         let rect = Rect::new_span(Pos { x: 1, y: 1 }, Pos { x: 1, y: 10 });
         let result = gc
-            .js_summarize_selection(sheet_id.to_string(), &rect, 9)
+            .js_summarize_selection(sheet_id.to_string(), &rect, 9, None)
             .unwrap();
         assert_eq!(result.count, 3);
         assert_eq!(result.sum, Some(12322.123456789));
@@ -124,10 +132,32 @@ mod tests {
         set_value(&mut gc, -1, -1, "0.00100000000000");
         let rect = Rect::new_span(Pos { x: -1, y: -1 }, Pos { x: -1, y: -10 });
         let result = gc
-            .js_summarize_selection(sheet_id.to_string(), &rect, 9)
+            .js_summarize_selection(sheet_id.to_string(), &rect, 9, None)
             .unwrap();
         assert_eq!(result.count, 1);
         assert_eq!(result.sum, Some(0.001));
         assert_eq!(result.average, Some(0.001));
     }
+
+    #[test]
+    fn test_summarize_skip_hidden() {
+        let mut gc = GridController::default();
+        let sheet_id = gc.sheet_ids()[0];
+
+        set_value(&mut gc, 1, 1, "1");
+        set_value(&mut gc, 1, 2, "10");
+        set_value(&mut gc, 1, 3, "100");
+        gc.set_rows_hidden(sheet_id, vec![2], true, None);
+
+        let rect = Rect::new_span(Pos { x: 1, y: 1 }, Pos { x: 1, y: 3 });
+        let result = gc
+            .js_summarize_selection(sheet_id.to_string(), &rect, 9, None)
+            .unwrap();
+        assert_eq!(result.sum, Some(111.0));
+        let result = gc
+            .js_summarize_selection(sheet_id.to_string(), &rect, 9, Some(true))
+            .unwrap();
+        assert_eq!(result.count, 2);
+        assert_eq!(result.sum, Some(101.0));
+    }
 }
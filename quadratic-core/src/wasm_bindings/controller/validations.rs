@@ -0,0 +1,66 @@
+use super::*;
+use crate::grid::sheet::validations::Validation;
+
+#[wasm_bindgen]
+impl GridController {
+    /// Adds a data validation (a [`Validation`]) to a sheet.
+    ///
+    /// Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "addValidation")]
+    pub fn js_add_validation(
+        &mut self,
+        sheet_id: String,
+        validation: JsValue,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|e| e.to_string())?;
+        let validation: Validation = serde_wasm_bindgen::from_value(validation)?;
+        Ok(serde_wasm_bindgen::to_value(
+            &self.add_validation(sheet_id, validation, cursor),
+        )?)
+    }
+
+    /// Removes all data validations that intersect a rectangle.
+    ///
+    /// Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "removeValidations")]
+    pub fn js_remove_validations(
+        &mut self,
+        sheet_id: String,
+        rect: Rect,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|e| e.to_string())?;
+        Ok(serde_wasm_bindgen::to_value(
+            &self.remove_validations(rect.to_sheet_rect(sheet_id), cursor),
+        )?)
+    }
+
+    /// Returns the data validations in a sheet as a string containing a JSON
+    /// array of [`Validation`].
+    #[wasm_bindgen(js_name = "getValidations")]
+    pub fn js_get_validations(&self, sheet_id: String) -> Result<String, JsValue> {
+        let Some(sheet) = self.try_sheet_from_string_id(sheet_id) else {
+            return Result::Err("Sheet not found".into());
+        };
+        Ok(serde_json::to_string(&sheet.validations).map_err(|e| e.to_string())?)
+    }
+
+    /// Returns the dropdown options for a cell with a list validation, or
+    /// undefined if the cell does not have one.
+    #[wasm_bindgen(js_name = "getValidationList")]
+    pub fn js_get_validation_list(&self, sheet_id: String, pos: Pos) -> Result<JsValue, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|e| e.to_string())?;
+        Ok(serde_wasm_bindgen::to_value(
+            &self.validation_list(pos.to_sheet_pos(sheet_id)),
+        )?)
+    }
+
+    /// Returns the cells in a sheet that fail their data validation as a
+    /// string containing a JSON array of [`Pos`].
+    #[wasm_bindgen(js_name = "getInvalidCells")]
+    pub fn js_get_invalid_cells(&self, sheet_id: String) -> Result<String, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|e| e.to_string())?;
+        Ok(serde_json::to_string(&self.invalid_cells(sheet_id)).map_err(|e| e.to_string())?)
+    }
+}
@@ -7,7 +7,7 @@ use quadratic_core::{
         operations::operation::Operation, transaction::TransactionServer, GridController,
     },
     grid::{
-        file::{export_vec, import, CURRENT_VERSION},
+        file::{export_binary, import_vec_with_report, CURRENT_VERSION},
         Grid,
     },
 };
@@ -27,14 +27,20 @@ use crate::{
 
 pub static GROUP_NAME: &str = "quadratic-file-service-1";
 
-/// Load a .grid file
-pub(crate) fn load_file(key: &str, file: &str) -> Result<Grid> {
-    import(file).map_err(|e| FilesError::ImportFile(key.into(), e.to_string()))
+/// Load a .grid file, which may be a binary or JSON file, repairing any
+/// problems in the file
+pub(crate) fn load_file(key: &str, file: &[u8]) -> Result<Grid> {
+    let (grid, report) = import_vec_with_report(file, true)
+        .map_err(|e| FilesError::ImportFile(key.into(), e.to_string()))?;
+    for problem in report.problems {
+        tracing::warn!("Repaired file {key}: {problem}");
+    }
+    Ok(grid)
 }
 
-/// Exports a .grid file
+/// Exports a binary .grid file
 pub(crate) fn export_file(key: &str, grid: &mut Grid) -> Result<Vec<u8>> {
-    export_vec(grid).map_err(|e| FilesError::ExportFile(key.into(), e.to_string()))
+    export_binary(grid).map_err(|e| FilesError::ExportFile(key.into(), e.to_string()))
 }
 
 /// Apply a vec of operations to the grid
@@ -56,9 +62,7 @@ pub(crate) async fn get_and_load_object(
         .await
         .map_err(|e| FilesError::LoadFile(key.into(), bucket.to_string(), e.to_string()))?
         .into_bytes();
-    let body = std::str::from_utf8(&body)
-        .map_err(|e| FilesError::LoadFile(key.into(), bucket.to_string(), e.to_string()))?;
-    let grid = load_file(key, body)?;
+    let grid = load_file(key, &body)?;
 
     Ok(GridController::from_grid(grid, sequence_num))
 }
@@ -248,7 +252,7 @@ mod tests {
     // use crate::test_util::new_arc_state;
 
     use super::*;
-    use quadratic_core::{CellValue, Pos, SheetPos};
+    use quadratic_core::{grid::file::BINARY_MAGIC, CellValue, Pos, SheetPos};
 
     #[test]
     fn loads_a_file_and_applies_a_transaction_and_exports_the_file() {
@@ -257,7 +261,7 @@ mod tests {
         // load the file
         let mut file = load_file(
             key,
-            include_str!("../../quadratic-rust-shared/data/grid/v1_4_simple.grid"),
+            include_bytes!("../../quadratic-rust-shared/data/grid/v1_4_simple.grid"),
         )
         .unwrap();
 
@@ -296,6 +300,21 @@ mod tests {
         assert!(grid.is_ok());
     }
 
+    #[test]
+    fn loads_a_binary_file() {
+        let key = "test";
+        let mut file = load_file(
+            key,
+            include_bytes!("../../quadratic-rust-shared/data/grid/v1_4_simple.grid"),
+        )
+        .unwrap();
+        let binary = export_file(key, &mut file).unwrap();
+        assert!(binary.starts_with(BINARY_MAGIC));
+        let loaded = load_file(key, &binary).unwrap();
+
+        assert_eq!(loaded.sheets().len(), file.sheets().len());
+    }
+
     #[tokio::test]
     async fn processes_a_file() {
         // let state = new_arc_state().await;
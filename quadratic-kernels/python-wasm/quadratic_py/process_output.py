@@ -86,6 +86,10 @@ def process_output_value(output_value):
     else:
         output_value = to_quadratic_type(output_value)
 
+        # matplotlib figures, PIL images and image bytes
+        if output_value[1] == "image":
+            output_type = "Image"
+
     return {
         "typed_array_output": typed_array_output,
         "array_output": array_output,
@@ -1,4 +1,6 @@
 import ast
+import base64
+import io
 import operator
 import re
 import traceback
@@ -102,8 +104,41 @@ def normalize_bool(value: str) -> bool:
 
     return value
 
+# Convert images (matplotlib figures, PIL images and PNG or JPEG bytes) to a
+# data URL, or return None if the value is not an image
+def to_image_data_url(value) -> str | None:
+    data = None
+
+    if isinstance(value, (bytes, bytearray)):
+        data = bytes(value)
+    elif hasattr(value, "savefig") or (hasattr(value, "save") and hasattr(value, "getbbox")):
+        buffer = io.BytesIO()
+        try:
+            if hasattr(value, "savefig"):
+                value.savefig(buffer, format="png", bbox_inches="tight")
+            else:
+                value.save(buffer, format="PNG")
+        except Exception:
+            return None
+        data = buffer.getvalue()
+
+    # label the data by its contents, not by how it was made
+    if data is None:
+        return None
+    elif data.startswith(b"\x89PNG\r\n\x1a\n"):
+        mime_type = "image/png"
+    elif data.startswith(b"\xff\xd8"):
+        mime_type = "image/jpeg"
+    else:
+        return None
+    return f"data:{mime_type};base64,{base64.b64encode(data).decode('ascii')}"
+
 # Convert from python types to quadratic types
 def to_quadratic_type(value: int | float | str | bool | pd.Timestamp | date | time | datetime | pd.Period | timedelta | None) -> Tuple[str, str]:
+    image = to_image_data_url(value)
+    if image is not None:
+        return (image, "image")
+
     try:    
         if value == None or value == "":
             return ("", "blank")
@@ -1,3 +1,6 @@
+import base64
+import struct
+import zlib
 from datetime import datetime
 from unittest import TestCase
 
@@ -13,6 +16,22 @@ def assert_pov(self, input, typed_array_output, array_output, output_size, outpu
     self.assertEqual(result['output_value'], output_value)
     self.assertEqual(result['output_type'], output_type)
 
+# a complete, transparent PNG
+def png(width: int, height: int) -> bytes:
+    def chunk(kind: bytes, data: bytes) -> bytes:
+        return struct.pack(">I", len(data)) + kind + data + struct.pack(">I", zlib.crc32(kind + data))
+
+    pixels = b"".join(b"\x00" + b"\x00\x00\x00\x00" * width for _ in range(height))
+    return (
+        b"\x89PNG\r\n\x1a\n"
+        + chunk(b"IHDR", struct.pack(">IIBBBBB", width, height, 8, 6, 0, 0, 0))
+        + chunk(b"IDAT", zlib.compress(pixels))
+        + chunk(b"IEND", b"")
+    )
+
+def data_url(mime_type: str, data: bytes) -> str:
+    return f"data:{mime_type};base64,{base64.b64encode(data).decode('ascii')}"
+
 class TestProcessOutput(TestCase):
     def test_value(self):
     
@@ -85,4 +104,38 @@ class TestProcessOutput(TestCase):
         assert_pov(self, pd.DataFrame([[None, None], [None, None]]), [[('', 'blank'), ('', 'blank')], [('', 'blank'), ('', 'blank')]], [[None, None], [None, None]], (2, 2), None, 'DataFrame')
         assert_pov(self, pd.DataFrame([["", " "], ["", " "]]), [[('', 'blank'), (' ', 'text')], [('', 'blank'), (' ', 'text')]], [["", " "], ["", " "]], (2, 2), None, 'DataFrame')
         # assert_pov(self, pd.DataFrame([[datetime(2021, 1, 1), datetime(2021, 1, 2)], [datetime(2021, 1, 3), datetime(2021, 1, 4)]]), [[('1609459200', 'instant'), ('1609545600', 'instant')], [('1609632000', 'instant'), ('1609718400', 'instant')]], [[datetime(2021, 1, 1), datetime(2021, 1, 2)], [datetime(2021, 1, 3), datetime(2021, 1, 4)]], (2, 2), None, 'DataFrame')
-        
\ No newline at end of file
+        
+    def test_image(self):
+        image = png(2, 3)
+        result = process_output_value(image)
+        self.assertEqual(result['output_value'], (data_url("image/png", image), 'image'))
+        self.assertEqual(result['output_type'], 'Image')
+
+        # SOI, an APP0 segment, SOF0 with a height of 40 and width of 30, then EOI
+        jpeg = b"\xff\xd8\xff\xe0\x00\x04\x00\x00\xff\xc0\x00\x0b\x08\x00\x28\x00\x1e\x01\x01\x11\x00\xff\xd9"
+        result = process_output_value(jpeg)
+        self.assertEqual(result['output_value'], (data_url("image/jpeg", jpeg), 'image'))
+        self.assertEqual(result['output_type'], 'Image')
+
+        class Figure:
+            def savefig(self, buffer, **kwargs):
+                assert kwargs["format"] == "png"
+                buffer.write(image)
+
+        result = process_output_value(Figure())
+        self.assertEqual(result['output_value'], (data_url("image/png", image), 'image'))
+        self.assertEqual(result['output_type'], 'Image')
+
+        # images are labelled by their contents
+        class JpegImage:
+            def getbbox(self):
+                return (0, 0, 30, 40)
+
+            def save(self, buffer, **kwargs):
+                buffer.write(jpeg)
+
+        result = process_output_value(JpegImage())
+        self.assertEqual(result['output_value'], (data_url("image/jpeg", jpeg), 'image'))
+
+        # other bytes are not images
+        assert_pov(self, b"abc", None, None, None, ("b'abc'", 'text'), 'bytes')
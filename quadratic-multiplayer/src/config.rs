@@ -27,6 +27,10 @@ pub(crate) struct Config {
     pub(crate) auth0_jwks_uri: String,
     pub(crate) quadratic_api_uri: String,
     pub(crate) m2m_auth_token: String,
+
+    pub(crate) aws_s3_region: String,
+    pub(crate) aws_s3_access_key_id: String,
+    pub(crate) aws_s3_secret_access_key: String,
 }
 
 /// Load the global configuration from the environment into Config.
@@ -38,6 +38,9 @@ pub(crate) enum MpError {
     #[error("Error reading MinVersion file: {0}")]
     MinVersion(String),
 
+    #[error("Protected: {0}")]
+    Protected(String),
+
     #[error("PubSub error: {0}")]
     PubSub(String),
 
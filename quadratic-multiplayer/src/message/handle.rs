@@ -185,6 +185,9 @@ pub(crate) async fn handle_message(
             // unpack the operations or return an error
             let operations_unpacked: Vec<Operation> = serde_json::from_str(&operations)?;
 
+            // reject changes to locked sheets and protected ranges
+            get_mut_room!(state, file_id)?.apply_protections(&session_id, &operations_unpacked)?;
+
             // get and increment the room's sequence_num
             let room_sequence_num = get_mut_room!(state, file_id)?.increment_sequence_num();
 
@@ -269,7 +272,7 @@ pub(crate) async fn handle_message(
 #[cfg(test)]
 pub(crate) mod tests {
     use quadratic_core::controller::operations::operation::Operation;
-    use quadratic_core::grid::SheetId;
+    use quadratic_core::grid::{sheet::protection::SheetProtection, SheetId};
     use tokio::net::TcpStream;
     use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
     use uuid::Uuid;
@@ -417,8 +420,13 @@ pub(crate) mod tests {
         let (socket, state, _, file_id, user_1, _) = setup().await;
         let id = Uuid::new_v4();
         let session_id = user_1.session_id;
+        let sheet_id = SheetId::new();
+        get_mut_room!(state, file_id)
+            .unwrap()
+            .protections
+            .insert(sheet_id, SheetProtection::default());
         let operations = serde_json::to_string(&vec![Operation::SetSheetColor {
-            sheet_id: SheetId::new(),
+            sheet_id,
             color: Some("red".to_string()),
         }])
         .unwrap();
@@ -325,6 +325,7 @@ pub(crate) async fn healthcheck() -> impl IntoResponse {
 pub(crate) mod tests {
 
     use super::*;
+    use crate::get_mut_room;
     use crate::state::settings::MinVersion;
     use crate::state::user::{User, UserStateUpdate};
     use crate::test_util::{
@@ -335,7 +336,7 @@ pub(crate) mod tests {
         http::{self, Request},
     };
     use quadratic_core::controller::operations::operation::Operation;
-    use quadratic_core::grid::SheetId;
+    use quadratic_core::grid::{sheet::protection::SheetProtection, SheetId};
     use tower::ServiceExt;
     use uuid::Uuid;
 
@@ -521,10 +522,15 @@ pub(crate) mod tests {
 
     #[tokio::test]
     async fn user_shares_operations() {
-        let (socket, _, _, file_id, user, _) = setup().await;
+        let (socket, state, _, file_id, user, _) = setup().await;
         let session_id = user.session_id;
+        let sheet_id = SheetId::new();
+        get_mut_room!(state, file_id)
+            .unwrap()
+            .protections
+            .insert(sheet_id, SheetProtection::default());
         let operations = vec![Operation::SetSheetName {
-            sheet_id: SheetId::new(),
+            sheet_id,
             name: "test".to_string(),
         }];
         let id = Uuid::new_v4();
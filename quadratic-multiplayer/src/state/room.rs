@@ -1,5 +1,12 @@
 use dashmap::DashMap;
+use quadratic_core::controller::operations::operation::Operation;
+use quadratic_core::grid::file::import_vec;
+use quadratic_core::grid::{sheet::protection::SheetProtection, SheetId};
+use quadratic_rust_shared::aws::s3::download_object;
+use quadratic_rust_shared::quadratic_api::LastCheckpoint;
 use serde::Serialize;
+use std::cell::Cell;
+use std::collections::HashMap;
 use uuid::Uuid;
 
 use crate::error::{MpError, Result};
@@ -15,6 +22,13 @@ pub(crate) struct Room {
     pub(crate) sequence_num: u64,
     pub(crate) checkpoint_sequence_num: u64,
     pub(crate) user_index: usize,
+
+    /// Sheet protections, seeded from the file's last checkpoint when the
+    /// room is created and kept up to date with the transactions sent
+    /// through the room. Sheets missing from the map have an unknown
+    /// protection.
+    #[serde(skip)]
+    pub(crate) protections: HashMap<SheetId, SheetProtection>,
 }
 
 #[cfg(test)]
@@ -34,9 +48,27 @@ impl Room {
             sequence_num,
             checkpoint_sequence_num: sequence_num,
             user_index: 0,
+            protections: HashMap::new(),
         }
     }
 
+    /// Creates a room with the sheet protections loaded for its file. The
+    /// room is not created if they could not be loaded, since it could not
+    /// check that changes are allowed.
+    pub(crate) fn with_protections(
+        file_id: Uuid,
+        sequence_num: u64,
+        protections: Result<HashMap<SheetId, SheetProtection>>,
+    ) -> Result<Self> {
+        let protections = protections.map_err(|error| {
+            tracing::warn!("Error loading sheet protections for room {file_id}: {error}");
+            error
+        })?;
+        let mut room = Room::new(file_id, sequence_num);
+        room.protections = protections;
+        Ok(room)
+    }
+
     pub fn increment_sequence_num(&mut self) -> u64 {
         self.sequence_num += 1;
         self.sequence_num
@@ -51,6 +83,40 @@ impl Room {
         Ok(user.to_owned())
     }
 
+    /// Rejects operations that change locked sheets or protected ranges the
+    /// user cannot edit, then records any protections the operations change.
+    ///
+    /// Operations on a sheet whose protection the room does not know are
+    /// rejected, since the room cannot check that the user may make them.
+    /// Sheets added earlier in the same transaction are known.
+    pub fn apply_protections(&mut self, session_id: &Uuid, operations: &[Operation]) -> Result<()> {
+        let user_id = self.get_user(session_id)?.user_id;
+        let mut protections = self.protections.clone();
+
+        for operation in operations {
+            let unknown = Cell::new(None);
+            operation
+                .check_protection(Some(&user_id), |sheet_id| {
+                    let protection = protections.get(&sheet_id);
+                    if protection.is_none() {
+                        unknown.set(Some(sheet_id));
+                    }
+                    protection
+                })
+                .map_err(|error| MpError::Protected(error.to_string()))?;
+            if let Some(sheet_id) = unknown.get() {
+                return Err(MpError::Protected(format!(
+                    "the protection of sheet {sheet_id} is not known"
+                )));
+            }
+            record_protections(&mut protections, std::slice::from_ref(operation));
+        }
+
+        self.protections = protections;
+
+        Ok(())
+    }
+
     /// Gets the next user index and increments the user index to prepare for the next user.
     pub fn user_index_increment(&mut self) -> usize {
         let index = self.user_index;
@@ -59,7 +125,80 @@ impl Room {
     }
 }
 
+/// Records the sheet protections that operations change.
+fn record_protections(
+    protections: &mut HashMap<SheetId, SheetProtection>,
+    operations: &[Operation],
+) {
+    for operation in operations {
+        match operation {
+            Operation::AddSheet { sheet } => {
+                protections.insert(sheet.id, sheet.protection.clone());
+            }
+            Operation::DuplicateSheet {
+                sheet_id,
+                new_sheet_id,
+            } => {
+                if let Some(protection) = protections.get(sheet_id).cloned() {
+                    protections.insert(*new_sheet_id, protection);
+                }
+            }
+            Operation::DeleteSheet { sheet_id } => {
+                protections.remove(sheet_id);
+            }
+            Operation::SetSheetProtection {
+                sheet_id,
+                protection,
+            } => {
+                protections.insert(*sheet_id, protection.clone());
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Returns the sheet protections saved in a .grid file.
+pub(crate) fn file_protections(file: &[u8]) -> Result<HashMap<SheetId, SheetProtection>> {
+    let grid = import_vec(file).map_err(|e| MpError::Room(e.to_string()))?;
+    Ok(grid
+        .sheets()
+        .iter()
+        .map(|sheet| (sheet.id, sheet.protection.clone()))
+        .collect())
+}
+
 impl State {
+    /// Loads a file's sheet protections from its last checkpoint and the
+    /// transactions since.
+    pub(crate) async fn load_protections(
+        &self,
+        file_id: &Uuid,
+        checkpoint: &LastCheckpoint,
+    ) -> Result<HashMap<SheetId, SheetProtection>> {
+        let file = download_object(
+            &self.settings.aws_client,
+            &checkpoint.s3_bucket,
+            &checkpoint.s3_key,
+        )
+        .await?;
+        let body = file
+            .body
+            .collect()
+            .await
+            .map_err(|e| MpError::S3(e.to_string()))?
+            .into_bytes();
+        let mut protections = file_protections(&body)?;
+
+        let transactions = self
+            .get_messages_from_pubsub(file_id, checkpoint.sequence_number + 1)
+            .await?;
+        for transaction in transactions {
+            record_protections(&mut protections, &transaction.operations);
+        }
+
+        Ok(protections)
+    }
+
     /// Retrieves a copy of a room.
     pub(crate) async fn get_room(&self, file_id: &Uuid) -> Result<Room> {
         let room = get_room!(self, file_id)?.to_owned();
@@ -161,21 +300,31 @@ macro_rules! get_mut_room {
 #[macro_export]
 macro_rules! get_or_create_room {
     ( $self:ident, $file_id:ident, $sequence_num:ident ) => {{
-        let sequence_num = match get_room!($self, $file_id) {
-            Ok(room) => room.sequence_num.max($sequence_num),
+        let room_sequence_num = get_room!($self, $file_id).map(|room| room.sequence_num);
+        let (sequence_num, protections) = match room_sequence_num {
+            Ok(room_sequence_num) => (
+                room_sequence_num.max($sequence_num),
+                Ok(std::collections::HashMap::new()),
+            ),
             Err(_) => {
                 if cfg!(test) {
-                    0
+                    (0, Ok(std::collections::HashMap::new()))
                 } else {
                     let url = &$self.settings.quadratic_api_uri;
                     let jwt = &$self.settings.m2m_auth_token;
-                    quadratic_rust_shared::quadratic_api::get_file_checkpoint(url, jwt, &$file_id)
-                        .await?
-                        .sequence_number
-                        .max($sequence_num)
+                    let checkpoint = quadratic_rust_shared::quadratic_api::get_file_checkpoint(
+                        url, jwt, &$file_id,
+                    )
+                    .await?;
+
+                    (
+                        checkpoint.sequence_number.max($sequence_num),
+                        $self.load_protections(&$file_id, &checkpoint).await,
+                    )
                 }
             }
         };
+        let room = Room::with_protections($file_id, sequence_num, protections)?;
 
         $self.rooms.lock().await.entry($file_id).or_insert_with(|| {
             tracing::info!(
@@ -184,7 +333,7 @@ macro_rules! get_or_create_room {
                 $sequence_num
             );
 
-            Room::new($file_id, sequence_num)
+            room
         })
     }};
 }
@@ -192,9 +341,139 @@ macro_rules! get_or_create_room {
 #[cfg(test)]
 mod tests {
     use crate::test_util::{new_state, new_user};
+    use quadratic_core::grid::file::export_binary;
+    use quadratic_core::grid::sheet::protection::ProtectedRange;
+    use quadratic_core::grid::{Grid, Sheet};
+    use quadratic_core::{Rect, SheetPos, SheetRect};
 
     use super::*;
 
+    #[test]
+    fn rejects_operations_on_protected_ranges() {
+        let mut room = Room::new(Uuid::new_v4(), 0);
+        let owner = new_user();
+        let other = new_user();
+        room.users.insert(owner.session_id, owner.clone());
+        room.users.insert(other.session_id, other.clone());
+
+        let sheet_id = SheetId::new();
+        room.protections
+            .insert(sheet_id, SheetProtection::default());
+        let protect = Operation::SetSheetProtection {
+            sheet_id,
+            protection: SheetProtection {
+                locked: None,
+                ranges: vec![ProtectedRange {
+                    rect: Rect::from_numbers(0, 0, 2, 2),
+                    editors: vec![owner.user_id.clone()],
+                }],
+            },
+        };
+        room.apply_protections(&owner.session_id, &[protect])
+            .unwrap();
+        assert!(room.protections.contains_key(&sheet_id));
+
+        let merge = Operation::MergeCells {
+            sheet_rect: SheetRect::single_sheet_pos(SheetPos::new(sheet_id, 1, 1)),
+        };
+        let result = room.apply_protections(&other.session_id, &[merge.clone()]);
+        assert!(matches!(result, Err(MpError::Protected(_))));
+        room.apply_protections(&owner.session_id, &[merge]).unwrap();
+
+        // only the range's editors can remove it
+        let unprotect = Operation::SetSheetProtection {
+            sheet_id,
+            protection: SheetProtection::default(),
+        };
+        let result = room.apply_protections(&other.session_id, &[unprotect.clone()]);
+        assert!(matches!(result, Err(MpError::Protected(_))));
+        room.apply_protections(&owner.session_id, &[unprotect])
+            .unwrap();
+        assert!(room.protections[&sheet_id].is_empty());
+    }
+
+    #[test]
+    fn seeds_protections_from_the_file() {
+        let owner = new_user();
+        let other = new_user();
+
+        // the protection is saved before the room is created
+        let mut grid = Grid::new();
+        let sheet_id = grid.sheets()[0].id;
+        grid.try_sheet_mut(sheet_id).unwrap().protection = SheetProtection {
+            locked: None,
+            ranges: vec![ProtectedRange {
+                rect: Rect::from_numbers(0, 0, 2, 2),
+                editors: vec![owner.user_id.clone()],
+            }],
+        };
+        let file = export_binary(&mut grid).unwrap();
+
+        let mut room = Room::new(Uuid::new_v4(), 0);
+        room.users.insert(owner.session_id, owner.clone());
+        room.users.insert(other.session_id, other.clone());
+        room.protections = file_protections(&file).unwrap();
+
+        let merge = Operation::MergeCells {
+            sheet_rect: SheetRect::single_sheet_pos(SheetPos::new(sheet_id, 1, 1)),
+        };
+        let result = room.apply_protections(&other.session_id, &[merge.clone()]);
+        assert!(matches!(result, Err(MpError::Protected(_))));
+        let unprotect = Operation::SetSheetProtection {
+            sheet_id,
+            protection: SheetProtection::default(),
+        };
+        let result = room.apply_protections(&other.session_id, &[unprotect.clone()]);
+        assert!(matches!(result, Err(MpError::Protected(_))));
+        room.apply_protections(&owner.session_id, &[merge]).unwrap();
+
+        // sheets with an unknown protection cannot be changed
+        let unknown_id = SheetId::new();
+        let unprotect = Operation::SetSheetProtection {
+            sheet_id: unknown_id,
+            protection: SheetProtection::default(),
+        };
+        let delete = Operation::DeleteSheet {
+            sheet_id: unknown_id,
+        };
+        let merge = Operation::MergeCells {
+            sheet_rect: SheetRect::single_sheet_pos(SheetPos::new(unknown_id, 1, 1)),
+        };
+        for operation in [unprotect, delete, merge.clone()] {
+            let result = room.apply_protections(&owner.session_id, &[operation]);
+            assert!(matches!(result, Err(MpError::Protected(_))));
+        }
+
+        // unless they are added in the same transaction
+        let sheet = Sheet::new(unknown_id, "Sheet 2".into(), "a1".into());
+        let add = Operation::AddSheet { sheet };
+        room.apply_protections(&other.session_id, &[add, merge])
+            .unwrap();
+        assert!(room.protections.contains_key(&unknown_id));
+    }
+
+    #[test]
+    fn is_not_created_without_protections() {
+        // e.g. the file could not be downloaded
+        let result = Room::with_protections(
+            Uuid::new_v4(),
+            0,
+            Err(MpError::S3("the file could not be downloaded".into())),
+        );
+        assert!(matches!(result, Err(MpError::S3(_))));
+
+        // a room that knows no protections rejects every change
+        let mut room = Room::with_protections(Uuid::new_v4(), 0, Ok(HashMap::new())).unwrap();
+        let user = new_user();
+        room.users.insert(user.session_id, user.clone());
+        let operation = Operation::SetSheetColor {
+            sheet_id: SheetId::new(),
+            color: Some("red".into()),
+        };
+        let result = room.apply_protections(&user.session_id, &[operation]);
+        assert!(matches!(result, Err(MpError::Protected(_))));
+    }
+
     #[tokio::test]
     async fn enters_retrieves_leaves_and_removes_a_room() {
         let state = new_state().await;
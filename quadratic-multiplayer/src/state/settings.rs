@@ -1,4 +1,6 @@
 use jsonwebtoken::jwk::JwkSet;
+use quadratic_rust_shared::aws::{client, Client};
+use quadratic_rust_shared::environment::Environment;
 use serde::{Deserialize, Serialize};
 
 use crate::config::Config;
@@ -25,16 +27,26 @@ pub(crate) struct Settings {
     pub(crate) quadratic_api_uri: String,
     pub(crate) m2m_auth_token: String,
     pub(crate) min_version: MinVersion,
+    pub(crate) aws_client: Client,
 }
 
 impl Settings {
     pub(crate) async fn new(config: &Config, jwks: Option<JwkSet>) -> Self {
+        let is_local = config.environment == Environment::Docker;
         Settings {
             jwks,
             authenticate_jwt: config.authenticate_jwt,
             quadratic_api_uri: config.quadratic_api_uri.to_owned(),
             m2m_auth_token: config.m2m_auth_token.to_owned(),
             min_version: MinVersion::new().expect("Unable to load min version file"),
+            aws_client: client(
+                &config.aws_s3_access_key_id,
+                &config.aws_s3_secret_access_key,
+                &config.aws_s3_region,
+                "Quadratic Multiplayer",
+                is_local,
+            )
+            .await,
         }
     }
 }